@@ -1,12 +1,14 @@
 use dotenv::dotenv;
 use flow_like_api::axum;
 use flow_like_api::construct_router;
-use flow_like_storage::object_store::aws::AmazonS3Builder;
+use flow_like_storage::files::store::{FlowLikeStore, local_store::LocalObjectStore};
+use flow_like_storage::object_store::{aws::AmazonS3Builder, memory::InMemory};
 use flow_like_types::tokio;
 use socket2::{Domain, Socket, Type};
 use std::{
     io,
     net::{SocketAddr, ToSocketAddrs},
+    path::PathBuf,
     sync::Arc,
     time::Duration,
 };
@@ -39,12 +41,38 @@ async fn main() {
         Some(guard)
     };
 
-    let cdn_bucket = std::env::var("CDN_BUCKET_NAME").unwrap();
+    let cdn_bucket = match std::env::var("CDN_BUCKET_NAME") {
+        Ok(bucket) if !bucket.is_empty() => s3_cdn_bucket(bucket),
+        _ => local_cdn_bucket(),
+    };
+
+    let catalog = Arc::new(flow_like_catalog::get_catalog().await);
+    let state = Arc::new(flow_like_api::state::State::new(catalog, Arc::new(cdn_bucket)).await);
+
+    let app = construct_router(state);
+
+    let port = 3210;
+    let listener = match create_listener(format!("0.0.0.0:{}", port)) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("Failed to bind to port {}: {:?}", port, err);
+            return;
+        }
+    };
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
+}
+
+fn s3_cdn_bucket(bucket: String) -> FlowLikeStore {
     let cdn_bucket_endpoint = std::env::var("CDN_BUCKET_ENDPOINT").ok();
     let cdn_bucket_access_key = std::env::var("CDN_BUCKET_ACCESS_KEY_ID").ok();
     let cdn_bucket_secret_key = std::env::var("CDN_BUCKET_SECRET_ACCESS_KEY").ok();
 
-    let mut cdn_bucket = AmazonS3Builder::new().with_bucket_name(cdn_bucket);
+    let mut cdn_bucket = AmazonS3Builder::new().with_bucket_name(bucket);
     if let Some(endpoint) = cdn_bucket_endpoint {
         if !endpoint.is_empty() {
             cdn_bucket = cdn_bucket.with_endpoint(endpoint);
@@ -58,23 +86,17 @@ async fn main() {
         }
     }
 
-    let cdn_bucket =
-        flow_like_storage::files::store::FlowLikeStore::AWS(Arc::new(cdn_bucket.build().unwrap()));
-
-    let catalog = Arc::new(flow_like_catalog::get_catalog().await);
-    let state = Arc::new(flow_like_api::state::State::new(catalog, Arc::new(cdn_bucket)).await);
-
-    let app = construct_router(state);
+    FlowLikeStore::AWS(Arc::new(cdn_bucket.build().unwrap()))
+}
 
-    let port = 3210;
-    let listener = match create_listener(format!("0.0.0.0:{}", port)) {
-        Ok(listener) => listener,
-        Err(err) => {
-            eprintln!("Failed to bind to port {}: {:?}", port, err);
-            return;
-        }
-    };
-    axum::serve(listener, app).await.unwrap();
+/// Without a CDN bucket, public assets are kept in `LOCAL_STORAGE_DIR/cdn` or in memory.
+fn local_cdn_bucket() -> FlowLikeStore {
+    match std::env::var("LOCAL_STORAGE_DIR") {
+        Ok(dir) if !dir.is_empty() => FlowLikeStore::Local(Arc::new(
+            LocalObjectStore::new(PathBuf::from(dir).join("cdn")).unwrap(),
+        )),
+        _ => FlowLikeStore::Memory(Arc::new(InMemory::new())),
+    }
 }
 
 fn create_listener<A: ToSocketAddrs>(
@@ -0,0 +1,95 @@
+use dotenv::dotenv;
+use flow_like_api::{axum, mcp, state::AppState};
+use flow_like_storage::files::store::{FlowLikeStore, local_store::LocalObjectStore};
+use flow_like_storage::object_store::{aws::AmazonS3Builder, memory::InMemory};
+use flow_like_types::tokio;
+use std::{path::PathBuf, sync::Arc};
+use tracing_subscriber::prelude::*;
+
+const USAGE: &str = "Usage: local-mcp stdio <app_id> | local-mcp http";
+
+#[flow_like_types::tokio::main]
+async fn main() {
+    dotenv().ok();
+
+    // stdout carries the stdio transport, logs have to go elsewhere
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .init();
+
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    let cdn_bucket = match std::env::var("CDN_BUCKET_NAME") {
+        Ok(bucket) if !bucket.is_empty() => s3_cdn_bucket(bucket),
+        _ => local_cdn_bucket(),
+    };
+
+    let catalog = Arc::new(flow_like_catalog::get_catalog().await);
+    let state = Arc::new(flow_like_api::state::State::new(catalog, Arc::new(cdn_bucket)).await);
+
+    let result = match args.as_slice() {
+        ["stdio", app_id] => serve_stdio(state, app_id).await,
+        ["http"] => serve_http(state).await,
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(err) = result {
+        tracing::error!("MCP server stopped: {:?}", err);
+        std::process::exit(1);
+    }
+}
+
+async fn serve_stdio(state: AppState, app_id: &str) -> flow_like_types::Result<()> {
+    let token = std::env::var("FLOW_LIKE_TOKEN")
+        .map_err(|_| flow_like_types::anyhow!("FLOW_LIKE_TOKEN is not set"))?;
+    mcp::serve_stdio(state, app_id, token.trim()).await
+}
+
+async fn serve_http(state: AppState) -> flow_like_types::Result<()> {
+    let port = std::env::var("PORT")
+        .ok()
+        .and_then(|port| port.parse::<u16>().ok())
+        .unwrap_or(3211);
+
+    let app = axum::Router::new().nest("/api/v1", mcp::routes(state));
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", port)).await?;
+    tracing::info!("Serving MCP on port {}", port);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+fn s3_cdn_bucket(bucket: String) -> FlowLikeStore {
+    let cdn_bucket_endpoint = std::env::var("CDN_BUCKET_ENDPOINT").ok();
+    let cdn_bucket_access_key = std::env::var("CDN_BUCKET_ACCESS_KEY_ID").ok();
+    let cdn_bucket_secret_key = std::env::var("CDN_BUCKET_SECRET_ACCESS_KEY").ok();
+
+    let mut cdn_bucket = AmazonS3Builder::new().with_bucket_name(bucket);
+    if let Some(endpoint) = cdn_bucket_endpoint {
+        if !endpoint.is_empty() {
+            cdn_bucket = cdn_bucket.with_endpoint(endpoint);
+        }
+    }
+
+    if let (Some(access_key), Some(secret_key)) = (cdn_bucket_access_key, cdn_bucket_secret_key) {
+        if !access_key.is_empty() && !secret_key.is_empty() {
+            cdn_bucket = cdn_bucket.with_access_key_id(access_key);
+            cdn_bucket = cdn_bucket.with_secret_access_key(secret_key);
+        }
+    }
+
+    FlowLikeStore::AWS(Arc::new(cdn_bucket.build().unwrap()))
+}
+
+/// Without a CDN bucket, public assets are kept in `LOCAL_STORAGE_DIR/cdn` or in memory.
+fn local_cdn_bucket() -> FlowLikeStore {
+    match std::env::var("LOCAL_STORAGE_DIR") {
+        Ok(dir) if !dir.is_empty() => FlowLikeStore::Local(Arc::new(
+            LocalObjectStore::new(PathBuf::from(dir).join("cdn")).unwrap(),
+        )),
+        _ => FlowLikeStore::Memory(Arc::new(InMemory::new())),
+    }
+}
@@ -826,7 +826,9 @@ export function NavUser({
 		backend.userState,
 		[],
 	);
-	const notificationCount = notifications.data?.invites_count ?? 0;
+	const notificationCount =
+		(notifications.data?.invites_count ?? 0) +
+		(notifications.data?.publication_updates ?? 0);
 
 	return (
 		<SidebarMenu>
@@ -7,6 +7,7 @@ import {
 	type IGenericCommand,
 	type IIntercomEvent,
 	type ILog,
+	type ILogFilter,
 	type ILogLevel,
 	type ILogMetadata,
 	type INode,
@@ -494,15 +495,13 @@ export class BoardState implements IBoardState {
 
 	async queryRun(
 		logMeta: ILogMetadata,
-		query: string,
+		filter: ILogFilter,
 		offset?: number,
 		limit?: number,
 	): Promise<ILog[]> {
 		const runs: ILog[] = await invoke("query_run", {
 			logMeta: logMeta,
-			query: query,
-			limit: limit,
-			offset: offset,
+			filter: { ...filter, limit: limit, offset: offset },
 		});
 		return runs;
 	}
@@ -2,6 +2,7 @@ import {
 	ApiConfig,
 	ChatInterface,
 	type IEventMapping,
+	MqttConfig,
 	SimpleChatConfig,
 	UserMailConfig,
 	WebhookConfig,
@@ -57,6 +58,24 @@ export const EVENT_CONFIG: IEventMapping = {
 		},
 		useInterfaces: {},
 	},
+	events_mqtt: {
+		configInterfaces: {
+			mqtt: MqttConfig,
+		},
+		defaultEventType: "mqtt",
+		eventTypes: ["mqtt"],
+		configs: {
+			mqtt: {
+				host: "localhost",
+				port: 1883,
+				topic_filter: "",
+				qos: 1,
+				use_tls: false,
+				include_retained: false,
+			},
+		},
+		useInterfaces: {},
+	},
 	events_simple: {
 		configInterfaces: {
 			webhook: WebhookConfig,
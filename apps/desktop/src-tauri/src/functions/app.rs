@@ -4,7 +4,7 @@ use std::{
     time::{Duration, SystemTime},
 };
 
-use super::TauriFunctionError;
+use super::{TauriFunctionError, flow::event::stop_app_event_sources};
 use crate::state::{TauriFlowLikeState, TauriSettingsState};
 use flow_like::{
     app::App,
@@ -606,6 +606,8 @@ pub async fn delete_app(app_handle: AppHandle, app_id: String) -> Result<(), Tau
         return Err(TauriFunctionError::new("App ID is empty"));
     };
 
+    stop_app_event_sources(&app_handle, &app_id).await?;
+
     let store = TauriFlowLikeState::get_project_storage_store(&app_handle).await?;
     let settings = TauriSettingsState::construct(&app_handle).await?;
 
@@ -1,10 +1,102 @@
 use flow_like::{
     app::App,
-    flow::{board::VersionType, event::Event},
+    flow::{
+        board::VersionType,
+        event::{Event, MqttEventParameters, contract::EventContract},
+    },
 };
+use flow_like_catalog::mqtt::source::MqttEventSource;
+use flow_like_types::json;
 use tauri::AppHandle;
 
-use crate::{functions::TauriFunctionError, state::TauriFlowLikeState};
+use super::run::event_source_trigger;
+use crate::{
+    functions::TauriFunctionError,
+    state::{TauriEventSourceState, TauriFlowLikeState, TauriSettingsState},
+};
+
+const MQTT_EVENT_TYPE: &str = "mqtt";
+
+/// Restarts the source of an event with its current config, inactive events are only stopped.
+pub async fn sync_event_source(
+    handler: &AppHandle,
+    app_id: &str,
+    event: &Event,
+) -> Result<(), TauriFunctionError> {
+    stop_event_source(handler, &event.id).await?;
+
+    if !event.active || event.event_type != MQTT_EVENT_TYPE {
+        return Ok(());
+    }
+
+    let parameters: MqttEventParameters = json::from_slice(&event.config)
+        .map_err(|e| TauriFunctionError::new(&format!("Invalid MQTT event config: {}", e)))?;
+    let trigger = event_source_trigger(handler.clone(), app_id.to_string(), event.id.clone());
+    let source = MqttEventSource::start(&event.node_id, &parameters, trigger).await?;
+
+    TauriEventSourceState::construct(handler)
+        .await?
+        .lock()
+        .await
+        .insert(event.id.clone(), (app_id.to_string(), source));
+    Ok(())
+}
+
+pub async fn stop_event_source(
+    handler: &AppHandle,
+    event_id: &str,
+) -> Result<(), TauriFunctionError> {
+    let sources = TauriEventSourceState::construct(handler).await?;
+    let source = sources.lock().await.remove(event_id);
+    if let Some((_, source)) = source {
+        if let Err(e) = source.stop().await {
+            tracing::warn!("Failed to stop event source {}: {:?}", event_id, e);
+        }
+    }
+    Ok(())
+}
+
+pub async fn stop_app_event_sources(
+    handler: &AppHandle,
+    app_id: &str,
+) -> Result<(), TauriFunctionError> {
+    let sources = TauriEventSourceState::construct(handler).await?;
+    let event_ids = sources
+        .lock()
+        .await
+        .iter()
+        .filter(|(_, (source_app_id, _))| source_app_id == app_id)
+        .map(|(event_id, _)| event_id.clone())
+        .collect::<Vec<_>>();
+
+    for event_id in event_ids {
+        stop_event_source(handler, &event_id).await?;
+    }
+    Ok(())
+}
+
+/// Starts the sources of all active events in the apps of the current profile.
+pub async fn start_event_sources(handler: &AppHandle) -> Result<(), TauriFunctionError> {
+    let profile = TauriSettingsState::current_profile(handler).await?;
+    let flow_like_state = TauriFlowLikeState::construct(handler).await?;
+
+    for app in profile.hub_profile.apps.unwrap_or_default() {
+        let Ok(app) = App::load(app.app_id.clone(), flow_like_state.clone()).await else {
+            continue;
+        };
+
+        for event_id in &app.events {
+            let Ok(event) = Event::load(event_id, &app, None).await else {
+                continue;
+            };
+
+            if let Err(e) = sync_event_source(handler, &app.id, &event).await {
+                tracing::warn!("Failed to start event source {}: {:?}", event_id, e);
+            }
+        }
+    }
+    Ok(())
+}
 
 #[tauri::command(async)]
 pub async fn get_event(
@@ -39,6 +131,23 @@ pub async fn get_event_versions(
     Err(TauriFunctionError::new("Event not found"))
 }
 
+#[tauri::command(async)]
+pub async fn get_event_contract(
+    handler: AppHandle,
+    app_id: String,
+    event_id: String,
+    version: Option<(u32, u32, u32)>,
+) -> Result<EventContract, TauriFunctionError> {
+    let flow_like_state = TauriFlowLikeState::construct(&handler).await?;
+
+    if let Ok(app) = App::load(app_id, flow_like_state).await {
+        let contract = app.get_event_contract(&event_id, version).await?;
+        return Ok(contract);
+    }
+
+    Err(TauriFunctionError::new("Event not found"))
+}
+
 #[tauri::command(async)]
 pub async fn get_events(
     handler: AppHandle,
@@ -76,6 +185,7 @@ pub async fn upsert_event(
 
     if let Ok(mut app) = App::load(app_id.clone(), flow_like_state).await {
         let event = app.upsert_event(event, version_type, enforce_id).await?;
+        sync_event_source(&handler, &app_id, &event).await?;
         return Ok(event);
     }
 
@@ -92,6 +202,7 @@ pub async fn delete_event(
 
     if let Ok(mut app) = App::load(app_id.clone(), flow_like_state).await {
         app.delete_event(&event_id).await?;
+        stop_event_source(&handler, &event_id).await?;
         return Ok(());
     }
 
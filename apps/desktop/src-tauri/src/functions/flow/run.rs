@@ -1,15 +1,17 @@
 use flow_like::app::App;
 use flow_like::credentials::SharedCredentials;
+use flow_like::flow::event::chain::ChainedRun;
 use flow_like::flow::execution::InternalRun;
 use flow_like::flow::execution::log::LogMessage;
+use flow_like::flow::execution::query::{LogFilter, RunCursor, RunFilter};
 use flow_like::flow::execution::{LogLevel, LogMeta, RunPayload};
-use flow_like::flow_like_storage::lancedb::query::{ExecutableQuery, QueryBase};
-use flow_like::flow_like_storage::{Path, serde_arrow};
+use flow_like::flow_like_storage::Path;
 use flow_like::state::RunData;
+use flow_like_catalog::mqtt::source::MqttTrigger;
 use flow_like_types::intercom::{BufferedInterComHandler, InterComEvent};
 use flow_like_types::tokio_util::sync::CancellationToken;
 use flow_like_types::{json, tokio};
-use futures::TryStreamExt;
+use futures::future::BoxFuture;
 use std::sync::Arc;
 use std::time::Duration;
 use tauri::{AppHandle, Emitter};
@@ -28,6 +30,7 @@ async fn execute_internal(
     event_id: Option<String>,
     stream_state: bool,
     credentials: Option<SharedCredentials>,
+    chained_from: Option<ChainedRun>,
 ) -> Result<Option<LogMeta>, TauriFunctionError> {
     let mut event = None;
     let flow_like_state = TauriFlowLikeState::construct(&app_handle).await?;
@@ -50,7 +53,12 @@ async fn execute_internal(
 
     let board = Arc::new(board.lock().await.clone());
 
+    if let Some(event) = &event {
+        event.validate_payload(&board, &payload)?;
+    }
+
     let profile = TauriSettingsState::current_profile(&app_handle).await?;
+    let chain_handle = app_handle.clone();
 
     let buffered_sender = Arc::new(BufferedInterComHandler::new(
         Arc::new(move |event| {
@@ -88,9 +96,12 @@ async fn execute_internal(
         None,
         stream_state,
         buffered_sender.into_callback(),
-        credentials,
+        credentials.clone(),
     )
     .await?;
+    if let Some(chained) = &chained_from {
+        internal_run.set_chained_from(chained).await;
+    }
     let run_id = internal_run.run.lock().await.id.clone();
 
     let _send_result = buffered_sender
@@ -143,7 +154,7 @@ async fn execute_internal(
         let db_fn = db
             .as_ref()
             .ok_or_else(|| flow_like_types::anyhow!("No log database configured"))?;
-        let base_path = Path::from("runs").child(app_id).child(board_id);
+        let base_path = Path::from("runs").child(app_id.clone()).child(board_id);
         let db = db_fn(base_path.clone()).execute().await.map_err(|e| {
             flow_like_types::anyhow!("Failed to open database: {}, {:?}", base_path, e)
         })?;
@@ -154,9 +165,84 @@ async fn execute_internal(
 
     let _res = flow_like_state.lock().await.remove_and_cancel_run(&run_id);
 
+    if let Some(meta) = &meta {
+        for chained in internal_run.chained_runs(meta).await {
+            tokio::spawn(execute_chained(
+                chain_handle.clone(),
+                app_id.clone(),
+                chained,
+                credentials.clone(),
+            ));
+        }
+    }
+
     Ok(meta)
 }
 
+/// Runs an event for every message of its event source, without a frontend channel.
+pub fn event_source_trigger(
+    app_handle: AppHandle,
+    app_id: String,
+    event_id: String,
+) -> MqttTrigger {
+    Arc::new(move |payload: RunPayload| {
+        let app_handle = app_handle.clone();
+        let app_id = app_id.clone();
+        let event_id = event_id.clone();
+        Box::pin(async move {
+            let events = tauri::ipc::Channel::new(|_| Ok(()));
+            if let Err(err) = execute_internal(
+                app_handle,
+                app_id,
+                String::new(), // Will be read from the event anyways
+                payload,
+                events,
+                Some(event_id.clone()),
+                false,
+                None,
+                None,
+            )
+            .await
+            {
+                println!(
+                    "Error executing event {} from its source: {:?}",
+                    event_id, err
+                );
+            }
+        })
+    })
+}
+
+fn execute_chained(
+    app_handle: AppHandle,
+    app_id: String,
+    chained: ChainedRun,
+    credentials: Option<SharedCredentials>,
+) -> BoxFuture<'static, ()> {
+    Box::pin(async move {
+        // Chained runs are not bound to a frontend channel, their events are only emitted globally
+        let events = tauri::ipc::Channel::new(|_| Ok(()));
+        if let Err(err) = execute_internal(
+            app_handle,
+            app_id,
+            String::new(), // Will be read from the event anyways
+            chained.payload.clone(),
+            events,
+            Some(chained.event_id.clone()),
+            false,
+            credentials,
+            Some(chained.clone()),
+        )
+        .await
+        {
+            println!(
+                "Error executing chained event {}: {:?}",
+                chained.event_id, err
+            );
+        }
+    })
+}
+
 #[tauri::command(async)]
 pub async fn execute_board(
     app_handle: AppHandle,
@@ -177,6 +263,7 @@ pub async fn execute_board(
         None,
         stream_state,
         credentials,
+        None,
     )
     .await
 }
@@ -201,6 +288,7 @@ pub async fn execute_event(
         Some(event_id),
         stream_state,
         credentials,
+        None,
     )
     .await
 }
@@ -228,142 +316,36 @@ pub async fn list_runs(
     offset: Option<usize>,
     last_meta: Option<LogMeta>,
 ) -> Result<Vec<LogMeta>, TauriFunctionError> {
-    let limit = limit.unwrap_or(100);
-    let offset = offset.unwrap_or(0);
     let state = TauriFlowLikeState::construct(&app_handle).await?;
-    let db = {
-        let guard = state.lock().await;
-        let guard = guard.config.read().await;
-
-        guard.callbacks.build_logs_database.clone()
+    let filter = RunFilter {
+        node_id,
+        event_id: None,
+        from,
+        to,
+        status,
+        cursor: last_meta.map(|meta| RunCursor::from_meta(&meta).to_string()),
+        limit,
+        offset,
     };
-    let db_fn = db
-        .as_ref()
-        .ok_or_else(|| flow_like_types::anyhow!("No log database configured"))?;
-    let base_path = Path::from("runs").child(app_id).child(board_id);
-    let db = db_fn(base_path.clone())
-        .execute()
-        .await
-        .map_err(|_| flow_like_types::anyhow!("Failed to open database: {}", base_path))?;
-
-    let db = db
-        .open_table("runs")
-        .execute()
-        .await
-        .map_err(|_| flow_like_types::anyhow!("Failed to open table: runs"))?;
-
-    let mut query_string = String::from("");
-
-    if let Some(node_id) = node_id {
-        query_string.push_str(&format!("node_id = '{}'", node_id));
-    }
-
-    if let Some(from) = from {
-        if !query_string.is_empty() {
-            query_string.push_str(" AND ");
-        }
-        query_string.push_str(&format!("start >= {}", from));
-    }
-
-    if let Some(to) = to {
-        if !query_string.is_empty() {
-            query_string.push_str(" AND ");
-        }
-        query_string.push_str(&format!("start <= {}", to));
-    }
-
-    if let Some(status) = status {
-        if !query_string.is_empty() {
-            query_string.push_str(" AND ");
-        }
-
-        let status = status.to_u8();
-        if status == 0 {
-            query_string.push_str("log_level <= 1");
-        } else {
-            query_string.push_str(&format!("log_level = {}", status));
-        }
-    }
-
-    let mut query = db.query();
-
-    if !query_string.is_empty() {
-        query = query.only_if(&query_string);
-    }
-
-    let runs = query
-        .limit(limit)
-        .offset(offset)
-        .execute()
-        .await
-        .map_err(|_| flow_like_types::anyhow!("Failed to execute query"))?;
-    let results = runs
-        .try_collect::<Vec<_>>()
+    let page = state
+        .lock()
         .await
-        .map_err(|_| flow_like_types::anyhow!("Failed to collect results"))?;
-    let mut log_meta = Vec::with_capacity(results.len() * 10);
-    for result in results {
-        let result = serde_arrow::from_record_batch::<Vec<LogMeta>>(&result).unwrap_or_default();
-        log_meta.extend(result);
-    }
-    Ok(log_meta)
-
-    // let mut stream = db
-    //     .query()
-    //     .execute()
-    //     .await
-    //     .map_err(|_| flow_like_types::anyhow!("Failed to execute query on table: runs"))?;
-
-    // let client = ClientBuilder::new().open().await?;
-    // let out = client.conn(move |conn| {
-    //     conn.execute_batch("
-    //         CREATE TABLE runs (
-    //             start     UBIGINT,
-    //             run_id    VARCHAR,
-    //             log_level UTINYINT,
-    //             node_id   VARCHAR
-    //         )
-    //     ")?;
-    //     let mut appender = conn.appender("runs").unwrap();
-    //     let (tx, rx) = std::sync::mpsc::channel();
-    //     tokio::spawn(async move {
-    //         while let Some(item_res) = stream.next().await {
-    //             if let Ok(item) = item_res {
-    //                 let _ = tx.send(item);
-    //             }
-    //         }
-    //     });
-
-    //     for meta in rx {
-    //         appender.append_record_batch(meta)?;
-    //     }
-    //     appender.flush()?;
-    //     let mut stmt = conn.prepare("SELECT start, run_id, log_level, node_id FROM runs ORDER BY start DESC LIMIT ?, ?")?;
-    //     let mut rows = stmt.query(params![offset as i64, limit as i64])?;
-    //     let mut out = Vec::new();
-    //     while let Some(r) = rows.next()? {
-    //         let start: u64 = r.get(0)?;
-    //         println!("Row: {:?}", start);
-    //     }
-    //     Ok(out)
-    // }).await?;
-
-    // return Ok(out);
+        .query_runs(&app_id, &board_id, &filter)
+        .await?;
+    Ok(page.runs)
 }
 
 #[tauri::command(async)]
 pub async fn query_run(
     app_handle: AppHandle,
     log_meta: LogMeta,
-    query: String,
-    limit: Option<usize>,
-    offset: Option<usize>,
+    filter: LogFilter,
 ) -> Result<Vec<LogMessage>, TauriFunctionError> {
     let state = TauriFlowLikeState::construct(&app_handle).await?;
     let logs = state
         .lock()
         .await
-        .query_run(&log_meta, &query, limit, offset)
+        .query_run_logs(&log_meta, &filter)
         .await?;
     Ok(logs)
 }
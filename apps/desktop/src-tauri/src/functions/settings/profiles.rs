@@ -3,7 +3,12 @@ use crate::{
     profile::UserProfile,
     state::{TauriFlowLikeState, TauriSettingsState},
 };
-use flow_like::{bit::Bit, hub::Hub, profile::Profile, utils::http::HTTPClient};
+use flow_like::{
+    bit::Bit,
+    hub::Hub,
+    profile::{Profile, RemoteEndpoint},
+    utils::http::HTTPClient,
+};
 use flow_like_types::tokio::task::JoinHandle;
 use futures::future::join_all;
 use std::{collections::HashMap, sync::Arc};
@@ -140,10 +145,11 @@ pub async fn get_bits_in_current_profile(
     }
 
     let results = join_all(tasks).await;
-    let found_bits: Vec<Bit> = results
+    let mut found_bits: Vec<Bit> = results
         .into_iter()
         .filter_map(|res| res.ok().flatten())
         .collect();
+    found_bits.extend(profile.hub_profile.remote_bits());
 
     Ok(found_bits)
 }
@@ -238,3 +244,89 @@ pub async fn remove_bit(
     settings.serialize();
     Ok(())
 }
+
+#[instrument(skip_all)]
+#[tauri::command(async)]
+pub async fn discover_remote_endpoint(
+    app_handle: AppHandle,
+    profile: UserProfile,
+    endpoint: RemoteEndpoint,
+    api_key: Option<String>,
+) -> Result<Vec<Bit>, TauriFunctionError> {
+    let settings = TauriSettingsState::construct(&app_handle).await?;
+    let mut settings = settings.lock().await;
+    let url = endpoint.config.endpoint.clone();
+    // Rediscovering without a key keeps the stored one
+    let api_key = api_key.or_else(|| settings.remote_keys.get(&url).cloned());
+    let profile = settings
+        .profiles
+        .get_mut(&profile.hub_profile.id)
+        .ok_or(anyhow::anyhow!("Profile not found"))?;
+    let bits = profile
+        .hub_profile
+        .discover_remote_endpoint(endpoint, api_key.as_deref())
+        .await?;
+    if let Some(api_key) = api_key {
+        settings.remote_keys.insert(url, api_key);
+    }
+    settings.serialize();
+    let remote_keys = settings.remote_keys.clone();
+    drop(settings);
+
+    set_remote_keys(&app_handle, remote_keys).await?;
+    Ok(bits)
+}
+
+#[instrument(skip_all)]
+#[tauri::command(async)]
+pub async fn remove_remote_endpoint(
+    app_handle: AppHandle,
+    profile: UserProfile,
+    endpoint_id: String,
+) -> Result<(), TauriFunctionError> {
+    let settings = TauriSettingsState::construct(&app_handle).await?;
+    let mut settings = settings.lock().await;
+    let profile = settings
+        .profiles
+        .get_mut(&profile.hub_profile.id)
+        .ok_or(anyhow::anyhow!("Profile not found"))?;
+    let url = profile
+        .hub_profile
+        .remote_endpoints
+        .iter()
+        .find(|endpoint| endpoint.id == endpoint_id)
+        .map(|endpoint| endpoint.config.endpoint.clone());
+    profile.hub_profile.remove_remote_endpoint(&endpoint_id);
+
+    // Other profiles may still use the same server
+    if let Some(url) = url {
+        let in_use = settings.profiles.values().any(|profile| {
+            profile
+                .hub_profile
+                .remote_endpoints
+                .iter()
+                .any(|endpoint| endpoint.config.endpoint == url)
+        });
+        if !in_use {
+            settings.remote_keys.remove(&url);
+        }
+    }
+    settings.serialize();
+    let remote_keys = settings.remote_keys.clone();
+    drop(settings);
+
+    set_remote_keys(&app_handle, remote_keys).await?;
+    Ok(())
+}
+
+async fn set_remote_keys(
+    app_handle: &AppHandle,
+    remote_keys: HashMap<String, String>,
+) -> Result<(), TauriFunctionError> {
+    let state = TauriFlowLikeState::construct(app_handle).await?;
+    let mut state = state.lock().await;
+    let mut config = (*state.model_provider_config).clone();
+    config.remote_keys = remote_keys;
+    state.model_provider_config = Arc::new(config);
+    Ok(())
+}
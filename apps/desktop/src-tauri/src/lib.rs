@@ -4,6 +4,7 @@ mod settings;
 mod state;
 mod utils;
 use flow_like::{
+    flow_like_model_provider::provider::ModelProviderConfiguration,
     flow_like_storage::{
         Path,
         files::store::{FlowLikeStore, local_store::LocalObjectStore},
@@ -28,6 +29,7 @@ pub fn run() {
     let project_dir = settings_state.project_dir.clone();
     let logs_dir = settings_state.logs_dir.clone();
     let temporary_dir = settings_state.temporary_dir.clone();
+    let remote_keys = settings_state.remote_keys.clone();
 
     let mut config: FlowLikeConfig = FlowLikeConfig::new();
     config.register_bits_store(FlowLikeStore::Local(Arc::new(
@@ -67,7 +69,11 @@ pub fn run() {
     settings_state.set_config(&config);
     let settings_state = Arc::new(Mutex::new(settings_state));
     let (http_client, refetch_rx) = HTTPClient::new();
-    let state = FlowLikeState::new(config, http_client);
+    let mut state = FlowLikeState::new(config, http_client);
+    state.model_provider_config = Arc::new(ModelProviderConfiguration {
+        remote_keys,
+        ..Default::default()
+    });
     let state_ref = Arc::new(Mutex::new(state));
 
     let initialized_state = state_ref.clone();
@@ -127,6 +133,7 @@ pub fn run() {
             let gc_handle = relay_handle.clone();
             let refetch_handle = relay_handle.clone();
             let deep_link_handle = relay_handle.clone();
+            let event_source_handle = relay_handle.clone();
 
             #[cfg(desktop)]
             {
@@ -154,6 +161,14 @@ pub fn run() {
                 handle_deep_link(&deep_link_handle, event);
             });
 
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) =
+                    functions::flow::event::start_event_sources(&event_source_handle).await
+                {
+                    eprintln!("Failed to start event sources: {:?}", e);
+                }
+            });
+
             tauri::async_runtime::spawn(async move {
                 let handle = gc_handle;
 
@@ -229,6 +244,7 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .manage(state::TauriSettingsState(settings_state))
         .manage(state::TauriFlowLikeState(state_ref))
+        .manage(state::TauriEventSourceState(Arc::default()))
         .on_page_load(|view, payload| {
             let label = view.label();
             let app_handle = view.app_handle();
@@ -268,6 +284,8 @@ pub fn run() {
             functions::settings::profiles::delete_profile,
             functions::settings::profiles::add_bit,
             functions::settings::profiles::remove_bit,
+            functions::settings::profiles::discover_remote_endpoint,
+            functions::settings::profiles::remove_remote_endpoint,
             functions::settings::profiles::get_bits_in_current_profile,
             functions::app::app_configured,
             functions::app::upsert_board,
@@ -321,6 +339,7 @@ pub fn run() {
             functions::flow::event::get_event,
             functions::flow::event::get_events,
             functions::flow::event::get_event_versions,
+            functions::flow::event::get_event_contract,
             functions::flow::event::upsert_event,
             functions::flow::event::delete_event,
             functions::flow::template::get_template,
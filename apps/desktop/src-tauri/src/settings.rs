@@ -32,6 +32,9 @@ pub struct Settings {
     pub temporary_dir: PathBuf,
     pub user_dir: PathBuf,
     pub profiles: HashMap<String, UserProfile>,
+    /// API keys of remote model servers by endpoint, kept out of the profiles since those get shared
+    #[serde(default)]
+    pub remote_keys: HashMap<String, String>,
     pub updated: SystemTime,
     pub created: SystemTime,
 
@@ -77,6 +80,7 @@ impl Settings {
             temporary_dir: default_temporary_dir(),
             user_dir: dirs_next::cache_dir().unwrap_or_default().join("flow-like"),
             profiles: HashMap::new(),
+            remote_keys: HashMap::new(),
             created: SystemTime::now(),
             updated: SystemTime::now(),
             config: None,
@@ -1,8 +1,9 @@
 use flow_like::{
     flow_like_storage::object_store::ObjectStore, state::FlowLikeState, utils::http::HTTPClient,
 };
+use flow_like_catalog::mqtt::source::MqttEventSource;
 use flow_like_types::sync::Mutex;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use tauri::{AppHandle, Manager};
 
 use crate::{profile::UserProfile, settings::Settings};
@@ -81,3 +82,17 @@ impl TauriSettingsState {
         Ok(current_profile)
     }
 }
+
+/// Running MQTT event sources by event id, together with the id of their app.
+pub struct TauriEventSourceState(pub Arc<Mutex<HashMap<String, (String, MqttEventSource)>>>);
+impl TauriEventSourceState {
+    #[inline]
+    pub async fn construct(
+        app_handle: &AppHandle,
+    ) -> anyhow::Result<Arc<Mutex<HashMap<String, (String, MqttEventSource)>>>> {
+        app_handle
+            .try_state::<TauriEventSourceState>()
+            .map(|state| state.0.clone())
+            .ok_or_else(|| anyhow::anyhow!("Event Source State not found"))
+    }
+}
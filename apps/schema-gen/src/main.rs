@@ -3,5 +3,13 @@ use std::path::PathBuf;
 fn main() {
     let base_path = PathBuf::from("packages/schema");
     flow_like::schema_gen::generate_schema(base_path.clone()).unwrap();
+
+    let openapi = flow_like_api::openapi::document(&flow_like_api::openapi::operations());
+    std::fs::write(
+        base_path.join("openapi.json"),
+        serde_json::to_string_pretty(&openapi).unwrap(),
+    )
+    .unwrap();
+
     println!("Schema generated in {:?}", base_path);
 }
@@ -0,0 +1,192 @@
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+    sync::LazyLock,
+};
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::request::Parts,
+};
+use flow_like_types::{Value, create_id, json::to_value};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ConnectionTrait};
+use serde::Serialize;
+
+use crate::entity::{audit_log, sea_orm_active_enums::AuditAction};
+
+/// Proxies allowed to report the caller via `X-Forwarded-For`, comma separated in `TRUSTED_PROXIES`.
+static TRUSTED_PROXIES: LazyLock<Vec<IpAddr>> = LazyLock::new(|| {
+    std::env::var("TRUSTED_PROXIES")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|proxy| proxy.trim().parse().ok())
+        .collect()
+});
+
+/// Address of the caller. The connection peer is used unless it is a trusted proxy, in which case
+/// the right-most untrusted hop of `X-Forwarded-For` is taken, as every hop left of it may be forged.
+#[derive(Clone, Debug, Default)]
+pub struct ClientIp(pub Option<String>);
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        let forwarded = parts
+            .headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let ip = client_ip(peer, &forwarded, &TRUSTED_PROXIES).map(|ip| ip.to_string());
+        Ok(ClientIp(ip))
+    }
+}
+
+/// Without a peer address (e.g. behind a Lambda integration) the forwarded chain is all there is.
+fn client_ip(peer: Option<IpAddr>, forwarded: &str, trusted: &[IpAddr]) -> Option<IpAddr> {
+    if let Some(peer) = peer.filter(|peer| !trusted.contains(peer)) {
+        return Some(peer);
+    }
+
+    forwarded
+        .rsplit(',')
+        .map(str::trim)
+        .filter(|hop| !hop.is_empty())
+        .map_while(|hop| hop.parse::<IpAddr>().ok())
+        .find(|hop| !trusted.contains(hop))
+        .or(peer)
+}
+
+/// Security relevant change within an app. Written in the same transaction as the change itself.
+pub struct Audit {
+    app_id: String,
+    action: AuditAction,
+    target: Option<String>,
+    before: Option<Value>,
+    after: Option<Value>,
+}
+
+impl Audit {
+    pub fn new(app_id: &str, action: AuditAction) -> Self {
+        Self {
+            app_id: app_id.to_string(),
+            action,
+            target: None,
+            before: None,
+            after: None,
+        }
+    }
+
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    pub fn before<T: Serialize>(mut self, value: &T) -> Self {
+        self.before = to_value(value).ok();
+        self
+    }
+
+    pub fn after<T: Serialize>(mut self, value: &T) -> Self {
+        self.after = to_value(value).ok();
+        self
+    }
+
+    pub async fn record<C: ConnectionTrait>(
+        self,
+        db: &C,
+        actor: Option<String>,
+        ip: &ClientIp,
+    ) -> flow_like_types::Result<()> {
+        let (before, after) = diff(self.before, self.after);
+        let entry = audit_log::ActiveModel {
+            id: Set(create_id()),
+            app_id: Set(self.app_id),
+            actor_id: Set(actor),
+            action: Set(self.action),
+            target: Set(self.target),
+            before: Set(before),
+            after: Set(after),
+            ip: Set(ip.0.clone()),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+        };
+        entry.insert(db).await?;
+        Ok(())
+    }
+}
+
+/// Reduces two objects to the fields that changed, other values are kept as they are.
+fn diff(before: Option<Value>, after: Option<Value>) -> (Option<Value>, Option<Value>) {
+    match (before, after) {
+        (Some(Value::Object(mut before)), Some(Value::Object(mut after))) => {
+            before.retain(|key, value| {
+                let changed = after.get(key) != Some(value);
+                if !changed {
+                    after.remove(key);
+                }
+                changed
+            });
+            (Some(Value::Object(before)), Some(Value::Object(after)))
+        }
+        (before, after) => (before, after),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_like_types::json::json;
+
+    #[test]
+    fn keeps_only_changed_fields() {
+        let (before, after) = diff(
+            Some(json!({ "name": "Editor", "permissions": 4, "attributes": [] })),
+            Some(json!({ "name": "Editor", "permissions": 12, "attributes": ["a"] })),
+        );
+        assert_eq!(before, Some(json!({ "permissions": 4, "attributes": [] })));
+        assert_eq!(
+            after,
+            Some(json!({ "permissions": 12, "attributes": ["a"] }))
+        );
+
+        let (before, after) = diff(None, Some(json!({ "name": "Editor" })));
+        assert_eq!(before, None);
+        assert_eq!(after, Some(json!({ "name": "Editor" })));
+    }
+
+    #[test]
+    fn client_ip_only_trusts_configured_proxies() {
+        let ip = |value: &str| value.parse::<IpAddr>().unwrap();
+        let proxy = ip("10.0.0.1");
+        let trusted = [proxy, ip("10.0.0.2")];
+
+        // Direct callers cannot spoof their address through the header.
+        assert_eq!(
+            client_ip(Some(ip("203.0.113.7")), "1.1.1.1", &trusted),
+            Some(ip("203.0.113.7"))
+        );
+        // Behind trusted proxies the right-most untrusted hop wins over forged ones on the left.
+        assert_eq!(
+            client_ip(Some(proxy), "1.1.1.1, 203.0.113.7, 10.0.0.2", &trusted),
+            Some(ip("203.0.113.7"))
+        );
+        // A malformed hop stops the walk instead of being skipped.
+        assert_eq!(
+            client_ip(Some(proxy), "203.0.113.7, garbage", &trusted),
+            Some(proxy)
+        );
+        assert_eq!(client_ip(Some(proxy), "", &trusted), Some(proxy));
+        assert_eq!(
+            client_ip(None, "1.1.1.1, 203.0.113.7", &[]),
+            Some(ip("203.0.113.7"))
+        );
+        assert_eq!(client_ip(None, "", &[]), None);
+    }
+}
@@ -1,18 +1,26 @@
 use std::fmt::Display;
 
+#[cfg(feature = "aws")]
 use aws_credentials::AwsRuntimeCredentials;
 use flow_like::credentials::SharedCredentials;
 use flow_like::flow_like_storage::files::store::FlowLikeStore;
 use flow_like::state::FlowLikeState;
 use flow_like_types::Result;
+#[cfg(not(feature = "aws"))]
+use flow_like_types::anyhow;
 use flow_like_types::async_trait;
+#[cfg(feature = "local")]
+use local_credentials::LocalRuntimeCredentials;
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
 use crate::state::AppState;
 use crate::state::State;
 
+#[cfg(feature = "aws")]
 pub mod aws_credentials;
+#[cfg(feature = "local")]
+pub mod local_credentials;
 
 #[async_trait]
 pub trait RuntimeCredentialsTrait {
@@ -47,6 +55,15 @@ impl Display for CredentialsAccess {
 pub enum RuntimeCredentials {
     #[cfg(feature = "aws")]
     Aws(AwsRuntimeCredentials),
+    #[cfg(feature = "local")]
+    Local(LocalRuntimeCredentials),
+}
+
+/// The local backend is used if it is the only one compiled in or if `STORAGE_BACKEND=local`.
+#[cfg(feature = "local")]
+fn use_local_backend() -> bool {
+    cfg!(not(feature = "aws"))
+        || std::env::var("STORAGE_BACKEND").is_ok_and(|backend| backend == "local")
 }
 
 impl RuntimeCredentials {
@@ -56,25 +73,56 @@ impl RuntimeCredentials {
         state: &State,
         mode: CredentialsAccess,
     ) -> Result<Self> {
+        #[cfg(feature = "local")]
+        if use_local_backend() {
+            return Ok(RuntimeCredentials::Local(
+                LocalRuntimeCredentials::from_env()
+                    .scoped_credentials(sub, app_id, mode)
+                    .await?,
+            ));
+        }
+
         #[cfg(feature = "aws")]
         return Ok(RuntimeCredentials::Aws(
             AwsRuntimeCredentials::from_env()
                 .scoped_credentials(sub, app_id, state, mode)
                 .await?,
         ));
+
+        #[cfg(not(feature = "aws"))]
+        {
+            let _ = (sub, app_id, state, mode);
+            Err(anyhow!("No runtime credentials backend is enabled"))
+        }
     }
 
     pub async fn master_credentials() -> Result<Self> {
+        #[cfg(feature = "local")]
+        if use_local_backend() {
+            return Ok(RuntimeCredentials::Local(
+                LocalRuntimeCredentials::from_env()
+                    .master_credentials()
+                    .await,
+            ));
+        }
+
         #[cfg(feature = "aws")]
         return Ok(RuntimeCredentials::Aws(
             AwsRuntimeCredentials::from_env().master_credentials().await,
         ));
+
+        #[cfg(not(feature = "aws"))]
+        Err(anyhow!("No runtime credentials backend is enabled"))
     }
 
     pub async fn to_store(&self, meta: bool) -> Result<FlowLikeStore> {
         match self {
             #[cfg(feature = "aws")]
             RuntimeCredentials::Aws(aws) => aws.into_shared_credentials().to_store(meta).await,
+            #[cfg(feature = "local")]
+            RuntimeCredentials::Local(local) => {
+                local.into_shared_credentials().to_store(meta).await
+            }
         }
     }
 
@@ -83,6 +131,8 @@ impl RuntimeCredentials {
         match self {
             #[cfg(feature = "aws")]
             RuntimeCredentials::Aws(aws) => aws.to_state(state).await,
+            #[cfg(feature = "local")]
+            RuntimeCredentials::Local(local) => local.to_state(state).await,
         }
     }
 
@@ -91,6 +141,8 @@ impl RuntimeCredentials {
         match self {
             #[cfg(feature = "aws")]
             RuntimeCredentials::Aws(aws) => aws.into_shared_credentials(),
+            #[cfg(feature = "local")]
+            RuntimeCredentials::Local(local) => local.into_shared_credentials(),
         }
     }
 }
@@ -0,0 +1,192 @@
+use crate::credentials::CredentialsAccess;
+use crate::state::AppState;
+use flow_like::credentials::{
+    SharedCredentials,
+    local_credentials::{LocalCredentialsScope, LocalSharedCredentials},
+};
+use flow_like::{
+    flow_like_storage::lancedb::connect,
+    state::{FlowLikeConfig, FlowLikeState},
+    utils::http::HTTPClient,
+};
+use flow_like_storage::object_store;
+use flow_like_types::{Result, anyhow, async_trait};
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, sync::Arc};
+
+use super::RuntimeCredentialsTrait;
+
+/// Runtime credentials for self-hosted deployments. Data lives below `LOCAL_STORAGE_DIR`,
+/// or in memory if it is not set. Scoping mirrors the prefixes of the AWS session policies.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LocalRuntimeCredentials {
+    pub root: Option<PathBuf>,
+    pub scope: Option<LocalCredentialsScope>,
+}
+
+impl LocalRuntimeCredentials {
+    pub fn new(root: Option<PathBuf>) -> Self {
+        LocalRuntimeCredentials { root, scope: None }
+    }
+
+    pub fn from_env() -> Self {
+        let root = std::env::var("LOCAL_STORAGE_DIR")
+            .ok()
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from);
+        LocalRuntimeCredentials::new(root)
+    }
+
+    pub async fn master_credentials(&self) -> Self {
+        LocalRuntimeCredentials::new(self.root.clone())
+    }
+
+    #[tracing::instrument(
+        name = "LocalRuntimeCredentials::scoped_credentials",
+        skip(self),
+        level = "debug"
+    )]
+    pub async fn scoped_credentials(
+        &self,
+        sub: &str,
+        app_id: &str,
+        mode: CredentialsAccess,
+    ) -> Result<Self> {
+        if sub.is_empty() || app_id.is_empty() {
+            return Err(anyhow!("Sub or App ID cannot be empty"));
+        }
+
+        // Identifiers become path segments, they must not escape their prefix
+        if [sub, app_id]
+            .iter()
+            .any(|id| id.contains(['/', '\\']) || id.contains(".."))
+        {
+            return Err(anyhow!("Invalid Sub or App ID"));
+        }
+
+        Ok(LocalRuntimeCredentials {
+            root: self.root.clone(),
+            scope: Some(scope_for(sub, app_id, &mode)),
+        })
+    }
+
+    fn shared(&self) -> LocalSharedCredentials {
+        LocalSharedCredentials {
+            root: self.root.clone(),
+            scope: self.scope.clone(),
+        }
+    }
+}
+
+fn scope_for(sub: &str, app_id: &str, mode: &CredentialsAccess) -> LocalCredentialsScope {
+    let apps_prefix = format!("apps/{}", app_id);
+    let user_prefix = format!("users/{}/apps/{}", sub, app_id);
+    let log_prefix = format!("logs/runs/{}", app_id);
+    let temporary_user_prefix = format!("tmp/user/{}/apps/{}", sub, app_id);
+    let temporary_global_prefix = format!("tmp/global/apps/{}", app_id);
+
+    let (content_read, content_write) = match mode {
+        CredentialsAccess::EditApp => (vec![], vec![apps_prefix.clone()]),
+        CredentialsAccess::ReadApp => (vec![apps_prefix.clone()], vec![]),
+        CredentialsAccess::InvokeNone => {
+            (vec![], vec![user_prefix, temporary_user_prefix, log_prefix])
+        }
+        CredentialsAccess::InvokeRead => (
+            vec![apps_prefix.clone(), temporary_global_prefix],
+            vec![user_prefix, temporary_user_prefix, log_prefix],
+        ),
+        CredentialsAccess::InvokeWrite => (
+            vec![],
+            vec![
+                apps_prefix.clone(),
+                user_prefix,
+                temporary_user_prefix,
+                temporary_global_prefix,
+                log_prefix,
+            ],
+        ),
+        CredentialsAccess::ReadLogs => (vec![log_prefix], vec![]),
+    };
+
+    let (meta_read, meta_write) = match mode {
+        CredentialsAccess::EditApp => (vec![], vec![apps_prefix]),
+        CredentialsAccess::ReadLogs => (vec![], vec![]),
+        _ => (vec![apps_prefix], vec![]),
+    };
+
+    LocalCredentialsScope {
+        meta_read,
+        meta_write,
+        content_read,
+        content_write,
+    }
+}
+
+#[async_trait]
+impl RuntimeCredentialsTrait for LocalRuntimeCredentials {
+    fn into_shared_credentials(&self) -> SharedCredentials {
+        SharedCredentials::Local(self.shared())
+    }
+
+    #[tracing::instrument(
+        name = "LocalRuntimeCredentials::to_state",
+        skip(self, state),
+        level = "debug"
+    )]
+    async fn to_state(&self, state: AppState) -> Result<FlowLikeState> {
+        let shared = self.shared();
+        let meta_store = shared.to_store(true).await?;
+        let content_store = shared.to_store(false).await?;
+        let (http_client, _refetch_rx) = HTTPClient::new();
+
+        let mut config = FlowLikeConfig::with_default_store(content_store);
+        config.register_app_meta_store(meta_store);
+
+        // Logs are stored below "logs", matching the prefix of the scoped credentials
+        let database_dir = shared.db_directory();
+        let logs_dir = database_dir.join("logs");
+        config.register_build_logs_database(Arc::new(move |path: object_store::path::Path| {
+            let directory = logs_dir.join(path.to_string());
+            connect(directory.to_str().unwrap_or_default())
+        }));
+        config.register_build_project_database(Arc::new(move |path: object_store::path::Path| {
+            let directory = database_dir.join(path.to_string());
+            connect(directory.to_str().unwrap_or_default())
+        }));
+
+        let mut flow_like_state = FlowLikeState::new(config, http_client);
+
+        flow_like_state.model_provider_config = state.provider.clone();
+        flow_like_state.node_registry.write().await.node_registry = state.registry.clone();
+
+        Ok(flow_like_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mirrors_aws_prefixes() {
+        let scope = scope_for("user1", "app1", &CredentialsAccess::InvokeRead);
+        assert_eq!(scope.meta_read, vec!["apps/app1"]);
+        assert!(scope.meta_write.is_empty());
+        assert_eq!(
+            scope.content_read,
+            vec!["apps/app1", "tmp/global/apps/app1"]
+        );
+        assert_eq!(
+            scope.content_write,
+            vec![
+                "users/user1/apps/app1",
+                "tmp/user/user1/apps/app1",
+                "logs/runs/app1"
+            ]
+        );
+
+        let scope = scope_for("user1", "app1", &CredentialsAccess::ReadLogs);
+        assert!(scope.meta_read.is_empty());
+        assert_eq!(scope.content_read, vec!["logs/runs/app1"]);
+    }
+}
@@ -7,7 +7,9 @@ use super::sea_orm_active_enums::Visibility;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(
+    Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, schemars::JsonSchema,
+)]
 #[sea_orm(table_name = "App")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
@@ -4,7 +4,9 @@ use super::sea_orm_active_enums::BitType;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(
+    Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
 #[sea_orm(table_name = "Bit")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
@@ -45,6 +47,8 @@ pub enum Relation {
     BitTreeCache,
     #[sea_orm(has_many = "super::meta::Entity")]
     Meta,
+    #[sea_orm(has_one = "super::provider_proxy::Entity")]
+    ProviderProxy,
     #[sea_orm(has_many = "super::swimlane_item::Entity")]
     SwimlaneItem,
 }
@@ -67,6 +71,12 @@ impl Related<super::meta::Entity> for Entity {
     }
 }
 
+impl Related<super::provider_proxy::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProviderProxy.def()
+    }
+}
+
 impl Related<super::swimlane_item::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::SwimlaneItem.def()
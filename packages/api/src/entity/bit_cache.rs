@@ -3,7 +3,9 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(
+    Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
 #[sea_orm(table_name = "BitCache")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
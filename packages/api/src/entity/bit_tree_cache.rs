@@ -3,7 +3,9 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(
+    Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
 #[sea_orm(table_name = "BitTreeCache")]
 pub struct Model {
     #[sea_orm(column_name = "createdAt")]
@@ -4,7 +4,9 @@ use super::sea_orm_active_enums::ExecutionStatus;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(
+    Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
 #[sea_orm(table_name = "ExecutionUsageTracking")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
@@ -3,7 +3,9 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(
+    Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
 #[sea_orm(table_name = "Feedback")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
@@ -23,6 +25,8 @@ pub struct Model {
     pub template_id: Option<String>,
     #[sea_orm(column_name = "eventId", column_type = "Text", nullable)]
     pub event_id: Option<String>,
+    #[sea_orm(column_name = "runId", column_type = "Text", nullable)]
+    pub run_id: Option<String>,
     #[sea_orm(column_type = "Text")]
     pub comment: String,
 }
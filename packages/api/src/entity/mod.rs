@@ -3,6 +3,7 @@
 pub mod prelude;
 
 pub mod app;
+pub mod audit_log;
 pub mod bit;
 pub mod bit_cache;
 pub mod bit_tree_cache;
@@ -21,6 +22,8 @@ pub mod meta;
 pub mod node;
 pub mod pat;
 pub mod profile;
+pub mod provider_invocation;
+pub mod provider_proxy;
 pub mod publication_log;
 pub mod publication_request;
 pub mod role;
@@ -32,3 +35,4 @@ pub mod template;
 pub mod template_profile;
 pub mod transaction;
 pub mod user;
+pub mod user_moderation;
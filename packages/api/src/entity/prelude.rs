@@ -1,6 +1,7 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.11
 
 pub use super::app::Entity as App;
+pub use super::audit_log::Entity as AuditLog;
 pub use super::bit::Entity as Bit;
 pub use super::bit_cache::Entity as BitCache;
 pub use super::bit_tree_cache::Entity as BitTreeCache;
@@ -19,6 +20,8 @@ pub use super::meta::Entity as Meta;
 pub use super::node::Entity as Node;
 pub use super::pat::Entity as Pat;
 pub use super::profile::Entity as Profile;
+pub use super::provider_invocation::Entity as ProviderInvocation;
+pub use super::provider_proxy::Entity as ProviderProxy;
 pub use super::publication_log::Entity as PublicationLog;
 pub use super::publication_request::Entity as PublicationRequest;
 pub use super::role::Entity as Role;
@@ -29,3 +32,4 @@ pub use super::template::Entity as Template;
 pub use super::template_profile::Entity as TemplateProfile;
 pub use super::transaction::Entity as Transaction;
 pub use super::user::Entity as User;
+pub use super::user_moderation::Entity as UserModeration;
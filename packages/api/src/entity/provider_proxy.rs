@@ -4,7 +4,9 @@ use super::sea_orm_active_enums::LlmProvider;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(
+    Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
 #[sea_orm(table_name = "ProviderProxy")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
@@ -18,6 +20,8 @@ pub struct Model {
     pub endpoint: Option<String>,
     #[sea_orm(column_type = "Text", nullable)]
     pub token: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub tier: Option<String>,
     #[sea_orm(column_name = "bitId", column_type = "Text", unique)]
     pub bit_id: String,
     #[sea_orm(column_name = "createdAt")]
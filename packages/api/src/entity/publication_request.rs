@@ -5,7 +5,9 @@ use super::sea_orm_active_enums::Visibility;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(
+    Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
 #[sea_orm(table_name = "PublicationRequest")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
@@ -21,6 +23,10 @@ pub struct Model {
     pub app_id: String,
     #[sea_orm(column_name = "targetVisibility")]
     pub target_visibility: Visibility,
+    #[sea_orm(column_name = "appliedAt")]
+    pub applied_at: Option<DateTime>,
+    #[sea_orm(column_name = "ownerSeen")]
+    pub owner_seen: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
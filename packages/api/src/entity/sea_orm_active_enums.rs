@@ -3,7 +3,51 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "AuditAction")]
+pub enum AuditAction {
+    #[sea_orm(string_value = "ROLE_ASSIGNED")]
+    RoleAssigned,
+    #[sea_orm(string_value = "ROLE_UPSERTED")]
+    RoleUpserted,
+    #[sea_orm(string_value = "ROLE_DELETED")]
+    RoleDeleted,
+    #[sea_orm(string_value = "USER_INVITED")]
+    UserInvited,
+    #[sea_orm(string_value = "USER_REMOVED")]
+    UserRemoved,
+    #[sea_orm(string_value = "JOIN_REQUEST_ACCEPTED")]
+    JoinRequestAccepted,
+    #[sea_orm(string_value = "JOIN_REQUEST_REJECTED")]
+    JoinRequestRejected,
+    #[sea_orm(string_value = "VISIBILITY_CHANGED")]
+    VisibilityChanged,
+    #[sea_orm(string_value = "FILES_DELETED")]
+    FilesDeleted,
+    #[sea_orm(string_value = "BOARD_DELETED")]
+    BoardDeleted,
+}
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "BitType")]
 pub enum BitType {
     #[sea_orm(string_value = "LLM")]
@@ -43,7 +87,17 @@ pub enum BitType {
     #[sea_orm(string_value = "COURSE")]
     Course,
 }
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "Category")]
 pub enum Category {
     #[sea_orm(string_value = "OTHER")]
@@ -89,7 +143,17 @@ pub enum Category {
     #[sea_orm(string_value = "ANIME")]
     Anime,
 }
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "ExecutionMode")]
 pub enum ExecutionMode {
     #[sea_orm(string_value = "ANY")]
@@ -99,7 +163,17 @@ pub enum ExecutionMode {
     #[sea_orm(string_value = "REMOTE")]
     Remote,
 }
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "ExecutionStatus")]
 pub enum ExecutionStatus {
     #[sea_orm(string_value = "DEBUG")]
@@ -113,7 +187,43 @@ pub enum ExecutionStatus {
     #[sea_orm(string_value = "FATAL")]
     Fatal,
 }
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "LLMProvider")]
+pub enum LlmProvider {
+    #[sea_orm(string_value = "OPENAI")]
+    Openai,
+    #[sea_orm(string_value = "AZURE_OPENAI")]
+    AzureOpenai,
+    #[sea_orm(string_value = "ANTHROPIC")]
+    Anthropic,
+    #[sea_orm(string_value = "GOOGLE")]
+    Google,
+    #[sea_orm(string_value = "OPENROUTER")]
+    Openrouter,
+    #[sea_orm(string_value = "CUSTOM")]
+    Custom,
+}
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 #[sea_orm(
     rs_type = "String",
     db_type = "Enum",
@@ -129,7 +239,17 @@ pub enum PublicationRequestStatus {
     #[sea_orm(string_value = "REJECTED")]
     Rejected,
 }
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "Status")]
 pub enum Status {
     #[sea_orm(string_value = "ACTIVE")]
@@ -139,7 +259,17 @@ pub enum Status {
     #[sea_orm(string_value = "ARCHIVED")]
     Archived,
 }
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "SwimlaneSize")]
 pub enum SwimlaneSize {
     #[sea_orm(string_value = "FULLSCREEN")]
@@ -151,7 +281,17 @@ pub enum SwimlaneSize {
     #[sea_orm(string_value = "THIRDSCREEN_MULTIROW")]
     ThirdscreenMultirow,
 }
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "SwimlaneType")]
 pub enum SwimlaneType {
     #[sea_orm(string_value = "PROJECT")]
@@ -165,7 +305,17 @@ pub enum SwimlaneType {
     #[sea_orm(string_value = "QUERY")]
     Query,
 }
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "UserStatus")]
 pub enum UserStatus {
     #[sea_orm(string_value = "ACTIVE")]
@@ -175,7 +325,17 @@ pub enum UserStatus {
     #[sea_orm(string_value = "BANNED")]
     Banned,
 }
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "UserTier")]
 pub enum UserTier {
     #[sea_orm(string_value = "FREE")]
@@ -187,7 +347,17 @@ pub enum UserTier {
     #[sea_orm(string_value = "ENTERPRISE")]
     Enterprise,
 }
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "Visibility")]
 pub enum Visibility {
     #[sea_orm(string_value = "PUBLIC")]
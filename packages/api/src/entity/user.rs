@@ -5,7 +5,9 @@ use super::sea_orm_active_enums::UserTier;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(
+    Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
 #[sea_orm(table_name = "User")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
@@ -48,6 +50,10 @@ pub struct Model {
     pub preferred_username: Option<String>,
     #[sea_orm(column_type = "Text", nullable)]
     pub avatar: Option<String>,
+    #[sea_orm(column_name = "statusUntil")]
+    pub status_until: Option<DateTime>,
+    #[sea_orm(column_name = "statusReason", column_type = "Text", nullable)]
+    pub status_reason: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -70,6 +76,8 @@ pub enum Relation {
     Pat,
     #[sea_orm(has_many = "super::profile::Entity")]
     Profile,
+    #[sea_orm(has_many = "super::provider_invocation::Entity")]
+    ProviderInvocation,
     #[sea_orm(has_many = "super::publication_log::Entity")]
     PublicationLog,
     #[sea_orm(has_many = "super::publication_request::Entity")]
@@ -132,6 +140,12 @@ impl Related<super::profile::Entity> for Entity {
     }
 }
 
+impl Related<super::provider_invocation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProviderInvocation.def()
+    }
+}
+
 impl Related<super::publication_log::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::PublicationLog.def()
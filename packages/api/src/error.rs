@@ -2,6 +2,9 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
 };
+use flow_like::flow::event::contract::PayloadValidationError;
+
+use crate::limits::LimitExceeded;
 
 pub struct InternalError(flow_like_types::Error);
 pub struct AuthorizationError(flow_like_types::Error);
@@ -13,6 +16,7 @@ pub enum ApiError {
     NotFound,
     Forbidden,
     BadRequest(String),
+    LimitExceeded(LimitExceeded),
 }
 
 impl IntoResponse for ApiError {
@@ -23,6 +27,7 @@ impl IntoResponse for ApiError {
             ApiError::NotFound => (StatusCode::NOT_FOUND, "Not Found").into_response(),
             ApiError::Forbidden => (StatusCode::FORBIDDEN, "Forbidden").into_response(),
             ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg).into_response(),
+            ApiError::LimitExceeded(limit) => limit.into_response(),
         }
     }
 }
@@ -33,6 +38,9 @@ where
 {
     fn from(err: E) -> Self {
         let err = err.into();
+        if let Some(validation) = err.downcast_ref::<PayloadValidationError>() {
+            return ApiError::BadRequest(validation.to_string());
+        }
         tracing::error!("Internal error: {:?}", err);
         ApiError::InternalError(InternalError(err))
     }
@@ -0,0 +1,402 @@
+use std::{collections::HashMap, convert::Infallible, sync::Arc, time::Duration};
+
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{
+        IntoResponse, Response,
+        sse::{Event as SseEvent, KeepAlive, Sse},
+    },
+};
+use flow_like::{
+    flow::{
+        event::{Event, chain::ChainedRun},
+        execution::{InternalRun, LogMeta, RunPayload, RunStatus},
+    },
+    flow_like_storage::Path,
+    profile::Profile,
+    state::RunData,
+};
+use flow_like_types::{
+    Value, anyhow,
+    intercom::{BufferedInterComHandler, InterComEvent},
+    sync::mpsc,
+    tokio,
+    tokio_util::sync::CancellationToken,
+};
+use futures_util::{StreamExt, future::BoxFuture, stream};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{limits::ExecutionPermit, state::AppState};
+
+/// Has to stay below the time to live of `State::run_cache`
+const RUN_CACHE_REFRESH: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionMode {
+    /// Streams the run's events as Server-Sent Events and finishes with the `LogMeta`
+    #[default]
+    Stream,
+    /// Returns the run id immediately, the result can be polled
+    Async,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct ExecutionQuery {
+    #[serde(default)]
+    pub mode: ExecutionMode,
+}
+
+/// Status of a run started in `ExecutionMode::Async`, kept in `State::run_cache`.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct AsyncRunStatus {
+    pub run_id: String,
+    pub app_id: String,
+    pub board_id: String,
+    pub event_id: Option<String>,
+    pub status: RunStatus,
+    pub meta: Option<LogMeta>,
+}
+
+pub enum RunUpdate {
+    Events(Vec<InterComEvent>),
+    Finished {
+        status: RunStatus,
+        meta: Option<LogMeta>,
+        /// Non-secret board variables at the end of the run
+        outputs: HashMap<String, Value>,
+    },
+}
+
+pub struct RunRequest {
+    pub app_id: String,
+    pub board_id: String,
+    pub version: Option<(u32, u32, u32)>,
+    pub event: Option<Event>,
+    pub payload: RunPayload,
+    pub stream_state: bool,
+    /// Concurrency slot of the caller, released and accounted once the run finishes
+    pub permit: Option<ExecutionPermit>,
+    /// Set if an `EventChain` of another run started this run
+    pub chained_from: Option<ChainedRun>,
+}
+
+pub struct StartedRun {
+    pub run_id: String,
+    pub app_id: String,
+    pub board_id: String,
+    pub event_id: Option<String>,
+    pub updates: mpsc::UnboundedReceiver<RunUpdate>,
+}
+
+/// Builds an `InternalRun` from the cached master `FlowLikeState` and executes it in the background.
+/// The run keeps going if the receiver of the updates is dropped (e.g. the client disconnects).
+#[tracing::instrument(name = "start_run", skip(state, request))]
+pub async fn start_run(
+    state: &AppState,
+    sub: &str,
+    request: RunRequest,
+) -> flow_like_types::Result<StartedRun> {
+    let RunRequest {
+        app_id,
+        board_id,
+        version,
+        event,
+        payload,
+        stream_state,
+        permit,
+        chained_from,
+    } = request;
+
+    let board = state
+        .master_board(sub, &app_id, &board_id, state, version)
+        .await?;
+    let flow_state = board
+        .app_state
+        .clone()
+        .ok_or_else(|| anyhow!("Board {} has no state attached", board_id))?;
+
+    if let Some(event) = &event {
+        event.validate_payload(&board, &payload)?;
+    }
+
+    let credentials = state.master_credentials().await?;
+    let (sender, updates) = mpsc::unbounded_channel();
+
+    let event_sender = sender.clone();
+    let buffered_sender = BufferedInterComHandler::new(
+        Arc::new(move |events| {
+            let sender = event_sender.clone();
+            Box::pin(async move {
+                let _ = sender.send(RunUpdate::Events(events));
+                Ok(())
+            })
+        }),
+        Some(50),
+        Some(100),
+        Some(true),
+    );
+
+    let mut internal_run = InternalRun::new(
+        &app_id,
+        Arc::new(board),
+        event.clone(),
+        &flow_state,
+        &Profile::default(),
+        &payload,
+        Some(sub.to_string()),
+        stream_state,
+        buffered_sender.into_callback(),
+        Some(credentials.into_shared_credentials()),
+    )
+    .await?;
+    if let Some(chained) = &chained_from {
+        internal_run.set_chained_from(chained).await;
+    }
+    let run_id = internal_run.run.lock().await.id.clone();
+
+    let _ = sender.send(RunUpdate::Events(vec![InterComEvent::with_type(
+        "run_initiated",
+        flow_like_types::json::json!({ "run_id": run_id.clone() }),
+    )]));
+
+    let event_id = event.as_ref().map(|event| event.id.clone());
+    let cancellation_token = CancellationToken::new();
+    let run_data = RunData::new(
+        &board_id,
+        &payload.id,
+        event_id.clone(),
+        cancellation_token.clone(),
+    );
+    flow_state.lock().await.register_run(&run_id, run_data);
+
+    let task_run_id = run_id.clone();
+    let task_app_id = app_id.clone();
+    let task_board_id = board_id.clone();
+    let task_state = state.clone();
+    let task_sub = sub.to_string();
+    tokio::spawn(async move {
+        let run_id = task_run_id;
+        let meta = tokio::select! {
+            meta = internal_run.execute(flow_state.clone()) => meta,
+            _ = cancellation_token.cancelled() => {
+                match tokio::time::timeout(Duration::from_secs(30), internal_run.flush_logs_cancelled()).await {
+                    Ok(Ok(meta)) => meta,
+                    Ok(Err(e)) => {
+                        tracing::error!("Error flushing logs of cancelled run {}: {:?}", run_id, e);
+                        None
+                    }
+                    Err(_) => {
+                        tracing::error!("Timeout flushing logs of cancelled run {}", run_id);
+                        None
+                    }
+                }
+            }
+        };
+
+        if let Err(e) = buffered_sender.flush().await {
+            tracing::error!("Error flushing events of run {}: {:?}", run_id, e);
+        }
+
+        if let Some(meta) = &meta {
+            if let Err(e) = flush_meta(&flow_state, &task_app_id, &task_board_id, meta).await {
+                tracing::error!("Error persisting run {}: {:?}", run_id, e);
+            }
+
+            if let Some(permit) = &permit {
+                if let Err(e) = permit.record(&task_app_id, meta).await {
+                    tracing::error!("Error recording usage of run {}: {:?}", run_id, e);
+                }
+            }
+
+            for chained in internal_run.chained_runs(meta).await {
+                tokio::spawn(start_chained_run(
+                    task_state.clone(),
+                    task_sub.clone(),
+                    task_app_id.clone(),
+                    chained,
+                    permit.as_ref().map(ExecutionPermit::follow_up),
+                ));
+            }
+        }
+        drop(permit);
+
+        let _ = flow_state.lock().await.remove_and_cancel_run(&run_id);
+
+        let status = internal_run.get_status().await;
+        let outputs = internal_run.get_outputs().await;
+        let _ = sender.send(RunUpdate::Finished {
+            status,
+            meta,
+            outputs,
+        });
+    });
+
+    Ok(StartedRun {
+        run_id,
+        app_id,
+        board_id,
+        event_id,
+        updates,
+    })
+}
+
+/// Starts the run of an `EventChain` against the released version of the target event.
+/// Nobody listens to its updates, the result ends up in the run logs like any other run.
+fn start_chained_run(
+    state: AppState,
+    sub: String,
+    app_id: String,
+    chained: ChainedRun,
+    permit: Option<ExecutionPermit>,
+) -> BoxFuture<'static, ()> {
+    Box::pin(async move {
+        let event_id = chained.event_id.clone();
+        let result: flow_like_types::Result<()> = async {
+            let app = state.master_app(&sub, &app_id, &state).await?;
+            let event = app.get_released_event(&chained.event_id).await?;
+            if !event.active {
+                tracing::warn!("Skipping chained event {}, it is not active", event.id);
+                return Ok(());
+            }
+
+            start_run(
+                &state,
+                &sub,
+                RunRequest {
+                    app_id: app_id.clone(),
+                    board_id: event.board_id.clone(),
+                    version: event.board_version,
+                    payload: RunPayload {
+                        id: event.node_id.clone(),
+                        payload: chained.payload.payload.clone(),
+                    },
+                    event: Some(event),
+                    stream_state: false,
+                    permit,
+                    chained_from: Some(chained),
+                },
+            )
+            .await
+            .map(|_| ())
+        }
+        .await;
+
+        if let Err(e) = result {
+            tracing::error!("Error executing chained event {}: {:?}", event_id, e);
+        }
+    })
+}
+
+async fn flush_meta(
+    flow_state: &Arc<flow_like_types::sync::Mutex<flow_like::state::FlowLikeState>>,
+    app_id: &str,
+    board_id: &str,
+    meta: &LogMeta,
+) -> flow_like_types::Result<()> {
+    let db = {
+        let guard = flow_state.lock().await;
+        let guard = guard.config.read().await;
+        guard.callbacks.build_logs_database.clone()
+    };
+    let db_fn = db
+        .as_ref()
+        .ok_or_else(|| anyhow!("No log database configured"))?;
+    let base_path = Path::from("runs").child(app_id).child(board_id);
+    let db = db_fn(base_path.clone())
+        .execute()
+        .await
+        .map_err(|e| anyhow!("Failed to open database: {}, {:?}", base_path, e))?;
+    meta.flush(db).await
+}
+
+/// Answers with a Server-Sent Events stream or, in async mode, with the run id to poll.
+pub fn respond(state: &AppState, run: StartedRun, mode: ExecutionMode) -> Response {
+    match mode {
+        ExecutionMode::Stream => stream_run(run).into_response(),
+        ExecutionMode::Async => {
+            let status = track_run(state, run);
+            (StatusCode::ACCEPTED, Json(status)).into_response()
+        }
+    }
+}
+
+fn stream_run(
+    run: StartedRun,
+) -> Sse<impl futures_util::Stream<Item = Result<SseEvent, Infallible>>> {
+    let stream = stream::unfold(Some(run.updates), |updates| async move {
+        let mut updates = updates?;
+        match updates.recv().await? {
+            RunUpdate::Events(events) => {
+                let events = events
+                    .into_iter()
+                    .map(|event| {
+                        SseEvent::default()
+                            .event(event.event_type.clone())
+                            .data(flow_like_types::json::to_string(&event).unwrap_or_default())
+                    })
+                    .collect::<Vec<_>>();
+                Some((events, Some(updates)))
+            }
+            RunUpdate::Finished { meta, .. } => {
+                let finished = SseEvent::default()
+                    .event("run_finished")
+                    .data(flow_like_types::json::to_string(&meta).unwrap_or_default());
+                Some((vec![finished], None))
+            }
+        }
+    })
+    .flat_map(|events| stream::iter(events.into_iter().map(Ok)));
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+fn track_run(state: &AppState, mut run: StartedRun) -> AsyncRunStatus {
+    let status = AsyncRunStatus {
+        run_id: run.run_id.clone(),
+        app_id: run.app_id.clone(),
+        board_id: run.board_id.clone(),
+        event_id: run.event_id.clone(),
+        status: RunStatus::Running,
+        meta: None,
+    };
+    state.run_cache.insert(run.run_id.clone(), status.clone());
+
+    let state = state.clone();
+    let running = status.clone();
+    tokio::spawn(async move {
+        // Inserting again restarts the time to live, runs longer than it would disappear otherwise
+        let mut refresh = tokio::time::interval(RUN_CACHE_REFRESH);
+        refresh.tick().await;
+        loop {
+            tokio::select! {
+                update = run.updates.recv() => match update {
+                    Some(RunUpdate::Finished { status, meta, .. }) => {
+                        let mut finished = running;
+                        finished.status = status;
+                        finished.meta = meta;
+                        state.run_cache.insert(run.run_id.clone(), finished);
+                        return;
+                    }
+                    Some(RunUpdate::Events(_)) => {}
+                    None => return,
+                },
+                _ = refresh.tick() => {
+                    state.run_cache.insert(run.run_id.clone(), running.clone());
+                }
+            }
+        }
+    });
+
+    status
+}
+
+/// Looks up an async run, only runs of the given app are visible.
+pub fn get_async_run(state: &AppState, app_id: &str, run_id: &str) -> Option<AsyncRunStatus> {
+    state
+        .run_cache
+        .get(run_id)
+        .filter(|status| status.app_id == app_id)
+}
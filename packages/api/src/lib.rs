@@ -3,6 +3,7 @@ use std::sync::Arc;
 use axum::{Json, Router, middleware::from_fn_with_state, routing::get};
 use error::InternalError;
 use flow_like::hub::Hub;
+use limits::rate_limit_middleware;
 use middleware::jwt::jwt_middleware;
 use state::{AppState, State};
 use tower::ServiceBuilder;
@@ -16,8 +17,13 @@ pub mod entity;
 mod middleware;
 mod routes;
 
+pub mod audit;
 pub mod credentials;
 pub mod error;
+pub mod execution;
+pub mod limits;
+pub mod mcp;
+pub mod openapi;
 pub mod permission;
 pub mod state;
 pub mod user_management;
@@ -42,9 +48,15 @@ pub fn construct_router(state: Arc<State>) -> Router {
         .nest("/store", routes::store::routes())
         .nest("/auth", routes::auth::routes())
         .nest("/admin", routes::admin::routes())
+        .nest("/llm", routes::llm::routes())
         .with_state(state.clone())
         .route("/version", get(|| async { "0.0.0" }))
+        .route("/openapi.json", get(openapi::openapi))
+        // Layers run bottom to top, the rate limit needs the user set by the JWT middleware
+        .layer(from_fn_with_state(state.clone(), rate_limit_middleware))
         .layer(from_fn_with_state(state.clone(), jwt_middleware))
+        // Authenticates on its own, MCP clients send PATs or API keys as bearer tokens
+        .merge(mcp::routes(state.clone()))
         .layer(CorsLayer::permissive())
         .layer(
             ServiceBuilder::new()
@@ -0,0 +1,547 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use flow_like::{flow::execution::LogMeta, hub::UserTier};
+use flow_like_types::{anyhow, create_id, sync::DashMap};
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, FromQueryResult, PaginatorTrait, QueryFilter,
+    QuerySelect,
+    sea_query::{Alias, Expr, Func},
+};
+use serde::Serialize;
+
+use crate::{
+    entity::{
+        app, execution_usage_tracking, llm_usage_tracking, membership,
+        sea_orm_active_enums::{self, ExecutionStatus},
+        user,
+    },
+    error::ApiError,
+    middleware::jwt::AppUser,
+    state::AppState,
+};
+
+const REQUEST_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitKind {
+    Requests,
+    ConcurrentExecutions,
+    ExecutionMinutes,
+    LlmCalls,
+    LlmTokens,
+    Storage,
+}
+
+/// Returned as `429 Too Many Requests` with this struct as JSON body.
+#[derive(Debug, Clone, Serialize)]
+pub struct LimitExceeded {
+    pub kind: LimitKind,
+    pub limit: i64,
+    pub used: i64,
+    /// Seconds until the limit resets, `None` if it does not reset by itself (e.g. storage)
+    pub retry_after: Option<u64>,
+}
+
+impl IntoResponse for LimitExceeded {
+    fn into_response(self) -> Response {
+        let mut headers = HeaderMap::new();
+        if let Some(retry_after) = self.retry_after {
+            headers.insert("Retry-After", HeaderValue::from(retry_after));
+        }
+        (
+            axum::http::StatusCode::TOO_MANY_REQUESTS,
+            headers,
+            axum::Json(self),
+        )
+            .into_response()
+    }
+}
+
+/// State of a rate limit window after a hit.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitStatus {
+    pub allowed: bool,
+    pub limit: u64,
+    pub remaining: u64,
+    /// Seconds until the window resets
+    pub reset: u64,
+}
+
+impl RateLimitStatus {
+    pub fn apply_headers(&self, headers: &mut HeaderMap) {
+        headers.insert("RateLimit-Limit", HeaderValue::from(self.limit));
+        headers.insert("RateLimit-Remaining", HeaderValue::from(self.remaining));
+        headers.insert("RateLimit-Reset", HeaderValue::from(self.reset));
+    }
+}
+
+/// Counters behind the limits. Multi-node deployments need a shared implementation.
+pub trait RateLimitStore: Send + Sync {
+    /// Counts a hit against a fixed window of `window` length.
+    fn hit(&self, key: &str, limit: u64, window: Duration) -> RateLimitStatus;
+    /// Takes a concurrency slot if less than `limit` are taken.
+    fn acquire(&self, key: &str, limit: u64) -> bool;
+    fn release(&self, key: &str);
+}
+
+/// In-process store for single-node deployments.
+#[derive(Default)]
+pub struct InMemoryRateLimitStore {
+    windows: DashMap<String, (u64, u64)>,
+    running: DashMap<String, u64>,
+}
+
+impl InMemoryRateLimitStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl RateLimitStore for InMemoryRateLimitStore {
+    fn hit(&self, key: &str, limit: u64, window: Duration) -> RateLimitStatus {
+        let window = window.as_secs().max(1);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let index = now / window;
+        let reset = (index + 1) * window - now;
+
+        // Drop counters of past windows from time to time
+        if self.windows.len() > 10_000 {
+            self.windows
+                .retain(|_, (window_index, _)| *window_index == index);
+        }
+
+        let mut entry = self.windows.entry(key.to_string()).or_insert((index, 0));
+        if entry.0 != index {
+            *entry = (index, 0);
+        }
+
+        let allowed = entry.1 < limit;
+        if allowed {
+            entry.1 += 1;
+        }
+
+        RateLimitStatus {
+            allowed,
+            limit,
+            remaining: limit.saturating_sub(entry.1),
+            reset,
+        }
+    }
+
+    fn acquire(&self, key: &str, limit: u64) -> bool {
+        let mut running = self.running.entry(key.to_string()).or_insert(0);
+        if *running >= limit {
+            return false;
+        }
+        *running += 1;
+        true
+    }
+
+    fn release(&self, key: &str) {
+        let remove = match self.running.get_mut(key) {
+            Some(mut running) => {
+                *running = running.saturating_sub(1);
+                *running == 0
+            }
+            None => false,
+        };
+        if remove {
+            self.running.remove_if(key, |_, running| *running == 0);
+        }
+    }
+}
+
+/// Who a request is accounted to. API keys are accounted to the owner of their app.
+#[derive(Debug, Clone)]
+pub struct LimitSubject {
+    /// Key of the rate limit counters
+    pub key: String,
+    pub user_id: String,
+    pub tier: Arc<UserTier>,
+}
+
+pub fn platform_tier(state: &AppState, tier: &sea_orm_active_enums::UserTier) -> Option<UserTier> {
+    let name = match tier {
+        sea_orm_active_enums::UserTier::Free => "FREE",
+        sea_orm_active_enums::UserTier::Premium => "PREMIUM",
+        sea_orm_active_enums::UserTier::Pro => "PRO",
+        sea_orm_active_enums::UserTier::Enterprise => "ENTERPRISE",
+    };
+    state.platform_config.tiers.get(name).cloned()
+}
+
+pub async fn limit_subject(
+    state: &AppState,
+    user: &AppUser,
+) -> flow_like_types::Result<Option<LimitSubject>> {
+    let (key, user_id) = match user {
+        AppUser::OpenID(_) | AppUser::PAT(_) => {
+            let sub = user.sub()?;
+            (format!("user:{}", sub), Some(sub))
+        }
+        AppUser::APIKey(api_key) => (format!("key:{}", api_key.key_id), None),
+        AppUser::Unauthorized => return Ok(None),
+    };
+
+    if let Some(subject) = state.limit_cache.get(&key) {
+        return Ok(Some(subject));
+    }
+
+    let user_id = match (user_id, user) {
+        (Some(user_id), _) => user_id,
+        (None, AppUser::APIKey(api_key)) => app_owner(&state.db, &api_key.app_id).await?,
+        _ => return Ok(None),
+    };
+
+    // Users get their row on the first `GET /user/info`, until then they are on the default tier
+    let user = user::Entity::find_by_id(&user_id).one(&state.db).await?;
+    let tier = user
+        .as_ref()
+        .map(|user| user.tier.clone())
+        .unwrap_or(sea_orm_active_enums::UserTier::Free);
+    let tier = platform_tier(state, &tier).ok_or_else(|| anyhow!("Tier not found"))?;
+
+    let subject = LimitSubject {
+        key: key.clone(),
+        user_id,
+        tier: Arc::new(tier),
+    };
+    if user.is_some() {
+        state.limit_cache.insert(key, subject.clone());
+    }
+    Ok(Some(subject))
+}
+
+async fn app_owner(db: &DatabaseConnection, app_id: &str) -> flow_like_types::Result<String> {
+    let app = app::Entity::find_by_id(app_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| anyhow!("App not found"))?;
+    let owner_role = app
+        .owner_role_id
+        .ok_or_else(|| anyhow!("App {} has no owner", app_id))?;
+    let owner = membership::Entity::find()
+        .filter(membership::Column::RoleId.eq(owner_role))
+        .one(db)
+        .await?
+        .ok_or_else(|| anyhow!("App {} has no owner", app_id))?;
+    Ok(owner.user_id)
+}
+
+/// Applies `max_requests_per_minute` of the caller's tier and sets the `RateLimit-*` headers.
+pub async fn rate_limit_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let Some(user) = request.extensions().get::<AppUser>().cloned() else {
+        return Ok(next.run(request).await);
+    };
+
+    let Some(subject) = limit_subject(&state, &user).await? else {
+        return Ok(next.run(request).await);
+    };
+
+    let Ok(limit) = u64::try_from(subject.tier.max_requests_per_minute) else {
+        return Ok(next.run(request).await);
+    };
+
+    let status = state
+        .rate_limits
+        .hit(&format!("requests:{}", subject.key), limit, REQUEST_WINDOW);
+
+    let mut response = if status.allowed {
+        next.run(request).await
+    } else {
+        LimitExceeded {
+            kind: LimitKind::Requests,
+            limit: limit as i64,
+            used: limit as i64,
+            retry_after: Some(status.reset),
+        }
+        .into_response()
+    };
+
+    status.apply_headers(response.headers_mut());
+    Ok(response)
+}
+
+/// Holds a concurrency slot of the subject while a run executes.
+pub struct ExecutionPermit {
+    state: AppState,
+    key: Option<String>,
+    user_id: String,
+}
+
+impl ExecutionPermit {
+    /// Accounts runs started by this run (e.g. event chains) to the same user, without taking another slot.
+    pub fn follow_up(&self) -> ExecutionPermit {
+        ExecutionPermit {
+            state: self.state.clone(),
+            key: None,
+            user_id: self.user_id.clone(),
+        }
+    }
+
+    /// Writes the run to `execution_usage_tracking`, the base of `max_execution_minutes`.
+    pub async fn record(&self, app_id: &str, meta: &LogMeta) -> flow_like_types::Result<()> {
+        let now = chrono::Utc::now().naive_utc();
+        let status = match meta.log_level {
+            0 => ExecutionStatus::Debug,
+            1 => ExecutionStatus::Info,
+            2 => ExecutionStatus::Warn,
+            3 => ExecutionStatus::Error,
+            _ => ExecutionStatus::Fatal,
+        };
+        let usage = execution_usage_tracking::Model {
+            id: create_id(),
+            instance: None,
+            board_id: meta.board_id.clone(),
+            node_id: meta.node_id.clone(),
+            version: meta.version.clone(),
+            microseconds: meta.end.saturating_sub(meta.start) as i64,
+            status,
+            user_id: Some(self.user_id.clone()),
+            app_id: Some(app_id.to_string()),
+            created_at: now,
+            updated_at: now,
+        };
+        let usage: execution_usage_tracking::ActiveModel = usage.into();
+        execution_usage_tracking::Entity::insert(usage)
+            .exec(&self.state.db)
+            .await?;
+        Ok(())
+    }
+}
+
+impl Drop for ExecutionPermit {
+    fn drop(&mut self) {
+        if let Some(key) = &self.key {
+            self.state.rate_limits.release(key);
+        }
+    }
+}
+
+#[derive(FromQueryResult)]
+struct Total {
+    total: Option<i64>,
+}
+
+#[derive(FromQueryResult)]
+struct TokenTotal {
+    token_in: Option<i64>,
+    token_out: Option<i64>,
+}
+
+/// Checks `max_execution_minutes` and takes a `max_concurrent_executions` slot.
+pub async fn acquire_execution(
+    state: &AppState,
+    user: &AppUser,
+) -> Result<Option<ExecutionPermit>, ApiError> {
+    let Some(subject) = limit_subject(state, user).await? else {
+        return Ok(None);
+    };
+    let tier = &subject.tier;
+
+    if tier.max_execution_minutes >= 0 {
+        let used = execution_usage_tracking::Entity::find()
+            .select_only()
+            // SUM(bigint) is NUMERIC on Postgres, which does not decode into i64
+            .column_as(
+                Func::cast_as(
+                    Func::sum(Expr::col(execution_usage_tracking::Column::Microseconds)),
+                    Alias::new("BIGINT"),
+                ),
+                "total",
+            )
+            .filter(execution_usage_tracking::Column::UserId.eq(&subject.user_id))
+            .filter(execution_usage_tracking::Column::CreatedAt.gte(month_start()?))
+            .into_model::<Total>()
+            .one(&state.db)
+            .await?
+            .and_then(|total| total.total)
+            .unwrap_or(0);
+        let used_minutes = used / 60_000_000;
+
+        if used_minutes >= tier.max_execution_minutes {
+            return Err(monthly_limit(
+                LimitKind::ExecutionMinutes,
+                tier.max_execution_minutes,
+                used_minutes,
+            ));
+        }
+    }
+
+    let key = match u64::try_from(tier.max_concurrent_executions) {
+        Ok(limit) => {
+            let key = format!("executions:{}", subject.key);
+            if !state.rate_limits.acquire(&key, limit) {
+                return Err(ApiError::LimitExceeded(LimitExceeded {
+                    kind: LimitKind::ConcurrentExecutions,
+                    limit: limit as i64,
+                    used: limit as i64,
+                    retry_after: None,
+                }));
+            }
+            Some(key)
+        }
+        Err(_) => None,
+    };
+
+    Ok(Some(ExecutionPermit {
+        state: state.clone(),
+        key,
+        user_id: subject.user_id.clone(),
+    }))
+}
+
+/// Checks `max_llm_calls` and `max_llm_tokens` of the current month.
+pub async fn ensure_llm_quota(
+    state: &AppState,
+    user_id: &str,
+    tier: &UserTier,
+) -> Result<(), ApiError> {
+    let month_start = month_start()?;
+
+    if tier.max_llm_calls >= 0 {
+        let calls = llm_usage_tracking::Entity::find()
+            .filter(llm_usage_tracking::Column::UserId.eq(user_id))
+            .filter(llm_usage_tracking::Column::CreatedAt.gte(month_start))
+            .count(&state.db)
+            .await? as i64;
+
+        if calls >= tier.max_llm_calls as i64 {
+            return Err(monthly_limit(
+                LimitKind::LlmCalls,
+                tier.max_llm_calls as i64,
+                calls,
+            ));
+        }
+    }
+
+    if tier.max_llm_tokens >= 0 {
+        let tokens = llm_usage_tracking::Entity::find()
+            .select_only()
+            .column_as(llm_usage_tracking::Column::TokenIn.sum(), "token_in")
+            .column_as(llm_usage_tracking::Column::TokenOut.sum(), "token_out")
+            .filter(llm_usage_tracking::Column::UserId.eq(user_id))
+            .filter(llm_usage_tracking::Column::CreatedAt.gte(month_start))
+            .into_model::<TokenTotal>()
+            .one(&state.db)
+            .await?
+            .map(|total| total.token_in.unwrap_or(0) + total.token_out.unwrap_or(0))
+            .unwrap_or(0);
+
+        if tokens >= tier.max_llm_tokens {
+            return Err(monthly_limit(
+                LimitKind::LlmTokens,
+                tier.max_llm_tokens,
+                tokens,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks `max_total_size` before handing out upload URLs.
+pub async fn ensure_storage(state: &AppState, user: &AppUser) -> Result<(), ApiError> {
+    let Some(subject) = limit_subject(state, user).await? else {
+        return Ok(());
+    };
+
+    if subject.tier.max_total_size < 0 {
+        return Ok(());
+    }
+
+    // Users without a row yet (see `limit_subject`) have not stored anything
+    let total_size = user::Entity::find_by_id(&subject.user_id)
+        .select_only()
+        .column(user::Column::TotalSize)
+        .into_tuple::<i64>()
+        .one(&state.db)
+        .await?
+        .unwrap_or(0);
+
+    if total_size >= subject.tier.max_total_size {
+        return Err(ApiError::LimitExceeded(LimitExceeded {
+            kind: LimitKind::Storage,
+            limit: subject.tier.max_total_size,
+            used: total_size,
+            retry_after: None,
+        }));
+    }
+
+    Ok(())
+}
+
+fn monthly_limit(kind: LimitKind, limit: i64, used: i64) -> ApiError {
+    let now = chrono::Utc::now().naive_utc();
+    let retry_after = next_month_start(now).map(|next| (next - now).num_seconds().max(0) as u64);
+    ApiError::LimitExceeded(LimitExceeded {
+        kind,
+        limit,
+        used,
+        retry_after,
+    })
+}
+
+pub(crate) fn month_start() -> flow_like_types::Result<NaiveDateTime> {
+    let today = chrono::Utc::now().date_naive();
+    NaiveDate::from_ymd_opt(today.year(), today.month(), 1)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .ok_or_else(|| anyhow!("Invalid date"))
+}
+
+fn next_month_start(now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let (year, month) = match now.month() {
+        12 => (now.year() + 1, 1),
+        month => (now.year(), month + 1),
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_requests_per_window() {
+        let store = InMemoryRateLimitStore::new();
+        let window = Duration::from_secs(3600);
+
+        assert!(store.hit("user:a", 2, window).allowed);
+        let status = store.hit("user:a", 2, window);
+        assert!(status.allowed);
+        assert_eq!(status.remaining, 0);
+
+        let status = store.hit("user:a", 2, window);
+        assert!(!status.allowed);
+        assert!(status.reset <= 3600);
+        assert!(store.hit("user:b", 2, window).allowed);
+    }
+
+    #[test]
+    fn limits_concurrency() {
+        let store = InMemoryRateLimitStore::new();
+
+        assert!(store.acquire("runs", 1));
+        assert!(!store.acquire("runs", 1));
+        store.release("runs");
+        assert!(store.acquire("runs", 1));
+    }
+}
@@ -0,0 +1,478 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    Router,
+    extract::{Path, Request, State},
+    http::{HeaderMap, request::Parts},
+    middleware::{Next, from_fn_with_state},
+    response::Response,
+};
+use flow_like::{
+    app::App,
+    flow::{
+        event::{Event, contract::EventContract},
+        execution::{RunPayload, RunStatus},
+    },
+};
+use flow_like_types::{
+    Value, anyhow,
+    json::{Map, json},
+};
+use hyper::header::AUTHORIZATION;
+use rmcp::{
+    ErrorData, RoleServer, ServerHandler, ServiceExt,
+    model::{
+        CallToolRequestParam, CallToolResult, Content, Implementation, JsonObject, ListToolsResult,
+        PaginatedRequestParam, ServerCapabilities, ServerInfo, Tool,
+    },
+    service::RequestContext,
+    transport::{
+        StreamableHttpServerConfig, StreamableHttpService, stdio,
+        streamable_http_server::session::local::LocalSessionManager,
+    },
+};
+
+use crate::{
+    ensure_permission,
+    error::{ApiError, AuthorizationError},
+    execution::{RunRequest, RunUpdate, start_run},
+    limits::{acquire_execution, rate_limit_middleware},
+    middleware::jwt::{AppUser, api_key_user, is_blocked, pat_user},
+    permission::role_permission::RolePermissions,
+    state::AppState,
+};
+
+/// The app whose events are offered and the user calling them.
+#[derive(Debug, Clone)]
+pub struct McpScope {
+    pub app_id: String,
+    pub user: AppUser,
+}
+
+/// Offers the released, active events of an app as MCP tools.
+/// Calls run through `start_run`, so permissions, limits and run logs apply like for HTTP executions.
+#[derive(Clone)]
+pub struct AppMcpServer {
+    state: AppState,
+    scope: Option<McpScope>,
+}
+
+impl AppMcpServer {
+    /// Serves a single app for a single user, e.g. over stdio.
+    pub fn new(state: AppState, scope: McpScope) -> Self {
+        Self {
+            state,
+            scope: Some(scope),
+        }
+    }
+
+    /// Reads the scope of every request from the extensions set by `mcp_auth_middleware`.
+    fn per_request(state: AppState) -> Self {
+        Self { state, scope: None }
+    }
+
+    fn scope(&self, context: &RequestContext<RoleServer>) -> Result<McpScope, ErrorData> {
+        if let Some(scope) = &self.scope {
+            return Ok(scope.clone());
+        }
+
+        context
+            .extensions
+            .get::<Parts>()
+            .and_then(|parts| parts.extensions.get::<McpScope>())
+            .cloned()
+            .ok_or_else(|| ErrorData::invalid_request("Unauthorized", None))
+    }
+}
+
+impl ServerHandler for AppMcpServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            server_info: Implementation {
+                name: "flow-like".to_string(),
+                title: Some("Flow-Like".to_string()),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                icons: None,
+                website_url: None,
+            },
+            instructions: Some(
+                "Every tool runs an event of a Flow-Like app and returns the board variables of the finished run"
+                    .to_string(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        let scope = self.scope(&context)?;
+        let tools = list_event_tools(&self.state, &scope)
+            .await
+            .map_err(to_error_data)?;
+        Ok(ListToolsResult::with_all_items(tools))
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let scope = self.scope(&context)?;
+        call_event_tool(&self.state, &scope, request)
+            .await
+            .map_err(to_error_data)
+    }
+}
+
+#[tracing::instrument(name = "MCP tools/list", skip(state, scope), fields(app_id = %scope.app_id))]
+async fn list_event_tools(state: &AppState, scope: &McpScope) -> Result<Vec<Tool>, ApiError> {
+    let permission = ensure_permission!(
+        scope.user,
+        &scope.app_id,
+        state,
+        RolePermissions::ExecuteEvents
+    );
+    let sub = permission.identifier();
+
+    let app = state.master_app(&sub, &scope.app_id, state).await?;
+    let mut tools = Vec::with_capacity(app.events.len());
+    for event_id in &app.events {
+        let (event, contract) = match released_event(&app, event_id).await {
+            Ok(released) => released,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load event {} of app {}: {:?}",
+                    event_id,
+                    scope.app_id,
+                    e
+                );
+                continue;
+            }
+        };
+
+        if event.active {
+            tools.push(event_tool(&event, &contract));
+        }
+    }
+
+    Ok(tools)
+}
+
+#[tracing::instrument(name = "MCP tools/call", skip(state, scope, request), fields(app_id = %scope.app_id, event_id = %request.name))]
+async fn call_event_tool(
+    state: &AppState,
+    scope: &McpScope,
+    request: CallToolRequestParam,
+) -> Result<CallToolResult, ApiError> {
+    let permission = ensure_permission!(
+        scope.user,
+        &scope.app_id,
+        state,
+        RolePermissions::ExecuteEvents
+    );
+    // API keys have no sub, their runs are attributed to the technical user
+    let sub = permission.identifier();
+
+    let app = state.master_app(&sub, &scope.app_id, state).await?;
+    if !app.events.iter().any(|event_id| *event_id == request.name) {
+        return Err(ApiError::NotFound);
+    }
+
+    let (event, contract) = released_event(&app, &request.name).await?;
+    if !event.active {
+        return Err(ApiError::BadRequest(format!(
+            "Event {} is not active",
+            event.id
+        )));
+    }
+
+    let permit = acquire_execution(state, &scope.user).await?;
+
+    let mut run = start_run(
+        state,
+        &sub,
+        RunRequest {
+            app_id: scope.app_id.clone(),
+            board_id: event.board_id.clone(),
+            version: event.board_version,
+            payload: RunPayload {
+                id: event.node_id.clone(),
+                payload: to_payload(&contract.input_schema, request.arguments),
+            },
+            event: Some(event),
+            stream_state: false,
+            permit,
+            chained_from: None,
+        },
+    )
+    .await?;
+
+    // The run keeps going if the client goes away, just like HTTP executions
+    while let Some(update) = run.updates.recv().await {
+        if let RunUpdate::Finished {
+            status, outputs, ..
+        } = update
+        {
+            return Ok(run_result(&run.run_id, status, outputs));
+        }
+    }
+
+    Err(ApiError::from(anyhow!(
+        "Run {} ended without a result",
+        run.run_id
+    )))
+}
+
+/// The event of the active release and its contract, falls back to the latest version without a release.
+async fn released_event(
+    app: &App,
+    event_id: &str,
+) -> flow_like_types::Result<(Event, EventContract)> {
+    let event = app.get_released_event(event_id).await?;
+    let board = app
+        .open_board(event.board_id.clone(), Some(false), event.board_version)
+        .await?;
+    let contract = event.contract(&*board.lock().await)?;
+    Ok((event, contract))
+}
+
+fn event_tool(event: &Event, contract: &EventContract) -> Tool {
+    let description = match event.description.is_empty() {
+        true => event.name.clone(),
+        false => event.description.clone(),
+    };
+
+    let mut tool = Tool::new(
+        event.id.clone(),
+        description,
+        Arc::new(input_schema(&contract.input_schema)),
+    );
+    tool.title = Some(event.name.clone());
+    // MCP only allows object output schemas
+    if is_object_schema(&contract.output_schema) {
+        tool.output_schema = contract.output_schema.as_object().cloned().map(Arc::new);
+    }
+    tool
+}
+
+fn is_object_schema(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("object")
+}
+
+fn is_empty_schema(schema: &Value) -> bool {
+    schema.as_object().is_some_and(Map::is_empty)
+}
+
+/// MCP tools take an object, events with a non-object payload get it as the "payload" argument.
+fn input_schema(schema: &Value) -> JsonObject {
+    if is_object_schema(schema) {
+        if let Some(schema) = schema.as_object() {
+            return schema.clone();
+        }
+    }
+
+    let schema = match is_empty_schema(schema) {
+        true => json!({ "type": "object" }),
+        false => json!({
+            "type": "object",
+            "properties": { "payload": schema },
+            "required": ["payload"],
+        }),
+    };
+    schema.as_object().cloned().unwrap_or_default()
+}
+
+/// Reverses `input_schema` to get the run payload from the tool arguments.
+fn to_payload(schema: &Value, arguments: Option<JsonObject>) -> Option<Value> {
+    if is_object_schema(schema) {
+        return Some(Value::Object(arguments.unwrap_or_default()));
+    }
+
+    if is_empty_schema(schema) {
+        return None;
+    }
+
+    arguments.and_then(|mut arguments| arguments.remove("payload"))
+}
+
+fn run_result(run_id: &str, status: RunStatus, outputs: HashMap<String, Value>) -> CallToolResult {
+    if status != RunStatus::Success {
+        return CallToolResult::error(vec![Content::text(format!(
+            "Run {} finished with status {:?}",
+            run_id, status
+        ))]);
+    }
+
+    let outputs = Value::Object(outputs.into_iter().collect());
+    let mut result = CallToolResult::success(vec![Content::text(outputs.to_string())]);
+    result.structured_content = Some(outputs);
+    result
+}
+
+fn to_error_data(err: ApiError) -> ErrorData {
+    match err {
+        ApiError::NotFound => ErrorData::resource_not_found("Not Found", None),
+        ApiError::Forbidden => ErrorData::invalid_request("Forbidden", None),
+        ApiError::Auth(_) => ErrorData::invalid_request("Unauthorized", None),
+        ApiError::BadRequest(message) => ErrorData::invalid_params(message, None),
+        ApiError::LimitExceeded(limit) => ErrorData::invalid_request(
+            format!("{:?} limit of {} exceeded", limit.kind, limit.limit),
+            Some(json!(limit)),
+        ),
+        ApiError::InternalError(_) => ErrorData::internal_error("Internal Server Error", None),
+    }
+}
+
+/// Resolves a PAT or an app API key, MCP clients can only send a single static credential.
+pub async fn authenticate(state: &AppState, token: &str) -> Result<AppUser, ApiError> {
+    if let Some(pat_user) = pat_user(state, token).await? {
+        if is_blocked(state, &pat_user.sub).await? {
+            return Err(ApiError::Forbidden);
+        }
+        return Ok(AppUser::PAT(pat_user));
+    }
+
+    if let Some(api_key) = api_key_user(state, token).await? {
+        return Ok(AppUser::APIKey(api_key));
+    }
+
+    Err(ApiError::Auth(AuthorizationError::from(anyhow!(
+        "Invalid PAT or API key"
+    ))))
+}
+
+fn credential(headers: &HeaderMap) -> Option<&str> {
+    let bearer = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.strip_prefix("Bearer ").unwrap_or(value));
+
+    bearer
+        .or_else(|| headers.get("x-pat").and_then(|value| value.to_str().ok()))
+        .or_else(|| {
+            headers
+                .get("x-api-key")
+                .and_then(|value| value.to_str().ok())
+        })
+        .map(str::trim)
+}
+
+/// Replaces the JWT middleware for the MCP endpoint, the bearer token is a PAT or an API key.
+async fn mcp_auth_middleware(
+    State(state): State<AppState>,
+    Path(app_id): Path<String>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let token = credential(request.headers()).ok_or_else(|| {
+        ApiError::Auth(AuthorizationError::from(anyhow!("Missing PAT or API key")))
+    })?;
+    let user = authenticate(&state, token).await?;
+
+    request.extensions_mut().insert(user.clone());
+    request.extensions_mut().insert(McpScope { app_id, user });
+    Ok(next.run(request).await)
+}
+
+/// Streamable HTTP endpoint of an app, sessions are kept in memory.
+pub fn routes(state: AppState) -> Router {
+    let service_state = state.clone();
+    let service = StreamableHttpService::new(
+        move || Ok(AppMcpServer::per_request(service_state.clone())),
+        Arc::new(LocalSessionManager::default()),
+        StreamableHttpServerConfig::default(),
+    );
+
+    // Layers run bottom to top, the rate limit needs the user set by the auth middleware
+    Router::new()
+        .route_service("/apps/{app_id}/mcp", service)
+        .route_layer(from_fn_with_state(state.clone(), rate_limit_middleware))
+        .route_layer(from_fn_with_state(state, mcp_auth_middleware))
+}
+
+/// Serves the events of an app over stdin and stdout until the client disconnects.
+pub async fn serve_stdio(
+    state: AppState,
+    app_id: &str,
+    token: &str,
+) -> flow_like_types::Result<()> {
+    let user = authenticate(&state, token)
+        .await
+        .map_err(|_| anyhow!("Invalid PAT or API key"))?;
+
+    let server = AppMcpServer::new(
+        state,
+        McpScope {
+            app_id: app_id.to_string(),
+            user,
+        },
+    );
+    let service = server.serve(stdio()).await?;
+    service.waiting().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn object_schemas_are_passed_through() {
+        let schema = json!({
+            "type": "object",
+            "properties": { "name": { "type": "string" } },
+        });
+
+        assert_eq!(Value::Object(input_schema(&schema)), schema);
+        let arguments = json!({ "name": "flow" }).as_object().cloned();
+        assert_eq!(
+            to_payload(&schema, arguments),
+            Some(json!({ "name": "flow" }))
+        );
+        assert_eq!(to_payload(&schema, None), Some(json!({})));
+    }
+
+    #[test]
+    fn other_schemas_are_wrapped_in_payload() {
+        let schema = json!({ "type": "array", "items": { "type": "string" } });
+
+        assert_eq!(
+            Value::Object(input_schema(&schema)),
+            json!({
+                "type": "object",
+                "properties": { "payload": schema },
+                "required": ["payload"],
+            })
+        );
+        let arguments = json!({ "payload": ["a", "b"] }).as_object().cloned();
+        assert_eq!(to_payload(&schema, arguments), Some(json!(["a", "b"])));
+    }
+
+    #[test]
+    fn events_without_pins_take_no_arguments() {
+        let schema = json!({});
+
+        assert_eq!(
+            Value::Object(input_schema(&schema)),
+            json!({ "type": "object" })
+        );
+        assert_eq!(to_payload(&schema, Some(JsonObject::new())), None);
+    }
+
+    #[test]
+    fn failed_runs_are_tool_errors() {
+        let outputs = HashMap::from([("answer".to_string(), json!(42))]);
+
+        let result = run_result("run", RunStatus::Success, outputs.clone());
+        assert_eq!(result.is_error, Some(false));
+        assert_eq!(result.structured_content, Some(json!({ "answer": 42 })));
+
+        let result = run_result("run", RunStatus::Failed, outputs);
+        assert_eq!(result.is_error, Some(true));
+        assert_eq!(result.structured_content, None);
+    }
+}
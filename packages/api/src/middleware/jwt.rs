@@ -1,7 +1,9 @@
 use std::sync::Arc;
 
 use crate::{
-    entity::{membership, pat, prelude::*, role, sea_orm_active_enums, technical_user, user},
+    entity::{
+        membership, pat, prelude::*, role, sea_orm_active_enums::UserStatus, technical_user, user,
+    },
     error::{ApiError, AuthorizationError},
     permission::{
         global_permission::GlobalPermission,
@@ -11,15 +13,16 @@ use crate::{
 use axum::{
     body::Body,
     extract::{Request, State},
+    http::StatusCode,
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use flow_like::hub::UserTier;
 use flow_like_types::Result;
 use flow_like_types::anyhow;
 use hyper::header::AUTHORIZATION;
 use sea_orm::{
-    ColumnTrait, EntityTrait, JoinType, QueryFilter, QuerySelect, RelationTrait,
+    ColumnTrait, EntityTrait, JoinType, QueryFilter, QuerySelect, RelationTrait, sea_query::Expr,
     sqlx::types::chrono,
 };
 
@@ -46,6 +49,25 @@ pub struct ApiKey {
     pub app_id: String,
 }
 
+impl ApiKey {
+    /// Creates a new random key. Only its hash is stored, the key itself is shown once.
+    pub fn generate() -> String {
+        use flow_like_types::base64::Engine;
+        use flow_like_types::rand::RngCore;
+
+        let mut bytes = [0u8; 32];
+        flow_like_types::rand::rng().fill_bytes(&mut bytes);
+        format!(
+            "flk_{}",
+            flow_like_types::base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+        )
+    }
+
+    pub fn hash(key: &str) -> String {
+        blake3::hash(key.as_bytes()).to_hex().to_string()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum AppUser {
     OpenID(OpenIDUser),
@@ -99,18 +121,7 @@ impl AppUser {
             .await?
             .ok_or_else(|| AuthorizationError::from(anyhow!("User not found")))?;
 
-        let db_tier = match user.tier {
-            sea_orm_active_enums::UserTier::Free => "FREE",
-            sea_orm_active_enums::UserTier::Premium => "PREMIUM",
-            sea_orm_active_enums::UserTier::Pro => "PRO",
-            sea_orm_active_enums::UserTier::Enterprise => "ENTERPRISE",
-        };
-
-        let tier = state
-            .platform_config
-            .tiers
-            .get(db_tier)
-            .cloned()
+        let tier = crate::limits::platform_tier(state, &user.tier)
             .ok_or_else(|| AuthorizationError::from(anyhow!("Tier not found")))?;
         Ok(tier)
     }
@@ -183,7 +194,8 @@ impl AppUser {
     ) -> Result<AppPermissionResponse, ApiError> {
         let sub = self.sub();
         if let Ok(sub) = sub {
-            let cached_permission = state.permission_cache.get(&sub);
+            let cache_key = format!("{}:{}", sub, app_id);
+            let cached_permission = state.permission_cache.get(&cache_key);
 
             if let Some(role_model) = cached_permission {
                 let permissions = RolePermissions::from_bits(role_model.permissions)
@@ -216,7 +228,7 @@ impl AppUser {
 
             state
                 .permission_cache
-                .insert(sub.clone(), Arc::new(role_model.clone()));
+                .insert(cache_key, Arc::new(role_model.clone()));
 
             return Ok(AppPermissionResponse {
                 state: state.clone(),
@@ -228,16 +240,32 @@ impl AppUser {
         }
 
         if let AppUser::APIKey(api_key) = self {
-            let role_model = role::Entity::find()
-                .join(JoinType::InnerJoin, role::Relation::TechnicalUser.def())
-                .filter(
-                    technical_user::Column::AppId
-                        .eq(&api_key.app_id)
-                        .and(technical_user::Column::Key.eq(&api_key.api_key)),
-                )
-                .one(&state.db)
-                .await?
-                .ok_or_else(|| ApiError::from(anyhow!("Technical user not found for API Key")))?;
+            // Keys are bound to the app they were created for
+            if api_key.app_id != app_id {
+                return Err(ApiError::Forbidden);
+            }
+
+            let cache_key = format!("{}:{}", api_key.key_id, app_id);
+            let role_model = match state.permission_cache.get(&cache_key) {
+                Some(role_model) => role_model,
+                None => {
+                    let role_model = role::Entity::find()
+                        .join(JoinType::InnerJoin, role::Relation::TechnicalUser.def())
+                        .filter(
+                            technical_user::Column::AppId
+                                .eq(&api_key.app_id)
+                                .and(technical_user::Column::Id.eq(&api_key.key_id)),
+                        )
+                        .one(&state.db)
+                        .await?
+                        .ok_or_else(|| {
+                            ApiError::from(anyhow!("Technical user not found for API Key"))
+                        })?;
+                    let role_model = Arc::new(role_model);
+                    state.permission_cache.insert(cache_key, role_model.clone());
+                    role_model
+                }
+            };
 
             let permissions = RolePermissions::from_bits(role_model.permissions)
                 .ok_or_else(|| anyhow!("Invalid role permission bits"))?;
@@ -245,7 +273,7 @@ impl AppUser {
             return Ok(AppPermissionResponse {
                 state: state.clone(),
                 permissions,
-                role: Arc::new(role_model),
+                role: role_model,
                 sub: None,
                 identifier: api_key.key_id.clone(),
             });
@@ -275,6 +303,9 @@ pub async fn jwt_middleware(
             let claims = state.validate_token(token)?;
             let sub = claims.get("sub").ok_or(anyhow!("sub not found"))?;
             let sub = sub.as_str().ok_or(anyhow!("sub not a string"))?;
+            if is_blocked(&state, sub).await? {
+                return Ok(blocked_response());
+            }
             let email = claims
                 .get("email")
                 .and_then(|v| v.as_str())
@@ -308,16 +339,13 @@ pub async fn jwt_middleware(
 
     if let Some(pat_header) = request.headers().get("x-pat") {
         if let Ok(pat_str) = pat_header.to_str() {
-            let db_pat = Pat::find()
-                .filter(pat::Column::Key.eq(pat_str))
-                .one(&state.db)
-                .await?;
-            if let Some(pat) = db_pat {
-                let pat_user = AppUser::PAT(PATUser {
-                    pat: pat_str.to_string(),
-                    sub: pat.user_id.clone(),
-                });
-                request.extensions_mut().insert::<AppUser>(pat_user);
+            if let Some(pat_user) = pat_user(&state, pat_str).await? {
+                if is_blocked(&state, &pat_user.sub).await? {
+                    return Ok(blocked_response());
+                }
+                request
+                    .extensions_mut()
+                    .insert::<AppUser>(AppUser::PAT(pat_user));
                 return Ok(next.run(request).await);
             }
         }
@@ -325,25 +353,10 @@ pub async fn jwt_middleware(
 
     if let Some(api_key_header) = request.headers().get("x-api-key") {
         if let Ok(api_key_str) = api_key_header.to_str() {
-            let db_app = TechnicalUser::find()
-                .filter(technical_user::Column::Key.eq(api_key_str))
-                .one(&state.db)
-                .await?;
-
-            if let Some(app) = db_app {
-                if let Some(valid_until) = app.valid_until {
-                    let now = chrono::Utc::now().naive_utc();
-                    if valid_until < now {
-                        return Err(AuthorizationError::from(anyhow!("API Key is expired")));
-                    }
-                }
-
-                let app_user = AppUser::APIKey(ApiKey {
-                    key_id: app.id.clone(),
-                    api_key: api_key_str.to_string(),
-                    app_id: app.app_id.clone(),
-                });
-                request.extensions_mut().insert::<AppUser>(app_user);
+            if let Some(api_key) = api_key_user(&state, api_key_str).await? {
+                request
+                    .extensions_mut()
+                    .insert::<AppUser>(AppUser::APIKey(api_key));
                 return Ok(next.run(request).await);
             }
         }
@@ -354,3 +367,92 @@ pub async fn jwt_middleware(
         .insert::<AppUser>(AppUser::Unauthorized);
     Ok(next.run(request).await)
 }
+
+/// Resolves a personal access token, blocked users still have to be rejected by the caller.
+pub(crate) async fn pat_user(state: &AppState, pat: &str) -> Result<Option<PATUser>> {
+    let db_pat = Pat::find()
+        .filter(pat::Column::Key.eq(pat))
+        .one(&state.db)
+        .await?;
+    Ok(db_pat.map(|db_pat| PATUser {
+        pat: pat.to_string(),
+        sub: db_pat.user_id,
+    }))
+}
+
+/// Resolves an app API key, expired keys are rejected.
+pub(crate) async fn api_key_user(
+    state: &AppState,
+    api_key: &str,
+) -> Result<Option<ApiKey>, AuthorizationError> {
+    let db_app = TechnicalUser::find()
+        .filter(technical_user::Column::Key.eq(ApiKey::hash(api_key.trim())))
+        .one(&state.db)
+        .await?;
+
+    let Some(app) = db_app else {
+        return Ok(None);
+    };
+
+    let now = chrono::Utc::now().naive_utc();
+    if let Some(valid_until) = app.valid_until {
+        if valid_until < now {
+            return Err(AuthorizationError::from(anyhow!("API Key is expired")));
+        }
+    }
+
+    if app
+        .last_used_at
+        .is_none_or(|last_used| now - last_used > LAST_USED_RESOLUTION)
+    {
+        touch_api_key(state, &app.id, now);
+    }
+
+    Ok(Some(ApiKey {
+        key_id: app.id,
+        api_key: api_key.to_string(),
+        app_id: app.app_id,
+    }))
+}
+
+/// Banned and suspended users, restrictions without an end date are permanent.
+pub fn is_restricted(status: &UserStatus, until: Option<chrono::NaiveDateTime>) -> bool {
+    matches!(status, UserStatus::Banned | UserStatus::Inactive)
+        && until.is_none_or(|until| until > chrono::Utc::now().naive_utc())
+}
+
+/// Cached for a minute, moderation invalidates the entry right away.
+pub(crate) async fn is_blocked(state: &AppState, sub: &str) -> Result<bool> {
+    if let Some(blocked) = state.blocked_users.get(sub) {
+        return Ok(blocked);
+    }
+
+    let blocked = User::find_by_id(sub)
+        .one(&state.db)
+        .await?
+        .is_some_and(|user| is_restricted(&user.status, user.status_until));
+    state.blocked_users.insert(sub.to_string(), blocked);
+    Ok(blocked)
+}
+
+fn blocked_response() -> Response<Body> {
+    (StatusCode::FORBIDDEN, "Account suspended").into_response()
+}
+
+/// `lastUsedAt` is only written once per interval to keep busy keys from writing on every request.
+const LAST_USED_RESOLUTION: chrono::TimeDelta = chrono::TimeDelta::minutes(1);
+
+fn touch_api_key(state: &AppState, key_id: &str, now: chrono::NaiveDateTime) {
+    let db = state.db.clone();
+    let key_id = key_id.to_string();
+    flow_like_types::tokio::spawn(async move {
+        let result = TechnicalUser::update_many()
+            .col_expr(technical_user::Column::LastUsedAt, Expr::value(now))
+            .filter(technical_user::Column::Id.eq(&key_id))
+            .exec(&db)
+            .await;
+        if let Err(e) = result {
+            tracing::warn!("Failed to update last use of API key {}: {:?}", key_id, e);
+        }
+    });
+}
@@ -0,0 +1,662 @@
+use std::{borrow::Cow, marker::PhantomData, sync::LazyLock};
+
+use axum::Json;
+use flow_like::{
+    app::{
+        App,
+        release::{Release, ReleaseState},
+    },
+    bit::{Bit, Metadata},
+    credentials::SharedCredentials,
+    flow::{
+        board::{Board, commands::GenericCommand},
+        event::{Event, contract::EventContract},
+        execution::{log::LogMessage, query::RunPage},
+        node::Node,
+    },
+    hub::{BitSearchQuery, Contact, Features, Hub, OpenIdConfig},
+    profile::Profile,
+};
+use flow_like_storage::files::store::StorageItem;
+use flow_like_types::{Value, json::json};
+use schemars::{
+    JsonSchema,
+    r#gen::{SchemaGenerator, SchemaSettings},
+    schema::Schema,
+};
+use sea_orm::{EntityName, ModelTrait};
+
+use crate::{
+    entity::{
+        audit_log, comment, feedback, invitation, invite_link, join_queue, membership,
+        profile as profile_entity, publication_request, role, template, user,
+    },
+    execution::AsyncRunStatus,
+    permission::{global_permission::GlobalPermission, role_permission::RolePermissions},
+    routes::{
+        admin::{
+            profiles::get_signed_profile_img_url::SignedProfileImgUrl,
+            publication::upsert_requests::PublicationDecisionBody,
+        },
+        app::{
+            api::{ApiKeyInfo, CreatedApiKey, create_api_key::CreateApiKeyPayload},
+            board::{
+                execute_board::ExecuteBoardBody,
+                execute_commands::ExecuteCommandsBody,
+                upsert_board::{UpsertBoard, UpsertBoardResponse},
+            },
+            comments::upsert_comment::UpsertCommentBody,
+            data::{
+                delete_files::DeleteFilesPayload, download_files::DownloadFilesPayload,
+                list_files::ListFilesPayload, upload_files::UploadFilesPayload,
+            },
+            events::{
+                execute_event::ExecuteEventBody,
+                upsert_event::EventUpsertBody,
+                upsert_event_feedback::{FeedbackBody, FeedbackResponse},
+            },
+            feedback::upsert_feedback::UpsertFeedbackBody,
+            internal::{change_visibility::UpdateVisibilityBody, upsert_app::AppUpsertBody},
+            meta::push_media::PushMediaResponse,
+            publication::{PublicationRequestDetails, upsert_publication::PublicationBody},
+            release::upsert_release::ReleaseUpsertBody,
+            team::{
+                create_invite_link::CreateInviteLinkPayload, invite_user::InviteUserParams,
+                request_join::RequestJoinParams,
+            },
+            template::upsert_template::TemplateUpsert,
+        },
+        llm::usage::LlmUsage,
+        profile::upsert_profile::ProfileBody,
+        user::{
+            ban_user::BanUserBody,
+            billing::BillingSession,
+            lookup::UserLookupResponse,
+            notifications::NotificationOverview,
+            transactions::TransactionOverview,
+            upsert_info::{UpsertInfoBody, UpsertInfoResponse},
+        },
+    },
+};
+
+/// Who may call an operation. Mirrors the checks done in the handlers.
+#[derive(Clone, Copy, Debug)]
+pub enum Access {
+    Public,
+    /// Any authenticated user
+    User,
+    /// Any member of the app, the handler decides on the details
+    Member,
+    /// Role permission within the app, `Owner` and `Admin` always pass
+    App(RolePermissions),
+    Global(GlobalPermission),
+}
+
+type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+pub struct Operation {
+    pub method: &'static str,
+    /// Path relative to `/api/v1`, as registered with the axum routers
+    pub path: &'static str,
+    pub tag: &'static str,
+    pub summary: &'static str,
+    pub access: Access,
+    /// `()` for write operations that take no body
+    pub body: Option<SchemaFn>,
+    pub response: Option<SchemaFn>,
+}
+
+fn schema<T: JsonSchema>(generator: &mut SchemaGenerator) -> Schema {
+    generator.subschema_for::<T>()
+}
+
+/// Schema of a generated entity. Their models are all called `Model` and their enums may clash
+/// with core types, so the schema is named after the table and generated without references.
+pub struct Record<M>(PhantomData<M>);
+
+impl<M: ModelTrait + JsonSchema> JsonSchema for Record<M> {
+    fn schema_name() -> String {
+        format!("{}Record", M::Entity::default().table_name())
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        Cow::Owned(format!("Record<{}>", std::any::type_name::<M>()))
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        SchemaSettings::draft2019_09()
+            .with(|settings| {
+                settings.inline_subschemas = true;
+                settings.meta_schema = None;
+            })
+            .into_generator()
+            .into_root_schema_for::<M>()
+            .schema
+            .into()
+    }
+}
+
+macro_rules! op {
+    ($method:ident $path:literal, $tag:literal, $summary:literal, $access:expr $(, body: $body:ty)? $(, response: $response:ty)?) => {
+        Operation {
+            method: stringify!($method),
+            path: $path,
+            tag: $tag,
+            summary: $summary,
+            access: $access,
+            body: op!(@schema $($body)?),
+            response: op!(@schema $($response)?),
+        }
+    };
+    (@schema) => { None };
+    (@schema $ty:ty) => { Some(schema::<$ty> as SchemaFn) };
+}
+
+use Access::{App as Role, Global, Member, Public, User};
+
+type Version = (u32, u32, u32);
+
+/// Every route of `construct_router`, `operations_match_routes` fails if a route is missing.
+pub fn operations() -> Vec<Operation> {
+    vec![
+        op!(get "/", "hub", "Hub configuration", Public, response: Hub),
+        op!(get "/version", "hub", "API version", Public, response: String),
+        op!(get "/openapi.json", "hub", "This OpenAPI document", Public, response: Value),
+        // health
+        op!(get "/health", "health", "Service health", Public, response: Value),
+        op!(get "/health/db", "health", "Database health", Public, response: Value),
+        op!(get "/store", "health", "Store health", Public, response: String),
+        op!(get "/store/db", "health", "Store database state", Public, response: Value),
+        // info
+        op!(get "/info/legal", "info", "Legal notice", Public, response: String),
+        op!(get "/info/privacy", "info", "Privacy policy", Public, response: String),
+        op!(get "/info/terms", "info", "Terms of service", Public, response: String),
+        op!(get "/info/contact", "info", "Contact information", Public, response: Contact),
+        op!(get "/info/features", "info", "Enabled hub features", Public, response: Features),
+        op!(get "/info/profiles", "info", "Profile templates", User, response: Vec<Profile>),
+        // auth, the proxies pass requests and responses of the identity provider through
+        op!(get "/auth/discovery", "auth", "OpenID discovery document", Public, response: Value),
+        op!(get "/auth/jwks", "auth", "JSON Web Key Set", Public, response: Value),
+        op!(get "/auth/authorize", "auth", "OAuth authorize proxy", Public, response: Value),
+        op!(post "/auth/authorize", "auth", "OAuth authorize proxy", Public, body: Value, response: Value),
+        op!(post "/auth/token", "auth", "OAuth token proxy", Public, body: Value, response: Value),
+        op!(get "/auth/userinfo", "auth", "OpenID userinfo proxy", Public, response: Value),
+        op!(post "/auth/userinfo", "auth", "OpenID userinfo proxy", Public, body: Value, response: Value),
+        op!(get "/auth/revoke", "auth", "OAuth revoke proxy", Public, response: Value),
+        op!(post "/auth/revoke", "auth", "OAuth revoke proxy", Public, body: Value, response: Value),
+        op!(get "/auth/openid", "auth", "OpenID client configuration", Public, response: OpenIdConfig),
+        // user
+        op!(get "/user/info", "user", "Current user", User, response: Record<user::Model>),
+        op!(put "/user/info", "user", "Update the current user", User, body: UpsertInfoBody, response: UpsertInfoResponse),
+        op!(get "/user/billing", "user", "Billing portal session", User, response: BillingSession),
+        op!(get "/user/lookup/{sub}", "user", "Look up a user", User, response: UserLookupResponse),
+        op!(get "/user/search/{query}", "user", "Search users", User, response: Vec<UserLookupResponse>),
+        op!(get "/user/invites", "user", "Pending invites", User, response: Vec<Record<invitation::Model>>),
+        op!(post "/user/invites/{invite_id}", "user", "Accept an invite", User, body: (), response: ()),
+        op!(delete "/user/invites/{invite_id}", "user", "Reject an invite", User, response: ()),
+        op!(get "/user/templates", "user", "Templates of all apps of the user", User, response: Vec<(String, String, Metadata)>),
+        op!(get "/user/notifications", "user", "Notification overview", User, response: NotificationOverview),
+        op!(get "/user/transactions", "user", "Transactions and usage based charges", User, response: TransactionOverview),
+        op!(put "/user/{sub}/status", "user", "Ban, suspend or reinstate a user", Global(GlobalPermission::WriteUsers), body: BanUserBody, response: Record<user::Model>),
+        // profile
+        op!(get "/profile", "profile", "Profiles of the user", User, response: Vec<Record<profile_entity::Model>>),
+        op!(post "/profile/{profile_id}", "profile", "Create or update a profile", User, body: ProfileBody, response: Record<profile_entity::Model>),
+        op!(delete "/profile/{profile_id}", "profile", "Delete a profile", User, response: ()),
+        // bit
+        op!(post "/bit", "bit", "Search bits", User, body: BitSearchQuery, response: Vec<Bit>),
+        op!(get "/bit/{bit_id}", "bit", "Get a bit", User, response: Bit),
+        op!(get "/bit/{bit_id}/dependencies", "bit", "Bit with its dependencies", User, response: Vec<Bit>),
+        // llm
+        op!(post "/llm/invoke", "llm", "OpenAI compatible chat completion, `InvokeApi` is required when `app_id` is set", User, body: Value, response: Value),
+        op!(get "/llm/usage", "llm", "LLM usage, `ReadAnalytics` is required when `app_id` is set", User, response: Vec<LlmUsage>),
+        // admin
+        op!(put "/admin/bit/{bit_id}", "admin", "Create or update a bit", Global(GlobalPermission::WriteBits), body: Bit, response: Bit),
+        op!(delete "/admin/bit/{bit_id}", "admin", "Delete a bit", Global(GlobalPermission::WriteBits), response: Vec<Bit>),
+        op!(put "/admin/bit/{bit_id}/{language}", "admin", "Set the metadata of a bit", Global(GlobalPermission::WriteBits), body: Metadata, response: ()),
+        op!(get "/admin/profiles/media", "admin", "Signed upload url for profile images", Global(GlobalPermission::WriteBits), response: SignedProfileImgUrl),
+        op!(put "/admin/profiles/{profile_id}", "admin", "Create or update a profile template", Global(GlobalPermission::WriteBits), body: Profile, response: Profile),
+        op!(delete "/admin/profiles/{profile_id}", "admin", "Delete a profile template", Global(GlobalPermission::WriteBits), response: Vec<Profile>),
+        op!(get "/admin/publication", "admin", "Publication requests", Global(GlobalPermission::ReadPublishing), response: Vec<PublicationRequestDetails>),
+        op!(put "/admin/publication/{request_id}", "admin", "Decide on a publication request", Global(GlobalPermission::WritePublishing), body: PublicationDecisionBody, response: Record<publication_request::Model>),
+        // apps
+        op!(get "/apps", "apps", "Apps of the user", User, response: Vec<(App, Option<Metadata>)>),
+        op!(get "/apps/nodes", "apps", "Available nodes", User, response: Vec<Node>),
+        op!(get "/apps/search", "apps", "Search apps", User, response: Vec<(App, Option<Metadata>)>),
+        op!(get "/apps/{app_id}", "apps", "Get an app", Member, response: App),
+        op!(put "/apps/{app_id}", "apps", "Create or update an app", User, body: AppUpsertBody, response: App),
+        op!(delete "/apps/{app_id}", "apps", "Delete an app", Role(RolePermissions::Owner), response: ()),
+        op!(patch "/apps/{app_id}/visibility", "apps", "Change the visibility of an app", Role(RolePermissions::Owner), body: UpdateVisibilityBody, response: ()),
+        // meta
+        op!(get "/apps/{app_id}/meta", "meta", "Metadata of the app, a template or a course", Member, response: Metadata),
+        op!(put "/apps/{app_id}/meta", "meta", "Set metadata, templates and courses need their write permission", Role(RolePermissions::WriteMeta), body: Metadata, response: ()),
+        op!(put "/apps/{app_id}/meta/media", "meta", "Upload url for media", Role(RolePermissions::WriteMeta), body: (), response: PushMediaResponse),
+        op!(delete "/apps/{app_id}/meta/media/{media_id}", "meta", "Remove media", Role(RolePermissions::WriteMeta), response: ()),
+        // templates
+        op!(get "/apps/{app_id}/templates", "templates", "Templates of the app", Role(RolePermissions::ReadTemplates), response: Vec<(String, String, Metadata)>),
+        op!(get "/apps/{app_id}/templates/{template_id}", "templates", "Get a template", Role(RolePermissions::ReadTemplates), response: Board),
+        op!(put "/apps/{app_id}/templates/{template_id}", "templates", "Create or update a template", Role(RolePermissions::WriteTemplates), body: TemplateUpsert, response: (String, Version)),
+        op!(delete "/apps/{app_id}/templates/{template_id}", "templates", "Delete a template", Role(RolePermissions::WriteTemplates), response: Vec<Record<template::Model>>),
+        // boards
+        op!(get "/apps/{app_id}/board", "boards", "Boards of the app", Role(RolePermissions::ReadBoards), response: Vec<Board>),
+        op!(get "/apps/{app_id}/board/{board_id}", "boards", "Get a board", Role(RolePermissions::ReadBoards), response: Board),
+        op!(put "/apps/{app_id}/board/{board_id}", "boards", "Create or update a board", Role(RolePermissions::WriteBoards), body: UpsertBoard, response: UpsertBoardResponse),
+        op!(post "/apps/{app_id}/board/{board_id}", "boards", "Execute board commands", Role(RolePermissions::WriteBoards), body: ExecuteCommandsBody, response: Vec<GenericCommand>),
+        op!(patch "/apps/{app_id}/board/{board_id}", "boards", "Create a board version", Role(RolePermissions::WriteBoards), body: (), response: Version),
+        op!(delete "/apps/{app_id}/board/{board_id}", "boards", "Delete a board", Role(RolePermissions::WriteBoards), response: ()),
+        op!(get "/apps/{app_id}/board/{board_id}/version", "boards", "Versions of a board", Role(RolePermissions::ReadBoards), response: Vec<Version>),
+        op!(post "/apps/{app_id}/board/{board_id}/execute", "boards", "Execute a board, streams Server-Sent Events unless `mode=async`", Role(RolePermissions::ExecuteBoards), body: ExecuteBoardBody, response: AsyncRunStatus),
+        op!(get "/apps/{app_id}/board/{board_id}/execute/{run_id}", "boards", "Status of an asynchronous board run", Role(RolePermissions::ExecuteBoards), response: AsyncRunStatus),
+        op!(get "/apps/{app_id}/board/{board_id}/runs", "boards", "Runs of a board", Role(RolePermissions::ReadLogs), response: RunPage),
+        op!(get "/apps/{app_id}/board/{board_id}/runs/{run_id}/logs", "boards", "Logs of a run", Role(RolePermissions::ReadLogs), response: Vec<LogMessage>),
+        op!(patch "/apps/{app_id}/board/{board_id}/undo", "boards", "Undo board commands", Role(RolePermissions::WriteBoards), body: ExecuteCommandsBody, response: ()),
+        op!(patch "/apps/{app_id}/board/{board_id}/redo", "boards", "Redo board commands", Role(RolePermissions::WriteBoards), body: ExecuteCommandsBody, response: ()),
+        // events
+        op!(get "/apps/{app_id}/events", "events", "Events of the app", Role(RolePermissions::WriteEvents), response: Vec<Event>),
+        op!(get "/apps/{app_id}/events/{event_id}", "events", "Get an event", Role(RolePermissions::WriteEvents), response: Event),
+        op!(put "/apps/{app_id}/events/{event_id}", "events", "Create or update an event", Role(RolePermissions::WriteEvents), body: EventUpsertBody, response: Event),
+        op!(delete "/apps/{app_id}/events/{event_id}", "events", "Delete an event", Role(RolePermissions::WriteEvents), response: ()),
+        op!(get "/apps/{app_id}/events/{event_id}/versions", "events", "Versions of an event", Role(RolePermissions::WriteEvents), response: Vec<Version>),
+        op!(get "/apps/{app_id}/events/{event_id}/contract", "events", "Input and output contract of an event", Role(RolePermissions::ReadEvents), response: EventContract),
+        op!(post "/apps/{app_id}/events/{event_id}/execute", "events", "Execute an event, streams Server-Sent Events unless `mode=async`", Role(RolePermissions::ExecuteEvents), body: ExecuteEventBody, response: AsyncRunStatus),
+        op!(get "/apps/{app_id}/events/{event_id}/execute/{run_id}", "events", "Status of an asynchronous event run", Role(RolePermissions::ExecuteEvents), response: AsyncRunStatus),
+        op!(post "/apps/{app_id}/events/{event_id}/validate", "events", "Validate an event", Role(RolePermissions::WriteEvents), body: (), response: ()),
+        op!(put "/apps/{app_id}/events/{event_id}/feedback", "events", "Feedback for an event run", Role(RolePermissions::ExecuteEvents), body: FeedbackBody, response: FeedbackResponse),
+        // releases
+        op!(get "/apps/{app_id}/releases", "releases", "Releases of the app", Role(RolePermissions::ReadEvents), response: Vec<Release>),
+        op!(get "/apps/{app_id}/releases/state", "releases", "Active release and history", Role(RolePermissions::ReadEvents), response: ReleaseState),
+        op!(post "/apps/{app_id}/releases/rollback", "releases", "Roll back to the previous release", Role(RolePermissions::WriteEvents), body: (), response: ReleaseState),
+        op!(get "/apps/{app_id}/releases/{release_id}", "releases", "Get a release", Role(RolePermissions::ReadEvents), response: Release),
+        op!(put "/apps/{app_id}/releases/{release_id}", "releases", "Create a release or update its notes", Role(RolePermissions::WriteEvents), body: ReleaseUpsertBody, response: Release),
+        op!(delete "/apps/{app_id}/releases/{release_id}", "releases", "Delete an inactive release", Role(RolePermissions::WriteEvents), response: ()),
+        op!(post "/apps/{app_id}/releases/{release_id}/activate", "releases", "Activate a release", Role(RolePermissions::WriteEvents), body: (), response: ReleaseState),
+        // roles
+        op!(get "/apps/{app_id}/roles", "roles", "Roles of the app and the default role", Role(RolePermissions::ReadRoles), response: (Option<String>, Vec<Record<role::Model>>)),
+        op!(put "/apps/{app_id}/roles/{role_id}", "roles", "Create or update a role", Role(RolePermissions::Admin), body: Record<role::Model>, response: ()),
+        op!(delete "/apps/{app_id}/roles/{role_id}", "roles", "Delete a role", Role(RolePermissions::Admin), response: ()),
+        op!(put "/apps/{app_id}/roles/{role_id}/default", "roles", "Make a role the default", Role(RolePermissions::Admin), body: (), response: ()),
+        op!(post "/apps/{app_id}/roles/{role_id}/assign/{sub}", "roles", "Assign a role to a member", Role(RolePermissions::Admin), body: (), response: ()),
+        // team
+        op!(get "/apps/{app_id}/team", "team", "Members of the app", Role(RolePermissions::ReadTeam), response: Vec<Record<membership::Model>>),
+        op!(get "/apps/{app_id}/team/link", "team", "Invite links", Role(RolePermissions::Admin), response: Vec<Record<invite_link::Model>>),
+        op!(put "/apps/{app_id}/team/link", "team", "Create an invite link", Role(RolePermissions::Admin), body: CreateInviteLinkPayload, response: ()),
+        op!(delete "/apps/{app_id}/team/link/{link_id}", "team", "Remove an invite link", Role(RolePermissions::Admin), response: ()),
+        op!(post "/apps/{app_id}/team/link/join/{token}", "team", "Join through an invite link", User, body: (), response: ()),
+        op!(get "/apps/{app_id}/team/queue", "team", "Join requests", Role(RolePermissions::Admin), response: Vec<Record<join_queue::Model>>),
+        op!(put "/apps/{app_id}/team/queue", "team", "Request to join", User, body: RequestJoinParams, response: ()),
+        op!(post "/apps/{app_id}/team/queue/{request_id}", "team", "Accept a join request", Role(RolePermissions::Admin), body: (), response: ()),
+        op!(delete "/apps/{app_id}/team/queue/{request_id}", "team", "Reject a join request", Role(RolePermissions::Admin), response: ()),
+        op!(put "/apps/{app_id}/team/invite", "team", "Invite a user", Role(RolePermissions::Admin), body: InviteUserParams, response: ()),
+        op!(delete "/apps/{app_id}/team/{sub}", "team", "Remove a member", Role(RolePermissions::Admin), response: ()),
+        // data
+        op!(put "/apps/{app_id}/data", "data", "Upload urls for files", Role(RolePermissions::WriteFiles), body: UploadFilesPayload, response: Vec<Value>),
+        op!(delete "/apps/{app_id}/data", "data", "Delete files", Role(RolePermissions::WriteFiles), body: DeleteFilesPayload, response: ()),
+        op!(post "/apps/{app_id}/data/download", "data", "Download urls for files", Role(RolePermissions::ReadFiles), body: DownloadFilesPayload, response: Vec<Value>),
+        op!(post "/apps/{app_id}/data/list", "data", "List files", Role(RolePermissions::ReadFiles), body: ListFilesPayload, response: Vec<StorageItem>),
+        op!(get "/apps/{app_id}/invoke/presign", "data", "Scoped storage credentials", Role(RolePermissions::ExecuteEvents), response: SharedCredentials),
+        // audit
+        op!(get "/apps/{app_id}/audit", "audit", "Audit log, latest entries first", Role(RolePermissions::Admin), response: Vec<Record<audit_log::Model>>),
+        op!(get "/apps/{app_id}/audit/export", "audit", "Audit log as JSON lines", Role(RolePermissions::Admin), response: String),
+        // api keys
+        op!(get "/apps/{app_id}/api", "api-keys", "API keys of the app", Role(RolePermissions::Admin), response: Vec<ApiKeyInfo>),
+        op!(put "/apps/{app_id}/api", "api-keys", "Create an API key", Role(RolePermissions::Admin), body: CreateApiKeyPayload, response: CreatedApiKey),
+        op!(delete "/apps/{app_id}/api/{key_id}", "api-keys", "Delete an API key", Role(RolePermissions::Admin), response: ()),
+        op!(post "/apps/{app_id}/api/{key_id}/rotate", "api-keys", "Rotate an API key", Role(RolePermissions::Admin), body: (), response: CreatedApiKey),
+        // publication
+        op!(get "/apps/{app_id}/publication", "publication", "Publication requests of the app", Role(RolePermissions::Admin), response: Vec<PublicationRequestDetails>),
+        op!(put "/apps/{app_id}/publication", "publication", "Request publication", Role(RolePermissions::Owner), body: PublicationBody, response: Record<publication_request::Model>),
+        // comments
+        op!(get "/apps/{app_id}/comments", "comments", "Comments or replies", Member, response: Vec<Record<comment::Model>>),
+        op!(put "/apps/{app_id}/comments", "comments", "Create or update a comment", Member, body: UpsertCommentBody, response: Record<comment::Model>),
+        op!(delete "/apps/{app_id}/comments/{comment_id}", "comments", "Remove a comment, others need `ModerateComments`", Member, response: ()),
+        // feedback
+        op!(get "/apps/{app_id}/feedback", "feedback", "Own feedback, all of it with `ReadAnalytics`", Member, response: Vec<Record<feedback::Model>>),
+        op!(put "/apps/{app_id}/feedback", "feedback", "Feedback for a run, needs `ExecuteEvents` or `ExecuteBoards`", Member, body: UpsertFeedbackBody, response: Record<feedback::Model>),
+        op!(delete "/apps/{app_id}/feedback/{feedback_id}", "feedback", "Remove own feedback", Member, response: ()),
+        // mcp, JSON-RPC messages of the Model Context Protocol
+        op!(post "/apps/{app_id}/mcp", "mcp", "MCP streamable HTTP endpoint, the events of the app are its tools", Role(RolePermissions::ExecuteEvents), body: Value, response: Value),
+        op!(get "/apps/{app_id}/mcp", "mcp", "Server-sent events of an MCP session", Role(RolePermissions::ExecuteEvents), response: Value),
+        op!(delete "/apps/{app_id}/mcp", "mcp", "Close an MCP session", Role(RolePermissions::ExecuteEvents), response: ()),
+    ]
+}
+
+static DOCUMENT: LazyLock<Value> = LazyLock::new(|| document(&operations()));
+
+pub async fn openapi() -> Json<Value> {
+    Json(DOCUMENT.clone())
+}
+
+/// OpenAPI 3.1 document of the given operations, schemas end up in `components.schemas`.
+pub fn document(operations: &[Operation]) -> Value {
+    let mut generator = SchemaSettings::draft2019_09()
+        .with(|settings| {
+            settings.definitions_path = "#/components/schemas/".to_string();
+            settings.meta_schema = None;
+        })
+        .into_generator();
+
+    let mut paths = flow_like_types::json::Map::new();
+    for operation in operations {
+        let mut entry = json!({
+            "operationId": operation_id(operation),
+            "summary": operation.summary,
+            "tags": [operation.tag],
+            "security": security(operation.access),
+            "x-permissions": permissions(operation.access),
+            "responses": {
+                "200": {
+                    "description": "Success",
+                    "content": { "application/json": { "schema": response_schema(operation, &mut generator) } }
+                },
+                "400": { "$ref": "#/components/responses/BadRequest" },
+                "401": { "$ref": "#/components/responses/Unauthorized" },
+                "403": { "$ref": "#/components/responses/Forbidden" },
+                "404": { "$ref": "#/components/responses/NotFound" },
+                "429": { "$ref": "#/components/responses/LimitExceeded" },
+            },
+        });
+
+        let parameters = path_parameters(operation.path);
+        if !parameters.is_empty() {
+            entry["parameters"] = Value::Array(parameters);
+        }
+
+        let body = operation
+            .body
+            .map(|body| flow_like_types::json::to_value(body(&mut generator)).unwrap_or_default())
+            .filter(|body| body != &json!({ "type": "null" }));
+        if let Some(body) = body {
+            entry["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": body } }
+            });
+        }
+
+        let path = paths
+            .entry(operation.path.to_string())
+            .or_insert_with(|| json!({}));
+        path[operation.method] = entry;
+    }
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "Flow-Like Hub API",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "servers": [{ "url": "/api/v1" }],
+        "paths": paths,
+        "components": {
+            "schemas": generator.take_definitions(),
+            "securitySchemes": {
+                "bearerAuth": {
+                    "type": "http",
+                    "scheme": "bearer",
+                    "bearerFormat": "JWT",
+                    "description": "OpenID Connect token of the configured identity provider",
+                },
+                "pat": {
+                    "type": "apiKey",
+                    "in": "header",
+                    "name": "x-pat",
+                    "description": "Personal access token, acts as the user that created it",
+                },
+                "apiKey": {
+                    "type": "apiKey",
+                    "in": "header",
+                    "name": "x-api-key",
+                    "description": "App API key, limited to the app and the role of the key",
+                },
+            },
+            "responses": {
+                "BadRequest": { "description": "Invalid request", "content": { "text/plain": { "schema": { "type": "string" } } } },
+                "Unauthorized": { "description": "Missing or invalid credentials" },
+                "Forbidden": { "description": "Missing permission" },
+                "NotFound": { "description": "Resource not found" },
+                "LimitExceeded": {
+                    "description": "Rate limit or quota of the tier exceeded",
+                    "headers": { "Retry-After": { "schema": { "type": "integer" } } },
+                    "content": { "application/json": { "schema": { "type": "object" } } }
+                },
+            },
+        },
+    })
+}
+
+fn response_schema(operation: &Operation, generator: &mut SchemaGenerator) -> Value {
+    match operation.response {
+        Some(response) => flow_like_types::json::to_value(response(generator)).unwrap_or_default(),
+        None => json!({}),
+    }
+}
+
+fn operation_id(operation: &Operation) -> String {
+    let path = operation
+        .path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| segment.trim_matches(|c| c == '{' || c == '}'))
+        .collect::<Vec<_>>()
+        .join("_");
+    format!("{}_{}", operation.method, path)
+        .replace(['.', '-'], "_")
+        .trim_end_matches('_')
+        .to_string()
+}
+
+fn path_parameters(path: &str) -> Vec<Value> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .map(|name| {
+            json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string" }
+            })
+        })
+        .collect()
+}
+
+fn security(access: Access) -> Value {
+    match access {
+        Access::Public => json!([]),
+        Access::User | Access::Global(_) => json!([{ "bearerAuth": [] }, { "pat": [] }]),
+        Access::Member | Access::App(_) => {
+            json!([{ "bearerAuth": [] }, { "pat": [] }, { "apiKey": [] }])
+        }
+    }
+}
+
+fn permissions(access: Access) -> Value {
+    match access {
+        Access::Public => json!({ "scope": "public" }),
+        Access::User => json!({ "scope": "user" }),
+        Access::Member => json!({ "scope": "app" }),
+        Access::App(permission) => json!({
+            "scope": "app",
+            "permissions": permission.iter_names().map(|(name, _)| name).collect::<Vec<_>>(),
+        }),
+        Access::Global(permission) => json!({
+            "scope": "global",
+            "permissions": permission.iter_names().map(|(name, _)| name).collect::<Vec<_>>(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        collections::HashSet,
+        path::{Path, PathBuf},
+    };
+
+    /// Method and path of every route, read from the router sources since axum can not list them.
+    /// `route_service` accepts any method and is recorded as `*`.
+    fn registered_routes() -> HashSet<(String, String)> {
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut routes = HashSet::new();
+        collect_routes(&src.join("lib.rs"), "", &mut routes);
+        collect_routes(&src.join("mcp.rs"), "", &mut routes);
+        routes
+    }
+
+    fn collect_routes(file: &Path, prefix: &str, routes: &mut HashSet<(String, String)>) {
+        let source = std::fs::read_to_string(file)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {}", file.display(), e));
+
+        for (call, args) in calls(&source, &[".route(", ".route_service(", ".nest("]) {
+            // Skips calls without a literal path, e.g. nesting the whole router under `/api/v1`
+            let Some((path, rest)) = args
+                .trim_start()
+                .strip_prefix('"')
+                .and_then(|args| args.split_once('"'))
+            else {
+                continue;
+            };
+            let path = if path == "/" && !prefix.is_empty() {
+                prefix.to_string()
+            } else {
+                format!("{}{}", prefix, path)
+            };
+
+            match call {
+                ".nest(" => {
+                    let Some(module) = rest
+                        .trim_start_matches([',', ' ', '\n'])
+                        .trim_end()
+                        .strip_suffix("::routes()")
+                    else {
+                        continue;
+                    };
+                    collect_routes(&module_file(file, module), &path, routes);
+                }
+                ".route_service(" => {
+                    routes.insert(("*".to_string(), path));
+                }
+                _ => {
+                    for (method, _) in calls(rest, &["get(", "post(", "put(", "patch(", "delete("])
+                    {
+                        routes.insert((method.trim_end_matches('(').to_string(), path.clone()));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Finds calls of the given functions that are not part of a longer path or name, with their arguments.
+    fn calls<'a>(source: &'a str, names: &[&'a str]) -> Vec<(&'a str, &'a str)> {
+        let mut calls = vec![];
+        for name in names {
+            for (start, _) in source.match_indices(name) {
+                let preceding = source[..start].chars().next_back();
+                if !name.starts_with('.')
+                    && preceding.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == ':')
+                {
+                    continue;
+                }
+
+                let args_start = start + name.len();
+                let mut depth = 1;
+                let args_end = source[args_start..].char_indices().find_map(|(i, c)| {
+                    match c {
+                        '(' => depth += 1,
+                        ')' => depth -= 1,
+                        _ => {}
+                    }
+                    (depth == 0).then_some(args_start + i)
+                });
+                if let Some(args_end) = args_end {
+                    calls.push((*name, &source[args_start..args_end]));
+                }
+            }
+        }
+        calls
+    }
+
+    /// `board::routes()` in `routes/app.rs` lives in `routes/app/board.rs`.
+    fn module_file(file: &Path, module: &str) -> PathBuf {
+        let mut dir = match file.file_name().and_then(|name| name.to_str()) {
+            Some("lib.rs") => file.parent().unwrap().to_path_buf(),
+            _ => file.with_extension(""),
+        };
+        for segment in module.split("::") {
+            dir.push(segment);
+        }
+        dir.with_extension("rs")
+    }
+
+    #[test]
+    fn operations_match_routes() {
+        let registered = registered_routes();
+        let documented = operations()
+            .iter()
+            .map(|operation| (operation.method.to_string(), operation.path.to_string()))
+            .collect::<HashSet<_>>();
+
+        for (method, path) in &registered {
+            let is_documented = match method.as_str() {
+                "*" => documented.iter().any(|(_, documented)| documented == path),
+                _ => documented.contains(&(method.clone(), path.clone())),
+            };
+            assert!(is_documented, "{} {} is not documented", method, path);
+        }
+
+        for (method, path) in &documented {
+            assert!(
+                registered.contains(&(method.clone(), path.clone()))
+                    || registered.contains(&("*".to_string(), path.clone())),
+                "{} {} is documented but not routed",
+                method,
+                path
+            );
+        }
+        assert!(registered.contains(&("*".to_string(), "/apps/{app_id}/mcp".to_string())));
+    }
+
+    #[test]
+    fn operations_are_unique() {
+        let operations = operations();
+        let mut ids = HashSet::new();
+        for operation in &operations {
+            assert!(operation.path.starts_with('/'), "{}", operation.path);
+            assert!(ids.insert(operation_id(operation)), "{}", operation.path);
+        }
+
+        let document = document(&operations);
+        let contract = &document["paths"]["/apps/{app_id}/events/{event_id}/contract"]["get"];
+        assert_eq!(contract["x-permissions"]["permissions"][0], "ReadEvents");
+        assert_eq!(contract["parameters"].as_array().unwrap().len(), 2);
+        assert!(document["components"]["schemas"]["EventContract"].is_object());
+    }
+
+    #[test]
+    fn operations_declare_bodies_and_responses() {
+        let operations = operations();
+        for operation in &operations {
+            let id = operation_id(operation);
+            assert!(operation.response.is_some(), "{} has no response", id);
+            if matches!(operation.method, "put" | "post" | "patch") {
+                assert!(operation.body.is_some(), "{} has no body", id);
+            }
+        }
+
+        let document = document(&operations);
+        let comments = &document["paths"]["/apps/{app_id}/comments"]["put"];
+        assert_eq!(
+            comments["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/UpsertCommentBody"
+        );
+        assert_eq!(
+            comments["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/CommentRecord"
+        );
+        let schemas = &document["components"]["schemas"];
+        assert!(schemas["CommentRecord"]["properties"]["rating"].is_object());
+        // Entity enums are inlined and do not replace core types of the same name
+        assert!(schemas["UserTier"]["properties"]["max_total_size"].is_object());
+
+        let accept = &document["paths"]["/user/invites/{invite_id}"]["post"];
+        assert!(accept.get("requestBody").is_none());
+    }
+}
@@ -27,6 +27,7 @@ bitflags! {
         const WriteTemplates    =   0b00010000_00000000_00000000;
         const ReadCourses       =   0b00100000_00000000_00000000;
         const WriteCourses      =   0b01000000_00000000_00000000;
+        const ModerateComments  =   0b10000000_00000000_00000000;
     }
 
 }
@@ -6,6 +6,7 @@ pub mod auth;
 pub mod bit;
 pub mod health;
 pub mod info;
+pub mod llm;
 pub mod profile;
 pub mod store;
 pub mod user;
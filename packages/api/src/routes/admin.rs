@@ -8,6 +8,7 @@ use crate::state::AppState;
 
 pub mod bit;
 pub mod profiles;
+pub mod publication;
 
 pub fn routes() -> Router<AppState> {
     Router::new()
@@ -20,6 +21,11 @@ pub fn routes() -> Router<AppState> {
             "/profiles/media",
             get(profiles::get_signed_profile_img_url::get_signed_profile_img_url),
         )
+        .route("/publication", get(publication::get_requests::get_requests))
+        .route(
+            "/publication/{request_id}",
+            put(publication::upsert_requests::upsert_request),
+        )
         .route(
             "/profiles/{profile_id}",
             put(profiles::upsert_profile_template::upsert_profile_template)
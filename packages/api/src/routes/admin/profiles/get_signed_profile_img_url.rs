@@ -6,8 +6,9 @@ use crate::{
 };
 use axum::{Extension, Json, extract::State};
 use flow_like_types::create_id;
+use schemars::JsonSchema;
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, JsonSchema)]
 pub struct SignedProfileImgUrl {
     pub url: String,
     pub final_url: Option<String>,
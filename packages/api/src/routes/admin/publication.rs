@@ -0,0 +1,2 @@
+pub mod get_requests;
+pub mod upsert_requests;
@@ -0,0 +1,46 @@
+use crate::{
+    entity::{publication_request, sea_orm_active_enums::PublicationRequestStatus},
+    error::ApiError,
+    middleware::jwt::AppUser,
+    permission::global_permission::GlobalPermission,
+    routes::app::publication::{PublicationRequestDetails, publication_details},
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct PublicationRequestQuery {
+    /// Defaults to the review queue (pending requests)
+    pub status: Option<PublicationRequestStatus>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// Review queue, oldest requests first.
+#[tracing::instrument(name = "GET /admin/publication", skip(state, user))]
+pub async fn get_requests(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Query(query): Query<PublicationRequestQuery>,
+) -> Result<Json<Vec<PublicationRequestDetails>>, ApiError> {
+    user.check_global_permission(&state, GlobalPermission::ReadPublishing)
+        .await?;
+
+    let status = query.status.unwrap_or(PublicationRequestStatus::Pending);
+    let requests = publication_request::Entity::find()
+        .filter(publication_request::Column::Status.eq(status))
+        .order_by_asc(publication_request::Column::UpdatedAt)
+        .limit(Some(query.limit.unwrap_or(50).min(100)))
+        .offset(query.offset)
+        .all(&state.db)
+        .await?;
+
+    let requests = publication_details(&state.db, requests).await?;
+
+    Ok(Json(requests))
+}
@@ -0,0 +1,78 @@
+use crate::{
+    entity::{publication_request, sea_orm_active_enums::PublicationRequestStatus},
+    error::ApiError,
+    middleware::jwt::AppUser,
+    permission::global_permission::GlobalPermission,
+    routes::app::publication::log_publication,
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use schemars::JsonSchema;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, EntityTrait, IntoActiveModel, TransactionTrait};
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, JsonSchema)]
+pub struct PublicationDecisionBody {
+    pub status: PublicationRequestStatus,
+    pub message: Option<String>,
+}
+
+/// Decides on a request. Accepted requests are applied by the owner through `change_visibility`.
+#[tracing::instrument(name = "PUT /admin/publication/{request_id}", skip(state, user, body))]
+pub async fn upsert_request(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path(request_id): Path<String>,
+    Json(body): Json<PublicationDecisionBody>,
+) -> Result<Json<publication_request::Model>, ApiError> {
+    user.check_global_permission(&state, GlobalPermission::WritePublishing)
+        .await?;
+    let sub = user.sub()?;
+
+    if body.status == PublicationRequestStatus::Pending {
+        return Err(ApiError::BadRequest(
+            "A decision has to accept, reject or hold the request".to_string(),
+        ));
+    }
+
+    let txn = state.db.begin().await?;
+
+    let request = publication_request::Entity::find_by_id(&request_id)
+        .one(&txn)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    if request.applied_at.is_some() {
+        return Err(ApiError::BadRequest(format!(
+            "Request {} was already applied",
+            request_id
+        )));
+    }
+
+    let visibility = request.target_visibility.clone();
+    let mut request = request.into_active_model();
+    request.status = Set(body.status.clone());
+    request.approver_id = Set(Some(sub.clone()));
+    request.owner_seen = Set(false);
+    request.updated_at = Set(chrono::Utc::now().naive_utc());
+    let request = request.update(&txn).await?;
+
+    let message = body
+        .message
+        .unwrap_or_else(|| format!("Request {:?}", body.status));
+    log_publication(
+        &txn,
+        &request.id,
+        Some(sub),
+        Some(message),
+        Some(visibility),
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(Json(request))
+}
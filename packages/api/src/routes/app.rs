@@ -8,11 +8,17 @@ use axum::{
 
 pub mod internal;
 
+pub mod api;
+pub mod audit;
 pub mod board;
+pub mod comments;
 pub mod data;
 pub mod events;
+pub mod feedback;
 pub mod invoke;
 pub mod meta;
+pub mod publication;
+pub mod release;
 pub mod roles;
 pub mod team;
 pub mod template;
@@ -40,6 +46,12 @@ pub fn routes() -> Router<AppState> {
         .nest("/{app_id}/events", events::routes())
         .nest("/{app_id}/data", data::routes())
         .nest("/{app_id}/invoke", invoke::routes())
+        .nest("/{app_id}/api", api::routes())
+        .nest("/{app_id}/releases", release::routes())
+        .nest("/{app_id}/publication", publication::routes())
+        .nest("/{app_id}/comments", comments::routes())
+        .nest("/{app_id}/feedback", feedback::routes())
+        .nest("/{app_id}/audit", audit::routes())
 }
 
 #[macro_export]
@@ -47,8 +59,7 @@ macro_rules! ensure_permission {
     ($user:expr, $app_id:expr, $state:expr, $perm:expr) => {{
         let sub = $user.app_permission($app_id, $state).await?;
         if !sub.has_permission($perm) {
-            let user_id = sub.sub()?;
-            $state.invalidate_permission(&user_id, $app_id);
+            $state.invalidate_permission(&sub.identifier(), $app_id);
             return Err($crate::error::ApiError::Forbidden);
         }
         sub
@@ -0,0 +1,69 @@
+use axum::{
+    Router,
+    routing::{delete, get, post},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{entity::technical_user, error::ApiError, middleware::jwt::AppUser, state::AppState};
+
+pub mod create_api_key;
+pub mod delete_api_key;
+pub mod get_api_keys;
+pub mod rotate_api_key;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(get_api_keys::get_api_keys).put(create_api_key::create_api_key),
+        )
+        .route("/{key_id}", delete(delete_api_key::delete_api_key))
+        .route("/{key_id}/rotate", post(rotate_api_key::rotate_api_key))
+}
+
+/// Keys are only managed by people, otherwise a leaked key could create or rotate keys to keep its access.
+fn ensure_not_api_key(user: &AppUser) -> Result<(), ApiError> {
+    if let AppUser::APIKey(_) = user {
+        tracing::warn!("Attempt to manage API keys with an API key");
+        return Err(ApiError::Forbidden);
+    }
+    Ok(())
+}
+
+/// A technical user without its (hashed) key.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ApiKeyInfo {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub role_id: Option<String>,
+    pub app_id: String,
+    pub valid_until: Option<chrono::NaiveDateTime>,
+    pub last_used_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl From<technical_user::Model> for ApiKeyInfo {
+    fn from(model: technical_user::Model) -> Self {
+        ApiKeyInfo {
+            id: model.id,
+            name: model.name,
+            description: model.description,
+            role_id: model.role_id,
+            app_id: model.app_id,
+            valid_until: model.valid_until,
+            last_used_at: model.last_used_at,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+        }
+    }
+}
+
+/// Returned on creation and rotation, the only time the plain key is available.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreatedApiKey {
+    pub key: String,
+    pub info: ApiKeyInfo,
+}
@@ -0,0 +1,83 @@
+use crate::{
+    ensure_permission,
+    entity::{role, technical_user},
+    error::ApiError,
+    middleware::jwt::{ApiKey, AppUser},
+    permission::role_permission::RolePermissions,
+    routes::app::api::{ApiKeyInfo, CreatedApiKey, ensure_not_api_key},
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use flow_like_types::create_id;
+use schemars::JsonSchema;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateApiKeyPayload {
+    pub name: String,
+    pub description: Option<String>,
+    pub role_id: String,
+    pub valid_until: Option<chrono::NaiveDateTime>,
+}
+
+#[tracing::instrument(name = "PUT /apps/{app_id}/api", skip(state, user, payload))]
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path(app_id): Path<String>,
+    Json(payload): Json<CreateApiKeyPayload>,
+) -> Result<Json<CreatedApiKey>, ApiError> {
+    ensure_not_api_key(&user)?;
+    ensure_permission!(user, &app_id, &state, RolePermissions::Admin);
+
+    if payload.name.trim().is_empty() {
+        return Err(ApiError::BadRequest("Name cannot be empty".to_string()));
+    }
+
+    if let Some(valid_until) = payload.valid_until {
+        if valid_until <= chrono::Utc::now().naive_utc() {
+            return Err(ApiError::BadRequest(
+                "Expiry must be in the future".to_string(),
+            ));
+        }
+    }
+
+    let role = role::Entity::find_by_id(&payload.role_id)
+        .filter(role::Column::AppId.eq(&app_id))
+        .one(&state.db)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    let permissions = RolePermissions::from_bits(role.permissions).ok_or(ApiError::Forbidden)?;
+    if permissions.contains(RolePermissions::Owner) {
+        tracing::warn!("Attempt to create an API key with Owner permission");
+        return Err(ApiError::Forbidden);
+    }
+
+    let key = ApiKey::generate();
+
+    let technical_user = technical_user::Model {
+        id: create_id(),
+        name: payload.name,
+        description: payload.description,
+        key: ApiKey::hash(&key),
+        role_id: Some(role.id),
+        app_id,
+        valid_until: payload.valid_until,
+        last_used_at: None,
+        created_at: chrono::Utc::now().naive_utc(),
+        updated_at: chrono::Utc::now().naive_utc(),
+    };
+
+    let technical_user: technical_user::ActiveModel = technical_user.into();
+    let technical_user = technical_user.insert(&state.db).await?;
+
+    Ok(Json(CreatedApiKey {
+        key,
+        info: ApiKeyInfo::from(technical_user),
+    }))
+}
@@ -0,0 +1,37 @@
+use crate::{
+    ensure_permission, entity::technical_user, error::ApiError, middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions, routes::app::api::ensure_not_api_key,
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+#[tracing::instrument(name = "DELETE /apps/{app_id}/api/{key_id}", skip(state, user))]
+pub async fn delete_api_key(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path((app_id, key_id)): Path<(String, String)>,
+) -> Result<Json<()>, ApiError> {
+    ensure_not_api_key(&user)?;
+    ensure_permission!(user, &app_id, &state, RolePermissions::Admin);
+
+    let result = technical_user::Entity::delete_many()
+        .filter(
+            technical_user::Column::AppId
+                .eq(app_id.clone())
+                .and(technical_user::Column::Id.eq(key_id.clone())),
+        )
+        .exec(&state.db)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(ApiError::NotFound);
+    }
+
+    state.invalidate_permission(&key_id, &app_id);
+
+    Ok(Json(()))
+}
@@ -0,0 +1,29 @@
+use crate::{
+    ensure_permission, entity::technical_user, error::ApiError, middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions, routes::app::api::ApiKeyInfo, state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+
+#[tracing::instrument(name = "GET /apps/{app_id}/api", skip(state, user))]
+pub async fn get_api_keys(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path(app_id): Path<String>,
+) -> Result<Json<Vec<ApiKeyInfo>>, ApiError> {
+    ensure_permission!(user, &app_id, &state, RolePermissions::Admin);
+
+    let keys = technical_user::Entity::find()
+        .filter(technical_user::Column::AppId.eq(app_id))
+        .order_by_desc(technical_user::Column::CreatedAt)
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(ApiKeyInfo::from)
+        .collect();
+
+    Ok(Json(keys))
+}
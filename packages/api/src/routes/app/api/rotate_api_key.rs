@@ -0,0 +1,46 @@
+use crate::{
+    ensure_permission,
+    entity::technical_user,
+    error::ApiError,
+    middleware::jwt::{ApiKey, AppUser},
+    permission::role_permission::RolePermissions,
+    routes::app::api::{ApiKeyInfo, CreatedApiKey, ensure_not_api_key},
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter};
+
+/// Replaces the key of a technical user, the old key stops working immediately.
+#[tracing::instrument(name = "POST /apps/{app_id}/api/{key_id}/rotate", skip(state, user))]
+pub async fn rotate_api_key(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path((app_id, key_id)): Path<(String, String)>,
+) -> Result<Json<CreatedApiKey>, ApiError> {
+    ensure_not_api_key(&user)?;
+    ensure_permission!(user, &app_id, &state, RolePermissions::Admin);
+
+    let technical_user = technical_user::Entity::find_by_id(&key_id)
+        .filter(technical_user::Column::AppId.eq(&app_id))
+        .one(&state.db)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    let key = ApiKey::generate();
+
+    let mut technical_user: technical_user::ActiveModel = technical_user.into();
+    technical_user.key = Set(ApiKey::hash(&key));
+    technical_user.last_used_at = Set(None);
+    technical_user.updated_at = Set(chrono::Utc::now().naive_utc());
+    let technical_user = technical_user.update(&state.db).await?;
+
+    state.invalidate_permission(&key_id, &app_id);
+
+    Ok(Json(CreatedApiKey {
+        key,
+        info: ApiKeyInfo::from(technical_user),
+    }))
+}
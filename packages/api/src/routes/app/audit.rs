@@ -0,0 +1,47 @@
+use axum::{Router, routing::get};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, Select};
+use serde::Deserialize;
+
+use crate::{
+    entity::{audit_log, sea_orm_active_enums::AuditAction},
+    state::AppState,
+};
+
+pub mod export_audit_log;
+pub mod get_audit_log;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_audit_log::get_audit_log))
+        .route("/export", get(export_audit_log::export_audit_log))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    pub from: Option<chrono::NaiveDateTime>,
+    pub to: Option<chrono::NaiveDateTime>,
+    pub action: Option<AuditAction>,
+    pub actor_id: Option<String>,
+    /// Ignored by the export, which always contains all matching entries
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+pub fn filter_audit_log(app_id: &str, query: &AuditQuery) -> Select<audit_log::Entity> {
+    let mut select = audit_log::Entity::find().filter(audit_log::Column::AppId.eq(app_id));
+
+    if let Some(from) = query.from {
+        select = select.filter(audit_log::Column::CreatedAt.gte(from));
+    }
+    if let Some(to) = query.to {
+        select = select.filter(audit_log::Column::CreatedAt.lt(to));
+    }
+    if let Some(action) = &query.action {
+        select = select.filter(audit_log::Column::Action.eq(action.clone()));
+    }
+    if let Some(actor_id) = &query.actor_id {
+        select = select.filter(audit_log::Column::ActorId.eq(actor_id));
+    }
+
+    select
+}
@@ -0,0 +1,55 @@
+use crate::{
+    ensure_permission,
+    entity::audit_log,
+    error::ApiError,
+    middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions,
+    routes::app::audit::{AuditQuery, filter_audit_log},
+    state::AppState,
+};
+use axum::{
+    Extension,
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use flow_like_types::json::to_string;
+use sea_orm::{PaginatorTrait, QueryOrder};
+
+const PAGE_SIZE: u64 = 1000;
+
+/// All matching entries as JSON lines, oldest first.
+#[tracing::instrument(name = "GET /apps/{app_id}/audit/export", skip(state, user))]
+pub async fn export_audit_log(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path(app_id): Path<String>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Response, ApiError> {
+    ensure_permission!(user, &app_id, &state, RolePermissions::Admin);
+
+    let mut pages = filter_audit_log(&app_id, &query)
+        .order_by_asc(audit_log::Column::CreatedAt)
+        .order_by_asc(audit_log::Column::Id)
+        .paginate(&state.db, PAGE_SIZE);
+
+    let mut lines = String::new();
+    while let Some(entries) = pages.fetch_and_next().await? {
+        for entry in entries {
+            lines.push_str(&to_string(&entry)?);
+            lines.push('\n');
+        }
+    }
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"audit-{}.jsonl\"", app_id),
+            ),
+        ],
+        lines,
+    )
+        .into_response())
+}
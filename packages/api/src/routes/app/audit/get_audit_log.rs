@@ -0,0 +1,34 @@
+use crate::{
+    ensure_permission,
+    entity::audit_log,
+    error::ApiError,
+    middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions,
+    routes::app::audit::{AuditQuery, filter_audit_log},
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+};
+use sea_orm::{EntityTrait, QueryOrder, QuerySelect};
+
+/// Latest entries first.
+#[tracing::instrument(name = "GET /apps/{app_id}/audit", skip(state, user))]
+pub async fn get_audit_log(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path(app_id): Path<String>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<Vec<audit_log::Model>>, ApiError> {
+    ensure_permission!(user, &app_id, &state, RolePermissions::Admin);
+
+    let entries = filter_audit_log(&app_id, &query)
+        .order_by_desc(audit_log::Column::CreatedAt)
+        .limit(Some(query.limit.unwrap_or(50).min(100)))
+        .offset(query.offset)
+        .all(&state.db)
+        .await?;
+
+    Ok(Json(entries))
+}
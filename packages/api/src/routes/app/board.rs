@@ -4,13 +4,15 @@ pub mod execute_commands;
 pub mod get_board;
 pub mod get_board_versions;
 pub mod get_boards;
+pub mod get_runs;
+pub mod query_logs;
 pub mod undo_redo_board;
 pub mod upsert_board;
 pub mod version_board;
 
 use axum::{
     Router,
-    routing::{get, patch},
+    routing::{get, patch, post},
 };
 
 use crate::state::AppState;
@@ -30,6 +32,16 @@ pub fn routes() -> Router<AppState> {
             "/{board_id}/version",
             get(get_board_versions::get_board_versions),
         )
+        .route("/{board_id}/execute", post(execute_board::execute_board))
+        .route(
+            "/{board_id}/execute/{run_id}",
+            get(execute_board::get_board_run),
+        )
+        .route("/{board_id}/runs", get(get_runs::get_runs))
+        .route(
+            "/{board_id}/runs/{run_id}/logs",
+            get(query_logs::query_logs),
+        )
         .route("/{board_id}/undo", patch(undo_redo_board::undo_board))
         .route("/{board_id}/redo", patch(undo_redo_board::redo_board))
 }
@@ -1,6 +1,11 @@
 use crate::{
-    ensure_permission, error::ApiError, middleware::jwt::AppUser,
-    permission::role_permission::RolePermissions, state::AppState,
+    audit::{Audit, ClientIp},
+    ensure_permission,
+    entity::sea_orm_active_enums::AuditAction,
+    error::ApiError,
+    middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions,
+    state::AppState,
 };
 use axum::{
     Extension, Json,
@@ -11,6 +16,7 @@ use axum::{
 pub async fn delete_board(
     State(state): State<AppState>,
     Extension(user): Extension<AppUser>,
+    ip: ClientIp,
     Path((app_id, board_id)): Path<(String, String)>,
 ) -> Result<Json<()>, ApiError> {
     let permission = ensure_permission!(user, &app_id, &state, RolePermissions::WriteBoards);
@@ -27,5 +33,10 @@ pub async fn delete_board(
     app.delete_board(&board_id).await?;
     app.save().await?;
 
+    Audit::new(&app_id, AuditAction::BoardDeleted)
+        .target(&board_id)
+        .record(&state.db, Some(permission.identifier()), &ip)
+        .await?;
+
     Ok(Json(()))
 }
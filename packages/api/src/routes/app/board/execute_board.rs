@@ -1 +1,83 @@
+use crate::{
+    ensure_permission,
+    error::ApiError,
+    execution::{AsyncRunStatus, ExecutionQuery, RunRequest, get_async_run, respond, start_run},
+    limits::acquire_execution,
+    middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions,
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    response::Response,
+};
+use flow_like::flow::execution::RunPayload;
+use flow_like_types::Value;
+use schemars::JsonSchema;
+use serde::Deserialize;
 
+#[derive(Clone, Deserialize, JsonSchema)]
+pub struct ExecuteBoardBody {
+    /// The node the run starts at
+    pub node_id: String,
+    pub payload: Option<Value>,
+    pub version: Option<(u32, u32, u32)>,
+    pub stream_state: Option<bool>,
+}
+
+#[tracing::instrument(
+    name = "POST /apps/{app_id}/board/{board_id}/execute",
+    skip(state, user, params)
+)]
+pub async fn execute_board(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path((app_id, board_id)): Path<(String, String)>,
+    Query(query): Query<ExecutionQuery>,
+    Json(params): Json<ExecuteBoardBody>,
+) -> Result<Response, ApiError> {
+    let permission = ensure_permission!(user, &app_id, &state, RolePermissions::ExecuteBoards);
+    let sub = permission.identifier();
+
+    let permit = acquire_execution(&state, &user).await?;
+
+    let run = start_run(
+        &state,
+        &sub,
+        RunRequest {
+            app_id,
+            board_id,
+            version: params.version,
+            event: None,
+            payload: RunPayload {
+                id: params.node_id,
+                payload: params.payload,
+            },
+            stream_state: params.stream_state.unwrap_or(false),
+            permit,
+            chained_from: None,
+        },
+    )
+    .await?;
+
+    Ok(respond(&state, run, query.mode))
+}
+
+#[tracing::instrument(
+    name = "GET /apps/{app_id}/board/{board_id}/execute/{run_id}",
+    skip(state, user)
+)]
+pub async fn get_board_run(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path((app_id, board_id, run_id)): Path<(String, String, String)>,
+) -> Result<Json<AsyncRunStatus>, ApiError> {
+    ensure_permission!(user, &app_id, &state, RolePermissions::ExecuteBoards);
+
+    let status = get_async_run(&state, &app_id, &run_id)
+        .filter(|status| status.board_id == board_id)
+        .ok_or(ApiError::NotFound)?;
+
+    Ok(Json(status))
+}
@@ -10,9 +10,10 @@ use axum::{
 };
 use flow_like::flow::board::commands::GenericCommand;
 use flow_like_types::sync::Mutex;
+use schemars::JsonSchema;
 use serde::Deserialize;
 
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Deserialize, JsonSchema)]
 pub struct ExecuteCommandsBody {
     pub commands: Vec<GenericCommand>,
 }
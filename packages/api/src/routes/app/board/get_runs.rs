@@ -0,0 +1,34 @@
+use crate::{
+    credentials::CredentialsAccess, ensure_permission, error::ApiError, middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions, state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+};
+use flow_like::flow::execution::query::{RunFilter, RunPage};
+
+#[tracing::instrument(name = "GET /apps/{app_id}/board/{board_id}/runs", skip(state, user))]
+pub async fn get_runs(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path((app_id, board_id)): Path<(String, String)>,
+    Query(filter): Query<RunFilter>,
+) -> Result<Json<RunPage>, ApiError> {
+    let permission = ensure_permission!(user, &app_id, &state, RolePermissions::ReadLogs);
+    let sub = permission.identifier();
+
+    if let Err(err) = filter.to_filter() {
+        return Err(ApiError::BadRequest(err.to_string()));
+    }
+
+    let flow_state = state
+        .scoped_credentials(&sub, &app_id, CredentialsAccess::ReadLogs)
+        .await?
+        .to_state(state.clone())
+        .await?;
+
+    let page = flow_state.query_runs(&app_id, &board_id, &filter).await?;
+
+    Ok(Json(page))
+}
@@ -0,0 +1,37 @@
+use crate::{
+    credentials::CredentialsAccess, ensure_permission, error::ApiError, middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions, state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+};
+use flow_like::flow::execution::{log::LogMessage, query::LogFilter};
+
+#[tracing::instrument(
+    name = "GET /apps/{app_id}/board/{board_id}/runs/{run_id}/logs",
+    skip(state, user)
+)]
+pub async fn query_logs(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path((app_id, board_id, run_id)): Path<(String, String, String)>,
+    Query(filter): Query<LogFilter>,
+) -> Result<Json<Vec<LogMessage>>, ApiError> {
+    let permission = ensure_permission!(user, &app_id, &state, RolePermissions::ReadLogs);
+    let sub = permission.identifier();
+
+    let flow_state = state
+        .scoped_credentials(&sub, &app_id, CredentialsAccess::ReadLogs)
+        .await?
+        .to_state(state.clone())
+        .await?;
+
+    let meta = flow_state
+        .get_run_meta(&app_id, &board_id, &run_id)
+        .await
+        .map_err(|_| ApiError::NotFound)?;
+    let logs = flow_state.query_run_logs(&meta, &filter).await?;
+
+    Ok(Json(logs))
+}
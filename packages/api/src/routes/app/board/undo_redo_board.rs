@@ -6,14 +6,9 @@ use axum::{
     Extension, Json,
     extract::{Path, State},
 };
-use flow_like::flow::board::commands::GenericCommand;
 use flow_like_types::anyhow;
-use serde::Deserialize;
 
-#[derive(Clone, Deserialize)]
-pub struct ExecuteCommandsBody {
-    pub commands: Vec<GenericCommand>,
-}
+use super::execute_commands::ExecuteCommandsBody;
 
 #[tracing::instrument(
     name = "PATCH /apps/{app_id}/board/{board_id}/undo",
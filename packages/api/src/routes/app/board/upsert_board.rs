@@ -10,9 +10,10 @@ use flow_like::flow::{
     board::{Board, ExecutionStage},
     execution::LogLevel,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Deserialize, JsonSchema)]
 pub struct UpsertBoard {
     pub name: Option<String>,
     pub description: Option<String>,
@@ -21,7 +22,7 @@ pub struct UpsertBoard {
     pub template: Option<Board>,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
 pub struct UpsertBoardResponse {
     pub id: String,
 }
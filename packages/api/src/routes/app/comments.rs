@@ -0,0 +1,64 @@
+use axum::{
+    Router,
+    routing::{delete, get},
+};
+use flow_like_types::anyhow;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel,
+    QueryFilter, sea_query::Expr,
+};
+
+use crate::{entity::app, state::AppState};
+
+pub mod get_comments;
+pub mod remove_comment;
+pub mod upsert_comment;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(get_comments::get_comments).put(upsert_comment::upsert_comment),
+        )
+        .route("/{comment_id}", delete(remove_comment::remove_comment))
+}
+
+/// Moves the rating aggregates of the app and refreshes its average and relevance score.
+pub async fn update_app_rating<C: ConnectionTrait>(
+    db: &C,
+    app_id: &str,
+    sum_delta: i64,
+    count_delta: i64,
+) -> flow_like_types::Result<()> {
+    if sum_delta == 0 && count_delta == 0 {
+        return Ok(());
+    }
+
+    app::Entity::update_many()
+        .col_expr(
+            app::Column::RatingSum,
+            Expr::col(app::Column::RatingSum).add(sum_delta),
+        )
+        .col_expr(
+            app::Column::RatingCount,
+            Expr::col(app::Column::RatingCount).add(count_delta),
+        )
+        .filter(app::Column::Id.eq(app_id))
+        .exec(db)
+        .await?;
+
+    let model = app::Entity::find_by_id(app_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| anyhow!("App {} not found", app_id))?;
+
+    let mut scored = flow_like::app::App::from(model.clone());
+    scored.calculate_relevance_score();
+
+    let mut model = model.into_active_model();
+    model.avg_rating = Set(scored.avg_rating);
+    model.relevance_score = Set(scored.relevance_score);
+    model.update(db).await?;
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use crate::{
+    ensure_in_project, entity::comment, error::ApiError, middleware::jwt::AppUser, state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct CommentQuery {
+    /// Replies of this comment, top level comments (ratings) if not set
+    pub parent_id: Option<String>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[tracing::instrument(name = "GET /apps/{app_id}/comments", skip(state, user))]
+pub async fn get_comments(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path(app_id): Path<String>,
+    Query(query): Query<CommentQuery>,
+) -> Result<Json<Vec<comment::Model>>, ApiError> {
+    ensure_in_project!(user, &app_id, &state);
+
+    let select = comment::Entity::find().filter(comment::Column::AppId.eq(&app_id));
+    let select = match &query.parent_id {
+        Some(parent_id) => select
+            .filter(comment::Column::ParentId.eq(parent_id))
+            .order_by_asc(comment::Column::CreatedAt),
+        None => select
+            .filter(comment::Column::ParentId.is_null())
+            .order_by_desc(comment::Column::CreatedAt),
+    };
+
+    let comments = select
+        .limit(Some(query.limit.unwrap_or(50).min(100)))
+        .offset(query.offset)
+        .all(&state.db)
+        .await?;
+
+    Ok(Json(comments))
+}
@@ -0,0 +1,49 @@
+use crate::{
+    ensure_in_project, entity::comment, error::ApiError, middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions, routes::app::comments::update_app_rating,
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use sea_orm::{ColumnTrait, EntityTrait, ModelTrait, QueryFilter, TransactionTrait};
+
+/// Authors can remove their comments, `ModerateComments` allows removing any comment.
+/// Replies are removed with their parent.
+#[tracing::instrument(
+    name = "DELETE /apps/{app_id}/comments/{comment_id}",
+    skip(state, user)
+)]
+pub async fn remove_comment(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path((app_id, comment_id)): Path<(String, String)>,
+) -> Result<Json<()>, ApiError> {
+    let permission = ensure_in_project!(user, &app_id, &state);
+
+    let txn = state.db.begin().await?;
+
+    let comment = comment::Entity::find_by_id(&comment_id)
+        .filter(comment::Column::AppId.eq(&app_id))
+        .one(&txn)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    let is_author = permission.sub.as_deref() == Some(comment.user_id.as_str());
+    if !is_author && !permission.has_permission(RolePermissions::ModerateComments) {
+        return Err(ApiError::Forbidden);
+    }
+
+    let rated = comment.parent_id.is_none();
+    let rating = comment.rating as i64;
+    comment.delete(&txn).await?;
+
+    if rated {
+        update_app_rating(&txn, &app_id, -rating, -1).await?;
+    }
+
+    txn.commit().await?;
+
+    Ok(Json(()))
+}
@@ -0,0 +1,125 @@
+use crate::{
+    ensure_in_project,
+    entity::{app, comment},
+    error::ApiError,
+    middleware::jwt::AppUser,
+    routes::app::comments::update_app_rating,
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use flow_like_types::create_id;
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    QuerySelect, TransactionTrait,
+};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, JsonSchema)]
+pub struct UpsertCommentBody {
+    /// Replies to this comment, the reply carries no rating
+    pub parent_id: Option<String>,
+    pub text: String,
+    /// 1 to 5, required for top level comments
+    pub rating: Option<i32>,
+}
+
+/// Top level comments are the user's rating of the app, there is one per user and app.
+/// Replies can be posted multiple times.
+#[tracing::instrument(name = "PUT /apps/{app_id}/comments", skip(state, user, body))]
+pub async fn upsert_comment(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path(app_id): Path<String>,
+    Json(body): Json<UpsertCommentBody>,
+) -> Result<Json<comment::Model>, ApiError> {
+    let permission = ensure_in_project!(user, &app_id, &state);
+    let sub = permission.sub()?;
+
+    if body.text.trim().is_empty() {
+        return Err(ApiError::BadRequest("Comment text is empty".to_string()));
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    let txn = state.db.begin().await?;
+
+    if let Some(parent_id) = &body.parent_id {
+        comment::Entity::find_by_id(parent_id)
+            .filter(comment::Column::AppId.eq(&app_id))
+            .one(&txn)
+            .await?
+            .ok_or(ApiError::NotFound)?;
+
+        let reply = comment::ActiveModel {
+            id: Set(create_id()),
+            text: Set(body.text),
+            rating: Set(0),
+            user_id: Set(sub),
+            app_id: Set(Some(app_id)),
+            template_id: Set(None),
+            parent_id: Set(Some(parent_id.clone())),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+        let reply = reply.insert(&txn).await?;
+        txn.commit().await?;
+        return Ok(Json(reply));
+    }
+
+    let rating = body
+        .rating
+        .filter(|rating| (1..=5).contains(rating))
+        .ok_or_else(|| ApiError::BadRequest("Rating has to be between 1 and 5".to_string()))?;
+
+    // Serializes concurrent ratings of the app, the second one then finds and updates the first
+    // instead of inserting another top level comment. Prisma can not express the partial unique
+    // index on (userId, appId) that would make this unnecessary.
+    app::Entity::find_by_id(&app_id)
+        .lock_exclusive()
+        .one(&txn)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    let existing = comment::Entity::find()
+        .filter(comment::Column::AppId.eq(&app_id))
+        .filter(comment::Column::UserId.eq(&sub))
+        .filter(comment::Column::ParentId.is_null())
+        .one(&txn)
+        .await?;
+
+    let comment = match existing {
+        Some(existing) => {
+            let previous_rating = existing.rating;
+            let mut comment = existing.into_active_model();
+            comment.text = Set(body.text);
+            comment.rating = Set(rating);
+            comment.updated_at = Set(now);
+            let comment = comment.update(&txn).await?;
+            update_app_rating(&txn, &app_id, (rating - previous_rating) as i64, 0).await?;
+            comment
+        }
+        None => {
+            let comment = comment::ActiveModel {
+                id: Set(create_id()),
+                text: Set(body.text),
+                rating: Set(rating),
+                user_id: Set(sub),
+                app_id: Set(Some(app_id.clone())),
+                template_id: Set(None),
+                parent_id: Set(None),
+                created_at: Set(now),
+                updated_at: Set(now),
+            };
+            let comment = comment.insert(&txn).await?;
+            update_app_rating(&txn, &app_id, rating as i64, 1).await?;
+            comment
+        }
+    };
+
+    txn.commit().await?;
+
+    Ok(Json(comment))
+}
@@ -1,15 +1,21 @@
 use crate::{
-    ensure_permission, error::ApiError, middleware::jwt::AppUser,
-    permission::role_permission::RolePermissions, state::AppState,
+    audit::{Audit, ClientIp},
+    ensure_permission,
+    entity::sea_orm_active_enums::AuditAction,
+    error::ApiError,
+    middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions,
+    state::AppState,
 };
 use axum::{
     Extension, Json,
     extract::{Path, State},
 };
-use flow_like_types::anyhow;
+use flow_like_types::{anyhow, json::json};
 use futures_util::{StreamExt, TryStreamExt};
+use schemars::JsonSchema;
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, JsonSchema)]
 pub struct DeleteFilesPayload {
     pub prefixes: Vec<String>,
 }
@@ -18,6 +24,7 @@ pub struct DeleteFilesPayload {
 pub async fn delete_files(
     State(state): State<AppState>,
     Extension(user): Extension<AppUser>,
+    ip: ClientIp,
     Path(app_id): Path<String>,
     Json(payload): Json<DeleteFilesPayload>,
 ) -> Result<Json<()>, ApiError> {
@@ -51,5 +58,10 @@ pub async fn delete_files(
             .map_err(|e| anyhow!("Failed to delete path: {}", e))?;
     }
 
+    Audit::new(&app_id, AuditAction::FilesDeleted)
+        .before(&json!({ "prefixes": payload.prefixes }))
+        .record(&state.db, Some(sub), &ip)
+        .await?;
+
     Ok(Json(()))
 }
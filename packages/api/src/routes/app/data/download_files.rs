@@ -9,10 +9,11 @@ use axum::{
     extract::{Path, State},
 };
 use flow_like_types::{Value, create_id, json};
+use schemars::JsonSchema;
 
 const MAX_PREFIXES: usize = 100;
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, JsonSchema)]
 pub struct DownloadFilesPayload {
     pub prefixes: Vec<String>,
 }
@@ -8,8 +8,9 @@ use axum::{
 };
 use flow_like_storage::files::store::StorageItem;
 use flow_like_types::anyhow;
+use schemars::JsonSchema;
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, JsonSchema)]
 pub struct ListFilesPayload {
     pub prefix: String,
 }
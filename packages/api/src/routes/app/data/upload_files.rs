@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use crate::{
-    ensure_permission, error::ApiError, middleware::jwt::AppUser,
+    ensure_permission, error::ApiError, limits::ensure_storage, middleware::jwt::AppUser,
     permission::role_permission::RolePermissions, state::AppState,
 };
 use axum::{
@@ -9,10 +9,11 @@ use axum::{
     extract::{Path, State},
 };
 use flow_like_types::{Value, create_id, json};
+use schemars::JsonSchema;
 
 const MAX_PREFIXES: usize = 100;
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, JsonSchema)]
 pub struct UploadFilesPayload {
     pub prefixes: Vec<String>,
 }
@@ -25,6 +26,7 @@ pub async fn upload_files(
     Json(payload): Json<UploadFilesPayload>,
 ) -> Result<Json<Vec<Value>>, ApiError> {
     ensure_permission!(user, &app_id, &state, RolePermissions::WriteFiles);
+    ensure_storage(&state, &user).await?;
 
     let sub = user.sub()?;
 
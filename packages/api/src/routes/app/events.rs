@@ -1,5 +1,7 @@
 pub mod delete_event;
+pub mod execute_event;
 pub mod get_event;
+pub mod get_event_contract;
 pub mod get_event_versions;
 pub mod get_events;
 pub mod upsert_event;
@@ -26,9 +28,18 @@ pub fn routes() -> Router<AppState> {
             "/{event_id}/versions",
             get(get_event_versions::get_event_versions),
         )
+        .route(
+            "/{event_id}/contract",
+            get(get_event_contract::get_event_contract),
+        )
+        .route("/{event_id}/execute", post(execute_event::execute_event))
+        .route(
+            "/{event_id}/execute/{run_id}",
+            get(execute_event::get_event_run),
+        )
         .route("/{event_id}/validate", post(validate_event::validate_event))
         .route(
-            "/{board_id}/feedback",
+            "/{event_id}/feedback",
             put(upsert_event_feedback::upsert_event_feedback),
         )
 }
@@ -0,0 +1,96 @@
+use crate::{
+    ensure_permission,
+    error::ApiError,
+    execution::{AsyncRunStatus, ExecutionQuery, RunRequest, get_async_run, respond, start_run},
+    limits::acquire_execution,
+    middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions,
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    response::Response,
+};
+use flow_like::flow::execution::RunPayload;
+use flow_like_types::Value;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, JsonSchema)]
+pub struct ExecuteEventBody {
+    pub payload: Option<Value>,
+    pub version: Option<(u32, u32, u32)>,
+    pub stream_state: Option<bool>,
+}
+
+#[tracing::instrument(
+    name = "POST /apps/{app_id}/events/{event_id}/execute",
+    skip(state, user, params)
+)]
+pub async fn execute_event(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path((app_id, event_id)): Path<(String, String)>,
+    Query(query): Query<ExecutionQuery>,
+    Json(params): Json<ExecuteEventBody>,
+) -> Result<Response, ApiError> {
+    let permission = ensure_permission!(user, &app_id, &state, RolePermissions::ExecuteEvents);
+    // API keys have no sub, their runs are attributed to the technical user
+    let sub = permission.identifier();
+
+    let app = state.master_app(&sub, &app_id, &state).await?;
+    // Without an explicit version, the active release decides which event version runs
+    let event = match params.version {
+        Some(version) => app.get_event(&event_id, Some(version)).await?,
+        None => app.get_released_event(&event_id).await?,
+    };
+
+    if !event.active {
+        return Err(ApiError::BadRequest(format!(
+            "Event {} is not active",
+            event_id
+        )));
+    }
+
+    let permit = acquire_execution(&state, &user).await?;
+
+    let run = start_run(
+        &state,
+        &sub,
+        RunRequest {
+            app_id,
+            board_id: event.board_id.clone(),
+            version: event.board_version,
+            payload: RunPayload {
+                id: event.node_id.clone(),
+                payload: params.payload,
+            },
+            event: Some(event),
+            stream_state: params.stream_state.unwrap_or(false),
+            permit,
+            chained_from: None,
+        },
+    )
+    .await?;
+
+    Ok(respond(&state, run, query.mode))
+}
+
+#[tracing::instrument(
+    name = "GET /apps/{app_id}/events/{event_id}/execute/{run_id}",
+    skip(state, user)
+)]
+pub async fn get_event_run(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path((app_id, event_id, run_id)): Path<(String, String, String)>,
+) -> Result<Json<AsyncRunStatus>, ApiError> {
+    ensure_permission!(user, &app_id, &state, RolePermissions::ExecuteEvents);
+
+    let status = get_async_run(&state, &app_id, &run_id)
+        .filter(|status| status.event_id.as_deref() == Some(event_id.as_str()))
+        .ok_or(ApiError::NotFound)?;
+
+    Ok(Json(status))
+}
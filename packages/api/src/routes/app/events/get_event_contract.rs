@@ -0,0 +1,48 @@
+use crate::{
+    ensure_permission, error::ApiError, middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions, state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+};
+use flow_like::flow::event::contract::EventContract;
+use flow_like_types::anyhow;
+
+use super::get_event::VersionQuery;
+
+#[tracing::instrument(
+    name = "GET /apps/{app_id}/events/{event_id}/contract",
+    skip(state, user)
+)]
+pub async fn get_event_contract(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path((app_id, event_id)): Path<(String, String)>,
+    Query(query): Query<VersionQuery>,
+) -> Result<Json<EventContract>, ApiError> {
+    let permission = ensure_permission!(user, &app_id, &state, RolePermissions::ReadEvents);
+    let sub = permission.sub()?;
+
+    let version_opt = if let Some(ver_str) = query.version {
+        let parts = ver_str
+            .split('_')
+            .map(str::parse::<u32>)
+            .collect::<Result<Vec<u32>, _>>()?;
+        match parts.as_slice() {
+            [maj, min, pat] => Some((*maj, *min, *pat)),
+            _ => {
+                return Err(ApiError::InternalError(
+                    anyhow!("version must be in MAJOR_MINOR_PATCH format").into(),
+                ));
+            }
+        }
+    } else {
+        None
+    };
+
+    let app = state.master_app(&sub, &app_id, &state).await?;
+    let contract = app.get_event_contract(&event_id, version_opt).await?;
+
+    Ok(Json(contract))
+}
@@ -7,9 +7,10 @@ use axum::{
     extract::{Path, State},
 };
 use flow_like::flow::{board::VersionType, event::Event};
+use schemars::JsonSchema;
 use serde::Deserialize;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 pub struct EventUpsertBody {
     event: Event,
     version_type: Option<VersionType>,
@@ -1,27 +1,29 @@
 use crate::{
-    ensure_permission, entity::feedback, error::ApiError, middleware::jwt::AppUser,
-    permission::role_permission::RolePermissions, state::AppState,
+    ensure_permission,
+    error::ApiError,
+    middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions,
+    routes::app::feedback::{FeedbackEntry, upsert_feedback_entry},
+    state::AppState,
 };
 use axum::{
     Extension, Json,
     extract::{Path, State},
 };
-use flow_like_types::{Value, create_id};
-use sea_orm::{
-    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
-    TransactionTrait,
-};
+use flow_like_types::Value;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, JsonSchema)]
 pub struct FeedbackBody {
     pub rating: i32,
     pub context: Option<Value>,
     pub comment: String,
     pub feedback_id: String,
+    pub run_id: Option<String>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, JsonSchema)]
 pub struct FeedbackResponse {
     pub feedback_id: String,
 }
@@ -39,53 +41,22 @@ pub async fn upsert_event_feedback(
     let permission = ensure_permission!(user, &app_id, &state, RolePermissions::ExecuteEvents);
     let sub = permission.sub()?;
 
-    let txn = state.db.begin().await?;
-
-    let existing_feedback = feedback::Entity::find()
-        .filter(feedback::Column::AppId.eq(app_id.clone()))
-        .filter(feedback::Column::EventId.eq(event_id.clone()))
-        .filter(feedback::Column::Id.eq(body.feedback_id.clone()))
-        .one(&txn)
-        .await?;
-
-    if let Some(existing) = existing_feedback {
-        if existing.user_id.as_ref() != Some(&sub) {
-            return Err(ApiError::Forbidden);
-        }
-
-        // Update existing feedback
-        let mut feedback = existing.into_active_model();
-        feedback.context = Set(body.context);
-        feedback.comment = Set(body.comment);
-        feedback.rating = Set(body.rating.clamp(0, 5));
-        feedback.updated_at = Set(chrono::Utc::now().naive_utc());
-
-        feedback.update(&txn).await?;
-        txn.commit().await?;
-        return Ok(Json(FeedbackResponse {
-            feedback_id: body.feedback_id.clone(),
-        }));
-    }
-
-    let id = create_id();
-    let feedback = feedback::Model {
-        id: id.clone(),
-        app_id: Some(app_id.clone()),
-        user_id: Some(sub),
-        event_id: Some(event_id.clone()),
-        context: body.context,
-        comment: body.comment,
-        rating: body.rating.clamp(0, 5),
-        template_id: None,
-        created_at: chrono::Utc::now().naive_utc(),
-        updated_at: chrono::Utc::now().naive_utc(),
-    };
-
-    let mut feedback = feedback::ActiveModel::from(feedback);
-    feedback = feedback.reset_all();
-
-    feedback.insert(&txn).await?;
-    txn.commit().await?;
-
-    Ok(Json(FeedbackResponse { feedback_id: id }))
+    let feedback = upsert_feedback_entry(
+        &state.db,
+        &app_id,
+        &sub,
+        FeedbackEntry {
+            feedback_id: Some(body.feedback_id).filter(|id| !id.is_empty()),
+            event_id: Some(event_id),
+            run_id: body.run_id,
+            rating: body.rating,
+            comment: body.comment,
+            context: body.context,
+        },
+    )
+    .await?;
+
+    Ok(Json(FeedbackResponse {
+        feedback_id: feedback.id,
+    }))
 }
@@ -0,0 +1,98 @@
+use axum::{
+    Router,
+    routing::{delete, get},
+};
+use flow_like_types::{Value, create_id};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel,
+    QueryFilter,
+};
+use serde::Deserialize;
+
+use crate::{entity::feedback, error::ApiError, state::AppState};
+
+pub mod get_feedback;
+pub mod remove_feedback;
+pub mod upsert_feedback;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(get_feedback::get_feedback).put(upsert_feedback::upsert_feedback),
+        )
+        .route("/{feedback_id}", delete(remove_feedback::remove_feedback))
+}
+
+/// Feedback is always created for the calling user, `event_id` and `run_id` pin it to a run.
+pub struct FeedbackEntry {
+    pub feedback_id: Option<String>,
+    pub event_id: Option<String>,
+    pub run_id: Option<String>,
+    pub rating: i32,
+    pub comment: String,
+    pub context: Option<Value>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct FeedbackQuery {
+    pub event_id: Option<String>,
+    pub run_id: Option<String>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// Updates the user's feedback if `feedback_id` points to it, creates new feedback otherwise.
+pub async fn upsert_feedback_entry<C: ConnectionTrait>(
+    db: &C,
+    app_id: &str,
+    sub: &str,
+    entry: FeedbackEntry,
+) -> Result<feedback::Model, ApiError> {
+    let now = chrono::Utc::now().naive_utc();
+
+    let existing = match &entry.feedback_id {
+        Some(feedback_id) => {
+            feedback::Entity::find_by_id(feedback_id)
+                .filter(feedback::Column::AppId.eq(app_id))
+                .one(db)
+                .await?
+        }
+        None => None,
+    };
+
+    if let Some(existing) = existing {
+        if existing.user_id.as_deref() != Some(sub) {
+            return Err(ApiError::Forbidden);
+        }
+
+        let mut feedback = existing.into_active_model();
+        feedback.context = Set(entry.context);
+        feedback.comment = Set(entry.comment);
+        feedback.rating = Set(entry.rating.clamp(0, 5));
+        if entry.event_id.is_some() {
+            feedback.event_id = Set(entry.event_id);
+        }
+        if entry.run_id.is_some() {
+            feedback.run_id = Set(entry.run_id);
+        }
+        feedback.updated_at = Set(now);
+        return Ok(feedback.update(db).await?);
+    }
+
+    let feedback = feedback::ActiveModel {
+        id: Set(create_id()),
+        app_id: Set(Some(app_id.to_string())),
+        user_id: Set(Some(sub.to_string())),
+        event_id: Set(entry.event_id),
+        run_id: Set(entry.run_id),
+        context: Set(entry.context),
+        comment: Set(entry.comment),
+        rating: Set(entry.rating.clamp(0, 5)),
+        template_id: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+
+    Ok(feedback.insert(db).await?)
+}
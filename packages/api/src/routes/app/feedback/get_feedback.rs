@@ -0,0 +1,42 @@
+use crate::{
+    ensure_in_project, entity::feedback, error::ApiError, middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions, routes::app::feedback::FeedbackQuery,
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+
+/// All feedback of the app with `ReadAnalytics`, otherwise only the own feedback.
+#[tracing::instrument(name = "GET /apps/{app_id}/feedback", skip(state, user))]
+pub async fn get_feedback(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path(app_id): Path<String>,
+    Query(query): Query<FeedbackQuery>,
+) -> Result<Json<Vec<feedback::Model>>, ApiError> {
+    let permission = ensure_in_project!(user, &app_id, &state);
+
+    let mut select = feedback::Entity::find().filter(feedback::Column::AppId.eq(&app_id));
+
+    if !permission.has_permission(RolePermissions::ReadAnalytics) {
+        select = select.filter(feedback::Column::UserId.eq(permission.sub()?));
+    }
+    if let Some(event_id) = &query.event_id {
+        select = select.filter(feedback::Column::EventId.eq(event_id));
+    }
+    if let Some(run_id) = &query.run_id {
+        select = select.filter(feedback::Column::RunId.eq(run_id));
+    }
+
+    let feedback = select
+        .order_by_desc(feedback::Column::CreatedAt)
+        .limit(Some(query.limit.unwrap_or(50).min(100)))
+        .offset(query.offset)
+        .all(&state.db)
+        .await?;
+
+    Ok(Json(feedback))
+}
@@ -0,0 +1,36 @@
+use crate::{
+    ensure_in_project, entity::feedback, error::ApiError, middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions, state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use sea_orm::{ColumnTrait, EntityTrait, ModelTrait, QueryFilter};
+
+#[tracing::instrument(
+    name = "DELETE /apps/{app_id}/feedback/{feedback_id}",
+    skip(state, user)
+)]
+pub async fn remove_feedback(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path((app_id, feedback_id)): Path<(String, String)>,
+) -> Result<Json<()>, ApiError> {
+    let permission = ensure_in_project!(user, &app_id, &state);
+
+    let feedback = feedback::Entity::find_by_id(&feedback_id)
+        .filter(feedback::Column::AppId.eq(&app_id))
+        .one(&state.db)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    let is_author = permission.sub.is_some() && feedback.user_id == permission.sub;
+    if !is_author && !permission.has_permission(RolePermissions::ModerateComments) {
+        return Err(ApiError::Forbidden);
+    }
+
+    feedback.delete(&state.db).await?;
+
+    Ok(Json(()))
+}
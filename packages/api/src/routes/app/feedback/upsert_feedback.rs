@@ -0,0 +1,60 @@
+use crate::{
+    ensure_in_project,
+    entity::feedback,
+    error::ApiError,
+    middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions,
+    routes::app::feedback::{FeedbackEntry, upsert_feedback_entry},
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use flow_like_types::Value;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, JsonSchema)]
+pub struct UpsertFeedbackBody {
+    pub feedback_id: Option<String>,
+    pub event_id: Option<String>,
+    pub run_id: Option<String>,
+    pub rating: i32,
+    pub comment: String,
+    pub context: Option<Value>,
+}
+
+/// Lets anyone who can run the app rate or report a run.
+#[tracing::instrument(name = "PUT /apps/{app_id}/feedback", skip(state, user, body))]
+pub async fn upsert_feedback(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path(app_id): Path<String>,
+    Json(body): Json<UpsertFeedbackBody>,
+) -> Result<Json<feedback::Model>, ApiError> {
+    let permission = ensure_in_project!(user, &app_id, &state);
+    if !permission.has_permission(RolePermissions::ExecuteEvents)
+        && !permission.has_permission(RolePermissions::ExecuteBoards)
+    {
+        return Err(ApiError::Forbidden);
+    }
+    let sub = permission.sub()?;
+
+    let feedback = upsert_feedback_entry(
+        &state.db,
+        &app_id,
+        &sub,
+        FeedbackEntry {
+            feedback_id: body.feedback_id,
+            event_id: body.event_id,
+            run_id: body.run_id,
+            rating: body.rating,
+            comment: body.comment,
+            context: body.context,
+        },
+    )
+    .await?;
+
+    Ok(Json(feedback))
+}
@@ -1,26 +1,29 @@
 use crate::{
+    audit::{Audit, ClientIp},
     ensure_permission,
     entity::{
-        app, membership, publication_log, publication_request, sea_orm_active_enums::Visibility,
+        app, membership, publication_request,
+        sea_orm_active_enums::{AuditAction, PublicationRequestStatus, Visibility},
     },
     error::ApiError,
     middleware::jwt::AppUser,
     permission::role_permission::RolePermissions,
+    routes::app::publication::{log_publication, request_publication, requires_review},
     state::AppState,
 };
 use axum::{
     Extension, Json,
     extract::{Path, State},
 };
-use flow_like_types::create_id;
+use flow_like_types::json::json;
+use schemars::JsonSchema;
 use sea_orm::{
-    ActiveModelTrait,
-    ActiveValue::{NotSet, Set},
-    ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, TransactionTrait,
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
 pub struct UpdateVisibilityBody {
     pub visibility: Visibility,
 }
@@ -31,14 +34,14 @@ pub struct UpdateVisibilityBody {
 /// - From Public Request Join to Public (no restrictions)
 ///
 /// - From Prototype to Private (all users except the owner are removed)
-/// - From Prototype to Public (goes to review)
-/// - From Prototype to Public Request Join (goes to review)
+/// - To Public or Public Request Join from anything else (goes to review, applies an accepted request)
 /// - From Public to Prototype (requires review -> might be a paid app for example)
 /// - From Public Request Join to Prototype (requires review -> might be a paid app for example)
 #[tracing::instrument(name = "PATCH /apps/{app_id}/visibility", skip(state, user, body))]
 pub async fn change_visibility(
     State(state): State<AppState>,
     Extension(user): Extension<AppUser>,
+    ip: ClientIp,
     Path(app_id): Path<String>,
     Json(body): Json<UpdateVisibilityBody>,
 ) -> Result<Json<()>, ApiError> {
@@ -62,6 +65,10 @@ pub async fn change_visibility(
         return Ok(Json(()));
     }
 
+    let audit = Audit::new(&app_id, AuditAction::VisibilityChanged)
+        .target(&app_id)
+        .before(&json!({ "visibility": app.visibility }));
+
     // The user should be able to switch between Prototype and Private visibility without restrictions
     if matches!(app.visibility, Visibility::Private | Visibility::Prototype)
         && matches!(body.visibility, Visibility::Private | Visibility::Prototype)
@@ -72,14 +79,20 @@ pub async fn change_visibility(
         app.update(&txn).await?;
 
         // If the visibility is changed to Private, remove all other users
+        let mut removed_members = 0;
         if body.visibility == Visibility::Private {
-            membership::Entity::delete_many()
+            removed_members = membership::Entity::delete_many()
                 .filter(membership::Column::AppId.eq(&app_id))
                 .filter(membership::Column::UserId.ne(sub.clone()))
                 .exec(&txn)
-                .await?;
+                .await?
+                .rows_affected;
         }
 
+        audit
+            .after(&json!({ "visibility": body.visibility, "removed_members": removed_members }))
+            .record(&txn, Some(sub), &ip)
+            .await?;
         txn.commit().await?;
         return Ok(Json(()));
     }
@@ -96,47 +109,57 @@ pub async fn change_visibility(
         app.updated_at = Set(chrono::Utc::now().naive_utc());
 
         app.update(&txn).await?;
+        audit
+            .after(&json!({ "visibility": body.visibility }))
+            .record(&txn, Some(sub), &ip)
+            .await?;
         txn.commit().await?;
 
         return Ok(Json(()));
     }
 
-    if app.visibility == Visibility::Prototype
-        && matches!(
-            body.visibility,
-            Visibility::Public | Visibility::PublicRequestAccess
-        )
-    {
-        let old_visibility = app.visibility.clone();
-        let mut updated_app = app.into_active_model();
-        updated_app.updated_at = Set(chrono::Utc::now().naive_utc());
-
-        let request = publication_request::ActiveModel {
-            id: Set(create_id()),
-            app_id: Set(app_id.clone()),
-            approver_id: NotSet,
-            target_visibility: Set(body.visibility.clone()),
-            status: Set(crate::entity::sea_orm_active_enums::PublicationRequestStatus::Pending),
-            created_at: Set(chrono::Utc::now().naive_utc()),
-            updated_at: Set(chrono::Utc::now().naive_utc()),
+    if requires_review(&body.visibility) {
+        let approved = publication_request::Entity::find()
+            .filter(publication_request::Column::AppId.eq(&app_id))
+            .filter(publication_request::Column::Status.eq(PublicationRequestStatus::Accepted))
+            .filter(publication_request::Column::TargetVisibility.eq(body.visibility.clone()))
+            .filter(publication_request::Column::AppliedAt.is_null())
+            .one(&txn)
+            .await?;
+
+        let Some(approved) = approved else {
+            request_publication(&txn, &app_id, &sub, app.visibility, body.visibility, None).await?;
+            txn.commit().await?;
+            return Ok(Json(()));
         };
 
-        let request = request.insert(&txn).await?;
+        let old_visibility = app.visibility.clone();
+        let now = chrono::Utc::now().naive_utc();
+        let mut updated_app = app.into_active_model();
+        updated_app.visibility = Set(body.visibility.clone());
+        updated_app.updated_at = Set(now);
+        updated_app.update(&txn).await?;
 
-        let log_entry = publication_log::ActiveModel {
-            id: Set(create_id()),
-            author_id: Set(Some(sub.clone())),
-            request_id: Set(request.id),
-            message: Set(Some("Request initiated".to_string())),
-            visibility: Set(Some(old_visibility)),
-            created_at: Set(chrono::Utc::now().naive_utc()),
-            updated_at: Set(chrono::Utc::now().naive_utc()),
-        };
+        let request_id = approved.id.clone();
+        let mut approved = approved.into_active_model();
+        approved.applied_at = Set(Some(now));
+        approved.updated_at = Set(now);
+        approved.update(&txn).await?;
+
+        log_publication(
+            &txn,
+            &request_id,
+            Some(sub.clone()),
+            Some(format!("Visibility changed from {:?}", old_visibility)),
+            Some(body.visibility.clone()),
+        )
+        .await?;
 
-        log_entry.insert(&txn).await?;
-        updated_app.update(&txn).await?;
+        audit
+            .after(&json!({ "visibility": body.visibility, "publication_request": request_id }))
+            .record(&txn, Some(sub), &ip)
+            .await?;
         txn.commit().await?;
-
         return Ok(Json(()));
     }
 
@@ -17,6 +17,7 @@ use axum::{
 };
 use flow_like::{app::App, bit::Metadata};
 use flow_like_types::{anyhow, create_id, sync::Mutex};
+use schemars::JsonSchema;
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue::{NotSet, Set},
@@ -25,7 +26,7 @@ use sea_orm::{
 };
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AppUpsertBody {
     pub app: Option<App>,
     pub meta: Option<Metadata>,
@@ -3,6 +3,7 @@ use std::time::Duration;
 use crate::{
     entity::meta,
     error::ApiError,
+    limits::ensure_storage,
     middleware::jwt::AppUser,
     routes::app::meta::{MediaItem, MediaQuery, MetaMode},
     state::AppState,
@@ -13,9 +14,10 @@ use axum::{
 };
 use flow_like_storage::Path as FlowPath;
 use flow_like_types::{anyhow, create_id};
+use schemars::JsonSchema;
 use sea_orm::{ActiveModelTrait, ActiveValue::Set, TransactionTrait};
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, serde::Serialize, JsonSchema)]
 pub struct PushMediaResponse {
     pub signed_url: String,
 }
@@ -29,6 +31,7 @@ pub async fn push_media(
 ) -> Result<Json<PushMediaResponse>, ApiError> {
     let mode = MetaMode::from_media_query(&query, &app_id);
     mode.ensure_write_permission(&user, &app_id, &state).await?;
+    ensure_storage(&state, &user).await?;
     let language = query.language.as_deref().unwrap_or("en");
 
     let txn = state.db.begin().await?;
@@ -0,0 +1,149 @@
+use axum::{Router, routing::get};
+use flow_like_types::create_id;
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+use serde::Serialize;
+
+use crate::{
+    entity::{
+        publication_log, publication_request,
+        sea_orm_active_enums::{PublicationRequestStatus, Visibility},
+    },
+    openapi::Record,
+    state::AppState,
+};
+
+pub mod get_publication;
+pub mod upsert_publication;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route(
+        "/",
+        get(get_publication::get_publication).put(upsert_publication::upsert_publication),
+    )
+}
+
+#[derive(Serialize, Debug, JsonSchema)]
+pub struct PublicationRequestDetails {
+    #[serde(flatten)]
+    #[schemars(with = "Record<publication_request::Model>")]
+    pub request: publication_request::Model,
+    #[schemars(with = "Vec<Record<publication_log::Model>>")]
+    pub logs: Vec<publication_log::Model>,
+}
+
+/// Visibilities that list the app in the store and therefore need an accepted request.
+pub fn requires_review(visibility: &Visibility) -> bool {
+    matches!(
+        visibility,
+        Visibility::Public | Visibility::PublicRequestAccess
+    )
+}
+
+pub async fn log_publication<C: ConnectionTrait>(
+    db: &C,
+    request_id: &str,
+    author_id: Option<String>,
+    message: Option<String>,
+    visibility: Option<Visibility>,
+) -> flow_like_types::Result<publication_log::Model> {
+    let now = chrono::Utc::now().naive_utc();
+    let log_entry = publication_log::ActiveModel {
+        id: Set(create_id()),
+        request_id: Set(request_id.to_string()),
+        author_id: Set(author_id),
+        message: Set(message),
+        visibility: Set(visibility),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+    Ok(log_entry.insert(db).await?)
+}
+
+/// Opens a review request for the target visibility. An open request of the app is
+/// retargeted instead, so there is at most one pending request per app.
+pub async fn request_publication<C: ConnectionTrait>(
+    db: &C,
+    app_id: &str,
+    sub: &str,
+    current: Visibility,
+    target: Visibility,
+    message: Option<String>,
+) -> flow_like_types::Result<publication_request::Model> {
+    let now = chrono::Utc::now().naive_utc();
+    let open = publication_request::Entity::find()
+        .filter(publication_request::Column::AppId.eq(app_id))
+        .filter(publication_request::Column::Status.is_in([
+            PublicationRequestStatus::Pending,
+            PublicationRequestStatus::OnHold,
+        ]))
+        .one(db)
+        .await?;
+
+    let (request, default_message) = match open {
+        Some(open) => {
+            let mut request: publication_request::ActiveModel = open.into();
+            request.target_visibility = Set(target);
+            request.status = Set(PublicationRequestStatus::Pending);
+            request.owner_seen = Set(true);
+            request.updated_at = Set(now);
+            (request.update(db).await?, "Request updated")
+        }
+        None => {
+            let request = publication_request::ActiveModel {
+                id: Set(create_id()),
+                app_id: Set(app_id.to_string()),
+                approver_id: Set(None),
+                target_visibility: Set(target),
+                status: Set(PublicationRequestStatus::Pending),
+                applied_at: Set(None),
+                owner_seen: Set(true),
+                created_at: Set(now),
+                updated_at: Set(now),
+            };
+            (request.insert(db).await?, "Request initiated")
+        }
+    };
+
+    log_publication(
+        db,
+        &request.id,
+        Some(sub.to_string()),
+        Some(message.unwrap_or_else(|| default_message.to_string())),
+        Some(current),
+    )
+    .await?;
+
+    Ok(request)
+}
+
+pub async fn publication_details<C: ConnectionTrait>(
+    db: &C,
+    requests: Vec<publication_request::Model>,
+) -> flow_like_types::Result<Vec<PublicationRequestDetails>> {
+    let request_ids = requests
+        .iter()
+        .map(|request| request.id.clone())
+        .collect::<Vec<_>>();
+
+    let mut logs = publication_log::Entity::find()
+        .filter(publication_log::Column::RequestId.is_in(request_ids))
+        .order_by_asc(publication_log::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    Ok(requests
+        .into_iter()
+        .map(|request| {
+            let (request_logs, rest) = logs.drain(..).partition(|log| log.request_id == request.id);
+            logs = rest;
+            PublicationRequestDetails {
+                request,
+                logs: request_logs,
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,44 @@
+use crate::{
+    ensure_permission,
+    entity::publication_request,
+    error::ApiError,
+    middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions,
+    routes::app::publication::{PublicationRequestDetails, publication_details},
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, sea_query::Expr};
+
+/// Review requests of the app with their logs, newest first. Marks decisions as seen by the owner.
+#[tracing::instrument(name = "GET /apps/{app_id}/publication", skip(state, user))]
+pub async fn get_publication(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path(app_id): Path<String>,
+) -> Result<Json<Vec<PublicationRequestDetails>>, ApiError> {
+    let permission = ensure_permission!(user, &app_id, &state, RolePermissions::Admin);
+
+    let requests = publication_request::Entity::find()
+        .filter(publication_request::Column::AppId.eq(&app_id))
+        .order_by_desc(publication_request::Column::CreatedAt)
+        .all(&state.db)
+        .await?;
+
+    if permission.permissions.contains(RolePermissions::Owner)
+        && requests.iter().any(|request| !request.owner_seen)
+    {
+        publication_request::Entity::update_many()
+            .col_expr(publication_request::Column::OwnerSeen, Expr::value(true))
+            .filter(publication_request::Column::AppId.eq(&app_id))
+            .exec(&state.db)
+            .await?;
+    }
+
+    let requests = publication_details(&state.db, requests).await?;
+
+    Ok(Json(requests))
+}
@@ -0,0 +1,69 @@
+use crate::{
+    ensure_permission,
+    entity::{app, publication_request, sea_orm_active_enums::Visibility},
+    error::ApiError,
+    middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions,
+    routes::app::publication::{request_publication, requires_review},
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use schemars::JsonSchema;
+use sea_orm::{EntityTrait, TransactionTrait};
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, JsonSchema)]
+pub struct PublicationBody {
+    pub visibility: Visibility,
+    pub message: Option<String>,
+}
+
+/// Asks the admins to review a visibility change, see `change_visibility` for how it is applied.
+#[tracing::instrument(name = "PUT /apps/{app_id}/publication", skip(state, user, body))]
+pub async fn upsert_publication(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path(app_id): Path<String>,
+    Json(body): Json<PublicationBody>,
+) -> Result<Json<publication_request::Model>, ApiError> {
+    ensure_permission!(user, &app_id, &state, RolePermissions::Owner);
+    let sub = user.sub()?;
+
+    if !requires_review(&body.visibility) {
+        return Err(ApiError::BadRequest(format!(
+            "{:?} does not require a review",
+            body.visibility
+        )));
+    }
+
+    let txn = state.db.begin().await?;
+
+    let app = app::Entity::find_by_id(&app_id)
+        .one(&txn)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    if app.visibility == body.visibility {
+        return Err(ApiError::BadRequest(format!(
+            "App already has visibility {:?}",
+            body.visibility
+        )));
+    }
+
+    let request = request_publication(
+        &txn,
+        &app_id,
+        &sub,
+        app.visibility,
+        body.visibility,
+        body.message,
+    )
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(Json(request))
+}
@@ -0,0 +1,28 @@
+use axum::{
+    Router,
+    routing::{get, post},
+};
+
+use crate::state::AppState;
+
+pub mod activate_release;
+pub mod delete_release;
+pub mod get_releases;
+pub mod upsert_release;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_releases::get_releases))
+        .route("/state", get(get_releases::get_release_state))
+        .route("/rollback", post(activate_release::rollback_release))
+        .route(
+            "/{release_id}",
+            get(get_releases::get_release)
+                .put(upsert_release::upsert_release)
+                .delete(delete_release::delete_release),
+        )
+        .route(
+            "/{release_id}/activate",
+            post(activate_release::activate_release),
+        )
+}
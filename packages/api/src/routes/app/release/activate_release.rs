@@ -0,0 +1,66 @@
+use crate::{
+    ensure_permission, error::ApiError, middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions, state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use flow_like::app::release::ReleaseState;
+
+#[tracing::instrument(
+    name = "POST /apps/{app_id}/releases/{release_id}/activate",
+    skip(state, user)
+)]
+pub async fn activate_release(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path((app_id, release_id)): Path<(String, String)>,
+) -> Result<Json<ReleaseState>, ApiError> {
+    let permission = ensure_permission!(user, &app_id, &state, RolePermissions::WriteEvents);
+    let sub = permission.sub()?;
+
+    let app = state
+        .scoped_app(
+            &sub,
+            &app_id,
+            &state,
+            crate::credentials::CredentialsAccess::EditApp,
+        )
+        .await?;
+
+    app.get_release(&release_id)
+        .await
+        .map_err(|_| ApiError::NotFound)?;
+    let release_state = app.activate_release(&release_id).await?;
+
+    Ok(Json(release_state))
+}
+
+#[tracing::instrument(name = "POST /apps/{app_id}/releases/rollback", skip(state, user))]
+pub async fn rollback_release(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path(app_id): Path<String>,
+) -> Result<Json<ReleaseState>, ApiError> {
+    let permission = ensure_permission!(user, &app_id, &state, RolePermissions::WriteEvents);
+    let sub = permission.sub()?;
+
+    let app = state
+        .scoped_app(
+            &sub,
+            &app_id,
+            &state,
+            crate::credentials::CredentialsAccess::EditApp,
+        )
+        .await?;
+
+    if app.release_state().await?.history.is_empty() {
+        return Err(ApiError::BadRequest(
+            "No previous release to roll back to".to_string(),
+        ));
+    }
+    let release_state = app.rollback_release().await?;
+
+    Ok(Json(release_state))
+}
@@ -0,0 +1,42 @@
+use crate::{
+    ensure_permission, error::ApiError, middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions, state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+
+#[tracing::instrument(
+    name = "DELETE /apps/{app_id}/releases/{release_id}",
+    skip(state, user)
+)]
+pub async fn delete_release(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path((app_id, release_id)): Path<(String, String)>,
+) -> Result<Json<()>, ApiError> {
+    let permission = ensure_permission!(user, &app_id, &state, RolePermissions::WriteEvents);
+    let sub = permission.sub()?;
+
+    let app = state
+        .scoped_app(
+            &sub,
+            &app_id,
+            &state,
+            crate::credentials::CredentialsAccess::EditApp,
+        )
+        .await?;
+
+    let release_state = app.release_state().await?;
+    if release_state.active.as_deref() == Some(release_id.as_str()) {
+        return Err(ApiError::BadRequest(format!(
+            "Release {} is active, activate another release first",
+            release_id
+        )));
+    }
+
+    app.delete_release(&release_id).await?;
+
+    Ok(Json(()))
+}
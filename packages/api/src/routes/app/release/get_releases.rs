@@ -0,0 +1,78 @@
+use crate::{
+    ensure_permission, error::ApiError, middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions, state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use flow_like::app::release::{Release, ReleaseState};
+
+#[tracing::instrument(name = "GET /apps/{app_id}/releases", skip(state, user))]
+pub async fn get_releases(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path(app_id): Path<String>,
+) -> Result<Json<Vec<Release>>, ApiError> {
+    let permission = ensure_permission!(user, &app_id, &state, RolePermissions::ReadEvents);
+    let sub = permission.sub()?;
+
+    let app = state
+        .scoped_app(
+            &sub,
+            &app_id,
+            &state,
+            crate::credentials::CredentialsAccess::ReadApp,
+        )
+        .await?;
+    let releases = app.get_releases().await?;
+
+    Ok(Json(releases))
+}
+
+#[tracing::instrument(name = "GET /apps/{app_id}/releases/{release_id}", skip(state, user))]
+pub async fn get_release(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path((app_id, release_id)): Path<(String, String)>,
+) -> Result<Json<Release>, ApiError> {
+    let permission = ensure_permission!(user, &app_id, &state, RolePermissions::ReadEvents);
+    let sub = permission.sub()?;
+
+    let app = state
+        .scoped_app(
+            &sub,
+            &app_id,
+            &state,
+            crate::credentials::CredentialsAccess::ReadApp,
+        )
+        .await?;
+    let release = app
+        .get_release(&release_id)
+        .await
+        .map_err(|_| ApiError::NotFound)?;
+
+    Ok(Json(release))
+}
+
+#[tracing::instrument(name = "GET /apps/{app_id}/releases/state", skip(state, user))]
+pub async fn get_release_state(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path(app_id): Path<String>,
+) -> Result<Json<ReleaseState>, ApiError> {
+    let permission = ensure_permission!(user, &app_id, &state, RolePermissions::ReadEvents);
+    let sub = permission.sub()?;
+
+    let app = state
+        .scoped_app(
+            &sub,
+            &app_id,
+            &state,
+            crate::credentials::CredentialsAccess::ReadApp,
+        )
+        .await?;
+    let release_state = app.release_state().await?;
+
+    Ok(Json(release_state))
+}
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use crate::{
+    ensure_permission, error::ApiError, middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions, state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use flow_like::{
+    app::release::{Release, ReleaseEvent},
+    flow::event::ReleaseNotes,
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ReleaseUpsertBody {
+    /// Required for new releases, must be greater than the latest release
+    pub version: Option<(u32, u32, u32)>,
+    pub notes: Option<ReleaseNotes>,
+    #[serde(default)]
+    pub boards: HashMap<String, (u32, u32, u32)>,
+    #[serde(default)]
+    pub events: Vec<ReleaseEvent>,
+}
+
+/// Creates the release, existing releases are immutable apart from their notes.
+#[tracing::instrument(
+    name = "PUT /apps/{app_id}/releases/{release_id}",
+    skip(state, user, params)
+)]
+pub async fn upsert_release(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path((app_id, release_id)): Path<(String, String)>,
+    Json(params): Json<ReleaseUpsertBody>,
+) -> Result<Json<Release>, ApiError> {
+    let permission = ensure_permission!(user, &app_id, &state, RolePermissions::WriteEvents);
+    let sub = permission.sub()?;
+
+    let app = state
+        .scoped_app(
+            &sub,
+            &app_id,
+            &state,
+            crate::credentials::CredentialsAccess::EditApp,
+        )
+        .await?;
+
+    if let Ok(existing) = app.get_release(&release_id).await {
+        let changes_pins = params.version.is_some_and(|v| v != existing.version)
+            || (!params.boards.is_empty() && params.boards != existing.boards)
+            || !params.events.is_empty();
+        if changes_pins {
+            return Err(ApiError::BadRequest(format!(
+                "Release {} is immutable, only its notes can be changed",
+                release_id
+            )));
+        }
+
+        let release = app.update_release_notes(&release_id, params.notes).await?;
+        return Ok(Json(release));
+    }
+
+    let version = params
+        .version
+        .ok_or_else(|| ApiError::BadRequest("Missing release version".to_string()))?;
+
+    let release = app
+        .create_release(
+            &release_id,
+            version,
+            params.notes,
+            params.boards,
+            params.events,
+        )
+        .await
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    Ok(Json(release))
+}
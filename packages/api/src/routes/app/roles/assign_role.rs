@@ -1,6 +1,7 @@
 use crate::{
+    audit::{Audit, ClientIp},
     ensure_permission,
-    entity::{app, membership, role},
+    entity::{app, membership, role, sea_orm_active_enums::AuditAction},
     error::ApiError,
     middleware::jwt::AppUser,
     permission::role_permission::RolePermissions,
@@ -10,7 +11,7 @@ use axum::{
     Extension, Json,
     extract::{Path, State},
 };
-use flow_like_types::anyhow;
+use flow_like_types::{anyhow, json::json};
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, TransactionTrait, prelude::Expr};
 
 #[tracing::instrument(
@@ -20,6 +21,7 @@ use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, TransactionTrait, prelude::
 pub async fn assign_role(
     State(state): State<AppState>,
     Extension(user): Extension<AppUser>,
+    ip: ClientIp,
     Path((app_id, role_id, sub)): Path<(String, String, String)>,
 ) -> Result<Json<()>, ApiError> {
     ensure_permission!(user, &app_id, &state, RolePermissions::Admin);
@@ -140,6 +142,19 @@ pub async fn assign_role(
                 ));
             }
 
+            Audit::new(&app_id, AuditAction::RoleAssigned)
+                .target(&sub)
+                .before(&json!({ "role_id": target_current_role.id }))
+                .after(&json!({ "role_id": target_role.id }))
+                .record(&txn, Some(caller_sub.clone()), &ip)
+                .await?;
+            Audit::new(&app_id, AuditAction::RoleAssigned)
+                .target(&caller_sub)
+                .before(&json!({ "role_id": caller_role.id }))
+                .after(&json!({ "role_id": new_role_for_owner.id }))
+                .record(&txn, Some(caller_sub.clone()), &ip)
+                .await?;
+
             txn.commit().await?;
             return Ok(Json(()));
         }
@@ -165,6 +180,13 @@ pub async fn assign_role(
         .exec(&txn)
         .await?;
 
+    Audit::new(&app_id, AuditAction::RoleAssigned)
+        .target(&sub)
+        .before(&json!({ "role_id": target_current_role.id }))
+        .after(&json!({ "role_id": target_role.id }))
+        .record(&txn, Some(called_sub), &ip)
+        .await?;
+
     txn.commit().await?;
     Ok(Json(()))
 }
@@ -1,6 +1,7 @@
 use crate::{
+    audit::{Audit, ClientIp},
     ensure_permission,
-    entity::{app, membership, role},
+    entity::{app, membership, role, sea_orm_active_enums::AuditAction},
     error::ApiError,
     middleware::jwt::AppUser,
     permission::role_permission::RolePermissions,
@@ -18,9 +19,10 @@ use sea_orm::{
 pub async fn delete_role(
     State(state): State<AppState>,
     Extension(user): Extension<AppUser>,
+    ip: ClientIp,
     Path((app_id, role_id)): Path<(String, String)>,
 ) -> Result<Json<()>, ApiError> {
-    ensure_permission!(user, &app_id, &state, RolePermissions::Admin);
+    let caller = ensure_permission!(user, &app_id, &state, RolePermissions::Admin);
 
     let txn = state.db.begin().await?;
 
@@ -52,13 +54,20 @@ pub async fn delete_role(
         return Err(ApiError::Forbidden);
     }
 
-    membership::Entity::update_many()
-        .filter(membership::Column::AppId.eq(app_id))
+    let reassigned = membership::Entity::update_many()
+        .filter(membership::Column::AppId.eq(&app_id))
         .filter(membership::Column::RoleId.eq(role_id))
         .col_expr(membership::Column::RoleId, Expr::value(default_role_id))
         .exec(&txn)
         .await?;
 
+    Audit::new(&app_id, AuditAction::RoleDeleted)
+        .target(&role.id)
+        .before(&role)
+        .after(&flow_like_types::json::json!({ "reassigned_members": reassigned.rows_affected }))
+        .record(&txn, Some(caller.identifier()), &ip)
+        .await?;
+
     let role: role::ActiveModel = role.into();
     role.delete(&txn).await?;
 
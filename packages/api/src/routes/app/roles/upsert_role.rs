@@ -1,6 +1,11 @@
 use crate::{
-    ensure_permission, entity::role, error::ApiError, middleware::jwt::AppUser,
-    permission::role_permission::RolePermissions, state::AppState,
+    audit::{Audit, ClientIp},
+    ensure_permission,
+    entity::{role, sea_orm_active_enums::AuditAction},
+    error::ApiError,
+    middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions,
+    state::AppState,
 };
 use axum::{
     Extension, Json,
@@ -13,10 +18,11 @@ use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Transacti
 pub async fn upsert_role(
     State(state): State<AppState>,
     Extension(user): Extension<AppUser>,
+    ip: ClientIp,
     Path((app_id, role_id)): Path<(String, String)>,
     Json(mut payload): Json<role::Model>,
 ) -> Result<Json<()>, ApiError> {
-    ensure_permission!(user, &app_id, &state, RolePermissions::Admin);
+    let caller = ensure_permission!(user, &app_id, &state, RolePermissions::Admin);
     let permission = RolePermissions::from_bits(payload.permissions).ok_or(ApiError::Forbidden)?;
 
     let txn = state.db.begin().await?;
@@ -29,6 +35,9 @@ pub async fn upsert_role(
         .await?;
 
     if let Some(role) = role {
+        let audit = Audit::new(&app_id, AuditAction::RoleUpserted)
+            .target(&role.id)
+            .before(&role);
         let permission = RolePermissions::from_bits(role.permissions).ok_or(ApiError::Forbidden)?;
 
         payload.id = role.id;
@@ -47,7 +56,11 @@ pub async fn upsert_role(
 
         let payload: role::ActiveModel = payload.into();
         let payload = payload.reset_all();
-        payload.update(&txn).await?;
+        let updated = payload.update(&txn).await?;
+        audit
+            .after(&updated)
+            .record(&txn, Some(caller.identifier()), &ip)
+            .await?;
         txn.commit().await?;
 
         return Ok(Json(()));
@@ -65,7 +78,12 @@ pub async fn upsert_role(
 
     let role: role::ActiveModel = payload.into();
     let role = role.reset_all();
-    role.insert(&txn).await?;
+    let created = role.insert(&txn).await?;
+    Audit::new(&app_id, AuditAction::RoleUpserted)
+        .target(&created.id)
+        .after(&created)
+        .record(&txn, Some(caller.identifier()), &ip)
+        .await?;
     txn.commit().await?;
 
     Ok(Json(()))
@@ -7,10 +7,11 @@ use axum::{
     extract::{Path, State},
 };
 use flow_like_types::create_id;
+use schemars::JsonSchema;
 use sea_orm::ActiveModelTrait;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CreateInviteLinkPayload {
     pub name: Option<String>,
     pub max_uses: Option<i32>,
@@ -1,6 +1,10 @@
 use crate::{
+    audit::{Audit, ClientIp},
     ensure_permission,
-    entity::{app, invitation, membership, meta, sea_orm_active_enums::Visibility},
+    entity::{
+        app, invitation, membership, meta,
+        sea_orm_active_enums::{AuditAction, Visibility},
+    },
     error::ApiError,
     middleware::jwt::AppUser,
     permission::role_permission::RolePermissions,
@@ -11,12 +15,13 @@ use axum::{
     extract::{Path, State},
 };
 use flow_like_types::{anyhow, create_id};
+use schemars::JsonSchema;
 use sea_orm::{
     ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter,
     TransactionTrait,
 };
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, JsonSchema)]
 pub struct InviteUserParams {
     pub message: Option<String>,
     pub sub: String,
@@ -26,6 +31,7 @@ pub struct InviteUserParams {
 pub async fn invite_user(
     State(state): State<AppState>,
     Extension(user): Extension<AppUser>,
+    ip: ClientIp,
     Path(app_id): Path<String>,
     Json(params): Json<InviteUserParams>,
 ) -> Result<Json<()>, ApiError> {
@@ -144,7 +150,12 @@ pub async fn invite_user(
         description: Set(meta.as_ref().and_then(|m| m.description.clone())),
     };
 
-    invitation.insert(&txn).await?;
+    let invitation = invitation.insert(&txn).await?;
+    Audit::new(&app_id, AuditAction::UserInvited)
+        .target(&invitation.user_id)
+        .after(&invitation)
+        .record(&txn, Some(permission.identifier()), &ip)
+        .await?;
     txn.commit().await?;
 
     Ok(Json(()))
@@ -1,6 +1,10 @@
 use crate::{
+    audit::{Audit, ClientIp},
     ensure_permission,
-    entity::{app, join_queue, membership, sea_orm_active_enums::Visibility},
+    entity::{
+        app, join_queue, membership,
+        sea_orm_active_enums::{AuditAction, Visibility},
+    },
     error::ApiError,
     middleware::jwt::AppUser,
     permission::role_permission::RolePermissions,
@@ -23,9 +27,10 @@ use sea_orm::{
 pub async fn accept_join_request(
     State(state): State<AppState>,
     Extension(user): Extension<AppUser>,
+    ip: ClientIp,
     Path((app_id, request_id)): Path<(String, String)>,
 ) -> Result<Json<()>, ApiError> {
-    ensure_permission!(user, &app_id, &state, RolePermissions::Admin);
+    let permission = ensure_permission!(user, &app_id, &state, RolePermissions::Admin);
 
     let max_prototypes = state.platform_config.max_users_prototype.unwrap_or(-1);
 
@@ -103,10 +108,19 @@ pub async fn accept_join_request(
         updated_at: Set(chrono::Utc::now().naive_utc()),
     };
 
+    let audit = Audit::new(&app_id, AuditAction::JoinRequestAccepted)
+        .target(&request.user_id)
+        .before(&request);
+
     let request: join_queue::ActiveModel = request.into();
 
     request.delete(&txn).await?;
-    membership.insert(&txn).await?;
+    let membership = membership.insert(&txn).await?;
+
+    audit
+        .after(&membership)
+        .record(&txn, Some(permission.identifier()), &ip)
+        .await?;
 
     txn.commit().await?;
 
@@ -127,9 +141,10 @@ pub async fn accept_join_request(
 pub async fn reject_join_request(
     State(state): State<AppState>,
     Extension(user): Extension<AppUser>,
+    ip: ClientIp,
     Path((app_id, request_id)): Path<(String, String)>,
 ) -> Result<Json<()>, ApiError> {
-    ensure_permission!(user, &app_id, &state, RolePermissions::Admin);
+    let permission = ensure_permission!(user, &app_id, &state, RolePermissions::Admin);
 
     let txn = state.db.begin().await?;
     let request = join_queue::Entity::find()
@@ -139,6 +154,12 @@ pub async fn reject_join_request(
         .await?
         .ok_or_else(|| ApiError::NotFound)?;
 
+    Audit::new(&app_id, AuditAction::JoinRequestRejected)
+        .target(&request.user_id)
+        .before(&request)
+        .record(&txn, Some(permission.identifier()), &ip)
+        .await?;
+
     let request: join_queue::ActiveModel = request.into();
     request.delete(&txn).await?;
     txn.commit().await?;
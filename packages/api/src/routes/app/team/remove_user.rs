@@ -1,6 +1,7 @@
 use crate::{
+    audit::{Audit, ClientIp},
     ensure_permission,
-    entity::{membership, role},
+    entity::{membership, role, sea_orm_active_enums::AuditAction},
     error::ApiError,
     middleware::jwt::AppUser,
     permission::role_permission::RolePermissions,
@@ -17,6 +18,7 @@ use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Transacti
 pub async fn remove_user(
     State(state): State<AppState>,
     Extension(user): Extension<AppUser>,
+    ip: ClientIp,
     Path((app_id, sub)): Path<(String, String)>,
 ) -> Result<Json<()>, ApiError> {
     let caller_sub = user.sub()?;
@@ -52,6 +54,12 @@ pub async fn remove_user(
         }
     }
 
+    Audit::new(&app_id, AuditAction::UserRemoved)
+        .target(&sub)
+        .before(&membership)
+        .record(&txn, Some(caller_sub), &ip)
+        .await?;
+
     let membership: membership::ActiveModel = membership.into();
     membership.delete(&txn).await?;
 
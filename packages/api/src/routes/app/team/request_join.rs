@@ -9,11 +9,12 @@ use axum::{
     extract::{Path, State},
 };
 use flow_like_types::create_id;
+use schemars::JsonSchema;
 use sea_orm::{
     ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter, TransactionTrait,
 };
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize, JsonSchema)]
 pub struct RequestJoinParams {
     pub comment: Option<String>,
 }
@@ -12,10 +12,11 @@ use axum::{
 };
 use flow_like::flow::board::VersionType;
 use flow_like_types::create_id;
+use schemars::JsonSchema;
 use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter};
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize, JsonSchema)]
 pub struct TemplateUpsert {
     pub changelog: Option<String>,
     pub version_type: Option<VersionType>,
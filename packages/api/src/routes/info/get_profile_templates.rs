@@ -32,6 +32,7 @@ impl From<template_profile::Model> for Profile {
             hub: model.hub,
             hubs: model.hubs.unwrap_or_default(),
             interests: model.interests.unwrap_or_default(),
+            remote_endpoints: vec![],
             settings: Settings::default(),
             tags: model.tags.unwrap_or_default(),
             theme: model.theme,
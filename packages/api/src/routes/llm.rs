@@ -0,0 +1,15 @@
+use axum::{
+    Router,
+    routing::{get, post},
+};
+
+use crate::state::AppState;
+
+pub mod invoke;
+pub mod usage;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/invoke", post(invoke::invoke_llm))
+        .route("/usage", get(usage::get_usage))
+}
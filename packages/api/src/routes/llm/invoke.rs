@@ -0,0 +1,262 @@
+use std::{
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    ensure_permission,
+    entity::{llm_usage_tracking, provider_proxy, sea_orm_active_enums::LlmProvider},
+    error::ApiError,
+    limits::ensure_llm_quota,
+    middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions,
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    body::Body,
+    extract::{Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use flow_like_types::{Value, anyhow, create_id, json::json, reqwest, tokio};
+use futures_util::StreamExt;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter};
+use serde::Deserialize;
+
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+
+#[derive(Debug, Deserialize)]
+pub struct InvokeQuery {
+    /// Attributes the usage to an app, requires `InvokeApi` in that app
+    pub app_id: Option<String>,
+}
+
+/// OpenAI compatible chat completion endpoint. `model` is the id of the bit,
+/// which is resolved to the hosted provider through `ProviderProxy`.
+#[tracing::instrument(name = "POST /llm/invoke", skip(state, user, request))]
+pub async fn invoke_llm(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Query(query): Query<InvokeQuery>,
+    Json(mut request): Json<Value>,
+) -> Result<Response, ApiError> {
+    let sub = user.sub()?;
+
+    if let Some(app_id) = &query.app_id {
+        ensure_permission!(user, app_id, &state, RolePermissions::InvokeApi);
+    }
+
+    let bit_id = request
+        .get("model")
+        .and_then(Value::as_str)
+        .ok_or_else(|| ApiError::BadRequest("Missing model".to_string()))?
+        .to_string();
+
+    let proxy = provider_proxy::Entity::find()
+        .filter(provider_proxy::Column::BitId.eq(&bit_id))
+        .one(&state.db)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    let tier = user.tier(&state).await?;
+    if let Some(model_tier) = &proxy.tier {
+        if !tier.llm_tiers.contains(model_tier) {
+            return Err(ApiError::Forbidden);
+        }
+    }
+    ensure_llm_quota(&state, &sub, &tier).await?;
+
+    let stream = request
+        .get("stream")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    request["model"] = Value::String(proxy.model_id.clone());
+    if stream {
+        request["stream_options"] = json!({ "include_usage": true });
+    }
+
+    let usage = UsageRecord {
+        state: state.clone(),
+        model_id: bit_id,
+        user_id: sub,
+        app_id: query.app_id,
+        started: Instant::now(),
+    };
+
+    let response = chat_completions(&proxy)?
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| anyhow!("Failed to reach LLM provider: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        tracing::warn!("LLM provider responded with {}: {}", status, body);
+        let status = StatusCode::from_u16(status.as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+        return Ok((status, body).into_response());
+    }
+
+    if !stream {
+        let body: Value = response.json().await?;
+        let (token_in, token_out) = token_usage(&body);
+        usage.record(token_in, token_out).await?;
+        return Ok(Json(body).into_response());
+    }
+
+    let mut tracker = StreamUsage {
+        usage: Some(usage),
+        scanner: UsageScanner::default(),
+    };
+    let body = response.bytes_stream().map(move |chunk| {
+        if let Ok(bytes) = &chunk {
+            tracker.scanner.feed(bytes);
+        }
+        chunk
+    });
+
+    Ok(Response::builder()
+        .header(header::CONTENT_TYPE, "text/event-stream")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .body(Body::from_stream(body))
+        .map_err(|e| anyhow!("Failed to build response: {}", e))?)
+}
+
+fn chat_completions(
+    proxy: &provider_proxy::Model,
+) -> flow_like_types::Result<reqwest::RequestBuilder> {
+    let token = proxy.token.clone().unwrap_or_default();
+
+    if proxy.provider == LlmProvider::AzureOpenai {
+        let endpoint = proxy
+            .endpoint
+            .as_deref()
+            .ok_or_else(|| anyhow!("Azure OpenAI proxy {} has no endpoint", proxy.id))?;
+        let url = format!(
+            "{}/openai/deployments/{}/chat/completions",
+            endpoint.trim_end_matches('/'),
+            proxy.model_id
+        );
+        let version = proxy.version.as_deref().unwrap_or("2024-10-21");
+        return Ok(CLIENT
+            .post(url)
+            .query(&[("api-version", version)])
+            .header("api-key", token));
+    }
+
+    let endpoint = match (&proxy.endpoint, &proxy.provider) {
+        (Some(endpoint), _) => endpoint.as_str(),
+        (None, LlmProvider::Openai) => "https://api.openai.com/v1",
+        (None, LlmProvider::Anthropic) => "https://api.anthropic.com/v1",
+        (None, LlmProvider::Google) => "https://generativelanguage.googleapis.com/v1beta/openai",
+        (None, LlmProvider::Openrouter) => "https://openrouter.ai/api/v1",
+        (None, _) => return Err(anyhow!("Provider proxy {} has no endpoint", proxy.id)),
+    };
+
+    Ok(CLIENT
+        .post(format!(
+            "{}/chat/completions",
+            endpoint.trim_end_matches('/')
+        ))
+        .bearer_auth(token)
+        .timeout(Duration::from_secs(15 * 60)))
+}
+
+fn token_usage(body: &Value) -> (i64, i64) {
+    let usage = &body["usage"];
+    (
+        usage["prompt_tokens"].as_i64().unwrap_or(0),
+        usage["completion_tokens"].as_i64().unwrap_or(0),
+    )
+}
+
+struct UsageRecord {
+    state: AppState,
+    model_id: String,
+    user_id: String,
+    app_id: Option<String>,
+    started: Instant,
+}
+
+impl UsageRecord {
+    async fn record(self, token_in: i64, token_out: i64) -> flow_like_types::Result<()> {
+        let now = chrono::Utc::now().naive_utc();
+        let usage = llm_usage_tracking::Model {
+            id: create_id(),
+            model_id: self.model_id,
+            token_in: i32::try_from(token_in).unwrap_or(i32::MAX),
+            token_out: i32::try_from(token_out).unwrap_or(i32::MAX),
+            latency: Some(self.started.elapsed().as_secs_f64()),
+            user_id: Some(self.user_id),
+            app_id: self.app_id,
+            created_at: now,
+            updated_at: now,
+        };
+        let usage: llm_usage_tracking::ActiveModel = usage.into();
+        usage.insert(&self.state.db).await?;
+        Ok(())
+    }
+}
+
+/// Records the usage once the stream is dropped, i.e. when it finished or the client disconnected.
+struct StreamUsage {
+    usage: Option<UsageRecord>,
+    scanner: UsageScanner,
+}
+
+impl Drop for StreamUsage {
+    fn drop(&mut self) {
+        let Some(usage) = self.usage.take() else {
+            return;
+        };
+        let (token_in, token_out) = self.scanner.usage.unwrap_or((0, 0));
+        tokio::spawn(async move {
+            if let Err(e) = usage.record(token_in, token_out).await {
+                tracing::error!("Failed to record LLM usage: {:?}", e);
+            }
+        });
+    }
+}
+
+/// Picks the `usage` object out of OpenAI style Server-Sent Events, chunks may split lines.
+#[derive(Default)]
+struct UsageScanner {
+    buffer: Vec<u8>,
+    usage: Option<(i64, i64)>,
+}
+
+impl UsageScanner {
+    fn feed(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+        while let Some(position) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let line = self.buffer.drain(..=position).collect::<Vec<u8>>();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim().strip_prefix("data:") else {
+                continue;
+            };
+            let Ok(event) = flow_like_types::json::from_str::<Value>(data.trim()) else {
+                continue;
+            };
+            if event.get("usage").is_some_and(|usage| !usage.is_null()) {
+                self.usage = Some(token_usage(&event));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scans_usage_across_chunks() {
+        let mut scanner = UsageScanner::default();
+        scanner.feed(b"data: {\"choices\":[{\"delta\":{\"content\":\"Hi\"}}],\"usage\":null}\n\n");
+        assert_eq!(scanner.usage, None);
+
+        scanner.feed(b"data: {\"choices\":[],\"usage\":{\"prompt_tok");
+        scanner.feed(b"ens\":12,\"completion_tokens\":3}}\n\ndata: [DONE]\n\n");
+        assert_eq!(scanner.usage, Some((12, 3)));
+    }
+}
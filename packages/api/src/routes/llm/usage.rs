@@ -0,0 +1,82 @@
+use crate::{
+    ensure_permission, entity::llm_usage_tracking, error::ApiError, middleware::jwt::AppUser,
+    permission::role_permission::RolePermissions, state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+};
+use schemars::JsonSchema;
+use sea_orm::{
+    ColumnTrait, EntityTrait, FromQueryResult, QueryFilter, QuerySelect,
+    sea_query::{Expr, Func},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    /// Defaults to 30 days ago
+    pub from: Option<chrono::NaiveDateTime>,
+    pub to: Option<chrono::NaiveDateTime>,
+    /// Usage of all members of the app instead of the own usage, requires `ReadAnalytics`
+    pub app_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromQueryResult, JsonSchema)]
+pub struct LlmUsage {
+    pub user_id: Option<String>,
+    pub app_id: Option<String>,
+    pub model_id: String,
+    pub calls: i64,
+    pub token_in: i64,
+    pub token_out: i64,
+    /// Seconds
+    pub avg_latency: Option<f64>,
+}
+
+#[tracing::instrument(name = "GET /llm/usage", skip(state, user))]
+pub async fn get_usage(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Query(query): Query<UsageQuery>,
+) -> Result<Json<Vec<LlmUsage>>, ApiError> {
+    let from = query
+        .from
+        .unwrap_or_else(|| chrono::Utc::now().naive_utc() - chrono::Duration::days(30));
+
+    let mut select =
+        llm_usage_tracking::Entity::find().filter(llm_usage_tracking::Column::CreatedAt.gte(from));
+
+    if let Some(to) = query.to {
+        select = select.filter(llm_usage_tracking::Column::CreatedAt.lte(to));
+    }
+
+    select = match &query.app_id {
+        Some(app_id) => {
+            ensure_permission!(user, app_id, &state, RolePermissions::ReadAnalytics);
+            select.filter(llm_usage_tracking::Column::AppId.eq(app_id))
+        }
+        None => select.filter(llm_usage_tracking::Column::UserId.eq(user.sub()?)),
+    };
+
+    let usage = select
+        .select_only()
+        .column(llm_usage_tracking::Column::UserId)
+        .column(llm_usage_tracking::Column::AppId)
+        .column(llm_usage_tracking::Column::ModelId)
+        .column_as(llm_usage_tracking::Column::Id.count(), "calls")
+        .column_as(llm_usage_tracking::Column::TokenIn.sum(), "token_in")
+        .column_as(llm_usage_tracking::Column::TokenOut.sum(), "token_out")
+        .column_as(
+            Func::avg(Expr::col(llm_usage_tracking::Column::Latency)),
+            "avg_latency",
+        )
+        .group_by(llm_usage_tracking::Column::UserId)
+        .group_by(llm_usage_tracking::Column::AppId)
+        .group_by(llm_usage_tracking::Column::ModelId)
+        .into_model::<LlmUsage>()
+        .all(&state.db)
+        .await?;
+
+    Ok(Json(usage))
+}
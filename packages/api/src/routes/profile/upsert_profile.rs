@@ -5,13 +5,14 @@ use axum::{
 };
 use flow_like::profile::{ProfileApp, Settings};
 use flow_like_types::{Value, create_id};
+use schemars::JsonSchema;
 use sea_orm::{
     ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter, sqlx::types::chrono,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::to_value;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct ProfileBody {
     name: Option<String>,
     description: Option<String>,
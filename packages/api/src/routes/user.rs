@@ -1,7 +1,7 @@
 use crate::state::AppState;
 use axum::{
     Router,
-    routing::{get, post},
+    routing::{get, post, put},
 };
 use billing::get_billing_session;
 use info::user_info;
@@ -24,6 +24,7 @@ pub async fn sign_avatar(
     Ok(url.to_string())
 }
 
+pub mod ban_user;
 pub mod billing;
 pub mod get_invites;
 pub mod info;
@@ -31,6 +32,7 @@ pub mod lookup;
 pub mod manage_invite;
 pub mod notifications;
 pub mod templates;
+pub mod transactions;
 pub mod upsert_info;
 
 pub fn routes() -> Router<AppState> {
@@ -42,6 +44,8 @@ pub fn routes() -> Router<AppState> {
         .route("/invites", get(get_invites::get_invites))
         .route("/templates", get(templates::get_templates))
         .route("/notifications", get(notifications::get_notifications))
+        .route("/transactions", get(transactions::get_transactions))
+        .route("/{sub}/status", put(ban_user::ban_user))
         .route(
             "/invites/{invite_id}",
             post(manage_invite::accept_invite).delete(manage_invite::reject_invite),
@@ -0,0 +1,115 @@
+use crate::{
+    entity::{pat, sea_orm_active_enums::UserStatus, user, user_moderation},
+    error::ApiError,
+    middleware::jwt::AppUser,
+    permission::global_permission::GlobalPermission,
+    state::AppState,
+    user_management::UserManagement,
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+};
+use flow_like_types::create_id;
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    TransactionTrait,
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct BanUserBody {
+    /// `Banned`, `Inactive` for a suspension or `Active` to lift a restriction
+    pub status: UserStatus,
+    pub reason: Option<String>,
+    /// Restrictions without an end date are permanent
+    pub until: Option<chrono::NaiveDateTime>,
+}
+
+/// Restricting a user revokes their personal access tokens and identity provider sessions.
+/// Tokens that are still valid are rejected by the JWT middleware.
+#[tracing::instrument(name = "PUT /user/{sub}/status", skip(state, user))]
+pub async fn ban_user(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Path(sub): Path<String>,
+    Json(body): Json<BanUserBody>,
+) -> Result<Json<user::Model>, ApiError> {
+    user.check_global_permission(&state, GlobalPermission::WriteUsers)
+        .await?;
+    let moderator = user.sub()?;
+
+    if moderator == sub {
+        return Err(ApiError::Forbidden);
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    let restricted = body.status != UserStatus::Active;
+    if restricted && body.until.is_some_and(|until| until <= now) {
+        return Err(ApiError::BadRequest(
+            "The end of the restriction has to be in the future".to_string(),
+        ));
+    }
+
+    let txn = state.db.begin().await?;
+
+    let target = user::Entity::find_by_id(&sub)
+        .one(&txn)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    let target_permission = GlobalPermission::from_bits_truncate(target.permission);
+    if target_permission.contains(GlobalPermission::Admin) {
+        tracing::warn!("User {} is trying to moderate the admin {}", moderator, sub);
+        return Err(ApiError::Forbidden);
+    }
+
+    let until = body.until.filter(|_| restricted);
+    let reason = body.reason.filter(|_| restricted);
+
+    let mut target = target.into_active_model();
+    target.status = Set(body.status.clone());
+    target.status_until = Set(until);
+    target.status_reason = Set(reason.clone());
+    target.updated_at = Set(now);
+    let target = target.update(&txn).await?;
+
+    if restricted {
+        pat::Entity::delete_many()
+            .filter(pat::Column::UserId.eq(&sub))
+            .exec(&txn)
+            .await?;
+    }
+
+    user_moderation::ActiveModel {
+        id: Set(create_id()),
+        user_id: Set(sub.clone()),
+        moderator_id: Set(Some(moderator.clone())),
+        status: Set(body.status),
+        reason: Set(reason),
+        until: Set(until),
+        created_at: Set(now),
+    }
+    .insert(&txn)
+    .await?;
+
+    txn.commit().await?;
+    state.blocked_users.invalidate(&sub);
+
+    tracing::info!(
+        "User {} set the status of {} to {:?}",
+        moderator,
+        sub,
+        target.status
+    );
+
+    if restricted {
+        let user_manager = UserManagement::new(&state).await;
+        if let Err(e) = user_manager.sign_out(&sub, &target.username).await {
+            tracing::error!("Failed to revoke the sessions of user {}: {:?}", sub, e);
+        }
+    }
+
+    Ok(Json(target))
+}
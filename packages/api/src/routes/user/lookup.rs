@@ -8,10 +8,11 @@ use axum::{
 };
 use flow_like::hub::Lookup;
 use flow_like_types::Value;
+use schemars::JsonSchema;
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect, sqlx::types::chrono};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct UserLookupResponse {
     id: String,
     email: Option<String>,
@@ -1,11 +1,19 @@
-use crate::{entity::invitation, error::ApiError, middleware::jwt::AppUser, state::AppState};
+use crate::{
+    entity::{app, invitation, membership, publication_request},
+    error::ApiError,
+    middleware::jwt::AppUser,
+    state::AppState,
+};
 use axum::{Extension, Json, extract::State};
-use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use schemars::JsonSchema;
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QuerySelect};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 pub struct NotificationOverview {
     pub invites_count: u64,
+    /// Publication decisions on owned apps the owner has not looked at yet
+    pub publication_updates: u64,
 }
 
 #[tracing::instrument(name = "GET /user/notifications", skip(state, user))]
@@ -16,9 +24,34 @@ pub async fn get_notifications(
     let sub = user.sub()?;
 
     let invites_count = invitation::Entity::find()
-        .filter(invitation::Column::UserId.eq(sub))
+        .filter(invitation::Column::UserId.eq(&sub))
         .count(&state.db)
         .await?;
 
-    Ok(Json(NotificationOverview { invites_count }))
+    let role_ids: Vec<String> = membership::Entity::find()
+        .select_only()
+        .column(membership::Column::RoleId)
+        .filter(membership::Column::UserId.eq(&sub))
+        .into_tuple()
+        .all(&state.db)
+        .await?;
+
+    let owned_apps: Vec<String> = app::Entity::find()
+        .select_only()
+        .column(app::Column::Id)
+        .filter(app::Column::OwnerRoleId.is_in(role_ids))
+        .into_tuple()
+        .all(&state.db)
+        .await?;
+
+    let publication_updates = publication_request::Entity::find()
+        .filter(publication_request::Column::AppId.is_in(owned_apps))
+        .filter(publication_request::Column::OwnerSeen.eq(false))
+        .count(&state.db)
+        .await?;
+
+    Ok(Json(NotificationOverview {
+        invites_count,
+        publication_updates,
+    }))
 }
@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    entity::{execution_usage_tracking, llm_usage_tracking, transaction},
+    error::ApiError,
+    limits::month_start,
+    middleware::jwt::AppUser,
+    openapi::Record,
+    state::AppState,
+};
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+};
+use schemars::JsonSchema;
+use sea_orm::{
+    ColumnTrait, EntityTrait, FromQueryResult, QueryFilter, QueryOrder, QuerySelect, Select,
+    sea_query::{Alias, Expr, Func},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct TransactionQuery {
+    /// Defaults to the start of the current month
+    pub from: Option<chrono::NaiveDateTime>,
+    pub to: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TransactionOverview {
+    #[schemars(with = "Vec<Record<transaction::Model>>")]
+    pub transactions: Vec<transaction::Model>,
+    /// Usage based charges of the period, per app
+    pub usage: Vec<UsageCharge>,
+}
+
+#[derive(Debug, Serialize, Default, JsonSchema)]
+pub struct UsageCharge {
+    pub app_id: Option<String>,
+    pub executions: i64,
+    pub execution_microseconds: i64,
+    pub llm_calls: i64,
+    pub token_in: i64,
+    pub token_out: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct ExecutionUsage {
+    app_id: Option<String>,
+    executions: i64,
+    microseconds: Option<i64>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct LlmUsage {
+    app_id: Option<String>,
+    calls: i64,
+    token_in: Option<i64>,
+    token_out: Option<i64>,
+}
+
+#[tracing::instrument(name = "GET /user/transactions", skip(state, user))]
+pub async fn get_transactions(
+    State(state): State<AppState>,
+    Extension(user): Extension<AppUser>,
+    Query(query): Query<TransactionQuery>,
+) -> Result<Json<TransactionOverview>, ApiError> {
+    let sub = user.sub()?;
+    let from = match query.from {
+        Some(from) => from,
+        None => month_start()?,
+    };
+
+    let mut transactions = transaction::Entity::find()
+        .filter(transaction::Column::UserId.eq(&sub))
+        .filter(transaction::Column::CreatedAt.gte(from));
+    let mut executions = execution_usage_tracking::Entity::find()
+        .filter(execution_usage_tracking::Column::UserId.eq(&sub))
+        .filter(execution_usage_tracking::Column::CreatedAt.gte(from));
+    let mut llm = llm_usage_tracking::Entity::find()
+        .filter(llm_usage_tracking::Column::UserId.eq(&sub))
+        .filter(llm_usage_tracking::Column::CreatedAt.gte(from));
+
+    if let Some(to) = query.to {
+        transactions = transactions.filter(transaction::Column::CreatedAt.lt(to));
+        executions = executions.filter(execution_usage_tracking::Column::CreatedAt.lt(to));
+        llm = llm.filter(llm_usage_tracking::Column::CreatedAt.lt(to));
+    }
+
+    let transactions = transactions
+        .order_by_desc(transaction::Column::CreatedAt)
+        .all(&state.db)
+        .await?;
+
+    let executions = execution_usage(executions)
+        .into_model::<ExecutionUsage>()
+        .all(&state.db)
+        .await?;
+
+    let llm = llm_usage(llm)
+        .into_model::<LlmUsage>()
+        .all(&state.db)
+        .await?;
+
+    let mut usage: BTreeMap<Option<String>, UsageCharge> = BTreeMap::new();
+    for execution in executions {
+        let charge = usage.entry(execution.app_id.clone()).or_default();
+        charge.app_id = execution.app_id;
+        charge.executions = execution.executions;
+        charge.execution_microseconds = execution.microseconds.unwrap_or(0);
+    }
+    for call in llm {
+        let charge = usage.entry(call.app_id.clone()).or_default();
+        charge.app_id = call.app_id;
+        charge.llm_calls = call.calls;
+        charge.token_in = call.token_in.unwrap_or(0);
+        charge.token_out = call.token_out.unwrap_or(0);
+    }
+
+    Ok(Json(TransactionOverview {
+        transactions,
+        usage: usage.into_values().collect(),
+    }))
+}
+
+fn execution_usage(
+    select: Select<execution_usage_tracking::Entity>,
+) -> Select<execution_usage_tracking::Entity> {
+    select
+        .select_only()
+        .column(execution_usage_tracking::Column::AppId)
+        .column_as(execution_usage_tracking::Column::Id.count(), "executions")
+        // SUM(bigint) is NUMERIC on Postgres, which does not decode into i64
+        .column_as(
+            Func::cast_as(
+                Func::sum(Expr::col(execution_usage_tracking::Column::Microseconds)),
+                Alias::new("BIGINT"),
+            ),
+            "microseconds",
+        )
+        .group_by(execution_usage_tracking::Column::AppId)
+}
+
+fn llm_usage(select: Select<llm_usage_tracking::Entity>) -> Select<llm_usage_tracking::Entity> {
+    select
+        .select_only()
+        .column(llm_usage_tracking::Column::AppId)
+        .column_as(llm_usage_tracking::Column::Id.count(), "calls")
+        .column_as(llm_usage_tracking::Column::TokenIn.sum(), "token_in")
+        .column_as(llm_usage_tracking::Column::TokenOut.sum(), "token_out")
+        .group_by(llm_usage_tracking::Column::AppId)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DbBackend, QueryTrait};
+
+    #[test]
+    fn execution_sum_decodes_as_bigint() {
+        let sql = execution_usage(execution_usage_tracking::Entity::find())
+            .build(DbBackend::Postgres)
+            .to_string();
+        assert!(
+            sql.contains(r#"CAST(SUM("microseconds") AS BIGINT) AS "microseconds""#),
+            "{}",
+            sql
+        );
+    }
+}
@@ -6,10 +6,11 @@ use crate::{
 };
 use axum::{Extension, Json, extract::State};
 use flow_like_types::create_id;
+use schemars::JsonSchema;
 use sea_orm::{ActiveModelTrait, ActiveValue::Set, EntityTrait};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 pub struct UpsertInfoBody {
     pub name: Option<String>,
     pub description: Option<String>,
@@ -18,7 +19,7 @@ pub struct UpsertInfoBody {
     pub tutorial_completed: Option<bool>,
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 pub struct UpsertInfoResponse {
     pub signed_url: Option<String>,
 }
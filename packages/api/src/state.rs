@@ -1,9 +1,13 @@
+#[cfg(feature = "aws")]
 use aws_config::SdkConfig;
 use axum::body::Body;
 use flow_like::app::App;
 use flow_like::flow::board::Board;
 use flow_like::flow::node::NodeLogic;
-use flow_like::flow_like_model_provider::provider::{ModelProviderConfiguration, OpenAIConfig};
+use flow_like::flow_like_model_provider::provider::{
+    AnthropicConfig, GeminiConfig, ModelProviderConfiguration, OpenAIConfig,
+};
+use flow_like::flow_like_model_provider::routing::RoutingStrategy;
 use flow_like::flow_like_storage::Path;
 use flow_like::flow_like_storage::files::store::FlowLikeStore;
 use flow_like::hub::{Environment, Hub};
@@ -25,6 +29,8 @@ use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use crate::credentials::{CredentialsAccess, RuntimeCredentials};
 use crate::entity::role;
+use crate::execution::AsyncRunStatus;
+use crate::limits::{InMemoryRateLimitStore, LimitSubject, RateLimitStore};
 
 pub type AppState = Arc<State>;
 
@@ -47,6 +53,10 @@ pub struct State {
     pub state_cache: moka::sync::Cache<String, Arc<Mutex<FlowLikeState>>>,
     pub cdn_bucket: Arc<FlowLikeStore>,
     pub response_cache: moka::sync::Cache<String, Value>,
+    pub run_cache: moka::sync::Cache<String, AsyncRunStatus>,
+    pub rate_limits: Arc<dyn RateLimitStore>,
+    pub limit_cache: moka::sync::Cache<String, LimitSubject>,
+    pub blocked_users: moka::sync::Cache<String, bool>,
 }
 
 impl State {
@@ -83,6 +93,11 @@ impl State {
         };
 
         let mut provider = ModelProviderConfiguration::default();
+        provider.routing.strategy = match std::env::var("MODEL_ROUTING_STRATEGY").as_deref() {
+            Ok("weighted") => RoutingStrategy::Weighted,
+            Ok("least_latency") => RoutingStrategy::LeastLatency,
+            _ => RoutingStrategy::Random,
+        };
 
         let openai_endpoint = std::env::var("OPENAI_ENDPOINT").ok();
         let openai_key = std::env::var("OPENAI_API_KEY").ok();
@@ -93,9 +108,55 @@ impl State {
                 api_key: Some(key),
                 organization: None,
                 proxy: None,
+                weight: None,
+            })
+        }
+
+        if let Ok(key) = std::env::var("ANTHROPIC_API_KEY") {
+            provider.anthropic_config.push(AnthropicConfig {
+                endpoint: std::env::var("ANTHROPIC_ENDPOINT").ok(),
+                api_key: Some(key),
+                version: None,
+                proxy: None,
+                weight: None,
             })
         }
 
+        if let Ok(key) = std::env::var("GEMINI_API_KEY") {
+            provider.gemini_config.push(GeminiConfig {
+                api_key: Some(key),
+                service_account_key: None,
+                project_id: None,
+                location: None,
+                endpoint: None,
+                weight: None,
+            })
+        }
+
+        if let Ok(key) = std::env::var("VERTEX_SERVICE_ACCOUNT_KEY") {
+            provider.gemini_config.push(GeminiConfig {
+                api_key: None,
+                service_account_key: Some(key),
+                project_id: std::env::var("VERTEX_PROJECT_ID").ok(),
+                location: std::env::var("VERTEX_LOCATION").ok(),
+                endpoint: None,
+                weight: None,
+            })
+        }
+
+        #[cfg(feature = "aws")]
+        let aws_client = Arc::new(aws_config::load_from_env().await);
+
+        #[cfg(feature = "aws")]
+        if std::env::var("BEDROCK_ENABLED").is_ok() {
+            provider.bedrock_config.push(
+                flow_like::flow_like_model_provider::provider::BedrockConfig {
+                    config: (*aws_client).clone(),
+                    weight: None,
+                },
+            )
+        }
+
         let config = FlowLikeConfig::new();
         let (http_client, _) = HTTPClient::new();
         let flow_like_state = FlowLikeState::new(config, http_client);
@@ -119,7 +180,7 @@ impl State {
             jwks,
             stripe_client,
             #[cfg(feature = "aws")]
-            aws_client: Arc::new(aws_config::load_from_env().await),
+            aws_client,
             catalog,
             provider: Arc::new(provider),
             registry: Arc::new(registry),
@@ -134,6 +195,19 @@ impl State {
             credentials_cache: cache,
             cdn_bucket,
             response_cache,
+            run_cache: moka::sync::Cache::builder()
+                .max_capacity(100_000)
+                .time_to_live(Duration::from_secs(60 * 60))
+                .build(),
+            rate_limits: Arc::new(InMemoryRateLimitStore::new()),
+            limit_cache: moka::sync::Cache::builder()
+                .max_capacity(100_000)
+                .time_to_live(Duration::from_secs(60))
+                .build(),
+            blocked_users: moka::sync::Cache::builder()
+                .max_capacity(100_000)
+                .time_to_live(Duration::from_secs(60))
+                .build(),
         }
     }
 
@@ -6,6 +6,9 @@ pub mod cognito;
 pub enum UserManagement {
     #[cfg(feature = "cognito")]
     Cognito(cognito::CognitoUserManagement),
+    /// No identity provider to query, the JWT claims are the only source of user attributes
+    #[cfg(not(feature = "cognito"))]
+    Claims,
 }
 
 impl UserManagement {
@@ -18,9 +21,10 @@ impl UserManagement {
         );
 
         #[cfg(not(feature = "cognito"))]
-        flow_like_types::anyhow::bail!(
-            "No user management implementation available for this environment"
-        );
+        {
+            let _ = state;
+            UserManagement::Claims
+        }
     }
 
     pub async fn get_attribute(
@@ -34,6 +38,25 @@ impl UserManagement {
             UserManagement::Cognito(cognito) => {
                 cognito.get_attribute(sub, username, attribute).await
             }
+            #[cfg(not(feature = "cognito"))]
+            UserManagement::Claims => Ok(None),
+        }
+    }
+
+    /// Revokes the sessions (refresh tokens) of the user at the identity provider.
+    pub async fn sign_out(
+        &self,
+        sub: &str,
+        username: &Option<String>,
+    ) -> flow_like_types::Result<()> {
+        match self {
+            #[cfg(feature = "cognito")]
+            UserManagement::Cognito(cognito) => cognito.sign_out(sub, username).await,
+            #[cfg(not(feature = "cognito"))]
+            UserManagement::Claims => {
+                let _ = (sub, username);
+                Ok(())
+            }
         }
     }
 }
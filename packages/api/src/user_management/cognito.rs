@@ -56,4 +56,19 @@ impl CognitoUserManagement {
 
         Ok(None)
     }
+
+    pub async fn sign_out(
+        &self,
+        sub: &str,
+        username: &Option<String>,
+    ) -> flow_like_types::Result<()> {
+        let username = username.clone().unwrap_or_else(|| sub.to_string());
+        self.client
+            .admin_user_global_sign_out()
+            .user_pool_id(self.pool_id.clone())
+            .username(username)
+            .send()
+            .await?;
+        Ok(())
+    }
 }
@@ -1,5 +1,7 @@
 pub mod branch;
+pub mod fallback;
 pub mod find_llm;
+pub mod function;
 pub mod history;
 pub mod invoke;
 pub mod invoke_simple;
@@ -26,6 +28,7 @@ pub async fn register_functions() -> Vec<Arc<dyn NodeLogic>> {
         Arc::new(history::message::push_content::PushContentNode::default()),
         Arc::new(history::message::extract_content::ExtractContentNode::default()),
         Arc::new(history::push_message::PushHistoryMessageNode::default()),
+        Arc::new(history::push_tool_result::PushToolResultNode::default()),
         Arc::new(history::pop_message::PopHistoryMessageNode::default()),
         Arc::new(history::clear::ClearHistoryNode::default()),
         Arc::new(history::get_system::GetSystemPromptNode::default()),
@@ -44,6 +47,8 @@ pub async fn register_functions() -> Vec<Arc<dyn NodeLogic>> {
         Arc::new(branch::LLMBranchNode::default()),
         Arc::new(with_structured_output::LLMWithStructuredOutput::default()),
         Arc::new(with_tools::LLMWithTools::default()),
+        Arc::new(function::LLMAgent::default()),
+        Arc::new(fallback::SetModelFallbackNode::default()),
         Arc::new(make_schema::LLMMakeSchema::default()),
     ];
 
@@ -0,0 +1,84 @@
+use flow_like::{
+    bit::Bit,
+    flow::{
+        execution::context::ExecutionContext,
+        node::{Node, NodeLogic},
+        pin::PinOptions,
+        variable::VariableType,
+    },
+    state::FlowLikeState,
+};
+use flow_like_types::{async_trait, json::json};
+
+#[derive(Default)]
+pub struct SetModelFallbackNode {}
+
+impl SetModelFallbackNode {
+    pub fn new() -> Self {
+        SetModelFallbackNode {}
+    }
+}
+
+#[async_trait]
+impl NodeLogic for SetModelFallbackNode {
+    async fn get_node(&self, _app_state: &FlowLikeState) -> Node {
+        let mut node = Node::new(
+            "ai_generative_set_model_fallback",
+            "Set Model Fallback",
+            "Sets the model that answers when all endpoints of the primary model keep failing",
+            "AI/Generative",
+        );
+        node.add_icon("/flow/icons/struct.svg");
+
+        node.add_input_pin(
+            "exec_in",
+            "Input",
+            "Initiate Execution",
+            VariableType::Execution,
+        );
+
+        node.add_input_pin("model", "Model", "Primary Model", VariableType::Struct)
+            .set_schema::<Bit>()
+            .set_options(PinOptions::new().set_enforce_schema(true).build());
+
+        node.add_input_pin(
+            "fallback",
+            "Fallback",
+            "Model to call when the primary model fails",
+            VariableType::Struct,
+        )
+        .set_schema::<Bit>()
+        .set_options(PinOptions::new().set_enforce_schema(true).build());
+
+        node.add_output_pin(
+            "exec_out",
+            "Output",
+            "Done with the Execution",
+            VariableType::Execution,
+        );
+
+        node.add_output_pin(
+            "model_out",
+            "Model",
+            "Primary Model with the Fallback set",
+            VariableType::Struct,
+        )
+        .set_schema::<Bit>();
+
+        node
+    }
+
+    async fn run(&self, context: &mut ExecutionContext) -> flow_like_types::Result<()> {
+        context.deactivate_exec_pin("exec_out").await?;
+
+        let model: Bit = context.evaluate_pin("model").await?;
+        let fallback: Bit = context.evaluate_pin("fallback").await?;
+
+        let model = model.with_fallback(Some(fallback))?;
+
+        context.set_pin_value("model_out", json!(model)).await?;
+        context.activate_exec_pin("exec_out").await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,311 @@
+use super::with_tools::{
+    STATIC_OUTPUT_PINS, add_mcp_pin, load_mcp_tools, native_tool_calls, prepare_tools,
+    run_tool_branch, run_tool_nodes, sync_tool_pins, tagged_tool_calls, tool_call_message,
+    tool_result_message,
+};
+use crate::utils::json::parse_with_schema::validate_openai_functions_str;
+use flow_like::{
+    bit::Bit,
+    flow::{
+        board::{Board, LayerType},
+        execution::{
+            LogLevel,
+            context::ExecutionContext,
+            internal_node::InternalNode,
+            log::{LogMessage, LogStat},
+        },
+        node::{Node, NodeLogic},
+        pin::{PinOptions, PinType},
+        variable::VariableType,
+    },
+    state::FlowLikeState,
+};
+use flow_like_model_provider::{
+    history::{History, HistoryMessage},
+    response::{Response, Usage},
+};
+use flow_like_types::{
+    JsonSchema, async_trait, bail,
+    json::{self, Deserialize, Serialize},
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// Streamed after every model call so the UI can follow the agent's reasoning.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct AgentStep {
+    pub iteration: u32,
+    pub thought: String,
+    pub tool_calls: Vec<String>,
+    pub usage: Usage,
+}
+
+/// Nodes that start the Function layer named after a tool, those whose exec inputs are not connected.
+fn function_layer_entry_nodes<'a>(board: &'a Board, name: &str) -> Option<Vec<&'a str>> {
+    let layer = board
+        .layers
+        .values()
+        .find(|layer| matches!(layer.r#type, LayerType::Function) && layer.name == name)?;
+
+    let entry_nodes = board
+        .nodes
+        .values()
+        .filter(|node| node.layer.as_deref() == Some(layer.id.as_str()))
+        .filter(|node| {
+            let mut exec_inputs = node
+                .pins
+                .values()
+                .filter(|pin| {
+                    pin.pin_type == PinType::Input && pin.data_type == VariableType::Execution
+                })
+                .peekable();
+            exec_inputs.peek().is_some() && exec_inputs.all(|pin| pin.depends_on.is_empty())
+        })
+        .map(|node| node.id.as_str())
+        .collect();
+
+    Some(entry_nodes)
+}
+
+#[derive(Default)]
+pub struct LLMAgent {}
+
+impl LLMAgent {
+    pub fn new() -> Self {
+        LLMAgent {}
+    }
+}
+
+#[async_trait]
+impl NodeLogic for LLMAgent {
+    async fn get_node(&self, _app_state: &FlowLikeState) -> Node {
+        let mut node = Node::new(
+            "ai_generative_agent",
+            "Agent",
+            "Calls tools in a loop until the model gives a final answer. Tools named after a Function layer run that layer, other flow tools run their exec branch. Both report back through Tool Result, MCP tools are called directly",
+            "AI/Generative",
+        );
+        node.add_icon("/flow/icons/bot-invoke.svg");
+
+        node.add_input_pin("exec_in", "Input", "Trigger Pin", VariableType::Execution);
+
+        node.add_input_pin("model", "Model", "Model", VariableType::Struct)
+            .set_schema::<Bit>()
+            .set_options(PinOptions::new().set_enforce_schema(true).build());
+
+        node.add_input_pin("history", "History", "Chat History", VariableType::Struct)
+            .set_schema::<History>()
+            .set_options(PinOptions::new().set_enforce_schema(true).build());
+
+        node.add_input_pin(
+            "tools",
+            "Tools",
+            "JSON or OpenAI Function Definitions",
+            VariableType::String,
+        )
+        .set_default_value(Some(json::json!("[]")));
+
+        add_mcp_pin(&mut node);
+
+        node.add_input_pin(
+            "max_iterations",
+            "Max Iterations",
+            "Maximum number of model calls before giving up",
+            VariableType::Integer,
+        )
+        .set_default_value(Some(json::json!(10)));
+
+        node.add_input_pin(
+            "token_budget",
+            "Token Budget",
+            "Maximum number of tokens all steps may use, 0 means unlimited",
+            VariableType::Integer,
+        )
+        .set_default_value(Some(json::json!(0)));
+
+        node.add_input_pin(
+            "tool_result",
+            "Tool Result",
+            "Result of the current Tool Call, read after its branch or Function layer finished",
+            VariableType::String,
+        )
+        .set_default_value(Some(json::json!("")));
+
+        node.add_output_pin("exec_done", "Done", "Done Pin", VariableType::Execution);
+
+        node.add_output_pin(
+            "response",
+            "Response",
+            "Final response of the agent",
+            VariableType::Struct,
+        )
+        .set_schema::<Response>()
+        .set_options(PinOptions::new().set_enforce_schema(true).build());
+
+        node.add_output_pin(
+            "history_out",
+            "History",
+            "Chat History including every step of the agent",
+            VariableType::Struct,
+        )
+        .set_schema::<History>()
+        .set_options(PinOptions::new().set_enforce_schema(true).build());
+
+        node.add_output_pin(
+            "tool_args",
+            "Tool Args",
+            "Tool Call Arguments",
+            VariableType::Struct,
+        );
+
+        node.add_output_pin(
+            "tool_call_id",
+            "Tool Call ID",
+            "ID of the current Tool Call",
+            VariableType::String,
+        );
+
+        node.set_long_running(true);
+
+        node
+    }
+
+    async fn run(&self, context: &mut ExecutionContext) -> flow_like_types::Result<()> {
+        context.deactivate_exec_pin("exec_done").await?;
+
+        let model_bit = context.evaluate_pin::<Bit>("model").await?;
+        let mut history_out = context.evaluate_pin::<History>("history").await?;
+        let mut tools_str: String = context.evaluate_pin("tools").await?;
+        let max_iterations: i64 = context.evaluate_pin("max_iterations").await?;
+        let token_budget: i64 = context.evaluate_pin("token_budget").await?;
+
+        let mut functions = validate_openai_functions_str(&tools_str)?;
+        let board = context.try_get_run()?.lock().await.board.clone();
+        let mut layer_tools: HashMap<String, Vec<Arc<InternalNode>>> = HashMap::new();
+        for function in &functions {
+            match function_layer_entry_nodes(&board, &function.name) {
+                Some(entry_nodes) => {
+                    let entry_nodes = entry_nodes
+                        .into_iter()
+                        .filter_map(|id| context.nodes.get(id).cloned())
+                        .collect();
+                    layer_tools.insert(function.name.clone(), entry_nodes);
+                }
+                None => context.deactivate_exec_pin(&function.name).await?,
+            }
+        }
+        let toolbox = load_mcp_tools(context, &mut functions, &mut tools_str).await?;
+
+        let mut history = history_out.clone();
+        let native = prepare_tools(context, &model_bit, &functions, &tools_str, &mut history);
+
+        let model_factory = context.app_state.lock().await.model_factory.clone();
+        let model = model_factory
+            .lock()
+            .await
+            .build(&model_bit, context.app_state.clone())
+            .await?;
+
+        let mut used_tokens: u64 = 0;
+        for iteration in 1..=max_iterations.max(0) as u32 {
+            let mut message =
+                LogMessage::new(&format!("Agent step {}", iteration), LogLevel::Info, None);
+            let response = model.invoke(&history, None).await?;
+            message.end();
+            message.put_stats(LogStat::new(
+                Some(response.usage.prompt_tokens as u64),
+                Some(response.usage.completion_tokens as u64),
+                Some(vec![model_bit.id.clone()]),
+            ));
+            context.log(message);
+            used_tokens += response.usage.total_tokens as u64;
+
+            let reply = response.last_message().cloned().unwrap_or_default();
+            let thought = reply.content.clone().unwrap_or_default();
+            let tool_calls = match native {
+                true => {
+                    Some(native_tool_calls(&functions, &reply)?).filter(|calls| !calls.is_empty())
+                }
+                false => tagged_tool_calls(&functions, &thought)?,
+            };
+
+            context
+                .stream_response(
+                    "agent_step",
+                    AgentStep {
+                        iteration,
+                        thought: thought.clone(),
+                        tool_calls: tool_calls
+                            .iter()
+                            .flatten()
+                            .map(|tool_call| tool_call.call.name.clone())
+                            .collect(),
+                        usage: response.usage.clone(),
+                    },
+                )
+                .await?;
+
+            let Some(tool_calls) = tool_calls else {
+                history_out.push_message(HistoryMessage::from_response(response.clone()));
+                context
+                    .set_pin_value("history_out", json::json!(history_out))
+                    .await?;
+                context
+                    .set_pin_value("response", json::json!(response))
+                    .await?;
+                context.activate_exec_pin("exec_done").await?;
+                return Ok(());
+            };
+
+            if token_budget > 0 && used_tokens >= token_budget as u64 {
+                bail!(
+                    "Agent used {} of its {} token budget after {} steps",
+                    used_tokens,
+                    token_budget,
+                    iteration
+                );
+            }
+
+            let message = tool_call_message(thought, &tool_calls);
+            history.push_message(message.clone());
+            history_out.push_message(message);
+
+            for tool_call in &tool_calls {
+                let name = &tool_call.call.name;
+                let result = if toolbox.contains(name) {
+                    toolbox
+                        .call(context, name, tool_call.call.args.clone())
+                        .await
+                } else {
+                    match layer_tools.get(name) {
+                        Some(entry_nodes) => {
+                            run_tool_nodes(context, tool_call, entry_nodes).await?
+                        }
+                        None => run_tool_branch(context, tool_call).await?,
+                    }
+                    context.evaluate_pin::<String>("tool_result").await?
+                };
+
+                let message = tool_result_message(&tool_call.id, &result);
+                history.push_message(message.clone());
+                history_out.push_message(message);
+            }
+        }
+
+        bail!(
+            "Agent reached {} iterations without a final answer",
+            max_iterations
+        )
+    }
+
+    async fn on_update(&self, node: &mut Node, board: Arc<Board>) {
+        sync_tool_pins(node, &STATIC_OUTPUT_PINS);
+
+        // tools bound to a Function layer are called through the layer, not an exec branch
+        node.pins.retain(|_, pin| {
+            pin.pin_type != PinType::Output
+                || pin.data_type != VariableType::Execution
+                || STATIC_OUTPUT_PINS.contains(&pin.name.as_str())
+                || function_layer_entry_nodes(&board, &pin.name).is_none()
+        });
+    }
+}
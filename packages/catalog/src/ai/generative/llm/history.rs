@@ -5,6 +5,7 @@ pub mod make;
 pub mod message;
 pub mod pop_message;
 pub mod push_message;
+pub mod push_tool_result;
 pub mod set_frequency_penalty;
 pub mod set_max_completion_tokens;
 pub mod set_n;
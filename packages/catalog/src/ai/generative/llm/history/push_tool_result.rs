@@ -0,0 +1,92 @@
+use flow_like::{
+    flow::{
+        execution::context::ExecutionContext,
+        node::{Node, NodeLogic},
+        pin::PinOptions,
+        variable::VariableType,
+    },
+    state::FlowLikeState,
+};
+use flow_like_model_provider::history::{History, HistoryMessage, Role};
+use flow_like_types::{async_trait, json::json};
+
+#[derive(Default)]
+pub struct PushToolResultNode {}
+
+impl PushToolResultNode {
+    pub fn new() -> Self {
+        PushToolResultNode {}
+    }
+}
+
+#[async_trait]
+impl NodeLogic for PushToolResultNode {
+    async fn get_node(&self, _app_state: &FlowLikeState) -> Node {
+        let mut node = Node::new(
+            "ai_generative_push_tool_result",
+            "Push Tool Result",
+            "Answers a tool call of the model with a tool message",
+            "AI/Generative/History",
+        );
+        node.add_icon("/flow/icons/history.svg");
+
+        node.add_input_pin(
+            "exec_in",
+            "Input",
+            "Initiate Execution",
+            VariableType::Execution,
+        );
+
+        node.add_input_pin("history", "History", "ChatHistory", VariableType::Struct)
+            .set_schema::<History>()
+            .set_options(PinOptions::new().set_enforce_schema(true).build());
+
+        node.add_input_pin(
+            "tool_call_id",
+            "Tool Call ID",
+            "ID of the tool call this result answers",
+            VariableType::String,
+        );
+
+        node.add_input_pin(
+            "result",
+            "Result",
+            "Output of the tool",
+            VariableType::String,
+        );
+
+        node.add_output_pin(
+            "exec_out",
+            "Output",
+            "Done with the Execution",
+            VariableType::Execution,
+        );
+
+        node.add_output_pin(
+            "history_out",
+            "History",
+            "Updated ChatHistory",
+            VariableType::Struct,
+        )
+        .set_schema::<History>();
+
+        return node;
+    }
+
+    async fn run(&self, context: &mut ExecutionContext) -> flow_like_types::Result<()> {
+        context.deactivate_exec_pin("exec_out").await?;
+
+        let mut history: History = context.evaluate_pin("history").await?;
+        let tool_call_id: String = context.evaluate_pin("tool_call_id").await?;
+        let result: String = context.evaluate_pin("result").await?;
+
+        let mut message = HistoryMessage::from_string(Role::Tool, &result);
+        message.tool_call_id = Some(tool_call_id);
+        history.push_message(message);
+
+        context.set_pin_value("history_out", json!(history)).await?;
+        context.activate_exec_pin("exec_out").await?;
+
+        Ok(())
+    }
+}
@@ -11,10 +11,14 @@ use flow_like::{
         pin::PinOptions,
         variable::VariableType,
     },
+    models::llm::cache::{CachedModel, ResponseCacheOptions},
     state::FlowLikeState,
 };
 use flow_like_model_provider::{
-    history::History, llm::LLMCallback, response::Response, response_chunk::ResponseChunk,
+    history::History,
+    llm::{LLMCallback, ModelLogic},
+    response::Response,
+    response_chunk::ResponseChunk,
 };
 use flow_like_types::{
     async_trait,
@@ -27,6 +31,7 @@ use std::{
         Arc,
         atomic::{AtomicUsize, Ordering},
     },
+    time::Duration,
 };
 
 #[derive(Default)]
@@ -59,6 +64,8 @@ impl NodeLogic for InvokeLLM {
             .set_schema::<History>()
             .set_options(PinOptions::new().set_enforce_schema(true).build());
 
+        add_cache_pins(&mut node);
+
         node.add_output_pin(
             "on_stream",
             "On Stream",
@@ -88,9 +95,9 @@ impl NodeLogic for InvokeLLM {
 
     async fn run(&self, context: &mut ExecutionContext) -> flow_like_types::Result<()> {
         context.deactivate_exec_pin("done").await?;
-        let model = context.evaluate_pin::<Bit>("model").await?;
-        let mut model_name = model.id.clone();
-        if let Some(meta) = model.meta.get("en") {
+        let model_bit = context.evaluate_pin::<Bit>("model").await?;
+        let mut model_name = model_bit.id.clone();
+        if let Some(meta) = model_bit.meta.get("en") {
             model_name = meta.name.clone();
         }
         let history = context.evaluate_pin::<History>("history").await?;
@@ -98,8 +105,9 @@ impl NodeLogic for InvokeLLM {
         let model = model_factory
             .lock()
             .await
-            .build(&model, context.app_state.clone())
+            .build(&model_bit, context.app_state.clone())
             .await?;
+        let model = cached_model(context, &model_bit, model).await?;
 
         let on_stream = context.get_pin_by_name("on_stream").await?;
         context.activate_exec_pin_ref(&on_stream).await?;
@@ -190,3 +198,66 @@ impl NodeLogic for InvokeLLM {
         return Ok(());
     }
 }
+
+/// Lets boards answer repeated requests of a node from the response cache.
+pub fn add_cache_pins(node: &mut Node) {
+    node.add_input_pin(
+        "cache",
+        "Cache",
+        "Answers repeated requests from the cache instead of calling the model",
+        VariableType::Boolean,
+    )
+    .set_default_value(Some(json!(false)));
+
+    node.add_input_pin(
+        "cache_ttl",
+        "Cache TTL",
+        "Seconds a cached response stays valid",
+        VariableType::Integer,
+    )
+    .set_default_value(Some(json!(86400)));
+
+    node.add_input_pin(
+        "replay_stream",
+        "Replay Stream",
+        "Triggers On Stream with the cached chunks when answering from the cache",
+        VariableType::Boolean,
+    )
+    .set_default_value(Some(json!(true)));
+}
+
+/// Wraps the model with the response cache if the node enabled it.
+pub async fn cached_model(
+    context: &mut ExecutionContext,
+    bit: &Bit,
+    model: Arc<dyn ModelLogic>,
+) -> flow_like_types::Result<Arc<dyn ModelLogic>> {
+    if !context.evaluate_pin::<bool>("cache").await? {
+        return Ok(model);
+    }
+
+    let ttl: i64 = context.evaluate_pin("cache_ttl").await?;
+    let replay_stream: bool = context.evaluate_pin("replay_stream").await?;
+
+    let cache = context.execution_cache.clone().and_then(|cache| {
+        let store = cache.stores.temporary_store.clone()?;
+        let dir = cache.get_cache(false, true).ok()?;
+        Some((store, dir.child("llm_responses")))
+    });
+    let Some((store, dir)) = cache else {
+        context.log_message(
+            "No cache store available, calling the model without cache",
+            LogLevel::Warn,
+        );
+        return Ok(model);
+    };
+
+    let options = ResponseCacheOptions {
+        ttl: Duration::from_secs(ttl.max(0) as u64),
+        replay_stream,
+        ..Default::default()
+    };
+    Ok(Arc::new(CachedModel::new(
+        model, &bit.id, &store, dir, options,
+    )))
+}
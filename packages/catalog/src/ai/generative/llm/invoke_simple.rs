@@ -1,3 +1,4 @@
+use super::invoke::{add_cache_pins, cached_model};
 use flow_like::{
     bit::Bit,
     flow::{
@@ -62,6 +63,8 @@ impl NodeLogic for InvokeLLMSimpleNode {
         node.add_input_pin("prompt", "Prompt", "", VariableType::String)
             .set_default_value(Some(json!("")));
 
+        add_cache_pins(&mut node);
+
         node.add_output_pin(
             "on_stream",
             "On Stream",
@@ -87,9 +90,9 @@ impl NodeLogic for InvokeLLMSimpleNode {
 
     async fn run(&self, context: &mut ExecutionContext) -> flow_like_types::Result<()> {
         context.deactivate_exec_pin("done").await?;
-        let model = context.evaluate_pin::<Bit>("model").await?;
-        let mut model_name = model.id.clone();
-        if let Some(meta) = model.meta.get("en") {
+        let model_bit = context.evaluate_pin::<Bit>("model").await?;
+        let mut model_name = model_bit.id.clone();
+        if let Some(meta) = model_bit.meta.get("en") {
             model_name = meta.name.clone();
         }
         let system_prompt = context.evaluate_pin::<String>("system_prompt").await?;
@@ -98,8 +101,9 @@ impl NodeLogic for InvokeLLMSimpleNode {
         let model = model_factory
             .lock()
             .await
-            .build(&model, context.app_state.clone())
+            .build(&model_bit, context.app_state.clone())
             .await?;
+        let model = cached_model(context, &model_bit, model).await?;
 
         let mut history = History::new(model_name.clone(), vec![]);
         history.set_system_prompt(system_prompt.clone());
@@ -1,26 +1,36 @@
-use crate::utils::json::parse_with_schema::{
-    OpenAIFunction, OpenAIToolCall, validate_openai_functions_str, validate_openai_tool_call_str,
+use crate::{
+    mcp::{McpConnection, McpToolbox},
+    utils::json::parse_with_schema::{
+        OpenAIFunction, OpenAIToolCall, validate_openai_functions_str, validate_openai_tool_call,
+        validate_openai_tool_call_str,
+    },
 };
 use flow_like::{
     bit::Bit,
     flow::{
         board::Board,
-        execution::{LogLevel, context::ExecutionContext},
+        execution::{LogLevel, context::ExecutionContext, internal_node::InternalNode},
         node::{Node, NodeLogic},
-        pin::{PinOptions, PinType},
+        pin::{PinOptions, PinType, ValueType},
         variable::VariableType,
     },
     state::FlowLikeState,
 };
-use flow_like_model_provider::{history::History, response::Response};
+use flow_like_model_provider::{
+    history::{
+        Content, ContentType, History, HistoryFunction, HistoryMessage, MessageContent, Role, Tool,
+        ToolCall, ToolCallFunction, ToolType,
+    },
+    response::{Response, ResponseMessage},
+};
 
-use flow_like_types::{Error, Value, anyhow, async_trait, json, regex::Regex};
+use flow_like_types::{Error, Value, anyhow, async_trait, bail, create_id, json, regex::Regex};
 use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
 };
 
-const SYSTEM_PROMPT_TEMPLATE: &str = r#"
+pub(crate) const SYSTEM_PROMPT_TEMPLATE: &str = r#"
 # Instruction
 You are a helpful assistant with access to the tools below.
 
@@ -100,6 +110,262 @@ pub fn extract_tagged(text: &str, tag: &str) -> Result<Vec<String>, Error> {
     Ok(out)
 }
 
+/// Pins that are not tool exec pins, everything else is generated from the tool definitions
+pub(crate) const STATIC_OUTPUT_PINS: [&str; 5] = [
+    "exec_done",
+    "response",
+    "tool_args",
+    "tool_call_id",
+    "history_out",
+];
+
+/// Tool call requested by the model, the id links the result back to the call
+pub(crate) struct PendingToolCall {
+    pub id: String,
+    pub call: OpenAIToolCall,
+}
+
+/// Local models run on the bundled llama server which has no reliable tool support,
+/// every hosted provider accepts tool definitions natively.
+pub(crate) fn supports_native_tools(bit: &Bit) -> bool {
+    bit.try_to_provider()
+        .is_some_and(|provider| provider.provider_name != "Local")
+}
+
+pub(crate) fn to_history_tools(functions: &[OpenAIFunction]) -> Result<Vec<Tool>, Error> {
+    functions
+        .iter()
+        .map(|function| {
+            let parameters = json::from_value(function.parameters.clone())
+                .map_err(|e| anyhow!("Unsupported parameters for tool {}: {}", function.name, e))?;
+            Ok(Tool {
+                tool_type: ToolType::Function,
+                function: HistoryFunction {
+                    name: function.name.clone(),
+                    description: Some(function.description.clone()),
+                    parameters,
+                },
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn native_tool_calls(
+    functions: &[OpenAIFunction],
+    message: &ResponseMessage,
+) -> Result<Vec<PendingToolCall>, Error> {
+    message
+        .tool_calls
+        .iter()
+        .map(|tool_call| {
+            let name = tool_call
+                .function
+                .name
+                .clone()
+                .ok_or_else(|| anyhow!("Tool call without a function name"))?;
+            let args = match tool_call.function.arguments.as_deref().map(str::trim) {
+                Some(arguments) if !arguments.is_empty() => json::from_str(arguments)
+                    .map_err(|e| anyhow!("Failed to parse arguments for {}: {}", name, e))?,
+                _ => json::json!({}),
+            };
+            let call = validate_openai_tool_call(functions, OpenAIToolCall { name, args })?;
+            let id = match tool_call.id.is_empty() {
+                true => create_id(),
+                false => tool_call.id.clone(),
+            };
+            Ok(PendingToolCall { id, call })
+        })
+        .collect()
+}
+
+/// Fallback for models without native tool support, `None` means the model replied to the user.
+pub(crate) fn tagged_tool_calls(
+    functions: &[OpenAIFunction],
+    response_string: &str,
+) -> Result<Option<Vec<PendingToolCall>>, Error> {
+    if response_string.contains("<tooluse>") {
+        let tool_calls = extract_tagged(response_string, "tooluse")?
+            .iter()
+            .map(|tool_call_str| {
+                let call = validate_openai_tool_call_str(functions, tool_call_str)?;
+                Ok(PendingToolCall {
+                    id: create_id(),
+                    call,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        return Ok(Some(tool_calls));
+    }
+
+    if response_string.contains("<replytouser>") {
+        let response_tagged = extract_tagged(response_string, "replytouser")?;
+        if response_tagged.len() != 1 {
+            return Err(anyhow!(format!(
+                "Invalid number of responses: Expected 1, got {}.",
+                response_tagged.len()
+            )));
+        }
+        return Ok(None);
+    }
+
+    Err(anyhow!("Invalid response."))
+}
+
+/// Hands the tools to the model, natively where the provider supports it and as a tagged system prompt otherwise.
+/// Returns whether the tool calls have to be read natively from the response.
+pub(crate) fn prepare_tools(
+    context: &mut ExecutionContext,
+    model_bit: &Bit,
+    functions: &[OpenAIFunction],
+    tools_str: &str,
+    history: &mut History,
+) -> bool {
+    let native_tools = match supports_native_tools(model_bit) {
+        true => match to_history_tools(functions) {
+            Ok(tools) => Some(tools),
+            Err(err) => {
+                context.log_message(
+                    &format!("Falling back to tagged tool calls: {err}"),
+                    LogLevel::Warn,
+                );
+                None
+            }
+        },
+        false => None,
+    };
+    let native = native_tools.is_some();
+
+    match native_tools {
+        Some(tools) => history.tools = Some(tools),
+        None => {
+            // ingest system prompt with tool definitions
+            let system_prompt = SYSTEM_PROMPT_TEMPLATE.replace("TOOLS_STR", tools_str);
+            context.log_message(&system_prompt, LogLevel::Debug);
+            history.set_system_prompt(system_prompt.to_string()); // todo: handle previously set system prompts
+        }
+    }
+
+    native
+}
+
+/// The assistant message announcing `tool_calls`, pushed ahead of their results.
+pub(crate) fn tool_call_message(content: String, tool_calls: &[PendingToolCall]) -> HistoryMessage {
+    HistoryMessage {
+        role: Role::Assistant,
+        content: MessageContent::Contents(vec![Content::Text {
+            content_type: ContentType::Text,
+            text: content,
+        }]),
+        name: None,
+        tool_call_id: None,
+        tool_calls: Some(
+            tool_calls
+                .iter()
+                .map(|tool_call| ToolCall {
+                    id: tool_call.id.clone(),
+                    r#type: "function".to_string(),
+                    function: ToolCallFunction {
+                        name: Some(tool_call.call.name.clone()),
+                        arguments: Some(tool_call.call.args.to_string()),
+                    },
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// MCP connections whose tools are offered to the model next to the flow's own tools.
+pub(crate) fn add_mcp_pin(node: &mut Node) {
+    node.add_input_pin(
+        "mcp",
+        "MCP Servers",
+        "MCP connections whose tools the model may call as well",
+        VariableType::Struct,
+    )
+    .set_schema::<McpConnection>()
+    .set_value_type(ValueType::Array)
+    .set_default_value(Some(json::json!([])));
+}
+
+/// Adds the tools of the connected MCP servers to `functions` and `tools_str`.
+pub(crate) async fn load_mcp_tools(
+    context: &mut ExecutionContext,
+    functions: &mut Vec<OpenAIFunction>,
+    tools_str: &mut String,
+) -> flow_like_types::Result<McpToolbox> {
+    let connections: Vec<McpConnection> = context.evaluate_pin("mcp").await?;
+    let toolbox = McpToolbox::load(context, &connections).await?;
+    if toolbox.functions.is_empty() {
+        return Ok(toolbox);
+    }
+
+    if let Some(function) = functions
+        .iter()
+        .find(|function| toolbox.contains(&function.name))
+    {
+        bail!(
+            "Tool {} is defined by the flow and by an MCP server",
+            function.name
+        );
+    }
+    functions.extend(toolbox.functions.iter().cloned());
+    *tools_str = json::to_string(&functions)?;
+
+    Ok(toolbox)
+}
+
+/// The tool message answering the call with `id`.
+pub(crate) fn tool_result_message(id: &str, result: &str) -> HistoryMessage {
+    let mut message = HistoryMessage::from_string(Role::Tool, result);
+    message.tool_call_id = Some(id.to_string());
+    message
+}
+
+/// Runs everything connected to the exec pin of the called tool with its arguments set.
+pub(crate) async fn run_tool_branch(
+    context: &mut ExecutionContext,
+    tool_call: &PendingToolCall,
+) -> flow_like_types::Result<()> {
+    let exec_tool = context.get_pin_by_name(&tool_call.call.name).await?;
+    let connected = exec_tool.lock().await.get_connected_nodes().await;
+
+    context.activate_exec_pin_ref(&exec_tool).await?;
+    run_tool_nodes(context, tool_call, &connected).await?;
+    context.deactivate_exec_pin_ref(&exec_tool).await?;
+
+    Ok(())
+}
+
+/// Triggers `nodes` one after the other with the arguments of the tool call set, errors are logged.
+pub(crate) async fn run_tool_nodes(
+    context: &mut ExecutionContext,
+    tool_call: &PendingToolCall,
+    nodes: &[Arc<InternalNode>],
+) -> flow_like_types::Result<()> {
+    context
+        .set_pin_value("tool_args", json::json!(tool_call.call.args))
+        .await?;
+    context
+        .set_pin_value("tool_call_id", json::json!(tool_call.id))
+        .await?;
+
+    for node in nodes {
+        let mut sub_context = context.create_sub_context(node).await;
+        let run = InternalNode::trigger(&mut sub_context, &mut None, true).await;
+        sub_context.end_trace();
+        context.push_sub_context(sub_context);
+
+        if let Err(error) = run {
+            context.log_message(
+                &format!("Error: {:?} in tool call {}", error, tool_call.call.name),
+                LogLevel::Error,
+            );
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Default)]
 pub struct LLMWithTools {}
 
@@ -138,7 +404,14 @@ impl NodeLogic for LLMWithTools {
         )
         .set_default_value(Some(json::json!("[]")));
 
-        node.add_output_pin("exec_done", "Done", "Done Pin", VariableType::Execution);
+        add_mcp_pin(&mut node);
+
+        node.add_output_pin(
+            "exec_done",
+            "Done",
+            "Fires with the final response, or once every Tool Call was answered by an MCP server",
+            VariableType::Execution,
+        );
 
         node.add_output_pin(
             "response",
@@ -149,6 +422,15 @@ impl NodeLogic for LLMWithTools {
         .set_schema::<Response>()
         .set_options(PinOptions::new().set_enforce_schema(true).build());
 
+        node.add_output_pin(
+            "history_out",
+            "History",
+            "Chat History including the model's reply or tool calls",
+            VariableType::Struct,
+        )
+        .set_schema::<History>()
+        .set_options(PinOptions::new().set_enforce_schema(true).build());
+
         node.add_output_pin(
             "tool_args",
             "Tool Args",
@@ -156,6 +438,13 @@ impl NodeLogic for LLMWithTools {
             VariableType::Struct,
         );
 
+        node.add_output_pin(
+            "tool_call_id",
+            "Tool Call ID",
+            "ID to answer the current Tool Call with a Tool message",
+            VariableType::String,
+        );
+
         node.set_long_running(true);
 
         return node;
@@ -166,24 +455,24 @@ impl NodeLogic for LLMWithTools {
 
         // fetch inputs
         let model_bit = context.evaluate_pin::<Bit>("model").await?;
-        let mut history = context.evaluate_pin::<History>("history").await?;
-        let tools_str: String = context.evaluate_pin("tools").await?;
+        let input_history = context.evaluate_pin::<History>("history").await?;
+        let mut tools_str: String = context.evaluate_pin("tools").await?;
 
         // deactivate all function exec output pins
-        let functions = validate_openai_functions_str(&tools_str)?;
+        let mut functions = validate_openai_functions_str(&tools_str)?;
         for function in &functions {
             context.deactivate_exec_pin(&function.name).await?
         }
+        let toolbox = load_mcp_tools(context, &mut functions, &mut tools_str).await?;
 
         // log model name
         if let Some(meta) = model_bit.meta.get("en") {
             context.log_message(&format!("Loading model {:?}", meta.name), LogLevel::Debug);
         }
 
-        // ingest system prompt with tool definitions
-        let system_prompt = SYSTEM_PROMPT_TEMPLATE.replace("TOOLS_STR", &tools_str); // todo: serlialize tools instead?
-        context.log_message(&system_prompt, LogLevel::Debug);
-        history.set_system_prompt(system_prompt.to_string()); // todo: handle previously set system prompts
+        // prefer provider tool calling, tags are only a fallback
+        let mut history = input_history.clone();
+        let native = prepare_tools(context, &model_bit, &functions, &tools_str, &mut history);
 
         // generate response, todo: wrap this
         let response = {
@@ -198,107 +487,124 @@ impl NodeLogic for LLMWithTools {
         }; // drop model
 
         // parse response
-        let mut response_string = "".to_string();
-        if let Some(response) = response.last_message() {
-            response_string = response.content.clone().unwrap_or("".to_string());
-        }
+        let message = response.last_message().cloned().unwrap_or_default();
+        let response_string = message.content.clone().unwrap_or_default();
         context.log_message(&response_string, LogLevel::Debug);
-        if response_string.contains("<tooluse>") {
-            let tool_calls_str = extract_tagged(&response_string, "tooluse")?;
-            let tool_calls: Result<Vec<OpenAIToolCall>, Error> = tool_calls_str
-                .iter()
-                .map(|tool_call_str| validate_openai_tool_call_str(&functions, tool_call_str))
-                .collect();
-            let mut tool_calls = tool_calls?;
-            let tool_call = if tool_calls.len() == 1 {
-                // todo: remove to support parallel tool calls
-                tool_calls.pop().unwrap()
-            } else {
-                return Err(anyhow!(format!(
-                    "Invalid number of tool calls: Expected 1, got {}.",
-                    tool_calls.len()
-                )));
-            };
+
+        let tool_calls = match native {
+            true => {
+                Some(native_tool_calls(&functions, &message)?).filter(|calls| !calls.is_empty())
+            }
+            false => tagged_tool_calls(&functions, &response_string)?,
+        };
+
+        let mut history_out = input_history;
+        let Some(tool_calls) = tool_calls else {
+            history_out.push_message(HistoryMessage::from_response(response.clone()));
             context
-                .set_pin_value("tool_args", json::json!(tool_call.args))
+                .set_pin_value("history_out", json::json!(history_out))
                 .await?;
-            context.activate_exec_pin(&tool_call.name).await?; // activate this tool call exec pin
-        } else if response_string.contains("<replytouser>") {
-            let mut response_tagged = extract_tagged(&response_string, "replytouser")?;
-            let response_tagged = if response_tagged.len() == 1 {
-                response_tagged.pop().unwrap()
-            } else {
-                return Err(anyhow!(format!(
-                    "Invalid number of responses: Expected 1, got {}.",
-                    response_tagged.len()
-                )));
-            };
             context
                 .set_pin_value("response", json::json!(response))
                 .await?; // todo: remove prefix from response struct
             context.activate_exec_pin("exec_done").await?;
-        } else {
-            return Err(anyhow!("Invalid response."));
-        }
+            return Ok(());
+        };
 
-        Ok(())
-    }
+        // the assistant turn has to precede the tool messages answering it
+        history_out.push_message(tool_call_message(response_string, &tool_calls));
 
-    async fn on_update(&self, node: &mut Node, board: Arc<Board>) {
-        let current_tool_exec_pins: Vec<_> = node
-            .pins
-            .values()
-            .filter(|p| {
-                p.pin_type == PinType::Output
-                    && (p.name != "exec_done" && p.name != "response" && p.name != "tool_args") // p.description == "Tool Exec" doesn't seem to work as filter cond
-            })
-            .collect();
+        // MCP tools are answered right away, the others run their exec branch
+        let mut branch_calls = Vec::new();
+        for tool_call in tool_calls {
+            if !toolbox.contains(&tool_call.call.name) {
+                branch_calls.push(tool_call);
+                continue;
+            }
+            let result = toolbox
+                .call(context, &tool_call.call.name, tool_call.call.args.clone())
+                .await;
+            history_out.push_message(tool_result_message(&tool_call.id, &result));
+        }
+        context
+            .set_pin_value("history_out", json::json!(history_out))
+            .await?;
 
-        let schema_str: String = node
-            .get_pin_by_name("tools")
-            .and_then(|pin| pin.default_value.clone())
-            .and_then(|bytes| flow_like_types::json::from_slice::<Value>(&bytes).ok())
-            .and_then(|json| json.as_str().map(ToOwned::to_owned))
-            .unwrap_or_default();
+        if branch_calls.is_empty() {
+            context
+                .set_pin_value("response", json::json!(response))
+                .await?;
+            context.activate_exec_pin("exec_done").await?;
+            return Ok(());
+        }
 
-        let mut current_tool_exec_refs = current_tool_exec_pins
-            .iter()
-            .map(|p| (p.name.clone(), *p))
-            .collect::<HashMap<_, _>>();
+        // run the branch of every requested tool, parallel calls run one after the other
+        for tool_call in &branch_calls {
+            run_tool_branch(context, tool_call).await?;
+        }
 
-        let update_tools: Vec<OpenAIFunction> = match validate_openai_functions_str(&schema_str) {
-            Ok(tools) => tools,
-            Err(err) => {
-                node.error = Some(format!("Failed to parse tools: {err:?}").to_string());
-                return;
-            }
-        };
+        Ok(())
+    }
 
-        let mut all_tool_exec_refs = HashSet::new();
-        let mut missing_tool_exec_refs = HashSet::new();
+    async fn on_update(&self, node: &mut Node, _board: Arc<Board>) {
+        sync_tool_pins(node, &STATIC_OUTPUT_PINS);
+    }
+}
 
-        for update_tool in update_tools {
-            all_tool_exec_refs.insert(update_tool.name.clone());
-            if current_tool_exec_refs.remove(&update_tool.name).is_none() {
-                missing_tool_exec_refs.insert(update_tool.name.clone());
-            }
+/// Keeps one exec output pin per tool definition, `static_pins` are never touched.
+pub(crate) fn sync_tool_pins(node: &mut Node, static_pins: &[&str]) {
+    let current_tool_exec_pins: Vec<_> = node
+        .pins
+        .values()
+        .filter(|p| {
+            p.pin_type == PinType::Output && !static_pins.contains(&p.name.as_str()) // p.description == "Tool Exec" doesn't seem to work as filter cond
+        })
+        .collect();
+
+    let schema_str: String = node
+        .get_pin_by_name("tools")
+        .and_then(|pin| pin.default_value.clone())
+        .and_then(|bytes| flow_like_types::json::from_slice::<Value>(&bytes).ok())
+        .and_then(|json| json.as_str().map(ToOwned::to_owned))
+        .unwrap_or_default();
+
+    let mut current_tool_exec_refs = current_tool_exec_pins
+        .iter()
+        .map(|p| (p.name.clone(), *p))
+        .collect::<HashMap<_, _>>();
+
+    let update_tools: Vec<OpenAIFunction> = match validate_openai_functions_str(&schema_str) {
+        Ok(tools) => tools,
+        Err(err) => {
+            node.error = Some(format!("Failed to parse tools: {err:?}").to_string());
+            return;
         }
+    };
 
-        let ids_to_remove = current_tool_exec_refs
-            .values()
-            .map(|p| p.id.clone())
-            .collect::<Vec<_>>();
-        ids_to_remove.iter().for_each(|id| {
-            node.pins.remove(id);
-        });
-
-        for missing_tool_ref in missing_tool_exec_refs {
-            node.add_output_pin(
-                &missing_tool_ref,
-                &missing_tool_ref,
-                "Tool Exec",
-                VariableType::Execution,
-            );
+    let mut all_tool_exec_refs = HashSet::new();
+    let mut missing_tool_exec_refs = HashSet::new();
+
+    for update_tool in update_tools {
+        all_tool_exec_refs.insert(update_tool.name.clone());
+        if current_tool_exec_refs.remove(&update_tool.name).is_none() {
+            missing_tool_exec_refs.insert(update_tool.name.clone());
         }
     }
+
+    let ids_to_remove = current_tool_exec_refs
+        .values()
+        .map(|p| p.id.clone())
+        .collect::<Vec<_>>();
+    ids_to_remove.iter().for_each(|id| {
+        node.pins.remove(id);
+    });
+
+    for missing_tool_ref in missing_tool_exec_refs {
+        node.add_output_pin(
+            &missing_tool_ref,
+            &missing_tool_ref,
+            "Tool Exec",
+            VariableType::Execution,
+        );
+    }
 }
@@ -2,6 +2,7 @@ pub mod api_event;
 pub mod chat_event;
 pub mod generic_event;
 pub mod mail_event;
+pub mod mqtt_event;
 pub mod simple_event;
 
 use flow_like::flow::node::NodeLogic;
@@ -11,5 +12,6 @@ pub async fn register_functions() -> Vec<Arc<dyn NodeLogic>> {
     let mut output = vec![Arc::new(simple_event::SimpleEventNode::default()) as Arc<dyn NodeLogic>];
     output.append(&mut chat_event::register_functions().await);
     output.push(Arc::new(generic_event::GenericEventNode::default()));
+    output.push(Arc::new(mqtt_event::MqttEventNode::default()));
     output
 }
@@ -0,0 +1,74 @@
+use flow_like::{
+    flow::{
+        execution::context::ExecutionContext,
+        node::{Node, NodeLogic},
+        variable::VariableType,
+    },
+    state::FlowLikeState,
+};
+use flow_like_types::async_trait;
+
+use crate::mqtt::MqttMessage;
+
+#[derive(Default)]
+pub struct MqttEventNode {}
+
+impl MqttEventNode {
+    pub fn new() -> Self {
+        MqttEventNode {}
+    }
+}
+
+#[async_trait]
+impl NodeLogic for MqttEventNode {
+    async fn get_node(&self, _app_state: &FlowLikeState) -> Node {
+        let mut node = Node::new(
+            "events_mqtt",
+            "MQTT Event",
+            "Starts a run for every message received on the configured topic filter",
+            "Events",
+        );
+        node.add_icon("/flow/icons/event.svg");
+        node.set_start(true);
+
+        node.add_output_pin(
+            "exec_out",
+            "Output",
+            "Starting an event",
+            VariableType::Execution,
+        );
+
+        node.add_output_pin(
+            "payload",
+            "Message",
+            "The received MQTT message",
+            VariableType::Struct,
+        )
+        .set_schema::<MqttMessage>();
+
+        return node;
+    }
+
+    async fn run(&self, context: &mut ExecutionContext) -> flow_like_types::Result<()> {
+        let exec_out_pin = context.get_pin_by_name("exec_out").await?;
+
+        if context.delegated {
+            context.activate_exec_pin_ref(&exec_out_pin).await?;
+            return Ok(());
+        }
+
+        let payload = context.get_payload().await?;
+        let payload = payload
+            .payload
+            .clone()
+            .ok_or_else(|| flow_like_types::anyhow!("Payload is missing",))?;
+        let message: MqttMessage = flow_like_types::json::from_value(payload)?;
+
+        context
+            .set_pin_value("payload", flow_like_types::json::json!(message))
+            .await?;
+        context.activate_exec_pin_ref(&exec_out_pin).await?;
+
+        return Ok(());
+    }
+}
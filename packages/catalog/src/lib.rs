@@ -11,6 +11,8 @@ pub mod image;
 pub mod logging;
 pub mod mail;
 pub mod math;
+pub mod mcp;
+pub mod mqtt;
 pub mod storage;
 pub mod structs;
 pub mod utils;
@@ -31,6 +33,8 @@ pub async fn get_catalog() -> Vec<Arc<dyn NodeLogic>> {
         variables::register_functions().await,
         web::register_functions().await,
         mail::register_functions().await,
+        mqtt::register_functions().await,
+        mcp::register_functions().await,
     ]
     .into_iter()
     .flatten()
@@ -0,0 +1,823 @@
+use crate::utils::json::parse_with_schema::OpenAIFunction;
+use flow_like::{
+    flow::{
+        execution::{LogLevel, context::ExecutionContext},
+        node::{Node, NodeLogic},
+        pin::{PinOptions, ValueType},
+        variable::VariableType,
+    },
+    state::FlowLikeState,
+};
+use flow_like_model_provider::history::{HistoryMessage, Role};
+use flow_like_types::{
+    Cacheable, Value, anyhow, async_trait, bail,
+    json::{self, json},
+    reqwest::{
+        self,
+        header::{HeaderMap, HeaderName, HeaderValue},
+    },
+};
+use rmcp::{
+    RoleClient, ServiceExt,
+    model::{
+        CallToolRequestParam, GetPromptRequestParam, JsonObject, PromptMessageContent,
+        PromptMessageRole, RawContent, ReadResourceRequestParam, ResourceContents,
+    },
+    service::RunningService,
+    transport::{
+        StreamableHttpClientTransport, streamable_http_client::StreamableHttpClientTransportConfig,
+    },
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{
+    any::Any,
+    collections::{HashMap, hash_map::DefaultHasher},
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+pub mod prompts;
+pub mod resources;
+pub mod tools;
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+pub struct McpConnection {
+    pub id: String,
+}
+
+impl McpConnection {
+    pub fn new(id: String) -> Self {
+        McpConnection { id }
+    }
+
+    pub async fn to_session(
+        &self,
+        context: &mut ExecutionContext,
+    ) -> flow_like_types::Result<McpSessionCache> {
+        let cache_key = format!("mcp_session_{}", self.id);
+        if let Some(session) = context.get_cache(&cache_key).await {
+            let session = session
+                .as_any()
+                .downcast_ref::<McpSessionCache>()
+                .ok_or_else(|| anyhow!("Failed to downcast McpSessionCache"))?
+                .clone();
+            Ok(session)
+        } else {
+            Err(anyhow!("MCP session not found"))
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+pub struct McpTool {
+    pub name: String,
+    pub description: String,
+    /// JSON Schema of the tool arguments
+    pub input_schema: Value,
+}
+
+impl McpTool {
+    pub fn to_openai_function(&self) -> OpenAIFunction {
+        OpenAIFunction {
+            r#type: "function".to_string(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            parameters: self.input_schema.clone(),
+            strict: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+pub struct McpToolResult {
+    /// Text content of the result, other content is included as JSON
+    pub text: String,
+    pub structured: Option<Value>,
+    pub is_error: bool,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+pub struct McpResource {
+    pub uri: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+pub struct McpResourceContent {
+    pub uri: String,
+    pub mime_type: Option<String>,
+    pub text: Option<String>,
+    /// Base64 encoded binary content
+    pub blob: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+pub struct McpPromptArgument {
+    pub name: String,
+    pub description: Option<String>,
+    pub required: bool,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+pub struct McpPrompt {
+    pub name: String,
+    pub description: Option<String>,
+    pub arguments: Vec<McpPromptArgument>,
+}
+
+/// A running MCP client, the server process or HTTP session ends once the last clone is dropped.
+#[derive(Clone)]
+pub struct McpSessionCache {
+    pub client: Arc<RunningService<RoleClient, ()>>,
+}
+
+impl McpSessionCache {
+    /// Spawns the server as a child process, only built with the `mcp-stdio` feature.
+    #[cfg(feature = "mcp-stdio")]
+    pub async fn connect_stdio(
+        command: &str,
+        args: &[String],
+        env: &HashMap<String, String>,
+    ) -> flow_like_types::Result<Self> {
+        use rmcp::transport::TokioChildProcess;
+        use std::process::Stdio;
+        use tokio::process::Command;
+
+        let mut process = Command::new(command);
+        process.args(args).envs(env);
+
+        let (transport, _) = TokioChildProcess::builder(process)
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| anyhow!("Failed to start MCP server {}: {}", command, e))?;
+        let client = ().serve(transport).await?;
+
+        Ok(McpSessionCache {
+            client: Arc::new(client),
+        })
+    }
+
+    pub async fn connect_http(
+        url: &str,
+        token: Option<&str>,
+        headers: &HashMap<String, String>,
+    ) -> flow_like_types::Result<Self> {
+        let mut header_map = HeaderMap::new();
+        for (key, value) in headers {
+            header_map.insert(
+                HeaderName::from_bytes(key.as_bytes())?,
+                HeaderValue::from_str(value)?,
+            );
+        }
+        let http_client = reqwest::Client::builder()
+            .default_headers(header_map)
+            .build()?;
+
+        let mut config = StreamableHttpClientTransportConfig::with_uri(url);
+        if let Some(token) = token.filter(|token| !token.is_empty()) {
+            config = config.auth_header(token);
+        }
+
+        let transport = StreamableHttpClientTransport::with_client(http_client, config);
+        let client = ().serve(transport).await?;
+
+        Ok(McpSessionCache {
+            client: Arc::new(client),
+        })
+    }
+
+    pub async fn list_tools(&self) -> flow_like_types::Result<Vec<McpTool>> {
+        let tools = self.client.list_all_tools().await?;
+        Ok(tools
+            .into_iter()
+            .map(|tool| McpTool {
+                name: tool.name.to_string(),
+                description: tool.description.unwrap_or_default().to_string(),
+                input_schema: Value::Object(tool.input_schema.as_ref().clone()),
+            })
+            .collect())
+    }
+
+    pub async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> flow_like_types::Result<McpToolResult> {
+        let result = self
+            .client
+            .call_tool(CallToolRequestParam {
+                name: name.to_string().into(),
+                arguments: to_arguments(arguments)?,
+            })
+            .await?;
+
+        let text = result
+            .content
+            .iter()
+            .map(|content| match &content.raw {
+                RawContent::Text(text) => Ok(text.text.clone()),
+                other => json::to_string(other),
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .join("\n");
+
+        Ok(McpToolResult {
+            text,
+            structured: result.structured_content,
+            is_error: result.is_error.unwrap_or(false),
+        })
+    }
+
+    pub async fn list_resources(&self) -> flow_like_types::Result<Vec<McpResource>> {
+        let resources = self.client.list_all_resources().await?;
+        Ok(resources
+            .into_iter()
+            .map(|resource| McpResource {
+                uri: resource.raw.uri,
+                name: resource.raw.name,
+                description: resource.raw.description,
+                mime_type: resource.raw.mime_type,
+            })
+            .collect())
+    }
+
+    pub async fn read_resource(
+        &self,
+        uri: &str,
+    ) -> flow_like_types::Result<Vec<McpResourceContent>> {
+        let result = self
+            .client
+            .read_resource(ReadResourceRequestParam {
+                uri: uri.to_string(),
+            })
+            .await?;
+
+        Ok(result
+            .contents
+            .into_iter()
+            .map(|content| match content {
+                ResourceContents::TextResourceContents {
+                    uri,
+                    mime_type,
+                    text,
+                    ..
+                } => McpResourceContent {
+                    uri,
+                    mime_type,
+                    text: Some(text),
+                    blob: None,
+                },
+                ResourceContents::BlobResourceContents {
+                    uri,
+                    mime_type,
+                    blob,
+                    ..
+                } => McpResourceContent {
+                    uri,
+                    mime_type,
+                    text: None,
+                    blob: Some(blob),
+                },
+            })
+            .collect())
+    }
+
+    pub async fn list_prompts(&self) -> flow_like_types::Result<Vec<McpPrompt>> {
+        let prompts = self.client.list_all_prompts().await?;
+        Ok(prompts
+            .into_iter()
+            .map(|prompt| McpPrompt {
+                name: prompt.name,
+                description: prompt.description,
+                arguments: prompt
+                    .arguments
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|argument| McpPromptArgument {
+                        name: argument.name,
+                        description: argument.description,
+                        required: argument.required.unwrap_or(false),
+                    })
+                    .collect(),
+            })
+            .collect())
+    }
+
+    /// Renders a prompt into chat messages, content other than text is included as JSON.
+    pub async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> flow_like_types::Result<Vec<HistoryMessage>> {
+        let result = self
+            .client
+            .get_prompt(GetPromptRequestParam {
+                name: name.to_string(),
+                arguments: to_arguments(arguments)?,
+            })
+            .await?;
+
+        result
+            .messages
+            .into_iter()
+            .map(|message| -> flow_like_types::Result<HistoryMessage> {
+                let role = match message.role {
+                    PromptMessageRole::User => Role::User,
+                    PromptMessageRole::Assistant => Role::Assistant,
+                };
+                let text = match message.content {
+                    PromptMessageContent::Text { text } => text,
+                    other => json::to_string(&other)?,
+                };
+                Ok(HistoryMessage::from_string(role, &text))
+            })
+            .collect()
+    }
+}
+
+impl Cacheable for McpSessionCache {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// MCP expects an argument object, `null` means no arguments.
+fn to_arguments(arguments: Value) -> flow_like_types::Result<Option<JsonObject>> {
+    match arguments {
+        Value::Null => Ok(None),
+        Value::Object(map) => Ok(Some(map)),
+        other => Err(anyhow!("MCP arguments have to be an object, got {}", other)),
+    }
+}
+
+/// MCP tools offered to a model next to the tools of the flow, calls are answered by the server that owns the tool.
+#[derive(Default)]
+pub struct McpToolbox {
+    pub functions: Vec<OpenAIFunction>,
+    sessions: HashMap<String, McpSessionCache>,
+}
+
+impl McpToolbox {
+    pub async fn load(
+        context: &mut ExecutionContext,
+        connections: &[McpConnection],
+    ) -> flow_like_types::Result<Self> {
+        let mut toolbox = McpToolbox::default();
+        for connection in connections {
+            let session = connection.to_session(context).await?;
+            for tool in session.list_tools().await? {
+                if toolbox.contains(&tool.name) {
+                    bail!("MCP tool {} is offered by more than one server", tool.name);
+                }
+                toolbox.functions.push(tool.to_openai_function());
+                toolbox.sessions.insert(tool.name, session.clone());
+            }
+        }
+        Ok(toolbox)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.sessions.contains_key(name)
+    }
+
+    /// Calls the tool and returns its text for the model, failures are reported to the model instead of the flow.
+    pub async fn call(
+        &self,
+        context: &mut ExecutionContext,
+        name: &str,
+        arguments: Value,
+    ) -> String {
+        let Some(session) = self.sessions.get(name) else {
+            return format!("Error: unknown tool {}", name);
+        };
+
+        match session.call_tool(name, arguments).await {
+            Ok(result) if result.is_error => format!("Error: {}", result.text),
+            Ok(result) => result.text,
+            Err(error) => {
+                context.log_message(
+                    &format!("MCP tool {} failed: {:?}", name, error),
+                    LogLevel::Warn,
+                );
+                format!("Error: {}", error)
+            }
+        }
+    }
+}
+
+async fn cache_session(
+    context: &mut ExecutionContext,
+    id: String,
+    session: McpSessionCache,
+) -> flow_like_types::Result<()> {
+    context
+        .cache
+        .write()
+        .await
+        .insert(format!("mcp_session_{}", id), Arc::new(session));
+
+    context
+        .set_pin_value("connection", json!(McpConnection { id }))
+        .await?;
+    context.activate_exec_pin("exec_out").await?;
+    Ok(())
+}
+
+async fn reuse_session(context: &mut ExecutionContext, id: &str) -> flow_like_types::Result<bool> {
+    {
+        let cache = context.cache.read().await;
+        if !cache.contains_key(&format!("mcp_session_{}", id)) {
+            return Ok(false);
+        }
+    }
+
+    context
+        .set_pin_value("connection", json!(McpConnection::new(id.to_string())))
+        .await?;
+    context.activate_exec_pin("exec_out").await?;
+    Ok(true)
+}
+
+fn add_connection_output(node: &mut Node) {
+    node.add_output_pin(
+        "exec_out",
+        "Out",
+        "Execution output",
+        VariableType::Execution,
+    );
+    node.add_output_pin(
+        "connection",
+        "Connection",
+        "Cached MCP connection reference",
+        VariableType::Struct,
+    )
+    .set_schema::<McpConnection>();
+}
+
+/// Runs an arbitrary command on the host, so it is only registered with the `mcp-stdio` feature.
+#[cfg(feature = "mcp-stdio")]
+#[derive(Default)]
+pub struct McpConnectStdioNode;
+
+#[cfg(feature = "mcp-stdio")]
+impl McpConnectStdioNode {
+    pub fn new() -> Self {
+        McpConnectStdioNode
+    }
+}
+
+#[cfg(feature = "mcp-stdio")]
+#[async_trait]
+impl NodeLogic for McpConnectStdioNode {
+    async fn get_node(&self, _app_state: &FlowLikeState) -> Node {
+        let mut node = Node::new(
+            "mcp_connect_stdio",
+            "MCP Connect (stdio)",
+            "Starts a local MCP server process and caches the session",
+            "AI/MCP",
+        );
+        node.add_icon("/flow/icons/workflow.svg");
+
+        node.add_input_pin("exec_in", "In", "Execution input", VariableType::Execution);
+        node.add_input_pin(
+            "command",
+            "Command",
+            "Executable of the MCP server, e.g. npx or uvx",
+            VariableType::String,
+        )
+        .set_default_value(Some(json!("npx")));
+        node.add_input_pin(
+            "args",
+            "Arguments",
+            "Command line arguments",
+            VariableType::String,
+        )
+        .set_value_type(ValueType::Array)
+        .set_default_value(Some(json!([])));
+        node.add_input_pin(
+            "env",
+            "Environment",
+            "Additional environment variables for the server process",
+            VariableType::String,
+        )
+        .set_value_type(ValueType::HashMap)
+        .set_default_value(Some(json!({})))
+        .set_options(PinOptions::new().set_sensitive(true).build());
+
+        add_connection_output(&mut node);
+
+        node
+    }
+
+    async fn run(&self, context: &mut ExecutionContext) -> flow_like_types::Result<()> {
+        context.deactivate_exec_pin("exec_out").await?;
+
+        let command: String = context.evaluate_pin("command").await?;
+        let args: Vec<String> = context.evaluate_pin("args").await?;
+        let env: HashMap<String, String> = context.evaluate_pin("env").await?;
+
+        let mut hasher = DefaultHasher::new();
+        "stdio".hash(&mut hasher);
+        command.hash(&mut hasher);
+        args.hash(&mut hasher);
+        let mut env_entries = env.iter().collect::<Vec<_>>();
+        env_entries.sort();
+        env_entries.hash(&mut hasher);
+        let id = hasher.finish().to_string();
+
+        if reuse_session(context, &id).await? {
+            return Ok(());
+        }
+
+        let session = McpSessionCache::connect_stdio(&command, &args, &env).await?;
+        context.log_message(
+            &format!("-- started MCP server {} {}", command, args.join(" ")),
+            LogLevel::Debug,
+        );
+
+        cache_session(context, id, session).await
+    }
+}
+
+#[derive(Default)]
+pub struct McpConnectHttpNode;
+
+impl McpConnectHttpNode {
+    pub fn new() -> Self {
+        McpConnectHttpNode
+    }
+}
+
+#[async_trait]
+impl NodeLogic for McpConnectHttpNode {
+    async fn get_node(&self, _app_state: &FlowLikeState) -> Node {
+        let mut node = Node::new(
+            "mcp_connect_http",
+            "MCP Connect (HTTP)",
+            "Connects to an MCP server over streamable HTTP and caches the session",
+            "AI/MCP",
+        );
+        node.add_icon("/flow/icons/workflow.svg");
+
+        node.add_input_pin("exec_in", "In", "Execution input", VariableType::Execution);
+        node.add_input_pin(
+            "url",
+            "URL",
+            "Endpoint of the MCP server",
+            VariableType::String,
+        )
+        .set_default_value(Some(json!("http://localhost:8000/mcp")));
+        node.add_input_pin(
+            "token",
+            "Token",
+            "Bearer token, leave empty for public servers",
+            VariableType::String,
+        )
+        .set_default_value(Some(json!("")))
+        .set_options(PinOptions::new().set_sensitive(true).build());
+        node.add_input_pin(
+            "headers",
+            "Headers",
+            "Additional headers sent with every request",
+            VariableType::String,
+        )
+        .set_value_type(ValueType::HashMap)
+        .set_default_value(Some(json!({})));
+
+        add_connection_output(&mut node);
+
+        node
+    }
+
+    async fn run(&self, context: &mut ExecutionContext) -> flow_like_types::Result<()> {
+        context.deactivate_exec_pin("exec_out").await?;
+
+        let url: String = context.evaluate_pin("url").await?;
+        let token: String = context.evaluate_pin("token").await?;
+        let headers: HashMap<String, String> = context.evaluate_pin("headers").await?;
+
+        let mut hasher = DefaultHasher::new();
+        "http".hash(&mut hasher);
+        url.hash(&mut hasher);
+        token.hash(&mut hasher);
+        let mut header_entries = headers.iter().collect::<Vec<_>>();
+        header_entries.sort();
+        header_entries.hash(&mut hasher);
+        let id = hasher.finish().to_string();
+
+        if reuse_session(context, &id).await? {
+            return Ok(());
+        }
+
+        let session = McpSessionCache::connect_http(&url, Some(&token), &headers).await?;
+        context.log_message(&format!("-- connected to {}", url), LogLevel::Debug);
+
+        cache_session(context, id, session).await
+    }
+}
+
+pub async fn register_functions() -> Vec<Arc<dyn NodeLogic>> {
+    let mut nodes = vec![
+        Arc::new(McpConnectHttpNode::default()) as Arc<dyn NodeLogic>,
+        Arc::new(tools::McpListToolsNode::default()) as Arc<dyn NodeLogic>,
+        Arc::new(tools::McpCallToolNode::default()) as Arc<dyn NodeLogic>,
+        Arc::new(resources::McpListResourcesNode::default()) as Arc<dyn NodeLogic>,
+        Arc::new(resources::McpReadResourceNode::default()) as Arc<dyn NodeLogic>,
+        Arc::new(prompts::McpListPromptsNode::default()) as Arc<dyn NodeLogic>,
+        Arc::new(prompts::McpGetPromptNode::default()) as Arc<dyn NodeLogic>,
+    ];
+
+    // servers executing boards must not spawn processes on their host
+    #[cfg(feature = "mcp-stdio")]
+    nodes.push(Arc::new(McpConnectStdioNode::default()) as Arc<dyn NodeLogic>);
+
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::{
+        ErrorData, RoleServer, ServerHandler,
+        model::{
+            AnnotateAble, CallToolResult, Content, GetPromptResult, ListPromptsResult,
+            ListResourcesResult, ListToolsResult, PaginatedRequestParam, Prompt, PromptArgument,
+            PromptMessage, RawResource, ReadResourceResult, ServerCapabilities, ServerInfo, Tool,
+        },
+        service::RequestContext,
+    };
+
+    /// Minimal MCP server with one tool, resource and prompt each.
+    struct TestServer;
+
+    impl ServerHandler for TestServer {
+        fn get_info(&self) -> ServerInfo {
+            ServerInfo {
+                capabilities: ServerCapabilities::builder()
+                    .enable_tools()
+                    .enable_resources()
+                    .enable_prompts()
+                    .build(),
+                ..Default::default()
+            }
+        }
+
+        async fn list_tools(
+            &self,
+            _request: Option<PaginatedRequestParam>,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<ListToolsResult, ErrorData> {
+            let schema = json!({
+                "type": "object",
+                "properties": { "a": { "type": "number" }, "b": { "type": "number" } },
+                "required": ["a", "b"]
+            });
+            let schema = schema.as_object().cloned().unwrap_or_default();
+            Ok(ListToolsResult::with_all_items(vec![Tool::new(
+                "add",
+                "Adds two numbers",
+                Arc::new(schema),
+            )]))
+        }
+
+        async fn call_tool(
+            &self,
+            request: CallToolRequestParam,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<CallToolResult, ErrorData> {
+            let arguments = request.arguments.unwrap_or_default();
+            let (Some(a), Some(b)) = (
+                arguments.get("a").and_then(Value::as_f64),
+                arguments.get("b").and_then(Value::as_f64),
+            ) else {
+                return Ok(CallToolResult::error(vec![Content::text(
+                    "a and b are required",
+                )]));
+            };
+            Ok(CallToolResult::success(vec![Content::text(
+                (a + b).to_string(),
+            )]))
+        }
+
+        async fn list_resources(
+            &self,
+            _request: Option<PaginatedRequestParam>,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<ListResourcesResult, ErrorData> {
+            Ok(ListResourcesResult::with_all_items(vec![
+                RawResource::new("memo://readme", "readme").no_annotation(),
+            ]))
+        }
+
+        async fn read_resource(
+            &self,
+            request: ReadResourceRequestParam,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<ReadResourceResult, ErrorData> {
+            Ok(ReadResourceResult {
+                contents: vec![ResourceContents::text("Hello from MCP", request.uri)],
+            })
+        }
+
+        async fn list_prompts(
+            &self,
+            _request: Option<PaginatedRequestParam>,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<ListPromptsResult, ErrorData> {
+            Ok(ListPromptsResult::with_all_items(vec![Prompt::new(
+                "greet",
+                Some("Greets somebody"),
+                Some(vec![PromptArgument {
+                    name: "name".to_string(),
+                    title: None,
+                    description: None,
+                    required: Some(true),
+                }]),
+            )]))
+        }
+
+        async fn get_prompt(
+            &self,
+            request: GetPromptRequestParam,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<GetPromptResult, ErrorData> {
+            let name = request
+                .arguments
+                .and_then(|arguments| arguments.get("name").cloned())
+                .and_then(|name| name.as_str().map(ToOwned::to_owned))
+                .unwrap_or_default();
+            Ok(GetPromptResult {
+                description: None,
+                messages: vec![PromptMessage::new_text(
+                    PromptMessageRole::User,
+                    format!("Say hello to {}", name),
+                )],
+            })
+        }
+    }
+
+    async fn session() -> McpSessionCache {
+        let (client_io, server_io) = tokio::io::duplex(4096);
+        tokio::spawn(async move {
+            let server = TestServer.serve(server_io).await.unwrap();
+            server.waiting().await.unwrap();
+        });
+        let client = ().serve(client_io).await.unwrap();
+        McpSessionCache {
+            client: Arc::new(client),
+        }
+    }
+
+    #[tokio::test]
+    async fn lists_and_calls_tools() {
+        let session = session().await;
+
+        let tools = session.list_tools().await.unwrap();
+        assert_eq!(tools.len(), 1);
+        let function = tools[0].to_openai_function();
+        assert_eq!(function.name, "add");
+        assert_eq!(function.parameters["required"], json!(["a", "b"]));
+
+        let result = session
+            .call_tool("add", json!({ "a": 2, "b": 3 }))
+            .await
+            .unwrap();
+        assert_eq!(result.text, "5");
+        assert!(!result.is_error);
+
+        let result = session.call_tool("add", Value::Null).await.unwrap();
+        assert!(result.is_error);
+
+        assert!(session.call_tool("add", json!([2, 3])).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn reads_resources_and_prompts() {
+        let session = session().await;
+
+        let resources = session.list_resources().await.unwrap();
+        assert_eq!(resources[0].uri, "memo://readme");
+        let contents = session.read_resource("memo://readme").await.unwrap();
+        assert_eq!(contents[0].text.as_deref(), Some("Hello from MCP"));
+
+        let prompts = session.list_prompts().await.unwrap();
+        assert_eq!(prompts[0].name, "greet");
+        assert!(prompts[0].arguments[0].required);
+        let messages = session
+            .get_prompt("greet", json!({ "name": "Ada" }))
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].role, Role::User);
+        assert_eq!(
+            messages[0].content,
+            HistoryMessage::from_string(Role::User, "Say hello to Ada").content
+        );
+    }
+}
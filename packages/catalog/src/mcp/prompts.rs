@@ -0,0 +1,145 @@
+use flow_like::{
+    flow::{
+        execution::context::ExecutionContext,
+        node::{Node, NodeLogic},
+        pin::{PinOptions, ValueType},
+        variable::VariableType,
+    },
+    state::FlowLikeState,
+};
+use flow_like_model_provider::history::HistoryMessage;
+use flow_like_types::{Value, async_trait, json::json};
+
+use super::{McpConnection, McpPrompt};
+
+#[derive(Default)]
+pub struct McpListPromptsNode;
+
+impl McpListPromptsNode {
+    pub fn new() -> Self {
+        McpListPromptsNode
+    }
+}
+
+#[async_trait]
+impl NodeLogic for McpListPromptsNode {
+    async fn get_node(&self, _app_state: &FlowLikeState) -> Node {
+        let mut node = Node::new(
+            "mcp_list_prompts",
+            "MCP List Prompts",
+            "Lists the prompts an MCP server offers",
+            "AI/MCP",
+        );
+        node.add_icon("/flow/icons/workflow.svg");
+
+        node.add_input_pin("exec_in", "In", "Execution input", VariableType::Execution);
+        node.add_input_pin(
+            "connection",
+            "Connection",
+            "MCP connection reference",
+            VariableType::Struct,
+        )
+        .set_schema::<McpConnection>();
+
+        node.add_output_pin(
+            "exec_out",
+            "Out",
+            "Execution output",
+            VariableType::Execution,
+        );
+        node.add_output_pin(
+            "prompts",
+            "Prompts",
+            "Available prompts",
+            VariableType::Struct,
+        )
+        .set_schema::<McpPrompt>()
+        .set_value_type(ValueType::Array);
+
+        node
+    }
+
+    async fn run(&self, context: &mut ExecutionContext) -> flow_like_types::Result<()> {
+        context.deactivate_exec_pin("exec_out").await?;
+
+        let connection: McpConnection = context.evaluate_pin("connection").await?;
+        let session = connection.to_session(context).await?;
+        let prompts = session.list_prompts().await?;
+
+        context.set_pin_value("prompts", json!(prompts)).await?;
+        context.activate_exec_pin("exec_out").await?;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct McpGetPromptNode;
+
+impl McpGetPromptNode {
+    pub fn new() -> Self {
+        McpGetPromptNode
+    }
+}
+
+#[async_trait]
+impl NodeLogic for McpGetPromptNode {
+    async fn get_node(&self, _app_state: &FlowLikeState) -> Node {
+        let mut node = Node::new(
+            "mcp_get_prompt",
+            "MCP Get Prompt",
+            "Renders a prompt of an MCP server into chat messages",
+            "AI/MCP",
+        );
+        node.add_icon("/flow/icons/workflow.svg");
+
+        node.add_input_pin("exec_in", "In", "Execution input", VariableType::Execution);
+        node.add_input_pin(
+            "connection",
+            "Connection",
+            "MCP connection reference",
+            VariableType::Struct,
+        )
+        .set_schema::<McpConnection>();
+        node.add_input_pin("name", "Name", "Name of the prompt", VariableType::String);
+        node.add_input_pin(
+            "arguments",
+            "Arguments",
+            "Prompt arguments",
+            VariableType::Struct,
+        )
+        .set_default_value(Some(json!({})));
+
+        node.add_output_pin(
+            "exec_out",
+            "Out",
+            "Execution output",
+            VariableType::Execution,
+        );
+        node.add_output_pin(
+            "messages",
+            "Messages",
+            "Chat Messages of the prompt",
+            VariableType::Struct,
+        )
+        .set_schema::<HistoryMessage>()
+        .set_value_type(ValueType::Array)
+        .set_options(PinOptions::new().set_enforce_schema(true).build());
+
+        node
+    }
+
+    async fn run(&self, context: &mut ExecutionContext) -> flow_like_types::Result<()> {
+        context.deactivate_exec_pin("exec_out").await?;
+
+        let connection: McpConnection = context.evaluate_pin("connection").await?;
+        let name: String = context.evaluate_pin("name").await?;
+        let arguments: Value = context.evaluate_pin("arguments").await?;
+
+        let session = connection.to_session(context).await?;
+        let messages = session.get_prompt(&name, arguments).await?;
+
+        context.set_pin_value("messages", json!(messages)).await?;
+        context.activate_exec_pin("exec_out").await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use flow_like::{
+    flow::{
+        execution::context::ExecutionContext,
+        node::{Node, NodeLogic},
+        pin::ValueType,
+        variable::VariableType,
+    },
+    state::FlowLikeState,
+};
+use flow_like_types::{async_trait, json::json};
+
+use super::{McpConnection, McpResource, McpResourceContent};
+
+#[derive(Default)]
+pub struct McpListResourcesNode;
+
+impl McpListResourcesNode {
+    pub fn new() -> Self {
+        McpListResourcesNode
+    }
+}
+
+#[async_trait]
+impl NodeLogic for McpListResourcesNode {
+    async fn get_node(&self, _app_state: &FlowLikeState) -> Node {
+        let mut node = Node::new(
+            "mcp_list_resources",
+            "MCP List Resources",
+            "Lists the resources an MCP server offers",
+            "AI/MCP",
+        );
+        node.add_icon("/flow/icons/workflow.svg");
+
+        node.add_input_pin("exec_in", "In", "Execution input", VariableType::Execution);
+        node.add_input_pin(
+            "connection",
+            "Connection",
+            "MCP connection reference",
+            VariableType::Struct,
+        )
+        .set_schema::<McpConnection>();
+
+        node.add_output_pin(
+            "exec_out",
+            "Out",
+            "Execution output",
+            VariableType::Execution,
+        );
+        node.add_output_pin(
+            "resources",
+            "Resources",
+            "Available resources",
+            VariableType::Struct,
+        )
+        .set_schema::<McpResource>()
+        .set_value_type(ValueType::Array);
+
+        node
+    }
+
+    async fn run(&self, context: &mut ExecutionContext) -> flow_like_types::Result<()> {
+        context.deactivate_exec_pin("exec_out").await?;
+
+        let connection: McpConnection = context.evaluate_pin("connection").await?;
+        let session = connection.to_session(context).await?;
+        let resources = session.list_resources().await?;
+
+        context.set_pin_value("resources", json!(resources)).await?;
+        context.activate_exec_pin("exec_out").await?;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct McpReadResourceNode;
+
+impl McpReadResourceNode {
+    pub fn new() -> Self {
+        McpReadResourceNode
+    }
+}
+
+#[async_trait]
+impl NodeLogic for McpReadResourceNode {
+    async fn get_node(&self, _app_state: &FlowLikeState) -> Node {
+        let mut node = Node::new(
+            "mcp_read_resource",
+            "MCP Read Resource",
+            "Reads a resource of an MCP server",
+            "AI/MCP",
+        );
+        node.add_icon("/flow/icons/workflow.svg");
+
+        node.add_input_pin("exec_in", "In", "Execution input", VariableType::Execution);
+        node.add_input_pin(
+            "connection",
+            "Connection",
+            "MCP connection reference",
+            VariableType::Struct,
+        )
+        .set_schema::<McpConnection>();
+        node.add_input_pin("uri", "URI", "URI of the resource", VariableType::String);
+
+        node.add_output_pin(
+            "exec_out",
+            "Out",
+            "Execution output",
+            VariableType::Execution,
+        );
+        node.add_output_pin(
+            "contents",
+            "Contents",
+            "Contents of the resource",
+            VariableType::Struct,
+        )
+        .set_schema::<McpResourceContent>()
+        .set_value_type(ValueType::Array);
+        node.add_output_pin(
+            "text",
+            "Text",
+            "All text contents joined by new lines",
+            VariableType::String,
+        );
+
+        node
+    }
+
+    async fn run(&self, context: &mut ExecutionContext) -> flow_like_types::Result<()> {
+        context.deactivate_exec_pin("exec_out").await?;
+
+        let connection: McpConnection = context.evaluate_pin("connection").await?;
+        let uri: String = context.evaluate_pin("uri").await?;
+
+        let session = connection.to_session(context).await?;
+        let contents = session.read_resource(&uri).await?;
+        let text = contents
+            .iter()
+            .filter_map(|content| content.text.as_deref())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        context.set_pin_value("contents", json!(contents)).await?;
+        context.set_pin_value("text", json!(text)).await?;
+        context.activate_exec_pin("exec_out").await?;
+        Ok(())
+    }
+}
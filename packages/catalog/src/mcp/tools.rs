@@ -0,0 +1,171 @@
+use flow_like::{
+    flow::{
+        execution::context::ExecutionContext,
+        node::{Node, NodeLogic},
+        pin::ValueType,
+        variable::VariableType,
+    },
+    state::FlowLikeState,
+};
+use flow_like_types::{
+    Value, async_trait,
+    json::{self, json},
+};
+
+use super::{McpConnection, McpTool};
+
+#[derive(Default)]
+pub struct McpListToolsNode;
+
+impl McpListToolsNode {
+    pub fn new() -> Self {
+        McpListToolsNode
+    }
+}
+
+#[async_trait]
+impl NodeLogic for McpListToolsNode {
+    async fn get_node(&self, _app_state: &FlowLikeState) -> Node {
+        let mut node = Node::new(
+            "mcp_list_tools",
+            "MCP List Tools",
+            "Lists the tools an MCP server offers",
+            "AI/MCP",
+        );
+        node.add_icon("/flow/icons/workflow.svg");
+
+        node.add_input_pin("exec_in", "In", "Execution input", VariableType::Execution);
+        node.add_input_pin(
+            "connection",
+            "Connection",
+            "MCP connection reference",
+            VariableType::Struct,
+        )
+        .set_schema::<McpConnection>();
+
+        node.add_output_pin(
+            "exec_out",
+            "Out",
+            "Execution output",
+            VariableType::Execution,
+        );
+        node.add_output_pin("tools", "Tools", "Available tools", VariableType::Struct)
+            .set_schema::<McpTool>()
+            .set_value_type(ValueType::Array);
+        node.add_output_pin(
+            "definitions",
+            "Definitions",
+            "OpenAI Function Definitions of the tools",
+            VariableType::String,
+        );
+
+        node
+    }
+
+    async fn run(&self, context: &mut ExecutionContext) -> flow_like_types::Result<()> {
+        context.deactivate_exec_pin("exec_out").await?;
+
+        let connection: McpConnection = context.evaluate_pin("connection").await?;
+        let session = connection.to_session(context).await?;
+        let tools = session.list_tools().await?;
+
+        let definitions = tools
+            .iter()
+            .map(|tool| tool.to_openai_function())
+            .collect::<Vec<_>>();
+
+        context.set_pin_value("tools", json!(tools)).await?;
+        context
+            .set_pin_value("definitions", json!(json::to_string(&definitions)?))
+            .await?;
+        context.activate_exec_pin("exec_out").await?;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct McpCallToolNode;
+
+impl McpCallToolNode {
+    pub fn new() -> Self {
+        McpCallToolNode
+    }
+}
+
+#[async_trait]
+impl NodeLogic for McpCallToolNode {
+    async fn get_node(&self, _app_state: &FlowLikeState) -> Node {
+        let mut node = Node::new(
+            "mcp_call_tool",
+            "MCP Call Tool",
+            "Calls a tool of an MCP server",
+            "AI/MCP",
+        );
+        node.add_icon("/flow/icons/workflow.svg");
+
+        node.add_input_pin("exec_in", "In", "Execution input", VariableType::Execution);
+        node.add_input_pin(
+            "connection",
+            "Connection",
+            "MCP connection reference",
+            VariableType::Struct,
+        )
+        .set_schema::<McpConnection>();
+        node.add_input_pin("name", "Name", "Name of the tool", VariableType::String);
+        node.add_input_pin(
+            "arguments",
+            "Arguments",
+            "Tool arguments, e.g. the Tool Args of a Tool Call",
+            VariableType::Struct,
+        )
+        .set_default_value(Some(json!({})));
+
+        node.add_output_pin(
+            "exec_out",
+            "Out",
+            "Execution output",
+            VariableType::Execution,
+        );
+        node.add_output_pin(
+            "result",
+            "Result",
+            "Text content of the result",
+            VariableType::String,
+        );
+        node.add_output_pin(
+            "structured",
+            "Structured",
+            "Structured content of the result, null if the tool returned none",
+            VariableType::Struct,
+        );
+        node.add_output_pin(
+            "is_error",
+            "Is Error",
+            "Whether the tool reported an error",
+            VariableType::Boolean,
+        );
+
+        node
+    }
+
+    async fn run(&self, context: &mut ExecutionContext) -> flow_like_types::Result<()> {
+        context.deactivate_exec_pin("exec_out").await?;
+
+        let connection: McpConnection = context.evaluate_pin("connection").await?;
+        let name: String = context.evaluate_pin("name").await?;
+        let arguments: Value = context.evaluate_pin("arguments").await?;
+
+        let session = connection.to_session(context).await?;
+        let result = session.call_tool(&name, arguments).await?;
+
+        context.set_pin_value("result", json!(result.text)).await?;
+        context
+            .set_pin_value("structured", json!(result.structured))
+            .await?;
+        context
+            .set_pin_value("is_error", json!(result.is_error))
+            .await?;
+        context.activate_exec_pin("exec_out").await?;
+        Ok(())
+    }
+}
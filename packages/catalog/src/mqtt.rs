@@ -0,0 +1,410 @@
+use flow_like::{
+    flow::{
+        execution::context::ExecutionContext,
+        node::{Node, NodeLogic},
+        pin::PinOptions,
+        variable::VariableType,
+    },
+    state::FlowLikeState,
+};
+use flow_like_types::{Cacheable, Value, anyhow, async_trait, json::json};
+use rumqttc::{
+    AsyncClient, ConnectReturnCode, Event, EventLoop, MqttOptions, Packet, QoS, Transport,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{
+    any::Any,
+    collections::{HashMap, hash_map::DefaultHasher},
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{sync::broadcast, task::JoinHandle};
+pub mod publish;
+pub mod source;
+pub mod subscribe;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const MESSAGE_BUFFER: usize = 1024;
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
+pub struct MqttConnection {
+    pub id: String,
+}
+
+impl MqttConnection {
+    pub fn new(id: String) -> Self {
+        MqttConnection { id }
+    }
+
+    pub async fn to_session(
+        &self,
+        context: &mut ExecutionContext,
+    ) -> flow_like_types::Result<MqttSessionCache> {
+        let cache_key = format!("mqtt_session_{}", self.id);
+        if let Some(session) = context.get_cache(&cache_key).await {
+            let session = session
+                .as_any()
+                .downcast_ref::<MqttSessionCache>()
+                .ok_or_else(|| anyhow!("Failed to downcast MqttSessionCache"))?
+                .clone();
+            Ok(session)
+        } else {
+            Err(anyhow!("MQTT session not found"))
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+pub struct MqttMessage {
+    pub topic: String,
+    /// The payload decoded as UTF-8 (lossy)
+    pub payload: String,
+    /// The payload parsed as JSON, if it is valid JSON
+    pub json: Option<Value>,
+    pub qos: u8,
+    pub retain: bool,
+}
+
+impl From<rumqttc::Publish> for MqttMessage {
+    fn from(publish: rumqttc::Publish) -> Self {
+        let json = flow_like_types::json::from_slice::<Value>(&publish.payload).ok();
+        MqttMessage {
+            topic: publish.topic,
+            payload: String::from_utf8_lossy(&publish.payload).to_string(),
+            json,
+            qos: publish.qos as u8,
+            retain: publish.retain,
+        }
+    }
+}
+
+pub fn parse_qos(qos: i64) -> flow_like_types::Result<QoS> {
+    match qos {
+        0 => Ok(QoS::AtMostOnce),
+        1 => Ok(QoS::AtLeastOnce),
+        2 => Ok(QoS::ExactlyOnce),
+        other => Err(anyhow!("Invalid QoS level: {} (valid: 0, 1, 2)", other)),
+    }
+}
+
+/// Aborts the event loop task once the last clone of the session is dropped.
+struct EventLoopGuard(JoinHandle<()>);
+
+impl Drop for EventLoopGuard {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// A connected MQTT client. Incoming publishes of all subscriptions are broadcast to `messages`.
+/// Subscriptions made through the session are restored when the broker drops them on a reconnect.
+#[derive(Clone)]
+pub struct MqttSessionCache {
+    pub client: AsyncClient,
+    pub messages: broadcast::Sender<MqttMessage>,
+    subscriptions: Arc<Mutex<HashMap<String, QoS>>>,
+    _event_loop: Arc<EventLoopGuard>,
+}
+
+impl MqttSessionCache {
+    pub async fn connect(options: MqttOptions) -> flow_like_types::Result<Self> {
+        let (client, mut event_loop) = AsyncClient::new(options, 64);
+
+        tokio::time::timeout(CONNECT_TIMEOUT, wait_for_connack(&mut event_loop))
+            .await
+            .map_err(|_| anyhow!("Timed out connecting to MQTT broker"))??;
+
+        let (sender, _) = broadcast::channel(MESSAGE_BUFFER);
+        let messages = sender.clone();
+        let subscriptions: Arc<Mutex<HashMap<String, QoS>>> = Arc::default();
+        let resubscribe = (client.clone(), subscriptions.clone());
+        let handle = tokio::spawn(async move {
+            loop {
+                match event_loop.poll().await {
+                    Ok(Event::Incoming(Packet::Publish(publish))) => {
+                        let _ = messages.send(MqttMessage::from(publish));
+                    }
+                    // a clean session forgets the subscriptions of the previous connection
+                    Ok(Event::Incoming(Packet::ConnAck(ack))) if !ack.session_present => {
+                        let (client, subscriptions) = &resubscribe;
+                        let subscriptions = match subscriptions.lock() {
+                            Ok(subscriptions) => subscriptions.clone(),
+                            Err(_) => continue,
+                        };
+                        for (filter, qos) in subscriptions {
+                            // the request channel is drained by this loop, so it must not block
+                            if let Err(e) = client.try_subscribe(&filter, qos) {
+                                eprintln!("MQTT resubscribe to {} failed: {}", filter, e);
+                            }
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        // rumqttc reconnects on the next poll
+                        eprintln!("MQTT connection error: {}", e);
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                }
+            }
+        });
+
+        Ok(MqttSessionCache {
+            client,
+            messages: sender,
+            subscriptions,
+            _event_loop: Arc::new(EventLoopGuard(handle)),
+        })
+    }
+
+    pub async fn subscribe(&self, filter: &str, qos: QoS) -> flow_like_types::Result<()> {
+        if let Ok(mut subscriptions) = self.subscriptions.lock() {
+            subscriptions.insert(filter.to_string(), qos);
+        }
+        self.client
+            .subscribe(filter, qos)
+            .await
+            .map_err(|e| anyhow!("MQTT subscribe failed: {}", e))
+    }
+
+    pub async fn unsubscribe(&self, filter: &str) -> flow_like_types::Result<()> {
+        if let Ok(mut subscriptions) = self.subscriptions.lock() {
+            subscriptions.remove(filter);
+        }
+        self.client
+            .unsubscribe(filter)
+            .await
+            .map_err(|e| anyhow!("MQTT unsubscribe failed: {}", e))
+    }
+}
+
+async fn wait_for_connack(event_loop: &mut EventLoop) -> flow_like_types::Result<()> {
+    loop {
+        match event_loop.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(ack))) => {
+                if ack.code != ConnectReturnCode::Success {
+                    return Err(anyhow!("MQTT broker refused connection: {:?}", ack.code));
+                }
+                return Ok(());
+            }
+            Ok(_) => continue,
+            Err(e) => return Err(anyhow!("Failed to connect to MQTT broker: {}", e)),
+        }
+    }
+}
+
+pub fn mqtt_options(
+    host: &str,
+    port: u16,
+    client_id: &str,
+    username: Option<&str>,
+    password: Option<&str>,
+    use_tls: bool,
+    keep_alive: u64,
+) -> MqttOptions {
+    let mut options = MqttOptions::new(client_id, host, port);
+    options.set_keep_alive(Duration::from_secs(keep_alive.max(5)));
+
+    if let Some(username) = username.filter(|username| !username.is_empty()) {
+        options.set_credentials(username, password.unwrap_or_default());
+    }
+
+    if use_tls {
+        options.set_transport(Transport::tls_with_default_config());
+    }
+
+    options
+}
+
+impl Cacheable for MqttSessionCache {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[derive(Default)]
+pub struct MqttConnectNode;
+
+impl MqttConnectNode {
+    pub fn new() -> Self {
+        MqttConnectNode
+    }
+}
+
+#[async_trait]
+impl NodeLogic for MqttConnectNode {
+    async fn get_node(&self, _app_state: &FlowLikeState) -> Node {
+        let mut node = Node::new(
+            "mqtt_connect",
+            "MQTT Connect",
+            "Connects to an MQTT broker and caches the session",
+            "IoT/MQTT",
+        );
+        node.add_icon("/flow/icons/web.svg");
+
+        node.add_input_pin("exec_in", "In", "Execution input", VariableType::Execution);
+        node.add_input_pin("host", "Host", "MQTT broker hostname", VariableType::String)
+            .set_default_value(Some(json!("localhost")));
+        node.add_input_pin("port", "Port", "MQTT broker port", VariableType::Integer)
+            .set_default_value(Some(json!(1883)));
+        node.add_input_pin(
+            "client_id",
+            "Client ID",
+            "MQTT client identifier, generated if empty",
+            VariableType::String,
+        )
+        .set_default_value(Some(json!("")));
+        node.add_input_pin(
+            "username",
+            "Username",
+            "Broker username, leave empty for anonymous access",
+            VariableType::String,
+        )
+        .set_default_value(Some(json!("")));
+        node.add_input_pin(
+            "password",
+            "Password",
+            "Broker password",
+            VariableType::String,
+        )
+        .set_default_value(Some(json!("")))
+        .set_options(PinOptions::new().set_sensitive(true).build());
+        node.add_input_pin(
+            "use_tls",
+            "Use TLS",
+            "Connect via TLS using the system root certificates",
+            VariableType::Boolean,
+        )
+        .set_default_value(Some(json!(false)));
+        node.add_input_pin(
+            "keep_alive",
+            "Keep Alive",
+            "Keep alive interval in seconds",
+            VariableType::Integer,
+        )
+        .set_default_value(Some(json!(30)));
+
+        node.add_output_pin(
+            "exec_out",
+            "Out",
+            "Execution output",
+            VariableType::Execution,
+        );
+        node.add_output_pin(
+            "connection",
+            "Connection",
+            "Cached MQTT connection reference",
+            VariableType::Struct,
+        )
+        .set_schema::<MqttConnection>();
+
+        node
+    }
+
+    async fn run(&self, context: &mut ExecutionContext) -> flow_like_types::Result<()> {
+        context.deactivate_exec_pin("exec_out").await?;
+
+        let host: String = context.evaluate_pin("host").await?;
+        let port: i64 = context.evaluate_pin("port").await?;
+        let port = u16::try_from(port).map_err(|_| anyhow!("Invalid port: {}", port))?;
+        let client_id: String = context.evaluate_pin("client_id").await?;
+        let username: String = context.evaluate_pin("username").await?;
+        let password: String = context.evaluate_pin("password").await?;
+        let use_tls: bool = context.evaluate_pin("use_tls").await?;
+        let keep_alive: i64 = context.evaluate_pin("keep_alive").await?;
+
+        let mut hasher = DefaultHasher::new();
+        host.hash(&mut hasher);
+        port.hash(&mut hasher);
+        client_id.hash(&mut hasher);
+        username.hash(&mut hasher);
+        password.hash(&mut hasher);
+        use_tls.hash(&mut hasher);
+        let id = hasher.finish().to_string();
+        let cache_key = format!("mqtt_session_{}", id);
+
+        {
+            let cache = context.cache.read().await;
+            if cache.contains_key(&cache_key) {
+                context
+                    .set_pin_value("connection", json!(MqttConnection { id: id.clone() }))
+                    .await?;
+                context.activate_exec_pin("exec_out").await?;
+                return Ok(());
+            }
+        }
+
+        let client_id = if client_id.is_empty() {
+            format!("flow-like-{}", flow_like_types::create_id())
+        } else {
+            client_id
+        };
+
+        let options = mqtt_options(
+            &host,
+            port,
+            &client_id,
+            Some(&username),
+            Some(&password),
+            use_tls,
+            keep_alive.max(0) as u64,
+        );
+        let session = MqttSessionCache::connect(options).await?;
+
+        context.log_message(
+            &format!("-- connected to {}:{} as {}", host, port, client_id),
+            flow_like::flow::execution::LogLevel::Debug,
+        );
+
+        context
+            .cache
+            .write()
+            .await
+            .insert(cache_key, Arc::new(session));
+
+        context
+            .set_pin_value("connection", json!(MqttConnection { id: id.clone() }))
+            .await?;
+        context.activate_exec_pin("exec_out").await?;
+        Ok(())
+    }
+}
+
+pub async fn register_functions() -> Vec<Arc<dyn NodeLogic>> {
+    vec![
+        Arc::new(MqttConnectNode::default()) as Arc<dyn NodeLogic>,
+        Arc::new(publish::MqttPublishNode::default()) as Arc<dyn NodeLogic>,
+        Arc::new(subscribe::MqttSubscribeNode::default()) as Arc<dyn NodeLogic>,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_qos_levels() {
+        assert_eq!(parse_qos(0).unwrap(), QoS::AtMostOnce);
+        assert_eq!(parse_qos(1).unwrap(), QoS::AtLeastOnce);
+        assert_eq!(parse_qos(2).unwrap(), QoS::ExactlyOnce);
+        assert!(parse_qos(3).is_err());
+    }
+
+    #[test]
+    fn decodes_json_payloads() {
+        let publish = rumqttc::Publish::new("sensors/1", QoS::AtLeastOnce, r#"{"temp":21.5}"#);
+        let message = MqttMessage::from(publish);
+        assert_eq!(message.topic, "sensors/1");
+        assert_eq!(message.qos, 1);
+        assert_eq!(message.json, Some(json!({ "temp": 21.5 })));
+
+        let publish = rumqttc::Publish::new("sensors/1", QoS::AtMostOnce, "on");
+        let message = MqttMessage::from(publish);
+        assert_eq!(message.payload, "on");
+        assert_eq!(message.json, None);
+    }
+}
@@ -0,0 +1,110 @@
+use flow_like::{
+    flow::{
+        execution::context::ExecutionContext,
+        node::{Node, NodeLogic},
+        pin::PinOptions,
+        variable::VariableType,
+    },
+    state::FlowLikeState,
+};
+use flow_like_types::{async_trait, json::json};
+
+use super::{MqttConnection, parse_qos};
+
+#[derive(Default)]
+pub struct MqttPublishNode;
+
+impl MqttPublishNode {
+    pub fn new() -> Self {
+        MqttPublishNode
+    }
+}
+
+#[async_trait]
+impl NodeLogic for MqttPublishNode {
+    async fn get_node(&self, _app_state: &FlowLikeState) -> Node {
+        let mut node = Node::new(
+            "mqtt_publish",
+            "MQTT Publish",
+            "Publishes a message to an MQTT topic",
+            "IoT/MQTT",
+        );
+        node.add_icon("/flow/icons/web.svg");
+
+        node.add_input_pin("exec_in", "In", "Execution input", VariableType::Execution);
+        node.add_input_pin(
+            "connection",
+            "Connection",
+            "MQTT connection reference",
+            VariableType::Struct,
+        )
+        .set_schema::<MqttConnection>();
+        node.add_input_pin(
+            "topic",
+            "Topic",
+            "Topic to publish to",
+            VariableType::String,
+        );
+        node.add_input_pin(
+            "payload",
+            "Payload",
+            "Message payload",
+            VariableType::String,
+        );
+        node.add_input_pin(
+            "qos",
+            "QoS",
+            "0 = at most once, 1 = at least once, 2 = exactly once",
+            VariableType::Integer,
+        )
+        .set_default_value(Some(json!(1)))
+        .set_options(
+            PinOptions::new()
+                .set_valid_values(vec!["0".into(), "1".into(), "2".into()])
+                .build(),
+        );
+        node.add_input_pin(
+            "retain",
+            "Retain",
+            "Whether the broker should retain the message for new subscribers",
+            VariableType::Boolean,
+        )
+        .set_default_value(Some(json!(false)));
+
+        node.add_output_pin(
+            "exec_out",
+            "Out",
+            "Execution output",
+            VariableType::Execution,
+        );
+
+        node
+    }
+
+    async fn run(&self, context: &mut ExecutionContext) -> flow_like_types::Result<()> {
+        context.deactivate_exec_pin("exec_out").await?;
+
+        let connection: MqttConnection = context.evaluate_pin("connection").await?;
+        let topic: String = context.evaluate_pin("topic").await?;
+        let payload: String = context.evaluate_pin("payload").await?;
+        let qos: i64 = context.evaluate_pin("qos").await?;
+        let retain: bool = context.evaluate_pin("retain").await?;
+
+        if topic.is_empty() || topic.contains(['+', '#']) {
+            return Err(flow_like_types::anyhow!(
+                "Invalid publish topic: {} (wildcards are not allowed)",
+                topic
+            ));
+        }
+
+        let session = connection.to_session(context).await?;
+        session
+            .client
+            .publish(&topic, parse_qos(qos)?, retain, payload.into_bytes())
+            .await
+            .map_err(|e| flow_like_types::anyhow!("MQTT publish failed: {}", e))?;
+
+        context.activate_exec_pin("exec_out").await?;
+        Ok(())
+    }
+}
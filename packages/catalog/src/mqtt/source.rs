@@ -0,0 +1,197 @@
+use flow_like::flow::{event::MqttEventParameters, execution::RunPayload};
+use flow_like_types::{anyhow, create_id, json::json};
+use futures::future::BoxFuture;
+use std::sync::Arc;
+use tokio::{sync::broadcast::error::RecvError, task::JoinHandle};
+
+use super::{MqttSessionCache, mqtt_options, parse_qos};
+
+/// Starts a run for a received message. The payload id is the start node of the event.
+pub type MqttTrigger = Arc<dyn Fn(RunPayload) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// Keeps a subscription for an MQTT event and fires one run per matching message.
+/// The subscription ends when the source is stopped or dropped.
+pub struct MqttEventSource {
+    session: MqttSessionCache,
+    topic_filter: String,
+    task: JoinHandle<()>,
+}
+
+impl MqttEventSource {
+    pub async fn start(
+        node_id: &str,
+        parameters: &MqttEventParameters,
+        trigger: MqttTrigger,
+    ) -> flow_like_types::Result<Self> {
+        let host = parameters
+            .host
+            .clone()
+            .ok_or_else(|| anyhow!("MQTT event is missing the broker host"))?;
+        let topic_filter = parameters
+            .topic_filter
+            .clone()
+            .ok_or_else(|| anyhow!("MQTT event is missing the topic filter"))?;
+        if !rumqttc::valid_filter(&topic_filter) {
+            return Err(anyhow!("Invalid topic filter: {}", topic_filter));
+        }
+
+        let qos = parse_qos(parameters.qos.unwrap_or(1) as i64)?;
+        let include_retained = parameters.include_retained.unwrap_or(false);
+        let client_id = parameters
+            .client_id
+            .clone()
+            .unwrap_or_else(|| format!("flow-like-{}", create_id()));
+
+        let options = mqtt_options(
+            &host,
+            parameters.port.unwrap_or(1883),
+            &client_id,
+            parameters.username.as_deref(),
+            parameters.secret_password.as_deref(),
+            parameters.use_tls.unwrap_or(false),
+            30,
+        );
+
+        let session = MqttSessionCache::connect(options).await?;
+        let mut receiver = session.messages.subscribe();
+        session.subscribe(&topic_filter, qos).await?;
+
+        let node_id = node_id.to_string();
+        let filter = topic_filter.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                let message = match receiver.recv().await {
+                    Ok(message) => message,
+                    Err(RecvError::Lagged(skipped)) => {
+                        eprintln!("MQTT event source skipped {} messages", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+
+                if !rumqttc::matches(&message.topic, &filter) {
+                    continue;
+                }
+
+                if message.retain && !include_retained {
+                    continue;
+                }
+
+                let payload = RunPayload {
+                    id: node_id.clone(),
+                    payload: Some(json!(message)),
+                };
+                tokio::spawn(trigger(payload));
+            }
+        });
+
+        Ok(MqttEventSource {
+            session,
+            topic_filter,
+            task,
+        })
+    }
+
+    pub async fn stop(self) -> flow_like_types::Result<()> {
+        self.task.abort();
+        self.session.unsubscribe(&self.topic_filter).await?;
+        self.session
+            .client
+            .disconnect()
+            .await
+            .map_err(|e| anyhow!("MQTT disconnect failed: {}", e))?;
+        Ok(())
+    }
+}
+
+impl Drop for MqttEventSource {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mqtt::MqttMessage;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    /// Runs against a local broker, e.g. `docker run -p 1883:1883 eclipse-mosquitto:2 mosquitto -c /mosquitto-no-auth.conf`
+    /// and `MQTT_TEST_BROKER=localhost:1883`.
+    #[tokio::test]
+    async fn fires_run_per_message() {
+        let Ok(broker) = std::env::var("MQTT_TEST_BROKER") else {
+            println!("MQTT_TEST_BROKER not set, skipping");
+            return;
+        };
+        let (host, port) = broker.split_once(':').unwrap_or((&broker, "1883"));
+        let port: u16 = port.parse().unwrap();
+        let topic = format!("flow-like/test/{}", create_id());
+
+        let parameters = MqttEventParameters {
+            host: Some(host.to_string()),
+            port: Some(port),
+            client_id: None,
+            username: None,
+            secret_password: None,
+            use_tls: Some(false),
+            topic_filter: Some(format!("{}/+", topic)),
+            qos: Some(1),
+            include_retained: Some(false),
+        };
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let trigger: MqttTrigger = Arc::new(move |payload| {
+            let sender = sender.clone();
+            Box::pin(async move {
+                let _ = sender.send(payload);
+            })
+        });
+
+        let source = MqttEventSource::start("start_node", &parameters, trigger)
+            .await
+            .unwrap();
+
+        let publisher = MqttSessionCache::connect(mqtt_options(
+            host,
+            port,
+            &format!("flow-like-{}", create_id()),
+            None,
+            None,
+            false,
+            30,
+        ))
+        .await
+        .unwrap();
+
+        for (device, value) in [("a", "1"), ("b", "2")] {
+            publisher
+                .client
+                .publish(
+                    format!("{}/{}", topic, device),
+                    rumqttc::QoS::AtLeastOnce,
+                    false,
+                    value,
+                )
+                .await
+                .unwrap();
+        }
+
+        let mut received = vec![];
+        for _ in 0..2 {
+            let payload = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(payload.id, "start_node");
+            let message: MqttMessage =
+                flow_like_types::json::from_value(payload.payload.unwrap()).unwrap();
+            received.push(message.payload);
+        }
+        received.sort();
+        assert_eq!(received, vec!["1", "2"]);
+
+        source.stop().await.unwrap();
+    }
+}
@@ -0,0 +1,156 @@
+use flow_like::{
+    flow::{
+        execution::context::ExecutionContext,
+        node::{Node, NodeLogic},
+        pin::{PinOptions, ValueType},
+        variable::VariableType,
+    },
+    state::FlowLikeState,
+};
+use flow_like_types::{anyhow, async_trait, json::json};
+use std::time::Duration;
+use tokio::{sync::broadcast::error::RecvError, time::Instant};
+
+use super::{MqttConnection, MqttMessage, parse_qos};
+
+#[derive(Default)]
+pub struct MqttSubscribeNode;
+
+impl MqttSubscribeNode {
+    pub fn new() -> Self {
+        MqttSubscribeNode
+    }
+}
+
+#[async_trait]
+impl NodeLogic for MqttSubscribeNode {
+    async fn get_node(&self, _app_state: &FlowLikeState) -> Node {
+        let mut node = Node::new(
+            "mqtt_subscribe",
+            "MQTT Subscribe",
+            "Subscribes to a topic filter and collects messages until the limit or timeout is reached",
+            "IoT/MQTT",
+        );
+        node.add_icon("/flow/icons/web.svg");
+
+        node.add_input_pin("exec_in", "In", "Execution input", VariableType::Execution);
+        node.add_input_pin(
+            "connection",
+            "Connection",
+            "MQTT connection reference",
+            VariableType::Struct,
+        )
+        .set_schema::<MqttConnection>();
+        node.add_input_pin(
+            "topic_filter",
+            "Topic Filter",
+            "Topic filter, supports + and # wildcards",
+            VariableType::String,
+        )
+        .set_default_value(Some(json!("#")));
+        node.add_input_pin(
+            "qos",
+            "QoS",
+            "0 = at most once, 1 = at least once, 2 = exactly once",
+            VariableType::Integer,
+        )
+        .set_default_value(Some(json!(1)))
+        .set_options(
+            PinOptions::new()
+                .set_valid_values(vec!["0".into(), "1".into(), "2".into()])
+                .build(),
+        );
+        node.add_input_pin(
+            "max_messages",
+            "Max Messages",
+            "Stop after this many messages",
+            VariableType::Integer,
+        )
+        .set_default_value(Some(json!(1)));
+        node.add_input_pin(
+            "timeout",
+            "Timeout (ms)",
+            "Stop waiting for messages after this many milliseconds",
+            VariableType::Integer,
+        )
+        .set_default_value(Some(json!(5000)));
+        node.add_input_pin(
+            "include_retained",
+            "Include Retained",
+            "Whether retained messages sent by the broker on subscribe are collected",
+            VariableType::Boolean,
+        )
+        .set_default_value(Some(json!(true)));
+
+        node.add_output_pin(
+            "exec_out",
+            "Out",
+            "Execution output",
+            VariableType::Execution,
+        );
+        node.add_output_pin(
+            "messages",
+            "Messages",
+            "Received messages",
+            VariableType::Struct,
+        )
+        .set_value_type(ValueType::Array)
+        .set_schema::<MqttMessage>();
+
+        node
+    }
+
+    async fn run(&self, context: &mut ExecutionContext) -> flow_like_types::Result<()> {
+        context.deactivate_exec_pin("exec_out").await?;
+
+        let connection: MqttConnection = context.evaluate_pin("connection").await?;
+        let topic_filter: String = context.evaluate_pin("topic_filter").await?;
+        let qos: i64 = context.evaluate_pin("qos").await?;
+        let max_messages: i64 = context.evaluate_pin("max_messages").await?;
+        let timeout: i64 = context.evaluate_pin("timeout").await?;
+        let include_retained: bool = context.evaluate_pin("include_retained").await?;
+
+        if !rumqttc::valid_filter(&topic_filter) {
+            return Err(anyhow!("Invalid topic filter: {}", topic_filter));
+        }
+
+        let session = connection.to_session(context).await?;
+        // Subscribe to the broadcast before the broker is asked, otherwise retained messages can be missed
+        let mut receiver = session.messages.subscribe();
+        session.subscribe(&topic_filter, parse_qos(qos)?).await?;
+
+        let max_messages = max_messages.max(1) as usize;
+        let deadline = Instant::now() + Duration::from_millis(timeout.max(0) as u64);
+        let mut messages = vec![];
+
+        while messages.len() < max_messages {
+            let message = match tokio::time::timeout_at(deadline, receiver.recv()).await {
+                Ok(Ok(message)) => message,
+                Ok(Err(RecvError::Lagged(skipped))) => {
+                    context.log_message(
+                        &format!("-- skipped {} MQTT messages", skipped),
+                        flow_like::flow::execution::LogLevel::Warn,
+                    );
+                    continue;
+                }
+                Ok(Err(RecvError::Closed)) | Err(_) => break,
+            };
+
+            if !rumqttc::matches(&message.topic, &topic_filter) {
+                continue;
+            }
+
+            if message.retain && !include_retained {
+                continue;
+            }
+
+            messages.push(message);
+        }
+
+        session.unsubscribe(&topic_filter).await?;
+
+        context.set_pin_value("messages", json!(messages)).await?;
+        context.activate_exec_pin("exec_out").await?;
+        Ok(())
+    }
+}
@@ -15,7 +15,7 @@ use flow_like::{
 use flow_like_types::{Error, Value, anyhow, async_trait, json, jsonschema};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct OpenAIFunction {
     pub r#type: String,
     pub name: String,
@@ -190,7 +190,7 @@ pub fn validate_json_data(schema: &str, data: &str) -> Result<Value, Error> {
 
 /// Validates a Tool Call string against a list of OpenAi-Functions
 pub fn validate_openai_tool_call_str(
-    functions: &Vec<OpenAIFunction>,
+    functions: &[OpenAIFunction],
     tool_call: &str,
 ) -> Result<OpenAIToolCall, Error> {
     // Deserialize tool call
@@ -198,7 +198,14 @@ pub fn validate_openai_tool_call_str(
         Ok(tool_call) => tool_call,
         Err(e) => return Err(anyhow!(format!("Failed to parse tool call: {}", e))),
     };
+    validate_openai_tool_call(functions, tool_call)
+}
 
+/// Validates a parsed Tool Call against a list of OpenAi-Functions
+pub fn validate_openai_tool_call(
+    functions: &[OpenAIFunction],
+    tool_call: OpenAIToolCall,
+) -> Result<OpenAIToolCall, Error> {
     for function in functions {
         if tool_call.name == function.name {
             let validator = match jsonschema::validator_for(&function.parameters) {
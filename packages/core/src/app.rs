@@ -2,7 +2,7 @@ use crate::{
     bit::Metadata,
     flow::{
         board::{Board, VersionType, commands::nodes::copy_paste::CopyPasteCommand},
-        event::Event,
+        event::{Event, contract::EventContract},
     },
     state::FlowLikeState,
     utils::compression::{compress_to_file, from_compressed},
@@ -14,6 +14,8 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{sync::Arc, time::SystemTime, vec};
 
+pub mod release;
+
 #[derive(Clone, Serialize, Deserialize, JsonSchema)]
 pub enum StandardInterfaces {
     Chat,
@@ -240,10 +242,16 @@ impl App {
         let sum_ratings = self.rating_sum as f64;
         let rating_count = self.rating_count as f64;
         let interactions = self.interactions_count as f64;
-        let avg_rating = sum_ratings / rating_count;
-        self.avg_rating = Some(avg_rating);
-        let relevance =
-            (downloads * 2.0 + interactions) * (1.0 + avg_rating / 5.0) * (rating_count.ln() + 1.0);
+        // Unrated apps rank by downloads and interactions only
+        let avg_rating = if rating_count > 0.0 {
+            sum_ratings / rating_count
+        } else {
+            0.0
+        };
+        self.avg_rating = (rating_count > 0.0).then_some(avg_rating);
+        let relevance = (downloads * 2.0 + interactions)
+            * (1.0 + avg_rating / 5.0)
+            * (rating_count.max(1.0).ln() + 1.0);
         self.relevance_score = Some(relevance);
         relevance
     }
@@ -457,6 +465,19 @@ impl App {
         Ok(event)
     }
 
+    pub async fn get_event_contract(
+        &self,
+        event_id: &str,
+        version: Option<(u32, u32, u32)>,
+    ) -> flow_like_types::Result<EventContract> {
+        let event = Event::load(event_id, self, version).await?;
+        let board = self
+            .open_board(event.board_id.clone(), Some(false), event.board_version)
+            .await?;
+        let contract = event.contract(&*board.lock().await)?;
+        Ok(contract)
+    }
+
     pub async fn upsert_event(
         &mut self,
         event: Event,
@@ -0,0 +1,380 @@
+use std::{collections::HashMap, sync::Arc, time::SystemTime};
+
+use flow_like_storage::{Path, object_store::ObjectStore};
+use flow_like_types::{anyhow, bail};
+use futures::TryStreamExt;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::App,
+    flow::event::{Event, ReleaseNotes},
+    state::FlowLikeState,
+    utils::compression::{compress_to_file_json, from_compressed_json},
+};
+
+/// Immutable bundle of board and event versions. Activating a release switches
+/// all of its events at once, the previous release stays available for rollbacks.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
+pub struct Release {
+    pub id: String,
+    pub version: (u32, u32, u32),
+    pub notes: Option<ReleaseNotes>,
+    /// Pinned board versions, only archived (immutable) versions can be pinned
+    pub boards: HashMap<String, (u32, u32, u32)>,
+    /// Snapshots of the released events, their `board_version` is always set
+    pub events: Vec<Event>,
+    pub created_at: SystemTime,
+    pub updated_at: SystemTime,
+}
+
+/// Which release is live. `history` holds the previously active releases, latest last.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, Default)]
+pub struct ReleaseState {
+    pub active: Option<String>,
+    pub history: Vec<String>,
+    pub updated_at: Option<SystemTime>,
+}
+
+/// Event to put into a release, `None` takes the current version of the event.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
+pub struct ReleaseEvent {
+    pub event_id: String,
+    pub version: Option<(u32, u32, u32)>,
+}
+
+impl Release {
+    pub fn event(&self, event_id: &str) -> Option<&Event> {
+        self.events.iter().find(|event| event.id == event_id)
+    }
+}
+
+impl App {
+    /// RELEASES
+
+    fn releases_dir(&self) -> Path {
+        Path::from("apps").child(self.id.clone()).child("releases")
+    }
+
+    async fn release_store(&self) -> flow_like_types::Result<Arc<dyn ObjectStore>> {
+        let app_state = self
+            .app_state
+            .clone()
+            .ok_or(anyhow!("App state not found"))?;
+        Ok(FlowLikeState::project_meta_store(&app_state)
+            .await?
+            .as_generic())
+    }
+
+    pub async fn get_releases(&self) -> flow_like_types::Result<Vec<Release>> {
+        let store = self.release_store().await?;
+        let locations: Vec<Path> = store
+            .list(Some(&self.releases_dir()))
+            .map_ok(|meta| meta.location)
+            .try_collect()
+            .await?;
+
+        let mut releases = Vec::with_capacity(locations.len());
+        for location in locations {
+            if location.extension() != Some("release") {
+                continue;
+            }
+            releases.push(from_compressed_json::<Release>(store.clone(), location).await?);
+        }
+
+        releases.sort_by(|a, b| b.version.cmp(&a.version));
+        Ok(releases)
+    }
+
+    pub async fn get_release(&self, release_id: &str) -> flow_like_types::Result<Release> {
+        let store = self.release_store().await?;
+        let path = self.releases_dir().child(format!("{}.release", release_id));
+        from_compressed_json(store, path).await
+    }
+
+    /// Snapshots the given events. Events without a pinned board version must
+    /// reference a board that is pinned in `boards`.
+    pub async fn create_release(
+        &self,
+        release_id: &str,
+        version: (u32, u32, u32),
+        notes: Option<ReleaseNotes>,
+        boards: HashMap<String, (u32, u32, u32)>,
+        events: Vec<ReleaseEvent>,
+    ) -> flow_like_types::Result<Release> {
+        if self.get_release(release_id).await.is_ok() {
+            bail!("Release {} already exists", release_id);
+        }
+
+        if let Some(latest) = self.get_releases().await?.first() {
+            if latest.version >= version {
+                bail!(
+                    "Release version {}.{}.{} must be greater than {}.{}.{}",
+                    version.0,
+                    version.1,
+                    version.2,
+                    latest.version.0,
+                    latest.version.1,
+                    latest.version.2
+                );
+            }
+        }
+
+        for (board_id, board_version) in &boards {
+            if !self.boards.contains(board_id) {
+                bail!("Board {} not found", board_id);
+            }
+            self.open_board(board_id.clone(), Some(false), Some(*board_version))
+                .await
+                .map_err(|_| {
+                    anyhow!(
+                        "Board {} has no version {}.{}.{}",
+                        board_id,
+                        board_version.0,
+                        board_version.1,
+                        board_version.2
+                    )
+                })?;
+        }
+
+        let mut snapshots = Vec::with_capacity(events.len());
+        for release_event in events {
+            let mut event = self
+                .get_event_version(&release_event.event_id, release_event.version)
+                .await?;
+
+            if let Some(board_version) = boards.get(&event.board_id) {
+                event.board_version = Some(*board_version);
+            }
+            if event.board_version.is_none() {
+                bail!(
+                    "Event {} uses the latest version of board {}, pin a board version",
+                    event.id,
+                    event.board_id
+                );
+            }
+
+            event.validate_event_references(self).await?;
+            snapshots.push(event);
+        }
+
+        let now = SystemTime::now();
+        let release = Release {
+            id: release_id.to_string(),
+            version,
+            notes,
+            boards,
+            events: snapshots,
+            created_at: now,
+            updated_at: now,
+        };
+
+        self.save_release(&release).await?;
+        Ok(release)
+    }
+
+    /// Only the notes of a release can change after it was created.
+    pub async fn update_release_notes(
+        &self,
+        release_id: &str,
+        notes: Option<ReleaseNotes>,
+    ) -> flow_like_types::Result<Release> {
+        let mut release = self.get_release(release_id).await?;
+        release.notes = notes;
+        release.updated_at = SystemTime::now();
+        self.save_release(&release).await?;
+        Ok(release)
+    }
+
+    pub async fn delete_release(&self, release_id: &str) -> flow_like_types::Result<()> {
+        let mut state = self.release_state().await?;
+        if state.active.as_deref() == Some(release_id) {
+            bail!("Release {} is active and cannot be deleted", release_id);
+        }
+
+        let store = self.release_store().await?;
+        store
+            .delete(&self.releases_dir().child(format!("{}.release", release_id)))
+            .await?;
+
+        let history_len = state.history.len();
+        state.history.retain(|id| id != release_id);
+        if state.history.len() != history_len {
+            self.save_release_state(&state).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn release_state(&self) -> flow_like_types::Result<ReleaseState> {
+        let store = self.release_store().await?;
+        let path = self.releases_dir().child("state.json");
+        match from_compressed_json(store, path).await {
+            Ok(state) => Ok(state),
+            Err(e) => match e.downcast_ref::<flow_like_storage::object_store::Error>() {
+                Some(flow_like_storage::object_store::Error::NotFound { .. }) => {
+                    Ok(ReleaseState::default())
+                }
+                _ => Err(e),
+            },
+        }
+    }
+
+    /// Switches all events of the app to the release with a single write.
+    pub async fn activate_release(
+        &self,
+        release_id: &str,
+    ) -> flow_like_types::Result<ReleaseState> {
+        self.get_release(release_id).await?;
+
+        let mut state = self.release_state().await?;
+        if state.active.as_deref() == Some(release_id) {
+            return Ok(state);
+        }
+
+        if let Some(active) = state.active.take() {
+            state.history.push(active);
+        }
+        state.history.retain(|id| id != release_id);
+        state.active = Some(release_id.to_string());
+        state.updated_at = Some(SystemTime::now());
+
+        self.save_release_state(&state).await?;
+        Ok(state)
+    }
+
+    /// Re-activates the release that was active before the current one.
+    pub async fn rollback_release(&self) -> flow_like_types::Result<ReleaseState> {
+        let mut state = self.release_state().await?;
+        let previous = state
+            .history
+            .pop()
+            .ok_or(anyhow!("No previous release to roll back to"))?;
+        self.get_release(&previous).await?;
+
+        state.active = Some(previous);
+        state.updated_at = Some(SystemTime::now());
+
+        self.save_release_state(&state).await?;
+        Ok(state)
+    }
+
+    /// The event as released by the active release, or the current event if it is not part of it.
+    pub async fn get_released_event(&self, event_id: &str) -> flow_like_types::Result<Event> {
+        if let Some(active) = self.release_state().await?.active {
+            let release = self.get_release(&active).await?;
+            if let Some(event) = release.event(event_id) {
+                return Ok(event.clone());
+            }
+        }
+
+        self.get_event(event_id, None).await
+    }
+
+    /// Older versions are archived, the current version only lives in the event itself.
+    async fn get_event_version(
+        &self,
+        event_id: &str,
+        version: Option<(u32, u32, u32)>,
+    ) -> flow_like_types::Result<Event> {
+        let Some(version) = version else {
+            return self.get_event(event_id, None).await;
+        };
+
+        if let Ok(event) = self.get_event(event_id, Some(version)).await {
+            return Ok(event);
+        }
+
+        let event = self.get_event(event_id, None).await?;
+        if event.event_version != version {
+            bail!(
+                "Event {} has no version {}.{}.{}",
+                event_id,
+                version.0,
+                version.1,
+                version.2
+            );
+        }
+        Ok(event)
+    }
+
+    async fn save_release(&self, release: &Release) -> flow_like_types::Result<()> {
+        let store = self.release_store().await?;
+        let path = self.releases_dir().child(format!("{}.release", release.id));
+        compress_to_file_json(store, path, release).await
+    }
+
+    async fn save_release_state(&self, state: &ReleaseState) -> flow_like_types::Result<()> {
+        let store = self.release_store().await?;
+        let path = self.releases_dir().child("state.json");
+        compress_to_file_json(store, path, state).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bit::Metadata, state::FlowLikeConfig, utils::http::HTTPClient};
+    use flow_like_storage::files::store::FlowLikeStore;
+    use flow_like_types::{sync::Mutex, tokio};
+
+    async fn app() -> App {
+        let mut config = FlowLikeConfig::new();
+        config.register_app_meta_store(FlowLikeStore::Other(Arc::new(
+            flow_like_storage::object_store::memory::InMemory::new(),
+        )));
+        let (http_client, _refetch_rx) = HTTPClient::new();
+        let state = Arc::new(Mutex::new(FlowLikeState::new(config, http_client)));
+        let meta = Metadata {
+            name: "Releases".to_string(),
+            description: String::new(),
+            long_description: None,
+            release_notes: None,
+            tags: vec![],
+            use_case: None,
+            icon: None,
+            thumbnail: None,
+            preview_media: vec![],
+            age_rating: None,
+            website: None,
+            support_url: None,
+            docs_url: None,
+            organization_specific_values: None,
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+        };
+        App::new(None, meta, vec![], state).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn activates_and_rolls_back_releases() {
+        let app = app().await;
+        assert!(app.release_state().await.unwrap().active.is_none());
+
+        app.create_release("r1", (1, 0, 0), None, HashMap::new(), vec![])
+            .await
+            .unwrap();
+        app.create_release("r2", (1, 1, 0), None, HashMap::new(), vec![])
+            .await
+            .unwrap();
+        assert!(
+            app.create_release("r3", (1, 0, 5), None, HashMap::new(), vec![])
+                .await
+                .is_err()
+        );
+
+        app.activate_release("r1").await.unwrap();
+        let state = app.activate_release("r2").await.unwrap();
+        assert_eq!(state.active.as_deref(), Some("r2"));
+        assert!(app.delete_release("r2").await.is_err());
+
+        let state = app.rollback_release().await.unwrap();
+        assert_eq!(state.active.as_deref(), Some("r1"));
+        assert!(state.history.is_empty());
+        assert!(app.rollback_release().await.is_err());
+
+        let releases = app.get_releases().await.unwrap();
+        assert_eq!(releases[0].id, "r2");
+        assert_eq!(releases.len(), 2);
+    }
+}
@@ -203,6 +203,25 @@ pub struct BitModelClassification {
     coding: f32,
 }
 
+/// Models without a classification, such as the ones discovered on a remote server,
+/// rank in the middle for every preference.
+impl Default for BitModelClassification {
+    fn default() -> Self {
+        BitModelClassification {
+            cost: 0.5,
+            speed: 0.5,
+            reasoning: 0.5,
+            creativity: 0.5,
+            factuality: 0.5,
+            function_calling: 0.5,
+            safety: 0.5,
+            openness: 0.5,
+            multilinguality: 0.5,
+            coding: 0.5,
+        }
+    }
+}
+
 impl BitModelClassification {
     fn name_similarity(&self, hint: &str, bit: &Bit) -> flow_like_types::Result<f32> {
         let mut similarity: f32 = 0.0;
@@ -560,6 +579,31 @@ impl Bit {
         None
     }
 
+    /// Model to use when every endpoint of this one keeps failing.
+    pub fn fallback(&self) -> Option<Bit> {
+        let fallback = self.parameters.get("fallback")?;
+        flow_like_types::json::from_value::<Bit>(fallback.clone()).ok()
+    }
+
+    pub fn with_fallback(mut self, fallback: Option<Bit>) -> flow_like_types::Result<Bit> {
+        let parameters = self
+            .parameters
+            .as_object_mut()
+            .ok_or_else(|| flow_like_types::anyhow!("Bit parameters are not an object"))?;
+        match fallback {
+            Some(fallback) => {
+                parameters.insert(
+                    "fallback".to_string(),
+                    flow_like_types::json::to_value(fallback)?,
+                );
+            }
+            None => {
+                parameters.remove("fallback");
+            }
+        }
+        Ok(self)
+    }
+
     pub fn try_to_embedding_provider(&self) -> Option<ModelProvider> {
         if let Some(parameters) = self.try_to_embedding() {
             return Some(parameters.provider);
@@ -4,8 +4,11 @@ use flow_like_storage::lancedb::connection::ConnectBuilder;
 use flow_like_storage::object_store;
 use flow_like_types::Result;
 use flow_like_types::async_trait;
+use local_credentials::LocalSharedCredentials;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 pub mod aws_credentials;
+pub mod local_credentials;
 
 #[async_trait]
 pub trait SharedCredentialsTrait {
@@ -13,21 +16,24 @@ pub trait SharedCredentialsTrait {
     async fn to_db(&self, path: object_store::path::Path) -> Result<ConnectBuilder>;
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum SharedCredentials {
     Aws(AwsSharedCredentials),
+    Local(LocalSharedCredentials),
 }
 
 impl SharedCredentials {
     pub async fn to_store(&self, meta: bool) -> Result<FlowLikeStore> {
         match self {
             SharedCredentials::Aws(aws) => aws.to_store(meta).await,
+            SharedCredentials::Local(local) => local.to_store(meta).await,
         }
     }
 
     pub async fn to_db(&self, path: object_store::path::Path) -> Result<ConnectBuilder> {
         match self {
             SharedCredentials::Aws(aws) => aws.to_db(path).await,
+            SharedCredentials::Local(local) => local.to_db(path).await,
         }
     }
 }
@@ -4,10 +4,11 @@ use flow_like_storage::object_store;
 use flow_like_storage::object_store::aws::AmazonS3Builder;
 use flow_like_storage::{files::store::FlowLikeStore, lancedb};
 use flow_like_types::{Result, anyhow, async_trait};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct AwsSharedCredentials {
     pub access_key_id: Option<String>,
     pub secret_access_key: Option<String>,
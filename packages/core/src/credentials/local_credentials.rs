@@ -0,0 +1,147 @@
+use crate::credentials::SharedCredentialsTrait;
+use flow_like_storage::files::store::local_store::LocalObjectStore;
+use flow_like_storage::files::store::scoped_store::{ScopedObjectStore, StoreScope};
+use flow_like_storage::lancedb::connection::ConnectBuilder;
+use flow_like_storage::object_store;
+use flow_like_storage::object_store::memory::InMemory;
+use flow_like_storage::{files::store::FlowLikeStore, lancedb};
+use flow_like_types::{Result, anyhow, async_trait};
+use once_cell::sync::Lazy;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// In-memory buckets are shared by all credentials of the process, like a bucket would be.
+static MEMORY_META: Lazy<Arc<InMemory>> = Lazy::new(|| Arc::new(InMemory::new()));
+static MEMORY_CONTENT: Lazy<Arc<InMemory>> = Lazy::new(|| Arc::new(InMemory::new()));
+
+/// Credentials for self-hosted setups without a cloud provider. The meta and content "buckets"
+/// are the `meta` and `content` directories below `root`, or in-memory stores if `root` is `None`.
+/// Since neither backend has a policy system, the prefixes are enforced by a `ScopedObjectStore`.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LocalSharedCredentials {
+    pub root: Option<PathBuf>,
+    /// `None` grants access to everything (master credentials)
+    pub scope: Option<LocalCredentialsScope>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct LocalCredentialsScope {
+    pub meta_read: Vec<String>,
+    pub meta_write: Vec<String>,
+    pub content_read: Vec<String>,
+    pub content_write: Vec<String>,
+}
+
+impl LocalSharedCredentials {
+    pub fn new(root: Option<PathBuf>) -> Self {
+        LocalSharedCredentials { root, scope: None }
+    }
+
+    pub fn with_scope(mut self, scope: LocalCredentialsScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    fn bucket(&self, meta: bool) -> Option<PathBuf> {
+        let name = if meta { "meta" } else { "content" };
+        self.root.as_ref().map(|root| root.join(name))
+    }
+
+    fn store_scope(&self, meta: bool) -> Option<StoreScope> {
+        let scope = self.scope.as_ref()?;
+        let (read, write) = if meta {
+            (&scope.meta_read, &scope.meta_write)
+        } else {
+            (&scope.content_read, &scope.content_write)
+        };
+        let store_scope = read.iter().fold(StoreScope::new(), |acc, prefix| {
+            acc.allow_read(prefix.as_str())
+        });
+        let store_scope = write
+            .iter()
+            .fold(store_scope, |acc, prefix| acc.allow_write(prefix.as_str()));
+        Some(store_scope)
+    }
+
+    /// LanceDB's memory connections are not shared between connections,
+    /// so in-memory setups keep their databases in a temporary directory.
+    pub fn db_directory(&self) -> PathBuf {
+        self.bucket(false)
+            .unwrap_or_else(|| std::env::temp_dir().join("flow-like").join("content"))
+    }
+}
+
+#[async_trait]
+impl SharedCredentialsTrait for LocalSharedCredentials {
+    #[tracing::instrument(name = "LocalSharedCredentials::to_store", skip(self, meta), fields(meta = meta), level="debug")]
+    async fn to_store(&self, meta: bool) -> Result<FlowLikeStore> {
+        let store = match self.bucket(meta) {
+            Some(directory) => FlowLikeStore::Local(Arc::new(LocalObjectStore::new(directory)?)),
+            None if meta => FlowLikeStore::Memory(MEMORY_META.clone()),
+            None => FlowLikeStore::Memory(MEMORY_CONTENT.clone()),
+        };
+
+        match self.store_scope(meta) {
+            Some(scope) => Ok(FlowLikeStore::Scoped(Arc::new(ScopedObjectStore::new(
+                store, scope,
+            )))),
+            None => Ok(store),
+        }
+    }
+
+    #[tracing::instrument(name = "LocalSharedCredentials::to_db", skip(self), level = "debug")]
+    async fn to_db(&self, path: object_store::path::Path) -> Result<ConnectBuilder> {
+        if let Some(scope) = self.store_scope(false) {
+            if !scope.can_read(&path) {
+                return Err(anyhow!(
+                    "Database {} is outside of the credential scope",
+                    path
+                ));
+            }
+        }
+
+        let directory = self.db_directory().join(path.to_string());
+        let directory = directory
+            .to_str()
+            .ok_or_else(|| anyhow!("Invalid database path: {:?}", directory))?;
+        Ok(lancedb::connect(directory))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_like_storage::object_store::{PutPayload, path::Path};
+    use flow_like_types::tokio;
+
+    #[tokio::test]
+    async fn scoped_credentials_share_the_memory_store() {
+        let master = LocalSharedCredentials::new(None);
+        let scoped = LocalSharedCredentials::new(None).with_scope(LocalCredentialsScope {
+            meta_read: vec!["apps/local-test".to_string()],
+            ..Default::default()
+        });
+
+        let location = Path::from("apps/local-test/manifest.json");
+        master
+            .to_store(true)
+            .await
+            .unwrap()
+            .as_generic()
+            .put(&location, PutPayload::from_static(b"{}"))
+            .await
+            .unwrap();
+
+        let store = scoped.to_store(true).await.unwrap().as_generic();
+        assert!(store.get(&location).await.is_ok());
+        assert!(
+            store
+                .put(&location, PutPayload::from_static(b"{}"))
+                .await
+                .is_err()
+        );
+        assert!(scoped.to_db(Path::from("apps/other")).await.is_err());
+    }
+}
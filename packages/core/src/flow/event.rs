@@ -13,6 +13,10 @@ use crate::{
 };
 
 use super::{board::VersionType, variable::Variable};
+use chain::EventChain;
+
+pub mod chain;
+pub mod contract;
 
 #[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
 pub enum ReleaseNotes {
@@ -51,9 +55,20 @@ pub struct Event {
     pub event_version: (u32, u32, u32),
     pub created_at: std::time::SystemTime,
     pub updated_at: std::time::SystemTime,
+
+    /// Explicit JSON Schema for the run payload. Derived from the start node's pins if not set.
+    #[serde(default)]
+    pub input_schema: Option<String>,
+    /// Explicit JSON Schema for the run result. Derived from the board's variables if not set.
+    #[serde(default)]
+    pub output_schema: Option<String>,
+    /// Events that are started once a run of this event finished
+    #[serde(default)]
+    pub chains: Vec<EventChain>,
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[serde(deny_unknown_fields)]
 pub struct ChatEventParameters {
     pub history_elements: Option<u32>,
     pub allow_file_upload: Option<bool>,
@@ -65,6 +80,7 @@ pub struct ChatEventParameters {
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[serde(deny_unknown_fields)]
 pub struct EmailEventParameters {
     pub mail: Option<String>,
     pub sender_name: Option<String>,
@@ -79,18 +95,39 @@ pub struct EmailEventParameters {
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[serde(deny_unknown_fields)]
 pub struct ApiEventParameters {
     pub path_suffix: Option<String>,
     pub method: Option<String>,
     pub public_endpoint: Option<bool>,
 }
 
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MqttEventParameters {
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub client_id: Option<String>,
+    pub username: Option<String>,
+    pub secret_password: Option<String>,
+    pub use_tls: Option<bool>,
+    /// MQTT topic filter, wildcards (`+`, `#`) are supported
+    pub topic_filter: Option<String>,
+    /// 0 = at most once, 1 = at least once, 2 = exactly once
+    pub qos: Option<u8>,
+    /// Whether retained messages delivered on subscribe start a run
+    pub include_retained: Option<bool>,
+}
+
+/// Configuration of an event, stored in `Event::config`. The parameter structs deny unknown
+/// fields, otherwise every config would match `ChatEvent`, whose fields are all optional.
 #[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
 #[serde(untagged)]
 pub enum EventPayload {
     ChatEvent(ChatEventParameters),
     MailEvent(EmailEventParameters),
     ApiEvent(ApiEventParameters),
+    MqttEvent(MqttEventParameters),
     AnyEvent(HashMap<String, flow_like_types::Value>),
     QuickAction,
 }
@@ -208,7 +245,8 @@ impl Event {
             .open_board(self.board_id.clone(), Some(false), self.board_version)
             .await?;
 
-        board.lock().await.nodes.get(&self.node_id).ok_or_else(|| {
+        let board = board.lock().await;
+        board.nodes.get(&self.node_id).ok_or_else(|| {
             flow_like_types::anyhow!(
                 "Node with id {} not found in board {}",
                 self.node_id,
@@ -216,6 +254,10 @@ impl Event {
             )
         })?;
 
+        // Makes sure authored input / output schemas are valid JSON Schemas
+        self.contract(&board)?;
+        drop(board);
+
         if let Some(canary) = &self.canary {
             let canary_board = app
                 .open_board(canary.board_id.clone(), Some(false), canary.board_version)
@@ -327,3 +369,29 @@ impl Event {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_variants_are_unambiguous() {
+        let payload = |config| flow_like_types::json::from_value::<EventPayload>(config).unwrap();
+
+        let mqtt = payload(flow_like_types::json::json!({
+            "host": "localhost",
+            "topic_filter": "sensors/+",
+            "qos": 1
+        }));
+        assert!(matches!(mqtt, EventPayload::MqttEvent(_)));
+
+        let mail = payload(flow_like_types::json::json!({ "mail": "bot@example.com" }));
+        assert!(matches!(mail, EventPayload::MailEvent(_)));
+
+        let api = payload(flow_like_types::json::json!({ "method": "POST" }));
+        assert!(matches!(api, EventPayload::ApiEvent(_)));
+
+        let chat = payload(flow_like_types::json::json!({ "history_elements": 5 }));
+        assert!(matches!(chat, EventPayload::ChatEvent(_)));
+    }
+}
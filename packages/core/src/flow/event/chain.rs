@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use flow_like_types::{Value, create_id, json::Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::flow::execution::{LogLevel, LogMeta, RunPayload, RunStatus};
+
+use super::Event;
+
+/// Chained runs deeper than this are dropped to protect against cycles (A -> B -> A).
+pub const MAX_CHAIN_DEPTH: u32 = 16;
+
+/// Starts another event once a run of this event finished.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+pub struct EventChain {
+    pub id: String,
+    pub target_event_id: String,
+    /// Run states that trigger the chained event. Empty means every finished run.
+    pub on_status: Vec<RunStatus>,
+    /// Only chain if the highest log level of the run is at least this level (e.g. chain on errors)
+    pub min_log_level: Option<LogLevel>,
+    /// Only chain if the highest log level of the run is at most this level (e.g. only clean runs)
+    pub max_log_level: Option<LogLevel>,
+    /// Maps payload keys of the chained event to result variable names of the finished run.
+    /// If empty, all result variables are forwarded.
+    pub payload_mapping: HashMap<String, String>,
+    pub active: bool,
+}
+
+impl EventChain {
+    pub fn new(target_event_id: &str) -> Self {
+        EventChain {
+            id: create_id(),
+            target_event_id: target_event_id.to_string(),
+            on_status: vec![RunStatus::Success],
+            min_log_level: None,
+            max_log_level: None,
+            payload_mapping: HashMap::new(),
+            active: true,
+        }
+    }
+
+    pub fn matches(&self, status: &RunStatus, log_level: LogLevel) -> bool {
+        if !self.active || *status == RunStatus::Running {
+            return false;
+        }
+
+        if !self.on_status.is_empty() && !self.on_status.contains(status) {
+            return false;
+        }
+
+        if let Some(min) = self.min_log_level {
+            if log_level < min {
+                return false;
+            }
+        }
+
+        if let Some(max) = self.max_log_level {
+            if log_level > max {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    pub fn build_payload(&self, outputs: &HashMap<String, Value>) -> Value {
+        if self.payload_mapping.is_empty() {
+            let payload = outputs
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect::<Map<String, Value>>();
+            return Value::Object(payload);
+        }
+
+        let payload = self
+            .payload_mapping
+            .iter()
+            .map(|(key, variable)| {
+                let value = outputs.get(variable).cloned().unwrap_or(Value::Null);
+                (key.clone(), value)
+            })
+            .collect::<Map<String, Value>>();
+        Value::Object(payload)
+    }
+}
+
+/// A run that should be started because of an `EventChain`.
+/// The `payload.id` is empty, it is resolved from the target event's start node.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
+pub struct ChainedRun {
+    pub chain_id: String,
+    pub event_id: String,
+    pub parent_run_id: String,
+    pub depth: u32,
+    pub payload: RunPayload,
+}
+
+impl Event {
+    /// Resolves the chained runs for a finished run of this event.
+    /// `depth` is the chain depth of the finished run, 0 for runs that were not chained.
+    pub fn chained_runs(
+        &self,
+        meta: &LogMeta,
+        status: &RunStatus,
+        outputs: &HashMap<String, Value>,
+        depth: u32,
+    ) -> Vec<ChainedRun> {
+        if depth >= MAX_CHAIN_DEPTH {
+            return vec![];
+        }
+
+        let log_level = LogLevel::from_u8(meta.log_level);
+
+        self.chains
+            .iter()
+            .filter(|chain| chain.matches(status, log_level))
+            .map(|chain| ChainedRun {
+                chain_id: chain.id.clone(),
+                event_id: chain.target_event_id.clone(),
+                parent_run_id: meta.run_id.clone(),
+                depth: depth + 1,
+                payload: RunPayload {
+                    id: String::new(),
+                    payload: Some(chain.build_payload(outputs)),
+                },
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_like_types::json::json;
+
+    #[test]
+    fn matches_status_and_log_level() {
+        let mut chain = EventChain::new("next");
+        chain.max_log_level = Some(LogLevel::Warn);
+
+        assert!(chain.matches(&RunStatus::Success, LogLevel::Info));
+        assert!(!chain.matches(&RunStatus::Success, LogLevel::Error));
+        assert!(!chain.matches(&RunStatus::Failed, LogLevel::Info));
+        assert!(!chain.matches(&RunStatus::Running, LogLevel::Info));
+
+        chain.on_status = vec![];
+        chain.max_log_level = None;
+        chain.min_log_level = Some(LogLevel::Error);
+        assert!(chain.matches(&RunStatus::Failed, LogLevel::Fatal));
+        assert!(!chain.matches(&RunStatus::Success, LogLevel::Info));
+
+        chain.active = false;
+        assert!(!chain.matches(&RunStatus::Failed, LogLevel::Fatal));
+    }
+
+    #[test]
+    fn maps_outputs_to_payload() {
+        let outputs = HashMap::from([
+            ("summary".to_string(), json!("done")),
+            ("count".to_string(), json!(3)),
+        ]);
+
+        let mut chain = EventChain::new("next");
+        assert_eq!(
+            chain.build_payload(&outputs),
+            json!({ "summary": "done", "count": 3 })
+        );
+
+        chain.payload_mapping = HashMap::from([
+            ("text".to_string(), "summary".to_string()),
+            ("missing".to_string(), "unknown".to_string()),
+        ]);
+        assert_eq!(
+            chain.build_payload(&outputs),
+            json!({ "text": "done", "missing": null })
+        );
+    }
+}
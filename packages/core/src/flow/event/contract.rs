@@ -0,0 +1,346 @@
+use flow_like_types::{
+    Value, anyhow,
+    json::{Map, json},
+    jsonschema,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::flow::{
+    board::Board,
+    execution::RunPayload,
+    node::Node,
+    pin::{Pin, PinType, ValueType},
+    variable::{Variable, VariableType},
+};
+
+use super::Event;
+
+/// The input and output contract of an event.
+/// Explicitly authored schemas on the event take precedence, otherwise they are derived
+/// from the start node's output pins (input) and the board's non-secret variables (output).
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
+pub struct EventContract {
+    pub event_id: String,
+    pub event_version: (u32, u32, u32),
+    pub input_schema: Value,
+    pub output_schema: Value,
+    pub derived_input: bool,
+    pub derived_output: bool,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
+pub struct PayloadValidationIssue {
+    pub path: String,
+    pub message: String,
+}
+
+/// Returned (wrapped in a `flow_like_types::Error`) when a payload does not match the event's input schema.
+/// Callers can downcast to this type to surface the individual issues.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
+pub struct PayloadValidationError {
+    pub event_id: String,
+    pub issues: Vec<PayloadValidationIssue>,
+}
+
+impl std::fmt::Display for PayloadValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let issues = self
+            .issues
+            .iter()
+            .map(|issue| format!("{} ({})", issue.message, issue.path))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(
+            f,
+            "Payload for event {} does not match its input schema: {}",
+            self.event_id, issues
+        )
+    }
+}
+
+impl std::error::Error for PayloadValidationError {}
+
+impl Event {
+    pub fn contract(&self, board: &Board) -> flow_like_types::Result<EventContract> {
+        let (input_schema, derived_input) = match &self.input_schema {
+            Some(schema) => (parse_schema(schema)?, false),
+            None => {
+                let node = board.nodes.get(&self.node_id).ok_or_else(|| {
+                    anyhow!(
+                        "Node with id {} not found in board {}",
+                        self.node_id,
+                        board.id
+                    )
+                })?;
+                (input_schema_from_node(node), true)
+            }
+        };
+
+        let (output_schema, derived_output) = match &self.output_schema {
+            Some(schema) => (parse_schema(schema)?, false),
+            None => (output_schema_from_board(board), true),
+        };
+
+        Ok(EventContract {
+            event_id: self.id.clone(),
+            event_version: self.event_version,
+            input_schema,
+            output_schema,
+            derived_input,
+            derived_output,
+        })
+    }
+
+    /// Validates the payload against the input schema of the event, authored or derived.
+    /// Should be called before constructing an `InternalRun` for the event.
+    pub fn validate_payload(
+        &self,
+        board: &Board,
+        payload: &RunPayload,
+    ) -> flow_like_types::Result<()> {
+        let contract = self.contract(board)?;
+        let validator = jsonschema::validator_for(&contract.input_schema)
+            .map_err(|e| anyhow!("Invalid input schema for event {}: {}", self.id, e))?;
+
+        let instance = payload.payload.clone().unwrap_or(Value::Null);
+        let issues = validator
+            .iter_errors(&instance)
+            .map(|e| PayloadValidationIssue {
+                path: e.instance_path.to_string(),
+                message: e.to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        if issues.is_empty() {
+            return Ok(());
+        }
+
+        Err(PayloadValidationError {
+            event_id: self.id.clone(),
+            issues,
+        }
+        .into())
+    }
+}
+
+fn parse_schema(schema: &str) -> flow_like_types::Result<Value> {
+    let schema: Value = flow_like_types::json::from_str(schema)?;
+    if !jsonschema::meta::is_valid(&schema) {
+        return Err(anyhow!("Schema is not a valid JSON Schema"));
+    }
+    Ok(schema)
+}
+
+/// Derives the payload schema from the data output pins of a start node.
+/// A start node with a single "payload" pin receives the whole payload on that pin.
+/// The schema is lenient: values may be null, unknown properties are allowed and struct
+/// schemas are only enforced for a whole payload, nested `$ref`s would not resolve.
+pub fn input_schema_from_node(node: &Node) -> Value {
+    let mut pins = node
+        .pins
+        .values()
+        .filter(|pin| pin.pin_type == PinType::Output && pin.data_type != VariableType::Execution)
+        .collect::<Vec<&Pin>>();
+    pins.sort_by_key(|pin| pin.index);
+
+    if pins.is_empty() {
+        return json!({});
+    }
+
+    if let [pin] = pins.as_slice() {
+        if pin.name == "payload" {
+            return nullable(pin_schema(pin, true));
+        }
+    }
+
+    let mut properties = Map::new();
+    for pin in pins {
+        properties.insert(pin.name.clone(), nullable(pin_schema(pin, false)));
+    }
+
+    json!({
+        "type": ["object", "null"],
+        "properties": properties,
+    })
+}
+
+/// Allows `null` next to the schema. Its dialect and definitions stay at the root,
+/// so the draft is still detected and `$ref`s still resolve.
+fn nullable(schema: Value) -> Value {
+    let Value::Object(mut schema) = schema else {
+        return schema;
+    };
+    if schema.is_empty() {
+        return Value::Object(schema);
+    }
+
+    let mut nullable = Map::new();
+    for key in ["$schema", "definitions", "$defs"] {
+        if let Some(definitions) = schema.remove(key) {
+            nullable.insert(key.to_string(), definitions);
+        }
+    }
+    nullable.insert(
+        "anyOf".to_string(),
+        json!([Value::Object(schema), { "type": "null" }]),
+    );
+    Value::Object(nullable)
+}
+
+/// Derives the result schema of a run from the board's variables. Secret variables are never returned.
+pub fn output_schema_from_board(board: &Board) -> Value {
+    let mut variables = board
+        .variables
+        .values()
+        .filter(|variable| !variable.secret)
+        .collect::<Vec<&Variable>>();
+    variables.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut properties = Map::new();
+    for variable in variables {
+        let mut schema = value_schema(&variable.data_type, &variable.value_type, None);
+        if let (Some(description), Value::Object(schema)) = (&variable.description, &mut schema) {
+            schema.insert("description".to_string(), json!(description));
+        }
+        properties.insert(variable.name.clone(), schema);
+    }
+
+    json!({
+        "type": "object",
+        "properties": properties,
+    })
+}
+
+fn pin_schema(pin: &Pin, with_struct_schema: bool) -> Value {
+    let struct_schema = pin.schema.as_deref().filter(|_| with_struct_schema);
+    let mut schema = value_schema(&pin.data_type, &pin.value_type, struct_schema);
+    if let Value::Object(schema) = &mut schema {
+        if !pin.description.is_empty() {
+            schema.insert("description".to_string(), json!(pin.description));
+        }
+    }
+    schema
+}
+
+fn value_schema(data_type: &VariableType, value_type: &ValueType, schema: Option<&str>) -> Value {
+    let item = match data_type {
+        VariableType::String | VariableType::PathBuf => json!({ "type": "string" }),
+        VariableType::Integer => json!({ "type": "integer" }),
+        VariableType::Float => json!({ "type": "number" }),
+        VariableType::Boolean => json!({ "type": "boolean" }),
+        VariableType::Byte => json!({ "type": "integer", "minimum": 0, "maximum": 255 }),
+        VariableType::Date => json!({
+            "type": "object",
+            "properties": {
+                "secs_since_epoch": { "type": "integer" },
+                "nanos_since_epoch": { "type": "integer" }
+            },
+            "required": ["secs_since_epoch", "nanos_since_epoch"]
+        }),
+        VariableType::Struct => schema
+            .and_then(|schema| flow_like_types::json::from_str::<Value>(schema).ok())
+            .unwrap_or_else(|| json!({ "type": "object" })),
+        VariableType::Generic | VariableType::Execution => json!({}),
+    };
+
+    match value_type {
+        ValueType::Normal => item,
+        ValueType::Array => json!({ "type": "array", "items": item }),
+        ValueType::HashSet => json!({ "type": "array", "items": item, "uniqueItems": true }),
+        ValueType::HashMap => json!({ "type": "object", "additionalProperties": item }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start_node() -> Node {
+        let mut node = Node::new("events_test", "Test Event", "", "Events");
+        node.set_start(true);
+        node.add_output_pin("exec_out", "Output", "", VariableType::Execution);
+        node.add_output_pin("name", "Name", "", VariableType::String);
+        node.add_output_pin("count", "Count", "", VariableType::Integer)
+            .set_value_type(ValueType::Array);
+        node
+    }
+
+    #[test]
+    fn derives_object_schema_from_pins() {
+        let schema = input_schema_from_node(&start_node());
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        assert!(validator.is_valid(&json!({ "name": "flow", "count": [1, 2] })));
+        assert!(!validator.is_valid(&json!({ "name": 1 })));
+        assert!(!validator.is_valid(&json!({ "count": ["a"] })));
+    }
+
+    #[test]
+    fn derived_schema_is_lenient() {
+        let mut node = start_node();
+        node.add_output_pin("session", "Session", "", VariableType::Struct)
+            .set_schema::<EventContract>();
+        let schema = input_schema_from_node(&node);
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        assert!(validator.is_valid(&Value::Null));
+        assert!(validator.is_valid(&json!({ "name": null, "session": null })));
+        assert!(validator.is_valid(&json!({ "messages": [], "session": {} })));
+        assert!(!validator.is_valid(&json!({ "session": "text" })));
+    }
+
+    #[test]
+    fn payload_pin_receives_whole_payload() {
+        let mut node = Node::new("events_generic", "Generic Event", "", "Events");
+        node.add_output_pin("exec_out", "Output", "", VariableType::Execution);
+        node.add_output_pin("payload", "Payload", "", VariableType::Struct);
+
+        let schema = input_schema_from_node(&node);
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        assert!(validator.is_valid(&json!({ "anything": true })));
+        assert!(validator.is_valid(&Value::Null));
+        assert!(!validator.is_valid(&json!("text")));
+    }
+
+    #[test]
+    fn payload_pin_keeps_struct_schema() {
+        let mut node = Node::new("events_generic", "Generic Event", "", "Events");
+        node.add_output_pin("payload", "Payload", "", VariableType::Struct)
+            .set_schema::<PayloadValidationError>();
+
+        let schema = input_schema_from_node(&node);
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        assert!(validator.is_valid(&json!({ "event_id": "event", "issues": [] })));
+        assert!(validator.is_valid(&Value::Null));
+        assert!(!validator.is_valid(&json!({ "event_id": 1, "issues": [] })));
+    }
+
+    #[test]
+    fn node_without_data_pins_accepts_anything() {
+        let mut node = Node::new("events_simple", "Simple Event", "", "Events");
+        node.add_output_pin("exec_out", "Output", "", VariableType::Execution);
+
+        let schema = input_schema_from_node(&node);
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        assert!(validator.is_valid(&Value::Null));
+    }
+
+    #[test]
+    fn validation_error_lists_issues() {
+        let error = PayloadValidationError {
+            event_id: "event".to_string(),
+            issues: vec![PayloadValidationIssue {
+                path: "/name".to_string(),
+                message: "1 is not of type \"string\"".to_string(),
+            }],
+        };
+
+        let error: flow_like_types::Error = error.into();
+        let error = error.downcast_ref::<PayloadValidationError>().unwrap();
+        assert_eq!(error.issues.len(), 1);
+    }
+}
@@ -1,5 +1,6 @@
 use super::board::ExecutionStage;
 use super::event::Event;
+use super::event::chain::ChainedRun;
 use super::{board::Board, node::NodeState, variable::Variable};
 use crate::credentials::SharedCredentials;
 use crate::profile::Profile;
@@ -11,6 +12,7 @@ use flow_like_storage::arrow_schema::FieldRef;
 use flow_like_storage::files::store::FlowLikeStore;
 use flow_like_storage::lancedb::Connection;
 use flow_like_storage::lancedb::index::scalar::BitmapIndexBuilder;
+use flow_like_storage::lancedb::table::NewColumnTransform;
 use flow_like_storage::serde_arrow::schema::{SchemaLike, TracingOptions};
 use flow_like_storage::{Path, serde_arrow};
 use flow_like_types::Value;
@@ -40,6 +42,7 @@ pub mod context;
 pub mod internal_node;
 pub mod internal_pin;
 pub mod log;
+pub mod query;
 pub mod trace;
 
 const USE_DEPENDENCY_GRAPH: bool = false;
@@ -122,6 +125,7 @@ pub struct LogMeta {
     pub event_version: Option<String>,
     pub event_id: String,
     pub payload: Vec<u8>,
+    pub parent_run_id: Option<String>,
 }
 
 impl LogMeta {
@@ -195,6 +199,25 @@ impl LogMeta {
             return Ok(());
         }
         let table = table?;
+
+        // Tables created before runs could be chained do not have the parent column yet
+        if table
+            .schema()
+            .await?
+            .field_with_name("parent_run_id")
+            .is_err()
+        {
+            table
+                .add_columns(
+                    NewColumnTransform::SqlExpressions(vec![(
+                        "parent_run_id".to_string(),
+                        "CAST(NULL AS STRING)".to_string(),
+                    )]),
+                    None,
+                )
+                .await?;
+        }
+
         let iter = RecordBatchIterator::new(vec![arrow_batch].into_iter().map(Ok), schema);
         table.add(iter).execute().await?;
         Ok(())
@@ -220,6 +243,7 @@ pub struct Run {
 
     pub event_id: Option<String>,
     pub event_version: Option<String>,
+    pub parent_run_id: Option<String>,
 
     pub visited_nodes: HashMap<String, LogLevel>,
     pub log_store: Option<FlowLikeStore>,
@@ -324,6 +348,7 @@ impl Run {
             event_id: self.event_id.clone().unwrap_or("".to_string()),
             event_version: self.event_version.clone(),
             payload,
+            parent_run_id: self.parent_run_id.clone(),
         };
 
         Ok(Some(content))
@@ -392,6 +417,8 @@ pub struct InternalRun {
     cpus: usize,
     log_level: LogLevel,
     completion_callbacks: Arc<RwLock<Vec<EventTrigger>>>,
+    event: Option<Arc<Event>>,
+    chain_depth: u32,
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
@@ -445,6 +472,7 @@ impl InternalRun {
                 let (major, minor, patch) = e.event_version;
                 format!("{}.{}.{}", major, minor, patch)
             }),
+            parent_run_id: None,
 
             visited_nodes: HashMap::with_capacity(board.nodes.len()),
             log_store,
@@ -618,6 +646,8 @@ impl InternalRun {
             log_level: board.log_level,
             profile: Arc::new(profile.clone()),
             completion_callbacks: Arc::new(RwLock::new(vec![])),
+            event: event.map(Arc::new),
+            chain_depth: 0,
         })
     }
 
@@ -881,6 +911,37 @@ impl InternalRun {
         self.run.lock().await.status.clone()
     }
 
+    /// Marks this run as started by an `EventChain` of another run
+    pub async fn set_chained_from(&mut self, chained: &ChainedRun) {
+        self.run.lock().await.parent_run_id = Some(chained.parent_run_id.clone());
+        self.chain_depth = chained.depth;
+    }
+
+    /// The runs the `EventChain`s of this run's event start, call once the run finished.
+    pub async fn chained_runs(&self, meta: &LogMeta) -> Vec<ChainedRun> {
+        let Some(event) = &self.event else {
+            return vec![];
+        };
+
+        let status = self.get_status().await;
+        let outputs = self.get_outputs().await;
+        event.chained_runs(meta, &status, &outputs, self.chain_depth)
+    }
+
+    /// The current values of the board variables, keyed by variable name. Secret variables are skipped.
+    pub async fn get_outputs(&self) -> HashMap<String, Value> {
+        let variables = self.variables.lock().await;
+        let mut outputs = HashMap::with_capacity(variables.len());
+        for variable in variables.values() {
+            if variable.secret {
+                continue;
+            }
+            let value = variable.value.lock().await.clone();
+            outputs.insert(variable.name.clone(), value);
+        }
+        outputs
+    }
+
     async fn trigger_completion_callbacks(&self) {
         let callbacks = self.completion_callbacks.read().await;
         for callback in callbacks.iter() {
@@ -955,7 +1016,7 @@ fn recursive_get_deps(
     found_dependencies
 }
 
-#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
 pub enum RunStatus {
     Running,
     Success,
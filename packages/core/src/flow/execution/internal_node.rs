@@ -1,3 +1,4 @@
+use flow_like_model_provider::routing::{RoutingDecision, RoutingOutcome, trace_routing};
 use flow_like_types::{Value, json::json, sync::Mutex};
 use std::{
     collections::{HashMap, HashSet},
@@ -531,7 +532,8 @@ impl InternalNode {
             LogLevel::Debug,
             None,
         );
-        let result = logic.run(context).await;
+        let (result, routing) = trace_routing(logic.run(context)).await;
+        log_routing(context, routing);
         drop(logic);
         if result.is_err() {
             let err_string = format!("{:?}", result.err());
@@ -651,7 +653,8 @@ impl InternalNode {
             LogLevel::Debug,
             None,
         );
-        let result = logic.run(context).await;
+        let (result, routing) = trace_routing(logic.run(context)).await;
+        log_routing(context, routing);
 
         if result.is_err() {
             let err_string = format!("{:?}", result.err());
@@ -707,3 +710,14 @@ impl InternalNode {
         Ok(())
     }
 }
+
+/// Retries and failovers of model calls end up in the run trace of the node that made them.
+fn log_routing(context: &mut ExecutionContext, decisions: Vec<RoutingDecision>) {
+    for decision in decisions {
+        let level = match decision.outcome {
+            RoutingOutcome::Success => LogLevel::Debug,
+            _ => LogLevel::Warn,
+        };
+        context.log_message(&format!("Model routing: {}", decision), level);
+    }
+}
@@ -0,0 +1,365 @@
+use flow_like_types::anyhow;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, de::IntoDeserializer};
+
+use super::{LogLevel, LogMeta};
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1000;
+
+/// Filter for the runs of a board. Values are quoted when building the LanceDB filter,
+/// so it is safe to pass user input.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, Default)]
+pub struct RunFilter {
+    pub node_id: Option<String>,
+    pub event_id: Option<String>,
+    /// Only runs started at or after this timestamp (microseconds since epoch)
+    pub from: Option<u64>,
+    /// Only runs started at or before this timestamp (microseconds since epoch)
+    pub to: Option<u64>,
+    /// `Debug` matches all runs without warnings, other levels match exactly
+    pub status: Option<LogLevel>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+    /// Runs to skip after ordering, ignored with a `cursor`, which already skips the previous pages
+    pub offset: Option<usize>,
+}
+
+/// A page of runs, newest first.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
+pub struct RunPage {
+    pub runs: Vec<LogMeta>,
+    pub next_cursor: Option<String>,
+}
+
+/// Position after the last run of a page. Runs are ordered by `start` and `run_id`, both descending.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RunCursor {
+    pub start: u64,
+    pub run_id: String,
+}
+
+impl RunCursor {
+    pub fn from_meta(meta: &LogMeta) -> Self {
+        RunCursor {
+            start: meta.start,
+            run_id: meta.run_id.clone(),
+        }
+    }
+
+    pub fn parse(cursor: &str) -> flow_like_types::Result<Self> {
+        let (start, run_id) = cursor
+            .split_once('_')
+            .ok_or_else(|| anyhow!("Invalid cursor: {}", cursor))?;
+        let start = start
+            .parse::<u64>()
+            .map_err(|_| anyhow!("Invalid cursor: {}", cursor))?;
+        Ok(RunCursor {
+            start,
+            run_id: run_id.to_string(),
+        })
+    }
+}
+
+impl std::fmt::Display for RunCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}_{}", self.start, self.run_id)
+    }
+}
+
+impl RunFilter {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+    }
+
+    pub fn offset(&self) -> usize {
+        match self.cursor {
+            Some(_) => 0,
+            None => self.offset.unwrap_or(0),
+        }
+    }
+
+    pub fn to_filter(&self) -> flow_like_types::Result<Option<String>> {
+        let mut clauses = vec![];
+
+        if let Some(node_id) = &self.node_id {
+            clauses.push(format!("node_id = {}", quote(node_id)));
+        }
+
+        if let Some(event_id) = &self.event_id {
+            clauses.push(format!("event_id = {}", quote(event_id)));
+        }
+
+        if let Some(from) = self.from {
+            clauses.push(format!("start >= {}", from));
+        }
+
+        if let Some(to) = self.to {
+            clauses.push(format!("start <= {}", to));
+        }
+
+        if let Some(status) = self.status {
+            let status = status.to_u8();
+            if status == 0 {
+                clauses.push("log_level <= 1".to_string());
+            } else {
+                clauses.push(format!("log_level = {}", status));
+            }
+        }
+
+        if let Some(cursor) = &self.cursor {
+            let cursor = RunCursor::parse(cursor)?;
+            clauses.push(format!(
+                "(start < {} OR (start = {} AND run_id < {}))",
+                cursor.start,
+                cursor.start,
+                quote(&cursor.run_id)
+            ));
+        }
+
+        Ok(join(clauses))
+    }
+
+    /// Orders the keys of the matching runs newest first and cuts them to a page.
+    /// Returns the keys of the page and the cursor of the next one.
+    pub fn paginate(&self, mut keys: Vec<RunCursor>) -> (Vec<RunCursor>, Option<String>) {
+        keys.sort_by(|a, b| b.cmp(a));
+        keys.drain(..self.offset().min(keys.len()));
+
+        let limit = self.limit();
+        let next_cursor = if keys.len() > limit {
+            keys.truncate(limit);
+            keys.last().map(|key| key.to_string())
+        } else {
+            None
+        };
+
+        (keys, next_cursor)
+    }
+}
+
+/// Filter matching exactly the runs of a page.
+pub fn page_filter(keys: &[RunCursor]) -> Option<String> {
+    if keys.is_empty() {
+        return None;
+    }
+
+    let run_ids = keys
+        .iter()
+        .map(|key| quote(&key.run_id))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(format!("run_id IN ({})", run_ids))
+}
+
+/// Filter for the log messages of a single run.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, Default)]
+pub struct LogFilter {
+    /// Only messages with at least this level
+    pub min_level: Option<LogLevel>,
+    /// Only messages with one of these levels, a list or comma separated in query strings
+    #[serde(default, deserialize_with = "deserialize_levels")]
+    pub levels: Option<Vec<LogLevel>>,
+    pub node_id: Option<String>,
+    /// Case sensitive substring of the message
+    pub search: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+impl LogFilter {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset.unwrap_or(0)
+    }
+
+    pub fn to_filter(&self) -> Option<String> {
+        let mut clauses = vec![];
+
+        if let Some(level) = self.min_level {
+            clauses.push(format!("log_level >= {}", level.to_u8()));
+        }
+
+        if let Some(levels) = self.levels.as_ref().filter(|levels| !levels.is_empty()) {
+            let levels = levels
+                .iter()
+                .map(|level| level.to_u8().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            clauses.push(format!("log_level IN ({})", levels));
+        }
+
+        if let Some(node_id) = &self.node_id {
+            clauses.push(format!("node_id = {}", quote(node_id)));
+        }
+
+        if let Some(search) = self.search.as_deref().filter(|search| !search.is_empty()) {
+            let pattern = format!("%{}%", escape_like(search));
+            clauses.push(format!("message LIKE {} ESCAPE '\\'", quote(&pattern)));
+        }
+
+        join(clauses)
+    }
+}
+
+fn deserialize_levels<'de, D>(deserializer: D) -> Result<Option<Vec<LogLevel>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Levels {
+        List(Vec<LogLevel>),
+        Joined(String),
+    }
+
+    let levels = match Option::<Levels>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(Levels::List(levels)) => levels,
+        Some(Levels::Joined(joined)) => joined
+            .split(',')
+            .map(str::trim)
+            .filter(|level| !level.is_empty())
+            .map(|level| LogLevel::deserialize(level.into_deserializer()))
+            .collect::<Result<_, serde::de::value::Error>>()
+            .map_err(serde::de::Error::custom)?,
+    };
+    Ok(Some(levels))
+}
+
+/// Quotes a value as a SQL string literal.
+pub fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+fn join(clauses: Vec<String>) -> Option<String> {
+    if clauses.is_empty() {
+        return None;
+    }
+    Some(clauses.join(" AND "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_like_types::json;
+
+    fn meta(run_id: &str, start: u64) -> LogMeta {
+        LogMeta {
+            app_id: "app".to_string(),
+            run_id: run_id.to_string(),
+            board_id: "board".to_string(),
+            start,
+            end: start,
+            log_level: 1,
+            version: "v1".to_string(),
+            nodes: None,
+            logs: None,
+            node_id: "node".to_string(),
+            event_version: None,
+            event_id: String::new(),
+            payload: vec![],
+            parent_run_id: None,
+        }
+    }
+
+    #[test]
+    fn quotes_filter_values() {
+        let filter = RunFilter {
+            node_id: Some("a' OR '1'='1".to_string()),
+            from: Some(10),
+            status: Some(LogLevel::Error),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            filter.to_filter().unwrap().unwrap(),
+            "node_id = 'a'' OR ''1''=''1' AND start >= 10 AND log_level = 3"
+        );
+        assert_eq!(RunFilter::default().to_filter().unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_invalid_cursor() {
+        let filter = RunFilter {
+            cursor: Some("1 OR 1=1".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.to_filter().is_err());
+    }
+
+    #[test]
+    fn paginates_newest_first() {
+        let filter = RunFilter {
+            limit: Some(2),
+            ..Default::default()
+        };
+        let keys = [meta("a", 1), meta("b", 3), meta("c", 2)]
+            .iter()
+            .map(RunCursor::from_meta)
+            .collect();
+        let (keys, cursor) = filter.paginate(keys);
+
+        assert_eq!(
+            keys.iter().map(|k| k.run_id.as_str()).collect::<Vec<_>>(),
+            vec!["b", "c"]
+        );
+        assert_eq!(page_filter(&keys).unwrap(), "run_id IN ('b', 'c')");
+        let cursor = cursor.unwrap();
+        assert_eq!(
+            RunCursor::parse(&cursor).unwrap(),
+            RunCursor::from_meta(&meta("c", 2))
+        );
+
+        let filter = RunFilter {
+            cursor: Some(cursor),
+            offset: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            filter.to_filter().unwrap().unwrap(),
+            "(start < 2 OR (start = 2 AND run_id < 'c'))"
+        );
+
+        // the store only returns keys past the cursor, the offset must not skip them again
+        let (keys, cursor) = filter.paginate(vec![RunCursor::from_meta(&meta("a", 1))]);
+        assert_eq!(keys, vec![RunCursor::from_meta(&meta("a", 1))]);
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn escapes_log_search() {
+        let filter = LogFilter {
+            min_level: Some(LogLevel::Warn),
+            search: Some("100%_done".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            filter.to_filter().unwrap(),
+            "log_level >= 2 AND message LIKE '%100\\%\\_done%' ESCAPE '\\'"
+        );
+    }
+
+    #[test]
+    fn filters_log_levels() {
+        let filter: LogFilter = json::from_str(r#"{"levels": ["Info", "Error"]}"#).unwrap();
+        assert_eq!(filter.to_filter().unwrap(), "log_level IN (1, 3)");
+
+        let filter: LogFilter = json::from_str(r#"{"levels": "Warn,Fatal"}"#).unwrap();
+        assert_eq!(filter.to_filter().unwrap(), "log_level IN (2, 4)");
+
+        assert!(json::from_str::<LogFilter>(r#"{"levels": "Loud"}"#).is_err());
+    }
+}
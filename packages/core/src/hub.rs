@@ -18,6 +18,21 @@ pub struct UserTier {
     pub max_total_size: i64,
     pub max_llm_calls: i32,
     pub llm_tiers: Vec<String>,
+    #[serde(default = "unlimited")]
+    pub max_requests_per_minute: i64,
+    #[serde(default = "unlimited")]
+    pub max_concurrent_executions: i64,
+    /// Remote execution time per calendar month
+    #[serde(default = "unlimited")]
+    pub max_execution_minutes: i64,
+    /// Input and output tokens of the LLM proxy per calendar month
+    #[serde(default = "unlimited")]
+    pub max_llm_tokens: i64,
+}
+
+/// Limits use -1 for unlimited
+fn unlimited() -> i64 {
+    -1
 }
 
 pub type UserTiers = HashMap<String, UserTier>;
@@ -1,7 +1,10 @@
 use std::{collections::HashMap, sync::Arc, time::SystemTime};
 
 use flow_like_model_provider::{
-    embedding::{EmbeddingModelLogic, openai::OpenAIEmbeddingModel},
+    embedding::{
+        EmbeddingModelLogic, bedrock::BedrockEmbeddingModel, gemini::GeminiEmbeddingModel,
+        openai::OpenAIEmbeddingModel,
+    },
     image_embedding::ImageEmbeddingModelLogic,
 };
 use flow_like_types::sync::Mutex;
@@ -68,6 +71,16 @@ impl EmbeddingFactory {
             return Ok(Arc::new(local_model));
         }
 
+        if provider_name == "gemini" || provider_name == "vertex" {
+            let model = GeminiEmbeddingModel::new(&embedding_provider, &provider_config).await?;
+            return Ok(Arc::new(model));
+        }
+
+        if provider_name == "bedrock" {
+            let model = BedrockEmbeddingModel::new(&embedding_provider, &provider_config).await?;
+            return Ok(Arc::new(model));
+        }
+
         Err(flow_like_types::anyhow!("Model type not supported"))
     }
 
@@ -1,7 +1,14 @@
+pub mod cache;
 pub mod local;
 
 use crate::{bit::Bit, state::FlowLikeState};
-use flow_like_model_provider::llm::{ModelLogic, openai::OpenAIModel};
+use flow_like_model_provider::{
+    llm::{
+        ModelLogic, anthropic::AnthropicModel, bedrock::BedrockModel, gcp::GeminiModel,
+        openai::OpenAIModel, remote::RemoteModel,
+    },
+    routing::FallbackModel,
+};
 use flow_like_types::{Result, sync::Mutex, tokio::time::interval};
 use local::LocalModel;
 use serde::{Deserialize, Serialize};
@@ -58,10 +65,36 @@ impl ModelFactory {
         self.execution_settings = settings;
     }
 
+    /// Builds the model of the bit, wrapped with its fallback models if it has any.
     pub async fn build(
         &mut self,
         bit: &Bit,
         app_state: Arc<Mutex<FlowLikeState>>,
+    ) -> Result<Arc<dyn ModelLogic>> {
+        let model = self.build_model(bit, app_state.clone()).await?;
+        let mut fallback = bit.fallback();
+        if fallback.is_none() {
+            return Ok(model);
+        }
+
+        let mut models = vec![(bit.id.clone(), model)];
+        while let Some(bit) = fallback.take() {
+            // Fallback chains may point back to a model that already failed
+            if models.iter().any(|(id, _)| id == &bit.id) {
+                break;
+            }
+            let model = self.build_model(&bit, app_state.clone()).await?;
+            fallback = bit.fallback();
+            models.push((bit.id, model));
+        }
+
+        Ok(Arc::new(FallbackModel::new(models)))
+    }
+
+    async fn build_model(
+        &mut self,
+        bit: &Bit,
+        app_state: Arc<Mutex<FlowLikeState>>,
     ) -> Result<Arc<dyn ModelLogic>> {
         let provider_config = app_state.lock().await.model_provider_config.clone();
         let settings = self.execution_settings.clone();
@@ -106,6 +139,62 @@ impl ModelFactory {
             return Ok(model);
         }
 
+        if provider == "anthropic" {
+            if let Some(model) = self.cached_models.get(&bit.id) {
+                self.ttl_list.insert(bit.id.clone(), SystemTime::now());
+                return Ok(model.clone());
+            }
+
+            let model = AnthropicModel::new(&model_provider, &provider_config).await?;
+
+            let model = Arc::new(model);
+            self.ttl_list.insert(bit.id.clone(), SystemTime::now());
+            self.cached_models.insert(bit.id.clone(), model.clone());
+            return Ok(model);
+        }
+
+        if provider == "gemini" || provider == "vertex" {
+            if let Some(model) = self.cached_models.get(&bit.id) {
+                self.ttl_list.insert(bit.id.clone(), SystemTime::now());
+                return Ok(model.clone());
+            }
+
+            let model = GeminiModel::new(&model_provider, &provider_config).await?;
+
+            let model = Arc::new(model);
+            self.ttl_list.insert(bit.id.clone(), SystemTime::now());
+            self.cached_models.insert(bit.id.clone(), model.clone());
+            return Ok(model);
+        }
+
+        if provider == "bedrock" {
+            if let Some(model) = self.cached_models.get(&bit.id) {
+                self.ttl_list.insert(bit.id.clone(), SystemTime::now());
+                return Ok(model.clone());
+            }
+
+            let model = BedrockModel::new(&model_provider, &provider_config).await?;
+
+            let model = Arc::new(model);
+            self.ttl_list.insert(bit.id.clone(), SystemTime::now());
+            self.cached_models.insert(bit.id.clone(), model.clone());
+            return Ok(model);
+        }
+
+        if provider == "remote" {
+            if let Some(model) = self.cached_models.get(&bit.id) {
+                self.ttl_list.insert(bit.id.clone(), SystemTime::now());
+                return Ok(model.clone());
+            }
+
+            let model = RemoteModel::new(&model_provider, &provider_config).await?;
+
+            let model = Arc::new(model);
+            self.ttl_list.insert(bit.id.clone(), SystemTime::now());
+            self.cached_models.insert(bit.id.clone(), model.clone());
+            return Ok(model);
+        }
+
         Err(flow_like_types::anyhow!("Model type not supported"))
     }
 
@@ -0,0 +1,317 @@
+use crate::utils::{
+    compression::{compress_to_file_json, from_compressed_json},
+    hash::hash_string,
+};
+use flow_like_model_provider::{
+    history::History,
+    llm::{LLMCallback, ModelLogic},
+    response::Response,
+    response_chunk::ResponseChunk,
+};
+use flow_like_storage::{Path, files::store::FlowLikeStore, object_store::ObjectStore};
+use flow_like_types::{Result, Value, async_trait, json};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Fields of a `History` that change how the answer is delivered, but not the answer itself.
+const DELIVERY_FIELDS: [&str; 3] = ["stream", "stream_options", "user"];
+
+#[derive(Clone, Debug)]
+pub struct ResponseCacheOptions {
+    pub ttl: Duration,
+    /// Oldest entries are evicted once the cache holds more responses
+    pub max_entries: usize,
+    /// Larger responses are not cached, in bytes
+    pub max_entry_size: usize,
+    /// Streams cached chunks through the callback again, so streaming nodes behave as on a miss
+    pub replay_stream: bool,
+}
+
+impl Default for ResponseCacheOptions {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(60 * 60 * 24),
+            max_entries: 1000,
+            max_entry_size: 1024 * 1024,
+            replay_stream: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    created_at: u64,
+    response: Response,
+    chunks: Vec<ResponseChunk>,
+}
+
+/// Key of a request, equal for histories that ask the same model for the same answer.
+pub fn cache_key(model_id: &str, history: &History) -> Result<String> {
+    let mut history = json::to_value(history)?;
+    if let Value::Object(fields) = &mut history {
+        for field in DELIVERY_FIELDS {
+            fields.remove(field);
+        }
+    }
+    let history = canonical_json::ser::to_string(&history)
+        .map_err(|e| flow_like_types::anyhow!("Failed to normalize history: {:?}", e))?;
+    Ok(hash_string(&format!("{}:{}", model_id, history)))
+}
+
+/// Answers repeated requests from the store instead of calling the model again.
+pub struct CachedModel {
+    model: Arc<dyn ModelLogic>,
+    model_id: String,
+    store: Arc<dyn ObjectStore>,
+    dir: Path,
+    options: ResponseCacheOptions,
+}
+
+impl CachedModel {
+    pub fn new(
+        model: Arc<dyn ModelLogic>,
+        model_id: &str,
+        store: &FlowLikeStore,
+        dir: Path,
+        options: ResponseCacheOptions,
+    ) -> Self {
+        Self {
+            model,
+            model_id: model_id.to_string(),
+            store: store.as_generic(),
+            dir,
+            options,
+        }
+    }
+
+    fn entry_path(&self, key: &str) -> Path {
+        self.dir.child(format!("{}.json.lz4", key))
+    }
+
+    async fn read(&self, key: &str) -> Option<CacheEntry> {
+        let path = self.entry_path(key);
+        let entry: CacheEntry = from_compressed_json(self.store.clone(), path.clone())
+            .await
+            .ok()?;
+
+        if now().saturating_sub(entry.created_at) >= self.options.ttl.as_secs() {
+            let _ = self.store.delete(&path).await;
+            return None;
+        }
+
+        Some(entry)
+    }
+
+    async fn write(&self, key: &str, entry: &CacheEntry) -> Result<()> {
+        if json::to_vec(entry)?.len() > self.options.max_entry_size {
+            return Ok(());
+        }
+
+        compress_to_file_json(self.store.clone(), self.entry_path(key), entry).await?;
+        self.evict().await
+    }
+
+    /// Drops expired entries and the oldest ones beyond `max_entries`.
+    async fn evict(&self) -> Result<()> {
+        let mut entries = self
+            .store
+            .list(Some(&self.dir))
+            .try_collect::<Vec<_>>()
+            .await?;
+        entries.sort_by_key(|entry| entry.last_modified);
+
+        let expired_before = chrono::Utc::now() - chrono::Duration::from_std(self.options.ttl)?;
+        let overflow = entries.len().saturating_sub(self.options.max_entries);
+        for (index, entry) in entries.iter().enumerate() {
+            if index < overflow || entry.last_modified < expired_before {
+                self.store.delete(&entry.location).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ModelLogic for CachedModel {
+    async fn invoke(&self, history: &History, callback: Option<LLMCallback>) -> Result<Response> {
+        let key = cache_key(&self.model_id, history)?;
+
+        if let Some(entry) = self.read(&key).await {
+            if let (Some(callback), true) = (&callback, self.options.replay_stream) {
+                for chunk in entry.chunks {
+                    callback(chunk).await?;
+                }
+            }
+            return Ok(entry.response);
+        }
+
+        // Chunks are recorded even without a callback, a later call may want them replayed
+        let chunks = Arc::new(Mutex::new(Vec::new()));
+        let recorded = chunks.clone();
+        let recording: LLMCallback = Arc::new(move |chunk: ResponseChunk| {
+            if let Ok(mut chunks) = recorded.lock() {
+                chunks.push(chunk.clone());
+            }
+            let callback = callback.clone();
+            Box::pin(async move {
+                match callback {
+                    Some(callback) => callback(chunk).await,
+                    None => Ok(()),
+                }
+            })
+        });
+
+        let response = self.model.invoke(history, Some(recording)).await?;
+
+        let chunks = match chunks.lock() {
+            Ok(mut chunks) => std::mem::take(&mut *chunks),
+            Err(_) => Vec::new(),
+        };
+        let entry = CacheEntry {
+            created_at: now(),
+            response,
+            chunks,
+        };
+        if let Err(e) = self.write(&key, &entry).await {
+            tracing::warn!("Failed to cache model response: {:?}", e);
+        }
+
+        Ok(entry.response)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_like_model_provider::history::{HistoryMessage, Role};
+    use flow_like_storage::object_store::memory::InMemory;
+    use flow_like_types::tokio;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct CountingModel {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ModelLogic for CountingModel {
+        async fn invoke(&self, _: &History, callback: Option<LLMCallback>) -> Result<Response> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            let chunk: ResponseChunk = json::from_value(json::json!({
+                "id": format!("chunk-{}", call),
+                "choices": [{ "index": 0, "delta": { "content": "Hello" } }]
+            }))?;
+            if let Some(callback) = callback {
+                callback(chunk.clone()).await?;
+            }
+            let mut response = Response::new();
+            response.id = Some(chunk.id.clone());
+            response.push_chunk(chunk);
+            Ok(response)
+        }
+    }
+
+    fn history(prompt: &str) -> History {
+        History::new(
+            "model".to_string(),
+            vec![HistoryMessage::from_string(Role::User, prompt)],
+        )
+    }
+
+    fn cached(model: Arc<CountingModel>, options: ResponseCacheOptions) -> CachedModel {
+        let store = FlowLikeStore::Memory(Arc::new(InMemory::new()));
+        CachedModel::new(model, "bit", &store, Path::from("cache"), options)
+    }
+
+    #[test]
+    fn key_ignores_delivery_fields() {
+        let mut streamed = history("Hi");
+        streamed.stream = Some(true);
+        streamed.user = Some("user".to_string());
+
+        let key = cache_key("bit", &history("Hi")).unwrap();
+        assert_eq!(key, cache_key("bit", &streamed).unwrap());
+        assert_ne!(key, cache_key("other", &history("Hi")).unwrap());
+        assert_ne!(key, cache_key("bit", &history("Hello")).unwrap());
+
+        let mut sampled = history("Hi");
+        sampled.temperature = Some(0.5);
+        assert_ne!(key, cache_key("bit", &sampled).unwrap());
+    }
+
+    #[tokio::test]
+    async fn repeated_requests_hit_the_cache() {
+        let model = Arc::new(CountingModel::default());
+        let cache = cached(model.clone(), ResponseCacheOptions::default());
+
+        let first = cache.invoke(&history("Hi"), None).await.unwrap();
+        let second = cache.invoke(&history("Hi"), None).await.unwrap();
+        cache.invoke(&history("Hello"), None).await.unwrap();
+
+        assert_eq!(model.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(first.id, second.id);
+    }
+
+    #[tokio::test]
+    async fn cached_chunks_are_replayed() {
+        let model = Arc::new(CountingModel::default());
+        let cache = cached(model.clone(), ResponseCacheOptions::default());
+        cache.invoke(&history("Hi"), None).await.unwrap();
+
+        let replayed = Arc::new(AtomicUsize::new(0));
+        let counter = replayed.clone();
+        let callback: LLMCallback = Arc::new(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { Ok(()) })
+        });
+        cache.invoke(&history("Hi"), Some(callback)).await.unwrap();
+
+        assert_eq!(model.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(replayed.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_refreshed() {
+        let model = Arc::new(CountingModel::default());
+        let options = ResponseCacheOptions {
+            ttl: Duration::ZERO,
+            ..Default::default()
+        };
+        let cache = cached(model.clone(), options);
+
+        cache.invoke(&history("Hi"), None).await.unwrap();
+        cache.invoke(&history("Hi"), None).await.unwrap();
+
+        assert_eq!(model.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn oldest_entries_are_evicted() {
+        let model = Arc::new(CountingModel::default());
+        let options = ResponseCacheOptions {
+            max_entries: 1,
+            ..Default::default()
+        };
+        let cache = cached(model.clone(), options);
+
+        cache.invoke(&history("Hi"), None).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        cache.invoke(&history("Hello"), None).await.unwrap();
+        cache.invoke(&history("Hello"), None).await.unwrap();
+        cache.invoke(&history("Hi"), None).await.unwrap();
+
+        assert_eq!(model.calls.load(Ordering::SeqCst), 3);
+    }
+}
@@ -1,14 +1,19 @@
 use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
+    time::SystemTime,
 };
 
 use crate::{
-    bit::{Bit, BitModelPreference, BitTypes},
+    bit::{Bit, BitModelClassification, BitModelPreference, BitTypes, LLMParameters, Metadata},
     hub::{BitSearchQuery, Hub},
     utils::http::HTTPClient,
 };
-use flow_like_types::{Result, Value, anyhow, tokio::task};
+use flow_like_model_provider::{
+    llm::remote::list_models,
+    provider::{ModelProvider, RemoteConfig},
+};
+use flow_like_types::{Result, Value, anyhow, json, tokio::task};
 use futures::future::join_all;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -56,6 +61,99 @@ impl ProfileApp {
     }
 }
 
+/// Context length assumed for remote models, `/v1/models` does not report it.
+const DEFAULT_REMOTE_CONTEXT_LENGTH: u32 = 8192;
+
+/// An OpenAI compatible server (Ollama, vLLM, LM Studio, ...) whose models are offered as bits.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Hash, PartialEq, Eq)]
+pub struct RemoteEndpoint {
+    #[serde(default = "flow_like_types::create_id")]
+    pub id: String,
+    pub name: String,
+    pub config: RemoteConfig,
+    #[serde(default)]
+    pub context_length: Option<u32>,
+    /// Model ids found by the last discovery
+    #[serde(default)]
+    pub models: Vec<String>,
+}
+
+impl RemoteEndpoint {
+    pub fn new(name: &str, config: RemoteConfig) -> Self {
+        Self {
+            id: flow_like_types::create_id(),
+            name: name.to_string(),
+            config,
+            context_length: None,
+            models: vec![],
+        }
+    }
+
+    pub async fn discover(&mut self, api_key: Option<&str>) -> Result<()> {
+        self.models = list_models(&self.config, api_key).await?;
+        Ok(())
+    }
+
+    pub fn to_bits(&self) -> Vec<Bit> {
+        self.models
+            .iter()
+            .filter_map(|model_id| self.to_bit(model_id).ok())
+            .collect()
+    }
+
+    fn to_bit(&self, model_id: &str) -> Result<Bit> {
+        let parameters = LLMParameters {
+            context_length: self.context_length.unwrap_or(DEFAULT_REMOTE_CONTEXT_LENGTH),
+            provider: ModelProvider {
+                provider_name: "remote".to_string(),
+                model_id: Some(model_id.to_string()),
+                version: None,
+                remote: Some(self.config.clone()),
+            },
+            model_classification: BitModelClassification::default(),
+        };
+        let meta = Metadata {
+            name: model_id.to_string(),
+            description: format!("{} served by {}", model_id, self.name),
+            long_description: None,
+            release_notes: None,
+            tags: vec!["remote".to_string()],
+            use_case: None,
+            icon: None,
+            thumbnail: None,
+            preview_media: vec![],
+            age_rating: None,
+            website: None,
+            support_url: None,
+            docs_url: None,
+            organization_specific_values: None,
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+        };
+        let id = format!("{}/{}", self.id, model_id);
+
+        Ok(Bit {
+            id: id.clone(),
+            bit_type: BitTypes::Llm,
+            meta: HashMap::from([("en".to_string(), meta)]),
+            authors: vec![],
+            repository: None,
+            download_link: None,
+            file_name: None,
+            hash: id,
+            size: None,
+            hub: "remote".to_string(),
+            parameters: json::to_value(parameters)?,
+            version: None,
+            license: None,
+            dependencies: vec![],
+            dependency_tree_hash: "".to_string(),
+            created: "".to_string(),
+            updated: "".to_string(),
+        })
+    }
+}
+
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Hash, PartialEq, Eq)]
 pub struct Profile {
     #[serde(default = "flow_like_types::create_id")]
@@ -79,6 +177,8 @@ pub struct Profile {
     pub theme: Option<Value>,
     pub bits: Vec<String>, // hub:id
     #[serde(default)]
+    pub remote_endpoints: Vec<RemoteEndpoint>,
+    #[serde(default)]
     pub settings: Settings,
     pub updated: String,
     pub created: String,
@@ -94,6 +194,7 @@ impl Default for Profile {
             hub: "".to_string(),
             hubs: vec![],
             bits: vec![],
+            remote_endpoints: vec![],
             icon: Some("".to_string()),
             interests: vec![],
             tags: vec![],
@@ -138,6 +239,17 @@ impl Profile {
                 }
             }
 
+            for bit in self.remote_bits() {
+                if multimodal && !bit.is_multimodal() {
+                    continue;
+                }
+                if let Ok(score) = bit.score(preference) {
+                    if best_bit.1.is_none() || (score > best_bit.0) {
+                        best_bit = (score, Some(bit));
+                    }
+                }
+            }
+
             if let Some(bit) = best_bit.1 {
                 return Ok(bit);
             }
@@ -281,4 +393,40 @@ impl Profile {
         let bit_id = format!("{}:{}", bit.hub, bit.id);
         self.bits.retain(|reference| reference != &bit_id);
     }
+
+    /// Bits for all models of the configured remote endpoints
+    pub fn remote_bits(&self) -> Vec<Bit> {
+        self.remote_endpoints
+            .iter()
+            .flat_map(RemoteEndpoint::to_bits)
+            .collect()
+    }
+
+    /// Adds or replaces the endpoint and registers the models it currently serves.
+    /// The `api_key` is only used for the discovery, it is not stored on the profile.
+    pub async fn discover_remote_endpoint(
+        &mut self,
+        endpoint: RemoteEndpoint,
+        api_key: Option<&str>,
+    ) -> Result<Vec<Bit>> {
+        let mut endpoint = endpoint;
+        endpoint.discover(api_key).await?;
+        let bits = endpoint.to_bits();
+
+        match self
+            .remote_endpoints
+            .iter_mut()
+            .find(|existing| existing.id == endpoint.id)
+        {
+            Some(existing) => *existing = endpoint,
+            None => self.remote_endpoints.push(endpoint),
+        }
+
+        Ok(bits)
+    }
+
+    pub fn remove_remote_endpoint(&mut self, endpoint_id: &str) {
+        self.remote_endpoints
+            .retain(|endpoint| endpoint.id != endpoint_id);
+    }
 }
@@ -3,7 +3,8 @@ use std::time::SystemTime;
 use flow_like_types::{FromProto, Timestamp, ToProto};
 
 use crate::flow::{
-    event::{CanaryEvent, Event, ReleaseNotes},
+    event::{CanaryEvent, Event, ReleaseNotes, chain::EventChain},
+    execution::{LogLevel, RunStatus},
     variable::Variable,
 };
 
@@ -45,6 +46,9 @@ impl ToProto<flow_like_types::proto::Event> for Event {
             created_at: Some(Timestamp::from(self.created_at)),
             updated_at: Some(Timestamp::from(self.updated_at)),
             event_type: self.event_type.clone(),
+            input_schema: self.input_schema.clone(),
+            output_schema: self.output_schema.clone(),
+            chains: self.chains.iter().map(|c| c.to_proto()).collect(),
         }
     }
 }
@@ -107,6 +111,13 @@ impl FromProto<flow_like_types::proto::Event> for Event {
                 .map(|t| SystemTime::try_from(t).unwrap_or(SystemTime::UNIX_EPOCH))
                 .unwrap_or(SystemTime::UNIX_EPOCH),
             event_type: proto.event_type,
+            input_schema: proto.input_schema,
+            output_schema: proto.output_schema,
+            chains: proto
+                .chains
+                .into_iter()
+                .map(EventChain::from_proto)
+                .collect(),
         }
     }
 }
@@ -134,3 +145,50 @@ impl FromProto<flow_like_types::proto::Canary> for CanaryEvent {
         }
     }
 }
+
+impl ToProto<flow_like_types::proto::EventChain> for EventChain {
+    fn to_proto(&self) -> flow_like_types::proto::EventChain {
+        flow_like_types::proto::EventChain {
+            id: self.id.clone(),
+            target_event_id: self.target_event_id.clone(),
+            on_status: self
+                .on_status
+                .iter()
+                .map(|status| match status {
+                    RunStatus::Running => flow_like_types::proto::ChainRunStatus::Running,
+                    RunStatus::Success => flow_like_types::proto::ChainRunStatus::Success,
+                    RunStatus::Failed => flow_like_types::proto::ChainRunStatus::Failed,
+                    RunStatus::Stopped => flow_like_types::proto::ChainRunStatus::Stopped,
+                } as i32)
+                .collect(),
+            min_log_level: self.min_log_level.map(|l| l.to_u32()),
+            max_log_level: self.max_log_level.map(|l| l.to_u32()),
+            payload_mapping: self.payload_mapping.clone().into_iter().collect(),
+            active: self.active,
+        }
+    }
+}
+
+impl FromProto<flow_like_types::proto::EventChain> for EventChain {
+    fn from_proto(proto: flow_like_types::proto::EventChain) -> Self {
+        EventChain {
+            id: proto.id,
+            target_event_id: proto.target_event_id,
+            on_status: proto
+                .on_status
+                .into_iter()
+                .filter_map(|status| flow_like_types::proto::ChainRunStatus::try_from(status).ok())
+                .map(|status| match status {
+                    flow_like_types::proto::ChainRunStatus::Running => RunStatus::Running,
+                    flow_like_types::proto::ChainRunStatus::Success => RunStatus::Success,
+                    flow_like_types::proto::ChainRunStatus::Failed => RunStatus::Failed,
+                    flow_like_types::proto::ChainRunStatus::Stopped => RunStatus::Stopped,
+                })
+                .collect(),
+            min_log_level: proto.min_log_level.map(LogLevel::from_u32),
+            max_log_level: proto.max_log_level.map(LogLevel::from_u32),
+            payload_mapping: proto.payload_mapping.into_iter().collect(),
+            active: proto.active,
+        }
+    }
+}
@@ -26,9 +26,14 @@ use crate::{
         },
         event::{
             ApiEventParameters, CanaryEvent, ChatEventParameters, EmailEventParameters, Event,
-            EventPayload,
+            EventPayload, MqttEventParameters,
+            contract::{EventContract, PayloadValidationError},
+        },
+        execution::{
+            LogMeta, RunPayload,
+            log::LogMessage,
+            query::{LogFilter, RunFilter, RunPage},
         },
-        execution::{LogMeta, RunPayload, log::LogMessage},
         node::Node,
         pin::Pin,
         variable::Variable,
@@ -99,8 +104,14 @@ pub fn generate_schema(base_path: PathBuf) -> flow_like_types::Result<()> {
     generate_and_save_schema::<EmailEventParameters>(&base_path, "flow/event-payload-mail.json")?;
     generate_and_save_schema::<ChatEventParameters>(&base_path, "flow/event-payload-chat.json")?;
     generate_and_save_schema::<ApiEventParameters>(&base_path, "flow/event-payload-api.json")?;
+    generate_and_save_schema::<MqttEventParameters>(&base_path, "flow/event-payload-mqtt.json")?;
     generate_and_save_schema::<VersionType>(&base_path, "flow/version-type.json")?;
     generate_and_save_schema::<CanaryEvent>(&base_path, "flow/canary.json")?;
+    generate_and_save_schema::<EventContract>(&base_path, "flow/event-contract.json")?;
+    generate_and_save_schema::<PayloadValidationError>(
+        &base_path,
+        "flow/payload-validation-error.json",
+    )?;
     generate_and_save_schema::<GenericCommand>(
         &base_path,
         "flow/board/commands/generic-command.json",
@@ -160,6 +171,9 @@ pub fn generate_schema(base_path: PathBuf) -> flow_like_types::Result<()> {
     generate_and_save_schema::<Variable>(&base_path, "flow/variable.json")?;
     generate_and_save_schema::<LogMessage>(&base_path, "flow/log.json")?;
     generate_and_save_schema::<LogMeta>(&base_path, "flow/log-metadata.json")?;
+    generate_and_save_schema::<RunFilter>(&base_path, "flow/run-filter.json")?;
+    generate_and_save_schema::<RunPage>(&base_path, "flow/run-page.json")?;
+    generate_and_save_schema::<LogFilter>(&base_path, "flow/log-filter.json")?;
 
     generate_and_save_schema::<Profile>(&base_path, "profile/profile.json")?;
 
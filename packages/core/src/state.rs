@@ -13,7 +13,11 @@ use std::time::Instant;
 
 use crate::flow::event::Event;
 #[cfg(feature = "flow-runtime")]
-use crate::flow::execution::{LogMeta, log::LogMessage};
+use crate::flow::execution::{
+    LogMeta,
+    log::LogMessage,
+    query::{LogFilter, RunCursor, RunFilter, RunPage, page_filter, quote},
+};
 
 #[cfg(feature = "flow-runtime")]
 use crate::flow::board::Board;
@@ -484,24 +488,12 @@ impl FlowLikeState {
     }
 
     #[cfg(feature = "flow-runtime")]
-    pub async fn query_run(
+    async fn open_logs_database(
         &self,
-        meta: &LogMeta,
-        query: &str,
-        limit: Option<usize>,
-        offset: Option<usize>,
-    ) -> flow_like_types::Result<Vec<LogMessage>> {
-        use flow_like_storage::{
-            lancedb::query::{ExecutableQuery, QueryBase},
-            serde_arrow,
-        };
+        app_id: &str,
+        board_id: &str,
+    ) -> flow_like_types::Result<flow_like_storage::lancedb::Connection> {
         use flow_like_types::anyhow;
-        use futures::TryStreamExt;
-
-        use crate::flow::execution::log::StoredLogMessage;
-
-        let limit = limit.unwrap_or(100);
-        let offset = offset.unwrap_or(0);
 
         let db = {
             let guard = self.config.read().await;
@@ -512,19 +504,147 @@ impl FlowLikeState {
         let db_fn = db
             .as_ref()
             .ok_or_else(|| anyhow!("No log database configured"))?;
-        let base_path = Path::from("runs")
-            .child(meta.app_id.clone())
-            .child(meta.board_id.clone());
-        let db = db_fn(base_path.clone()).execute().await?;
+        let base_path = Path::from("runs").child(app_id).child(board_id);
+        let db = db_fn(base_path.clone())
+            .execute()
+            .await
+            .map_err(|e| anyhow!("Failed to open database: {}, {:?}", base_path, e))?;
+        Ok(db)
+    }
+
+    /// Lists the finished runs of a board, newest first.
+    /// LanceDB cannot order a query, so only the keys of the matching runs are read to find the page,
+    /// the full rows are read for the runs of the page only.
+    #[cfg(feature = "flow-runtime")]
+    pub async fn query_runs(
+        &self,
+        app_id: &str,
+        board_id: &str,
+        filter: &RunFilter,
+    ) -> flow_like_types::Result<RunPage> {
+        use flow_like_storage::{
+            lancedb::query::{ExecutableQuery, QueryBase, Select},
+            serde_arrow,
+        };
+        use futures::TryStreamExt;
+
+        let db = self.open_logs_database(app_id, board_id).await?;
+        let tables = db.table_names().execute().await?;
+        if !tables.iter().any(|table| table == "runs") {
+            return Ok(RunPage {
+                runs: vec![],
+                next_cursor: None,
+            });
+        }
+
+        let table = db.open_table("runs").execute().await?;
+        let mut query = table.query().select(Select::columns(&["start", "run_id"]));
+
+        if let Some(filter) = filter.to_filter()? {
+            query = query.only_if(filter);
+        }
+
+        let mut keys = vec![];
+        let mut results = query.execute().await?;
+        while let Some(result) = results.try_next().await? {
+            keys.extend(
+                serde_arrow::from_record_batch::<Vec<RunCursor>>(&result).unwrap_or_default(),
+            );
+        }
+
+        let (keys, next_cursor) = filter.paginate(keys);
+        let Some(page_filter) = page_filter(&keys) else {
+            return Ok(RunPage {
+                runs: vec![],
+                next_cursor,
+            });
+        };
+
+        let results = table
+            .query()
+            .only_if(page_filter)
+            .limit(keys.len())
+            .execute()
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        let mut runs = Vec::with_capacity(keys.len());
+        for result in results {
+            let result =
+                serde_arrow::from_record_batch::<Vec<LogMeta>>(&result).unwrap_or_default();
+            runs.extend(result);
+        }
+        runs.sort_by_key(|meta| std::cmp::Reverse(RunCursor::from_meta(meta)));
+
+        Ok(RunPage { runs, next_cursor })
+    }
+
+    #[cfg(feature = "flow-runtime")]
+    pub async fn get_run_meta(
+        &self,
+        app_id: &str,
+        board_id: &str,
+        run_id: &str,
+    ) -> flow_like_types::Result<LogMeta> {
+        use flow_like_storage::{
+            lancedb::query::{ExecutableQuery, QueryBase},
+            serde_arrow,
+        };
+        use flow_like_types::anyhow;
+        use futures::TryStreamExt;
+
+        let db = self.open_logs_database(app_id, board_id).await?;
+        let table = db.open_table("runs").execute().await?;
+        let results = table
+            .query()
+            .only_if(format!("run_id = {}", quote(run_id)))
+            .limit(1)
+            .execute()
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        results
+            .iter()
+            .flat_map(|result| {
+                serde_arrow::from_record_batch::<Vec<LogMeta>>(result).unwrap_or_default()
+            })
+            .next()
+            .ok_or_else(|| anyhow!("Run {} not found", run_id))
+    }
+
+    /// Queries the logs of a run, the filter values are quoted for LanceDB.
+    #[cfg(feature = "flow-runtime")]
+    pub async fn query_run_logs(
+        &self,
+        meta: &LogMeta,
+        filter: &LogFilter,
+    ) -> flow_like_types::Result<Vec<LogMessage>> {
+        use flow_like_storage::{
+            lancedb::query::{ExecutableQuery, QueryBase},
+            serde_arrow,
+        };
+        use futures::TryStreamExt;
+
+        use crate::flow::execution::log::StoredLogMessage;
+
+        let db = self
+            .open_logs_database(&meta.app_id, &meta.board_id)
+            .await?;
 
         let db = db.open_table(meta.run_id.clone()).execute().await?;
         let mut q = db.query();
 
-        if !query.is_empty() {
-            q = q.only_if(query);
+        if let Some(filter) = filter.to_filter() {
+            q = q.only_if(filter);
         }
 
-        let results = q.offset(offset).limit(limit).execute().await?;
+        let results = q
+            .offset(filter.offset())
+            .limit(filter.limit())
+            .execute()
+            .await?;
         let results = results.try_collect::<Vec<_>>().await?;
 
         let mut log_messages = Vec::with_capacity(results.len() * 10);
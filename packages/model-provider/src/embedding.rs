@@ -6,6 +6,8 @@ use text_splitter::Characters;
 use text_splitter::{MarkdownSplitter, TextSplitter};
 use tiktoken_rs::CoreBPE;
 
+pub mod bedrock;
+pub mod gemini;
 pub mod openai;
 
 #[derive(Clone)]
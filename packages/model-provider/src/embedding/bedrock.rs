@@ -0,0 +1,237 @@
+use std::{any::Any, sync::Arc};
+
+use aws_sdk_bedrockruntime::{Client, primitives::Blob};
+use flow_like_types::{Cacheable, Result, Value, anyhow, async_trait, json};
+use text_splitter::{ChunkConfig, MarkdownSplitter, TextSplitter};
+use tiktoken_rs::{CoreBPE, cl100k_base};
+
+use crate::provider::{EmbeddingModelProvider, ModelProviderConfiguration, random_provider};
+
+use super::{EmbeddingModelLogic, GeneralTextSplitter};
+
+/// Cohere takes up to 96 texts per request, Titan only a single one.
+const COHERE_BATCH_SIZE: usize = 96;
+
+#[derive(Clone)]
+pub struct BedrockEmbeddingModel {
+    pub client: Arc<Client>,
+    provider: EmbeddingModelProvider,
+    tokenizer: Arc<CoreBPE>,
+}
+
+impl Cacheable for BedrockEmbeddingModel {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl BedrockEmbeddingModel {
+    pub async fn new(
+        provider: &EmbeddingModelProvider,
+        config: &ModelProviderConfiguration,
+    ) -> flow_like_types::Result<Self> {
+        let random_provider = random_provider(&config.bedrock_config)?;
+        let client = Client::new(&random_provider.config);
+        // Neither Titan nor Cohere publish their tokenizer, cl100k is close enough for chunking
+        let tokenizer = cl100k_base()?;
+
+        Ok(BedrockEmbeddingModel {
+            tokenizer: Arc::new(tokenizer),
+            client: Arc::new(client),
+            provider: provider.clone(),
+        })
+    }
+
+    async fn invoke(&self, model_id: &str, body: Value) -> Result<Value> {
+        let response = self
+            .client
+            .invoke_model()
+            .model_id(model_id)
+            .content_type("application/json")
+            .accept("application/json")
+            .body(Blob::new(json::to_vec(&body)?))
+            .send()
+            .await?;
+
+        Ok(json::from_slice(response.body.as_ref())?)
+    }
+
+    async fn embed(&self, texts: &[String], prefix: &str, query: bool) -> Result<Vec<Vec<f32>>> {
+        let model_id = self.provider.provider.model_id.clone();
+        let model_id = model_id.ok_or(anyhow!("Model ID is missing"))?;
+        let prefixed_array = texts
+            .iter()
+            .map(|text| format!("{}{}", prefix, text))
+            .collect::<Vec<String>>();
+
+        let mut embeddings = Vec::with_capacity(prefixed_array.len());
+
+        if model_id.starts_with("cohere.") {
+            let input_type = match query {
+                true => "search_query",
+                false => "search_document",
+            };
+            for batch in prefixed_array.chunks(COHERE_BATCH_SIZE) {
+                let body = json::json!({
+                    "texts": batch,
+                    "input_type": input_type,
+                    "truncate": "END",
+                });
+                let response = self.invoke(&model_id, body).await?;
+                let result = response
+                    .get("embeddings")
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Bedrock returned no embeddings"))?;
+                embeddings.extend(json::from_value::<Vec<Vec<f32>>>(result)?);
+            }
+            return Ok(embeddings);
+        }
+
+        // Only Titan v2 lets the caller pick the vector length
+        let dimensions = model_id
+            .starts_with("amazon.titan-embed-text-v2")
+            .then_some(self.provider.vector_length);
+        for text in prefixed_array {
+            let mut body = json::json!({ "inputText": text, "normalize": true });
+            if let Some(dimensions) = dimensions {
+                body["dimensions"] = json::json!(dimensions);
+            }
+            let response = self.invoke(&model_id, body).await?;
+            let result = response
+                .get("embedding")
+                .cloned()
+                .ok_or_else(|| anyhow!("Bedrock returned no embedding"))?;
+            embeddings.push(json::from_value::<Vec<f32>>(result)?);
+        }
+
+        Ok(embeddings)
+    }
+}
+
+#[async_trait]
+impl EmbeddingModelLogic for BedrockEmbeddingModel {
+    async fn get_splitter(
+        &self,
+        capacity: Option<usize>,
+        overlap: Option<usize>,
+    ) -> flow_like_types::Result<(GeneralTextSplitter, GeneralTextSplitter)> {
+        let params = &self.provider;
+        let max_tokens = capacity.unwrap_or(params.input_length as usize);
+        let max_tokens = std::cmp::min(max_tokens, params.input_length as usize);
+        let overlap = overlap.unwrap_or(20);
+
+        let config_md = ChunkConfig::new(max_tokens)
+            .with_sizer(self.tokenizer.clone())
+            .with_overlap(overlap)?;
+
+        let config = ChunkConfig::new(max_tokens)
+            .with_sizer(self.tokenizer.clone())
+            .with_overlap(overlap)?;
+
+        let text_splitter = Arc::new(TextSplitter::new(config));
+        let text_splitter = GeneralTextSplitter::TextTiktoken(text_splitter);
+        let markdown_splitter = Arc::new(MarkdownSplitter::new(config_md));
+        let markdown_splitter = GeneralTextSplitter::MarkdownTiktoken(markdown_splitter);
+
+        Ok((text_splitter, markdown_splitter))
+    }
+
+    async fn text_embed_query(&self, texts: &Vec<String>) -> Result<Vec<Vec<f32>>> {
+        self.embed(texts, &self.provider.prefix.query, true).await
+    }
+
+    async fn text_embed_document(&self, texts: &Vec<String>) -> Result<Vec<Vec<f32>>> {
+        self.embed(texts, &self.provider.prefix.paragraph, false)
+            .await
+    }
+
+    fn as_cacheable(&self) -> Arc<dyn Cacheable> {
+        Arc::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_config::{BehaviorVersion, Region, SdkConfig};
+    use aws_sdk_bedrockruntime::config::{Credentials, SharedCredentialsProvider};
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+    use aws_smithy_types::body::SdkBody;
+    use flow_like_types::tokio;
+
+    use super::*;
+    use crate::provider::{BedrockConfig, ModelProvider, Pooling, Prefix};
+
+    #[tokio::test]
+    async fn test_bedrock_cohere_embedding() {
+        let event = ReplayEvent::new(
+            http::Request::builder()
+                .uri("https://bedrock-runtime.us-east-1.amazonaws.com/")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .header("content-type", "application/json")
+                .body(SdkBody::from(
+                    r#"{"id":"1","embeddings":[[0.1,0.2,0.3],[0.4,0.5,0.6]],"texts":["a","b"]}"#,
+                ))
+                .unwrap(),
+        );
+        let http_client = StaticReplayClient::new(vec![event]);
+        let config = SdkConfig::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-east-1"))
+            .credentials_provider(SharedCredentialsProvider::new(Credentials::new(
+                "access-key",
+                "secret-key",
+                None,
+                None,
+                "test",
+            )))
+            .http_client(http_client.clone())
+            .build();
+        let config = ModelProviderConfiguration {
+            bedrock_config: vec![BedrockConfig {
+                config,
+                weight: None,
+            }],
+            ..Default::default()
+        };
+
+        let provider = EmbeddingModelProvider {
+            languages: vec!["en".to_string()],
+            vector_length: 3,
+            input_length: 512,
+            prefix: Prefix {
+                query: "".to_string(),
+                paragraph: "".to_string(),
+            },
+            pooling: Pooling::None,
+            provider: ModelProvider {
+                model_id: Some("cohere.embed-english-v3".to_string()),
+                version: None,
+                provider_name: "bedrock".to_string(),
+                remote: None,
+            },
+        };
+
+        let model = BedrockEmbeddingModel::new(&provider, &config)
+            .await
+            .unwrap();
+        let embeddings = model
+            .text_embed_query(&vec!["a".to_string(), "b".to_string()])
+            .await
+            .unwrap();
+
+        let request = http_client.actual_requests().next().unwrap();
+        let request: Value = json::from_slice(request.body().bytes().unwrap()).unwrap();
+        assert_eq!(request["input_type"], "search_query");
+        assert_eq!(request["texts"][1], "b");
+
+        assert_eq!(embeddings.len(), 2);
+        assert_eq!(embeddings[1], vec![0.4, 0.5, 0.6]);
+    }
+}
@@ -0,0 +1,128 @@
+use std::{any::Any, sync::Arc};
+
+use flow_like_types::{Cacheable, Result, async_trait};
+use text_splitter::{ChunkConfig, MarkdownSplitter, TextSplitter};
+use tiktoken_rs::{CoreBPE, cl100k_base};
+
+use crate::provider::{EmbeddingModelProvider, ModelProviderConfiguration, gemini::GeminiClient};
+
+use super::{EmbeddingModelLogic, GeneralTextSplitter};
+
+/// Texts per request, Vertex only takes a single text per request for Gemini embedding models.
+const AI_STUDIO_BATCH_SIZE: usize = 100;
+const VERTEX_BATCH_SIZE: usize = 250;
+
+#[derive(Clone)]
+pub struct GeminiEmbeddingModel {
+    pub client: Arc<GeminiClient>,
+    provider: EmbeddingModelProvider,
+    tokenizer: Arc<CoreBPE>,
+}
+
+impl Cacheable for GeminiEmbeddingModel {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl GeminiEmbeddingModel {
+    pub async fn new(
+        provider: &EmbeddingModelProvider,
+        config: &ModelProviderConfiguration,
+    ) -> flow_like_types::Result<Self> {
+        let client = GeminiClient::from_config(&provider.provider, config)?;
+        // Gemini does not publish its tokenizer, cl100k is close enough for chunking
+        let tokenizer = cl100k_base()?;
+
+        Ok(GeminiEmbeddingModel {
+            tokenizer: Arc::new(tokenizer),
+            client: Arc::new(client),
+            provider: provider.clone(),
+        })
+    }
+
+    fn batch_size(&self, model_id: &str) -> usize {
+        match self.client.is_vertex() {
+            true if model_id.starts_with("gemini-embedding") => 1,
+            true => VERTEX_BATCH_SIZE,
+            false => AI_STUDIO_BATCH_SIZE,
+        }
+    }
+
+    async fn embed(
+        &self,
+        texts: &[String],
+        prefix: &str,
+        task_type: &str,
+    ) -> Result<Vec<Vec<f32>>> {
+        let model_id = self.provider.provider.model_id.clone();
+        let model_id = model_id.ok_or(flow_like_types::anyhow!("Model ID is missing"))?;
+        let prefixed_array = texts
+            .iter()
+            .map(|text| format!("{}{}", prefix, text))
+            .collect::<Vec<String>>();
+
+        let mut embeddings = Vec::with_capacity(prefixed_array.len());
+        for batch in prefixed_array.chunks(self.batch_size(&model_id)) {
+            let result = self
+                .client
+                .embed(
+                    &model_id,
+                    batch,
+                    task_type,
+                    Some(self.provider.vector_length),
+                )
+                .await?;
+            embeddings.extend(result);
+        }
+
+        Ok(embeddings)
+    }
+}
+
+#[async_trait]
+impl EmbeddingModelLogic for GeminiEmbeddingModel {
+    async fn get_splitter(
+        &self,
+        capacity: Option<usize>,
+        overlap: Option<usize>,
+    ) -> flow_like_types::Result<(GeneralTextSplitter, GeneralTextSplitter)> {
+        let params = &self.provider;
+        let max_tokens = capacity.unwrap_or(params.input_length as usize);
+        let max_tokens = std::cmp::min(max_tokens, params.input_length as usize);
+        let overlap = overlap.unwrap_or(20);
+
+        let config_md = ChunkConfig::new(max_tokens)
+            .with_sizer(self.tokenizer.clone())
+            .with_overlap(overlap)?;
+
+        let config = ChunkConfig::new(max_tokens)
+            .with_sizer(self.tokenizer.clone())
+            .with_overlap(overlap)?;
+
+        let text_splitter = Arc::new(TextSplitter::new(config));
+        let text_splitter = GeneralTextSplitter::TextTiktoken(text_splitter);
+        let markdown_splitter = Arc::new(MarkdownSplitter::new(config_md));
+        let markdown_splitter = GeneralTextSplitter::MarkdownTiktoken(markdown_splitter);
+
+        Ok((text_splitter, markdown_splitter))
+    }
+
+    async fn text_embed_query(&self, texts: &Vec<String>) -> Result<Vec<Vec<f32>>> {
+        self.embed(texts, &self.provider.prefix.query, "RETRIEVAL_QUERY")
+            .await
+    }
+
+    async fn text_embed_document(&self, texts: &Vec<String>) -> Result<Vec<Vec<f32>>> {
+        self.embed(texts, &self.provider.prefix.paragraph, "RETRIEVAL_DOCUMENT")
+            .await
+    }
+
+    fn as_cacheable(&self) -> Arc<dyn Cacheable> {
+        Arc::new(self.clone())
+    }
+}
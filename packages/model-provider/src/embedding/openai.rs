@@ -136,6 +136,7 @@ mod tests {
             model_id: Some("text-embedding-3-small".to_string()),
             version: None,
             provider_name: "openai".to_string(),
+            remote: None,
         };
         let provider = EmbeddingModelProvider {
             provider,
@@ -155,8 +156,9 @@ mod tests {
                 organization: None,
                 endpoint: None,
                 proxy: None,
+                weight: None,
             }],
-            bedrock_config: vec![],
+            ..Default::default()
         };
 
         let model = OpenAIEmbeddingModel::new(&provider, &config).await.unwrap();
@@ -177,6 +179,7 @@ mod tests {
             model_id: Some("text-embedding-3-small".to_string()),
             version: None,
             provider_name: "openai".to_string(),
+            remote: None,
         };
         let provider = EmbeddingModelProvider {
             provider,
@@ -196,8 +199,9 @@ mod tests {
                 organization: None,
                 endpoint: None,
                 proxy: None,
+                weight: None,
             }],
-            bedrock_config: vec![],
+            ..Default::default()
         };
 
         let model = OpenAIEmbeddingModel::new(&provider, &config).await.unwrap();
@@ -215,6 +219,7 @@ mod tests {
             model_id: Some("embedding-test".to_string()),
             version: Some("2024-04-01-preview".to_string()),
             provider_name: "azure".to_string(),
+            remote: None,
         };
         let api_key = std::env::var("AZURE_OPENAI_API_KEY").unwrap();
         let endpoint = std::env::var("AZURE_OPENAI_ENDPOINT").unwrap();
@@ -224,8 +229,9 @@ mod tests {
                 organization: None,
                 endpoint: Some(endpoint),
                 proxy: None,
+                weight: None,
             }],
-            bedrock_config: vec![],
+            ..Default::default()
         };
         let provider = EmbeddingModelProvider {
             provider,
@@ -9,4 +9,5 @@ pub use text_splitter;
 pub use tokenizers;
 pub mod ml;
 pub mod provider;
+pub mod routing;
 pub mod tokenizer;
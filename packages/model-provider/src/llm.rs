@@ -4,8 +4,13 @@ use std::{future::Future, pin::Pin, sync::Arc};
 
 use super::{history::History, response::Response, response_chunk::ResponseChunk};
 
+pub mod anthropic;
 pub mod bedrock;
+pub mod gcp;
+#[cfg(test)]
+mod mock_server;
 pub mod openai;
+pub mod remote;
 
 pub type LLMCallback = Arc<
     dyn Fn(ResponseChunk) -> Pin<Box<dyn Future<Output = Result<()>> + Send>>
@@ -0,0 +1,263 @@
+use std::sync::Arc;
+
+use super::{LLMCallback, ModelLogic};
+use crate::{
+    history::History,
+    provider::{ModelProvider, ModelProviderConfiguration, anthropic::AnthropicClient},
+    response::Response,
+    routing::{ProviderRouter, RoutedEndpoint},
+};
+use flow_like_types::{
+    Result, async_trait, json,
+    reqwest_eventsource::{self, Event},
+};
+use futures::StreamExt;
+use history::MessagesRequest;
+use response::{StreamEvent, StreamState};
+
+pub mod history;
+pub mod response;
+
+pub struct AnthropicModel {
+    router: ProviderRouter<AnthropicClient>,
+    provider: ModelProvider,
+}
+
+impl AnthropicModel {
+    pub async fn new(
+        provider: &ModelProvider,
+        config: &ModelProviderConfiguration,
+    ) -> flow_like_types::Result<Self> {
+        let endpoints = config
+            .anthropic_config
+            .iter()
+            .map(|anthropic_config| {
+                let client = AnthropicClient::from_anthropic_config(anthropic_config.clone())?;
+                Ok(RoutedEndpoint {
+                    key: client.endpoint().to_string(),
+                    weight: anthropic_config.weight,
+                    client,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(AnthropicModel {
+            router: ProviderRouter::new(config, endpoints)?,
+            provider: provider.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl ModelLogic for AnthropicModel {
+    async fn invoke(&self, history: &History, callback: Option<LLMCallback>) -> Result<Response> {
+        let model_id = self
+            .provider
+            .model_id
+            .clone()
+            .ok_or_else(|| flow_like_types::anyhow!("Model ID is missing"))?;
+        let mut request = MessagesRequest::from(history.clone());
+        request.model = model_id;
+
+        self.router
+            .route(|client| invoke_client(client, &request, callback.clone()))
+            .await
+    }
+}
+
+async fn invoke_client(
+    client: Arc<AnthropicClient>,
+    request: &MessagesRequest,
+    callback: Option<LLMCallback>,
+) -> Result<Response> {
+    if !request.stream.unwrap_or(false) {
+        let response = client.messages(request).await?;
+        return Ok(Response::from(response));
+    }
+
+    let mut stream = client.messages_stream(request)?;
+    let mut state = StreamState::default();
+    let mut output = Response::new();
+
+    while let Some(event) = stream.next().await {
+        let message = match event {
+            Ok(Event::Open) => continue,
+            Ok(Event::Message(message)) => message,
+            Err(reqwest_eventsource::Error::StreamEnded) => break,
+            Err(error) => {
+                stream.close();
+                return Err(AnthropicClient::stream_error(error).await);
+            }
+        };
+
+        let event: StreamEvent = match json::from_str(&message.data) {
+            Ok(event) => event,
+            Err(e) => {
+                eprintln!("Failed to parse Anthropic event: {}", e);
+                continue;
+            }
+        };
+
+        if matches!(event, StreamEvent::MessageStop) {
+            break;
+        }
+
+        let chunk = match state.chunk(event) {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => continue,
+            Err(error) => {
+                stream.close();
+                return Err(error);
+            }
+        };
+
+        if let Some(callback) = &callback {
+            callback(chunk.clone()).await?;
+        }
+        output.push_chunk(chunk);
+    }
+
+    stream.close();
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use flow_like_types::tokio;
+
+    use super::*;
+    use crate::{
+        history::{HistoryMessage, Role, ToolCall, ToolCallFunction},
+        llm::mock_server::mock_server,
+        provider::AnthropicConfig,
+    };
+
+    fn model_config(endpoint: String) -> (ModelProvider, ModelProviderConfiguration) {
+        let provider = ModelProvider {
+            model_id: Some("claude-sonnet-4-5".to_string()),
+            version: None,
+            provider_name: "anthropic".to_string(),
+            remote: None,
+        };
+        let config = ModelProviderConfiguration {
+            anthropic_config: vec![AnthropicConfig {
+                api_key: Some("test-key".to_string()),
+                endpoint: Some(endpoint),
+                version: None,
+                proxy: None,
+                weight: None,
+            }],
+            ..Default::default()
+        };
+        (provider, config)
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_model_no_stream() {
+        let body = r#"{"id":"msg_1","type":"message","role":"assistant","model":"claude-sonnet-4-5","content":[{"type":"text","text":"It is sunny in Berlin."}],"stop_reason":"end_turn","stop_sequence":null,"usage":{"input_tokens":30,"output_tokens":8}}"#;
+        let (address, server) = mock_server("application/json", body.to_string()).await;
+        let (provider, config) = model_config(format!("{}/v1", address));
+
+        let mut assistant = HistoryMessage::from_string(Role::Assistant, "");
+        assistant.tool_calls = Some(vec![ToolCall {
+            id: "toolu_1".to_string(),
+            r#type: "function".to_string(),
+            function: ToolCallFunction {
+                name: Some("get_weather".to_string()),
+                arguments: Some(r#"{"city":"Berlin"}"#.to_string()),
+            },
+        }]);
+        let mut tool = HistoryMessage::from_string(Role::Tool, "Sunny");
+        tool.tool_call_id = Some("toolu_1".to_string());
+
+        let model = AnthropicModel::new(&provider, &config).await.unwrap();
+        let mut history = History::new(
+            "claude-sonnet-4-5".to_string(),
+            vec![
+                HistoryMessage::from_string(Role::System, "You are a helpful assistant."),
+                HistoryMessage::from_string(Role::User, "What is the weather in Berlin?"),
+                assistant,
+                tool,
+            ],
+        );
+        history.set_stream(false);
+        let response = model.invoke(&history, None).await.unwrap();
+
+        let request = server.await.unwrap().json();
+        assert_eq!(request["system"], "You are a helpful assistant.");
+        assert_eq!(request["max_tokens"], 4096);
+        assert_eq!(request["messages"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            request["messages"][1]["content"][0],
+            json::json!({ "type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": { "city": "Berlin" } })
+        );
+        assert_eq!(request["messages"][2]["role"], "user");
+        assert_eq!(request["messages"][2]["content"][0]["type"], "tool_result");
+
+        let message = response.last_message().unwrap();
+        assert_eq!(message.content.as_deref(), Some("It is sunny in Berlin."));
+        assert_eq!(response.choices[0].finish_reason, "stop");
+        assert_eq!(response.usage.total_tokens, 38);
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_model_stream() {
+        let events = [
+            r#"{"type":"message_start","message":{"id":"msg_2","type":"message","role":"assistant","model":"claude-sonnet-4-5","content":[],"stop_reason":null,"usage":{"input_tokens":12,"output_tokens":1}}}"#,
+            r#"{"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}"#,
+            r#"{"type":"ping"}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hello"}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":" world"}}"#,
+            r#"{"type":"content_block_stop","index":0}"#,
+            r#"{"type":"content_block_start","index":1,"content_block":{"type":"tool_use","id":"toolu_2","name":"get_weather","input":{}}}"#,
+            r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"{\"city\":"}}"#,
+            r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"\"Berlin\"}"}}"#,
+            r#"{"type":"content_block_stop","index":1}"#,
+            r#"{"type":"message_delta","delta":{"stop_reason":"tool_use","stop_sequence":null},"usage":{"output_tokens":20}}"#,
+            r#"{"type":"message_stop"}"#,
+        ];
+        let body = events
+            .iter()
+            .map(|event| format!("data: {}\n\n", event))
+            .collect::<String>();
+        let (address, server) = mock_server("text/event-stream", body).await;
+        let (provider, config) = model_config(format!("{}/v1", address));
+
+        let model = AnthropicModel::new(&provider, &config).await.unwrap();
+        let mut history = History::new(
+            "claude-sonnet-4-5".to_string(),
+            vec![HistoryMessage::from_string(Role::User, "Hello")],
+        );
+        history.set_stream(true);
+
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = counter.clone();
+        let callback: LLMCallback = Arc::new(move |_response| {
+            counter_clone.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move { Ok(()) })
+        });
+
+        let response = model.invoke(&history, Some(callback)).await.unwrap();
+
+        let request = server.await.unwrap().json();
+        assert_eq!(request["stream"], true);
+        assert!(request.get("system").is_none());
+
+        let message = response.last_message().unwrap();
+        assert_eq!(message.role, "assistant");
+        assert_eq!(message.content.as_deref(), Some("Hello world"));
+        assert_eq!(message.tool_calls.len(), 1);
+        assert_eq!(message.tool_calls[0].id, "toolu_2");
+        assert_eq!(
+            message.tool_calls[0].function.arguments.as_deref(),
+            Some(r#"{"city":"Berlin"}"#)
+        );
+        assert_eq!(response.choices[0].finish_reason, "tool_calls");
+        assert_eq!(response.usage.prompt_tokens, 12);
+        assert_eq!(response.usage.completion_tokens, 20);
+        assert_eq!(counter.load(Ordering::SeqCst), 7);
+    }
+}
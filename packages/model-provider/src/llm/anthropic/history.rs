@@ -0,0 +1,261 @@
+use flow_like_types::{
+    Value,
+    json::{self, json},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::history::{
+    Content, History, ImageUrl, MessageContent, Role, Tool, ToolCall, ToolChoice,
+};
+
+/// Anthropic requires `max_tokens`, used when the history does not set a limit.
+pub const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct MessagesRequest {
+    pub model: String,
+    pub max_tokens: u32,
+    pub messages: Vec<AnthropicMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<AnthropicTool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<AnthropicToolChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Metadata>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AnthropicMessage {
+    pub role: String,
+    pub content: Vec<ContentBlock>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentBlock {
+    Text {
+        text: String,
+    },
+    Image {
+        source: ImageSource,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        content: Vec<ContentBlock>,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ImageSource {
+    Base64 { media_type: String, data: String },
+    Url { url: String },
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AnthropicTool {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub input_schema: Value,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnthropicToolChoice {
+    Auto,
+    Any,
+    None,
+    Tool { name: String },
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Metadata {
+    pub user_id: String,
+}
+
+impl From<&ImageUrl> for ImageSource {
+    fn from(image_url: &ImageUrl) -> Self {
+        // data:image/png;base64,<data>
+        let inline = image_url
+            .url
+            .strip_prefix("data:")
+            .and_then(|url| url.split_once(','))
+            .and_then(|(header, data)| {
+                let media_type = header.strip_suffix(";base64")?;
+                Some((media_type.to_string(), data.to_string()))
+            });
+
+        match inline {
+            Some((media_type, data)) => ImageSource::Base64 { media_type, data },
+            None => ImageSource::Url {
+                url: image_url.url.clone(),
+            },
+        }
+    }
+}
+
+impl From<Tool> for AnthropicTool {
+    fn from(tool: Tool) -> Self {
+        let input_schema = json::to_value(&tool.function.parameters)
+            .map(without_nulls)
+            .unwrap_or_else(|_| json!({ "type": "object" }));
+
+        AnthropicTool {
+            name: tool.function.name,
+            description: tool.function.description,
+            input_schema,
+        }
+    }
+}
+
+impl From<ToolChoice> for AnthropicToolChoice {
+    fn from(tool_choice: ToolChoice) -> Self {
+        match tool_choice {
+            ToolChoice::None => AnthropicToolChoice::None,
+            ToolChoice::Auto => AnthropicToolChoice::Auto,
+            ToolChoice::Required => AnthropicToolChoice::Any,
+            ToolChoice::Specific { function, .. } => AnthropicToolChoice::Tool {
+                name: function.name,
+            },
+        }
+    }
+}
+
+impl From<History> for MessagesRequest {
+    fn from(history: History) -> Self {
+        let mut system = Vec::new();
+        let mut messages: Vec<AnthropicMessage> = Vec::new();
+
+        for message in &history.messages {
+            let (role, content) = match message.role {
+                Role::System => {
+                    system.extend(text_of(&message.content));
+                    continue;
+                }
+                Role::User => ("user", content_blocks(&message.content)),
+                Role::Assistant => {
+                    let mut content = content_blocks(&message.content);
+                    content.extend(message.tool_calls.iter().flatten().map(tool_use_block));
+                    ("assistant", content)
+                }
+                // Anthropic expects tool results as part of the next user turn
+                Role::Tool | Role::Function => (
+                    "user",
+                    vec![ContentBlock::ToolResult {
+                        tool_use_id: message.tool_call_id.clone().unwrap_or_default(),
+                        content: content_blocks(&message.content),
+                    }],
+                ),
+            };
+
+            if content.is_empty() {
+                continue;
+            }
+
+            // Consecutive turns of the same role have to be merged, roles must alternate
+            match messages.last_mut() {
+                Some(last) if last.role == role => last.content.extend(content),
+                _ => messages.push(AnthropicMessage {
+                    role: role.to_string(),
+                    content,
+                }),
+            }
+        }
+
+        MessagesRequest {
+            model: history.model,
+            max_tokens: history.max_completion_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            messages,
+            system: (!system.is_empty()).then(|| system.join("\n\n")),
+            stream: history.stream,
+            temperature: history.temperature,
+            top_p: history.top_p,
+            stop_sequences: history.stop,
+            tools: history
+                .tools
+                .map(|tools| tools.into_iter().map(AnthropicTool::from).collect()),
+            tool_choice: history.tool_choice.map(AnthropicToolChoice::from),
+            metadata: history.user.map(|user_id| Metadata { user_id }),
+        }
+    }
+}
+
+fn text_of(content: &MessageContent) -> Vec<String> {
+    match content {
+        MessageContent::String(text) => vec![text.clone()],
+        MessageContent::Contents(contents) => contents
+            .iter()
+            .filter_map(|content| match content {
+                Content::Text { text, .. } => Some(text.clone()),
+                Content::Image { .. } => None,
+            })
+            .collect(),
+    }
+}
+
+fn content_blocks(content: &MessageContent) -> Vec<ContentBlock> {
+    let blocks = match content {
+        MessageContent::String(text) => vec![ContentBlock::Text { text: text.clone() }],
+        MessageContent::Contents(contents) => contents
+            .iter()
+            .map(|content| match content {
+                Content::Text { text, .. } => ContentBlock::Text { text: text.clone() },
+                Content::Image { image_url, .. } => ContentBlock::Image {
+                    source: ImageSource::from(image_url),
+                },
+            })
+            .collect(),
+    };
+
+    // Empty text blocks are rejected by the API
+    blocks
+        .into_iter()
+        .filter(|block| !matches!(block, ContentBlock::Text { text } if text.is_empty()))
+        .collect()
+}
+
+fn tool_use_block(tool_call: &ToolCall) -> ContentBlock {
+    let input = tool_call
+        .function
+        .arguments
+        .as_deref()
+        .and_then(|arguments| json::from_str(arguments).ok())
+        .unwrap_or_else(|| json!({}));
+
+    ContentBlock::ToolUse {
+        id: tool_call.id.clone(),
+        name: tool_call.function.name.clone().unwrap_or_default(),
+        input,
+    }
+}
+
+fn without_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, without_nulls(value)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(without_nulls).collect()),
+        value => value,
+    }
+}
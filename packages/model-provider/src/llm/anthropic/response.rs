@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+
+use flow_like_types::{Result, bail};
+use serde::Deserialize;
+
+use super::history::ContentBlock;
+use crate::{
+    response::{Choice, FunctionCall, Response, ResponseFunction, ResponseMessage, Usage},
+    response_chunk::{Delta, ResponseChunk, ResponseChunkChoice},
+};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MessagesResponse {
+    pub id: String,
+    pub model: String,
+    pub role: String,
+    pub content: Vec<ContentBlock>,
+    pub stop_reason: Option<String>,
+    #[serde(default)]
+    pub usage: AnthropicUsage,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct AnthropicUsage {
+    #[serde(default)]
+    pub input_tokens: u32,
+    #[serde(default)]
+    pub output_tokens: u32,
+    #[serde(default)]
+    pub cache_creation_input_tokens: Option<u32>,
+    #[serde(default)]
+    pub cache_read_input_tokens: Option<u32>,
+}
+
+impl AnthropicUsage {
+    /// Anthropic reports cached input separately, we count it towards the prompt.
+    pub fn prompt_tokens(&self) -> u32 {
+        self.input_tokens
+            + self.cache_creation_input_tokens.unwrap_or(0)
+            + self.cache_read_input_tokens.unwrap_or(0)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent {
+    MessageStart {
+        message: MessageStart,
+    },
+    ContentBlockStart {
+        index: i32,
+        content_block: ContentBlock,
+    },
+    ContentBlockDelta {
+        index: i32,
+        delta: BlockDelta,
+    },
+    ContentBlockStop {
+        index: i32,
+    },
+    MessageDelta {
+        delta: MessageDelta,
+        #[serde(default)]
+        usage: Option<AnthropicUsage>,
+    },
+    MessageStop,
+    Ping,
+    Error {
+        error: StreamError,
+    },
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MessageStart {
+    pub id: String,
+    pub model: String,
+    #[serde(default)]
+    pub usage: AnthropicUsage,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BlockDelta {
+    TextDelta {
+        text: String,
+    },
+    InputJsonDelta {
+        partial_json: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MessageDelta {
+    pub stop_reason: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct StreamError {
+    #[serde(rename = "type")]
+    pub error_type: String,
+    pub message: String,
+}
+
+pub fn finish_reason(stop_reason: &str) -> String {
+    match stop_reason {
+        "end_turn" | "stop_sequence" | "pause_turn" => "stop",
+        "max_tokens" => "length",
+        "tool_use" => "tool_calls",
+        "refusal" => "content_filter",
+        other => other,
+    }
+    .to_string()
+}
+
+impl From<MessagesResponse> for Response {
+    fn from(response: MessagesResponse) -> Self {
+        let mut text = String::new();
+        let mut tool_calls = Vec::new();
+
+        for (index, block) in response.content.into_iter().enumerate() {
+            match block {
+                ContentBlock::Text { text: content } => text.push_str(&content),
+                ContentBlock::ToolUse { id, name, input } => tool_calls.push(FunctionCall {
+                    index: Some(index as i32),
+                    id,
+                    tool_type: Some("function".to_string()),
+                    function: ResponseFunction {
+                        name: Some(name),
+                        arguments: Some(input.to_string()),
+                    },
+                }),
+                _ => continue,
+            }
+        }
+
+        let prompt_tokens = response.usage.prompt_tokens();
+        let completion_tokens = response.usage.output_tokens;
+
+        Response {
+            id: Some(response.id),
+            choices: vec![Choice {
+                index: 0,
+                finish_reason: response
+                    .stop_reason
+                    .as_deref()
+                    .map(finish_reason)
+                    .unwrap_or_default(),
+                message: ResponseMessage {
+                    role: response.role,
+                    content: (!text.is_empty()).then_some(text),
+                    tool_calls,
+                    ..Default::default()
+                },
+                logprobs: None,
+            }],
+            created: None,
+            model: Some(response.model),
+            service_tier: None,
+            system_fingerprint: None,
+            object: Some("chat.completion".to_string()),
+            usage: Usage {
+                prompt_tokens,
+                completion_tokens,
+                total_tokens: prompt_tokens + completion_tokens,
+                prompt_tokens_details: None,
+                completion_tokens_details: None,
+            },
+        }
+    }
+}
+
+/// Translates Anthropic stream events into chunks. Tool call ids are only sent when a block
+/// starts, so they are remembered per block to keep every chunk self contained.
+#[derive(Default)]
+pub struct StreamState {
+    id: String,
+    tool_calls: HashMap<i32, String>,
+}
+
+impl StreamState {
+    pub fn chunk(&mut self, event: StreamEvent) -> Result<Option<ResponseChunk>> {
+        let chunk = match event {
+            StreamEvent::MessageStart { message } => {
+                self.id = message.id;
+                let prompt_tokens = message.usage.prompt_tokens();
+                ResponseChunk {
+                    model: Some(message.model),
+                    usage: Some(Usage {
+                        prompt_tokens,
+                        total_tokens: prompt_tokens,
+                        ..Default::default()
+                    }),
+                    ..self.delta(Delta {
+                        role: Some("assistant".to_string()),
+                        content: None,
+                        tool_calls: None,
+                        refusal: None,
+                    })
+                }
+            }
+            StreamEvent::ContentBlockStart {
+                index,
+                content_block: ContentBlock::ToolUse { id, name, .. },
+            } => {
+                self.tool_calls.insert(index, id.clone());
+                self.tool_call(FunctionCall {
+                    index: Some(index),
+                    id,
+                    tool_type: Some("function".to_string()),
+                    function: ResponseFunction {
+                        name: Some(name),
+                        arguments: None,
+                    },
+                })
+            }
+            StreamEvent::ContentBlockStart {
+                content_block: ContentBlock::Text { text },
+                ..
+            }
+            | StreamEvent::ContentBlockDelta {
+                delta: BlockDelta::TextDelta { text },
+                ..
+            } if !text.is_empty() => self.text(text),
+            StreamEvent::ContentBlockDelta {
+                index,
+                delta: BlockDelta::InputJsonDelta { partial_json },
+            } => {
+                let id = self.tool_calls.get(&index).cloned().unwrap_or_default();
+                self.tool_call(FunctionCall {
+                    index: Some(index),
+                    id,
+                    tool_type: None,
+                    function: ResponseFunction {
+                        name: None,
+                        arguments: Some(partial_json),
+                    },
+                })
+            }
+            StreamEvent::MessageDelta { delta, usage } => {
+                let completion_tokens = usage.map(|usage| usage.output_tokens).unwrap_or(0);
+                ResponseChunk {
+                    id: self.id.clone(),
+                    choices: vec![ResponseChunkChoice {
+                        index: 0,
+                        delta: None,
+                        finish_reason: delta.stop_reason.as_deref().map(finish_reason),
+                        logprobs: None,
+                    }],
+                    usage: Some(Usage {
+                        completion_tokens,
+                        total_tokens: completion_tokens,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }
+            }
+            StreamEvent::Error { error } => {
+                bail!(
+                    "Anthropic stream error ({}): {}",
+                    error.error_type,
+                    error.message
+                )
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(chunk))
+    }
+
+    fn delta(&self, delta: Delta) -> ResponseChunk {
+        ResponseChunk {
+            id: self.id.clone(),
+            choices: vec![ResponseChunkChoice {
+                index: 0,
+                delta: Some(delta),
+                finish_reason: None,
+                logprobs: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn text(&self, text: String) -> ResponseChunk {
+        self.delta(Delta {
+            role: None,
+            content: Some(text),
+            tool_calls: None,
+            refusal: None,
+        })
+    }
+
+    fn tool_call(&self, tool_call: FunctionCall) -> ResponseChunk {
+        self.delta(Delta {
+            role: None,
+            content: None,
+            tool_calls: Some(vec![tool_call]),
+            refusal: None,
+        })
+    }
+}
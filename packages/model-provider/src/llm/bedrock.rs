@@ -2,26 +2,29 @@ use std::sync::Arc;
 
 use super::{LLMCallback, ModelLogic};
 use crate::{
-    history::{History, Role},
-    provider::{ModelProvider, ModelProviderConfiguration, random_provider},
+    history::History,
+    provider::{ModelProvider, ModelProviderConfiguration},
     response::Response,
+    routing::{ProviderError, ProviderRouter, RoutedEndpoint},
 };
 use aws_sdk_bedrockruntime::{
     Client,
+    config::http::HttpResponse,
+    error::{DisplayErrorContext, SdkError},
     operation::{
         converse::builders::ConverseFluentBuilder,
         converse_stream::builders::ConverseStreamFluentBuilder,
     },
-    types::{InferenceConfiguration, SystemContentBlock},
+    types::InferenceConfiguration,
 };
 use flow_like_types::{Result, async_trait};
-use response::{convert_bedrock_response, convert_bedrock_stream_output};
+use response::{BedrockStreamState, convert_bedrock_response};
 
 pub mod history;
 pub mod response;
 
 pub struct BedrockModel {
-    client: Arc<Client>,
+    router: ProviderRouter<Client>,
     provider: ModelProvider,
 }
 
@@ -30,11 +33,21 @@ impl BedrockModel {
         provider: &ModelProvider,
         config: &ModelProviderConfiguration,
     ) -> flow_like_types::Result<Self> {
-        let random_provider = random_provider(&config.bedrock_config)?;
-        let client = Client::new(&random_provider.config);
+        let endpoints = config.bedrock_config.iter().map(|bedrock_config| {
+            let region = bedrock_config
+                .config
+                .region()
+                .map(|region| region.to_string())
+                .unwrap_or_default();
+            RoutedEndpoint {
+                key: format!("bedrock:{}", region),
+                weight: bedrock_config.weight,
+                client: Client::new(&bedrock_config.config),
+            }
+        });
 
         Ok(BedrockModel {
-            client: Arc::new(client),
+            router: ProviderRouter::new(config, endpoints)?,
             provider: provider.clone(),
         })
     }
@@ -49,93 +62,80 @@ impl ModelLogic for BedrockModel {
             .clone()
             .ok_or_else(|| flow_like_types::anyhow!("Model ID is missing"))?;
 
-        let stream = history.stream.unwrap_or(false);
-
-        if !stream {
-            let request = build(&self.client, history, &model_id).await?;
-            let response = request.send().await?;
-            return convert_bedrock_response(response).await;
-        }
-
-        let request = build_stream(&self.client, history, &model_id).await?;
-        let response = request.send().await?;
-
-        let mut stream = response.stream;
-        let mut response = Response::new();
-
-        loop {
-            let token = stream.recv().await?;
-            match token {
-                Some(text) => {
-                    if let Ok(chunk) = convert_bedrock_stream_output(text) {
-                        if let Some(callback) = &callback {
-                            callback(chunk.clone()).await?;
-                        }
-                        response.push_chunk(chunk);
-                    }
-                }
-                None => break,
-            };
-        }
-
-        Ok(response)
+        self.router
+            .route(|client| invoke_client(client, history, &model_id, callback.clone()))
+            .await
     }
 }
 
-async fn build_stream(
-    client: &Arc<Client>,
+async fn invoke_client(
+    client: Arc<Client>,
     history: &History,
     model_id: &str,
-) -> Result<ConverseStreamFluentBuilder> {
-    let mut request = client.converse_stream();
-
-    let system_prompt = history.messages.iter().find(|m| m.role == Role::System);
-    let messages = history.to_messages().await;
-    request = request.model_id(model_id);
-    request = request.set_messages(Some(messages));
-
-    if let Some(system_prompt) = system_prompt {
-        match system_prompt.content {
-            crate::history::MessageContent::String(ref text) => {
-                request = request.set_system(Some(vec![SystemContentBlock::Text(text.clone())]));
-            }
-            crate::history::MessageContent::Contents(ref contents) => {
-                for content in contents {
-                    match content {
-                        crate::history::Content::Text { text, .. } => {
-                            request = request
-                                .set_system(Some(vec![SystemContentBlock::Text(text.clone())]));
-                        }
-                        _ => continue,
-                    }
-                }
-            }
-        }
+    callback: Option<LLMCallback>,
+) -> Result<Response> {
+    let stream = history.stream.unwrap_or(false);
+
+    if !stream {
+        let request = build(&client, history, model_id).await?;
+        let response = request.send().await.map_err(provider_error)?;
+        return convert_bedrock_response(response).await;
     }
 
-    let mut inference_configuration = InferenceConfiguration::builder();
+    let request = build_stream(&client, history, model_id).await?;
+    let response = request.send().await.map_err(provider_error)?;
 
-    if let Some(max_token) = history.max_completion_tokens {
-        inference_configuration = inference_configuration.set_max_tokens(Some(max_token as i32));
-    }
-
-    if let Some(temperature) = history.temperature {
-        inference_configuration = inference_configuration.set_temperature(Some(temperature));
-    }
+    let mut stream = response.stream;
+    let mut state = BedrockStreamState::default();
+    let mut response = Response::new();
 
-    if let Some(top_p) = history.top_p {
-        inference_configuration = inference_configuration.set_top_p(Some(top_p));
+    while let Some(output) = stream.recv().await? {
+        let Some(chunk) = state.chunk(output) else {
+            continue;
+        };
+        if let Some(callback) = &callback {
+            callback(chunk.clone()).await?;
+        }
+        response.push_chunk(chunk);
     }
 
-    if let Some(stop) = &history.stop {
-        inference_configuration = inference_configuration.set_stop_sequences(Some(stop.clone()));
-    }
+    Ok(response)
+}
 
-    let inference_config = inference_configuration.build();
+/// Keeps the HTTP status of a failed call around, so the router knows whether to retry it.
+fn provider_error<E>(error: SdkError<E, HttpResponse>) -> flow_like_types::Error
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let status = match &error {
+        SdkError::TimeoutError(_) | SdkError::DispatchFailure(_) => None,
+        _ => match error.raw_response() {
+            Some(response) => Some(response.status().as_u16()),
+            None => return error.into(),
+        },
+    };
+    ProviderError::new(status, DisplayErrorContext(&error).to_string()).into()
+}
 
-    request = request.set_inference_config(Some(inference_config));
+/// Both Converse operations share their input, the builders just don't share a type.
+macro_rules! configure {
+    ($request:expr, $history:expr, $model_id:expr) => {{
+        let history: &History = $history;
+        $request
+            .model_id($model_id)
+            .set_messages(Some(history.to_messages().await))
+            .set_system(history.to_system_prompt())
+            .set_tool_config(history.to_tool_configuration())
+            .set_inference_config(Some(inference_configuration(history)))
+    }};
+}
 
-    Ok(request)
+async fn build_stream(
+    client: &Arc<Client>,
+    history: &History,
+    model_id: &str,
+) -> Result<ConverseStreamFluentBuilder> {
+    Ok(configure!(client.converse_stream(), history, model_id))
 }
 
 async fn build(
@@ -143,66 +143,122 @@ async fn build(
     history: &History,
     model_id: &str,
 ) -> Result<ConverseFluentBuilder> {
-    let mut request = client.converse();
-
-    let system_prompt = history.messages.iter().find(|m| m.role == Role::System);
-    let messages = history.to_messages().await;
-    request = request.model_id(model_id);
-    request = request.set_messages(Some(messages));
-
-    if let Some(system_prompt) = system_prompt {
-        match system_prompt.content {
-            crate::history::MessageContent::String(ref text) => {
-                request = request.set_system(Some(vec![SystemContentBlock::Text(text.clone())]));
-            }
-            crate::history::MessageContent::Contents(ref contents) => {
-                for content in contents {
-                    match content {
-                        crate::history::Content::Text { text, .. } => {
-                            request = request
-                                .set_system(Some(vec![SystemContentBlock::Text(text.clone())]));
-                        }
-                        _ => continue,
-                    }
-                }
-            }
-        }
-    }
-
-    let mut inference_configuration = InferenceConfiguration::builder();
-
-    if let Some(max_token) = history.max_completion_tokens {
-        inference_configuration = inference_configuration.set_max_tokens(Some(max_token as i32));
-    }
-
-    if let Some(temperature) = history.temperature {
-        inference_configuration = inference_configuration.set_temperature(Some(temperature));
-    }
-
-    if let Some(top_p) = history.top_p {
-        inference_configuration = inference_configuration.set_top_p(Some(top_p));
-    }
-
-    if let Some(stop) = &history.stop {
-        inference_configuration = inference_configuration.set_stop_sequences(Some(stop.clone()));
-    }
-
-    let inference_config = inference_configuration.build();
-
-    request = request.set_inference_config(Some(inference_config));
+    Ok(configure!(client.converse(), history, model_id))
+}
 
-    Ok(request)
+fn inference_configuration(history: &History) -> InferenceConfiguration {
+    InferenceConfiguration::builder()
+        .set_max_tokens(history.max_completion_tokens.map(|max| max as i32))
+        .set_temperature(history.temperature)
+        .set_top_p(history.top_p)
+        .set_stop_sequences(history.stop.clone())
+        .build()
 }
 
 #[cfg(test)]
 mod tests {
-
-    use flow_like_types::tokio;
-
-    use dotenv::dotenv;
+    use aws_config::{BehaviorVersion, Region, SdkConfig};
+    use aws_sdk_bedrockruntime::config::{Credentials, SharedCredentialsProvider};
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+    use aws_smithy_types::body::SdkBody;
+    use flow_like_types::{Value, json, tokio};
+
+    use super::*;
+    use crate::{
+        history::{
+            HistoryFunction, HistoryFunctionParameters, HistoryJSONSchemaType, HistoryMessage,
+            Role, Tool, ToolType,
+        },
+        provider::BedrockConfig,
+    };
+
+    fn replay_config(body: &str) -> (ModelProviderConfiguration, StaticReplayClient) {
+        let event = ReplayEvent::new(
+            http::Request::builder()
+                .uri("https://bedrock-runtime.us-east-1.amazonaws.com/")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .header("content-type", "application/json")
+                .body(SdkBody::from(body))
+                .unwrap(),
+        );
+        let http_client = StaticReplayClient::new(vec![event]);
+        let config = SdkConfig::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-east-1"))
+            .credentials_provider(SharedCredentialsProvider::new(Credentials::new(
+                "access-key",
+                "secret-key",
+                None,
+                None,
+                "test",
+            )))
+            .http_client(http_client.clone())
+            .build();
+
+        let config = ModelProviderConfiguration {
+            bedrock_config: vec![BedrockConfig {
+                config,
+                weight: None,
+            }],
+            ..Default::default()
+        };
+        (config, http_client)
+    }
 
     #[tokio::test]
     async fn test_bedrock_no_stream() {
-        dotenv().ok();
+        let body = r#"{"output":{"message":{"role":"assistant","content":[{"text":"Let me check."},{"toolUse":{"toolUseId":"tooluse_1","name":"get_weather","input":{"city":"Berlin"}}}]}},"stopReason":"tool_use","usage":{"inputTokens":24,"outputTokens":12,"totalTokens":36},"metrics":{"latencyMs":250}}"#;
+        let (config, http_client) = replay_config(body);
+        let provider = ModelProvider {
+            model_id: Some("anthropic.claude-3-5-haiku-20241022-v1:0".to_string()),
+            version: None,
+            provider_name: "bedrock".to_string(),
+            remote: None,
+        };
+
+        let model = BedrockModel::new(&provider, &config).await.unwrap();
+        let mut history = History::new(
+            "anthropic.claude-3-5-haiku-20241022-v1:0".to_string(),
+            vec![
+                HistoryMessage::from_string(Role::System, "You are a helpful assistant."),
+                HistoryMessage::from_string(Role::User, "What is the weather in Berlin?"),
+            ],
+        );
+        history.tools = Some(vec![Tool {
+            tool_type: ToolType::Function,
+            function: HistoryFunction {
+                name: "get_weather".to_string(),
+                description: Some("Current weather for a city".to_string()),
+                parameters: HistoryFunctionParameters {
+                    schema_type: HistoryJSONSchemaType::Object,
+                    properties: None,
+                    required: None,
+                },
+            },
+        }]);
+        history.set_stream(false);
+
+        let response = model.invoke(&history, None).await.unwrap();
+
+        let request = http_client.actual_requests().next().unwrap();
+        let request: Value = json::from_slice(request.body().bytes().unwrap()).unwrap();
+        assert_eq!(request["system"][0]["text"], "You are a helpful assistant.");
+        assert_eq!(
+            request["toolConfig"]["tools"][0]["toolSpec"]["name"],
+            "get_weather"
+        );
+
+        let message = response.last_message().unwrap();
+        assert_eq!(message.content.as_deref(), Some("Let me check."));
+        assert_eq!(message.tool_calls[0].id, "tooluse_1");
+        assert_eq!(
+            message.tool_calls[0].function.arguments.as_deref(),
+            Some(r#"{"city":"Berlin"}"#)
+        );
+        assert_eq!(response.choices[0].finish_reason, "tool_calls");
+        assert_eq!(response.usage.total_tokens, 36);
     }
 }
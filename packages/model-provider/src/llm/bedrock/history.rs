@@ -1,70 +1,78 @@
 use aws_sdk_bedrockruntime::{
     primitives::Blob,
-    types::{ContentBlock, ImageBlock, ImageFormat, ImageSource, Message},
+    types::{
+        AnyToolChoice, AutoToolChoice, ContentBlock, ConversationRole, ImageBlock, ImageFormat,
+        ImageSource, Message, SpecificToolChoice, SystemContentBlock, Tool, ToolConfiguration,
+        ToolInputSchema, ToolResultBlock, ToolResultContentBlock, ToolSpecification, ToolUseBlock,
+    },
+};
+use aws_smithy_types::{Document, Number};
+use flow_like_types::{
+    Value,
+    json::{self, json},
+    utils::data_url::{data_url_to_bytes, make_data_url},
 };
-use flow_like_types::utils::data_url::{data_url_to_bytes, make_data_url};
 
-use crate::history::History;
+use crate::history::{Content, History, ImageUrl, MessageContent, Role, ToolChoice};
 
 impl History {
     pub async fn to_messages(&self) -> Vec<Message> {
-        let mut messages = Vec::new();
+        let mut messages: Vec<Message> = Vec::new();
         for message in &self.messages {
-            let role = match message.role {
-                crate::history::Role::User => aws_sdk_bedrockruntime::types::ConversationRole::User,
-                crate::history::Role::Assistant => {
-                    aws_sdk_bedrockruntime::types::ConversationRole::Assistant
-                }
-                _ => continue,
-            };
-
-            let mut content: Vec<ContentBlock> = Vec::new();
-            match message.content {
-                crate::history::MessageContent::String(ref text) => {
-                    content.push(ContentBlock::Text(text.clone()));
+            let (role, content) = match message.role {
+                Role::System => continue,
+                Role::User => (
+                    ConversationRole::User,
+                    content_blocks(&message.content).await,
+                ),
+                Role::Assistant => {
+                    let mut content = content_blocks(&message.content).await;
+                    for tool_call in message.tool_calls.iter().flatten() {
+                        let input = tool_call
+                            .function
+                            .arguments
+                            .as_deref()
+                            .and_then(|arguments| json::from_str(arguments).ok())
+                            .unwrap_or_else(|| json!({}));
+                        let tool_use = ToolUseBlock::builder()
+                            .tool_use_id(tool_call.id.clone())
+                            .name(tool_call.function.name.clone().unwrap_or_default())
+                            .input(to_document(input))
+                            .build();
+                        match tool_use {
+                            Ok(tool_use) => content.push(ContentBlock::ToolUse(tool_use)),
+                            Err(err) => println!("Error creating tool use block: {}", err),
+                        }
+                    }
+                    (ConversationRole::Assistant, content)
                 }
-                crate::history::MessageContent::Contents(ref msg_content) => {
-                    for history_content in msg_content.iter() {
-                        match history_content {
-                            crate::history::Content::Text { text, .. } => {
-                                content.push(ContentBlock::Text(text.clone()));
-                            }
-                            crate::history::Content::Image { image_url, .. } => {
-                                let url = match make_data_url(&image_url.url).await {
-                                    Ok(url) => url,
-                                    Err(err) => {
-                                        println!("Error creating data URL: {}", err);
-                                        continue;
-                                    }
-                                };
-
-                                let bytes = match data_url_to_bytes(&url).await {
-                                    Ok(bytes) => bytes,
-                                    Err(err) => {
-                                        println!("Error converting data URL to bytes: {}", err);
-                                        continue;
-                                    }
-                                };
-
-                                let blob = Blob::new(bytes);
-
-                                let img_block = match ImageBlock::builder()
-                                    .set_format(Some(ImageFormat::Jpeg))
-                                    .set_source(Some(ImageSource::Bytes(blob)))
-                                    .build()
-                                {
-                                    Ok(block) => block,
-                                    Err(err) => {
-                                        println!("Error creating image block: {}", err);
-                                        continue;
-                                    }
-                                };
-
-                                content.push(ContentBlock::Image(img_block));
-                            }
+                // Bedrock expects tool results as part of the next user turn
+                Role::Tool | Role::Function => {
+                    let tool_result = ToolResultBlock::builder()
+                        .tool_use_id(message.tool_call_id.clone().unwrap_or_default())
+                        .content(ToolResultContentBlock::Text(text_of(&message.content)))
+                        .build();
+                    match tool_result {
+                        Ok(tool_result) => (
+                            ConversationRole::User,
+                            vec![ContentBlock::ToolResult(tool_result)],
+                        ),
+                        Err(err) => {
+                            println!("Error creating tool result block: {}", err);
+                            continue;
                         }
                     }
                 }
+            };
+
+            if content.is_empty() {
+                continue;
+            }
+
+            // Consecutive turns of the same role have to be merged, roles must alternate
+            if let Some(last) = messages.last_mut().filter(|last| last.role == role) {
+                last.content.extend(content);
+                continue;
             }
 
             let message = match Message::builder()
@@ -82,4 +90,185 @@ impl History {
         }
         messages
     }
+
+    pub fn to_system_prompt(&self) -> Option<Vec<SystemContentBlock>> {
+        let system = self
+            .messages
+            .iter()
+            .filter(|message| message.role == Role::System)
+            .map(|message| SystemContentBlock::Text(text_of(&message.content)))
+            .collect::<Vec<SystemContentBlock>>();
+
+        (!system.is_empty()).then_some(system)
+    }
+
+    pub fn to_tool_configuration(&self) -> Option<ToolConfiguration> {
+        let tools = self
+            .tools
+            .as_ref()?
+            .iter()
+            .filter_map(|tool| {
+                let schema = json::to_value(&tool.function.parameters).ok()?;
+                ToolSpecification::builder()
+                    .name(tool.function.name.clone())
+                    .set_description(tool.function.description.clone())
+                    .input_schema(ToolInputSchema::Json(to_document(without_nulls(schema))))
+                    .build()
+                    .ok()
+                    .map(Tool::ToolSpec)
+            })
+            .collect::<Vec<Tool>>();
+
+        // Bedrock has no way to disable tools, `None` falls back to the model's choice
+        let tool_choice = match &self.tool_choice {
+            Some(ToolChoice::Auto) => Some(aws_sdk_bedrockruntime::types::ToolChoice::Auto(
+                AutoToolChoice::builder().build(),
+            )),
+            Some(ToolChoice::Required) => Some(aws_sdk_bedrockruntime::types::ToolChoice::Any(
+                AnyToolChoice::builder().build(),
+            )),
+            Some(ToolChoice::Specific { function, .. }) => SpecificToolChoice::builder()
+                .name(function.name.clone())
+                .build()
+                .ok()
+                .map(aws_sdk_bedrockruntime::types::ToolChoice::Tool),
+            Some(ToolChoice::None) | None => None,
+        };
+
+        ToolConfiguration::builder()
+            .set_tools(Some(tools))
+            .set_tool_choice(tool_choice)
+            .build()
+            .ok()
+    }
+}
+
+async fn content_blocks(content: &MessageContent) -> Vec<ContentBlock> {
+    let mut blocks: Vec<ContentBlock> = Vec::new();
+    match content {
+        MessageContent::String(text) => {
+            blocks.push(ContentBlock::Text(text.clone()));
+        }
+        MessageContent::Contents(contents) => {
+            for content in contents {
+                match content {
+                    Content::Text { text, .. } => {
+                        blocks.push(ContentBlock::Text(text.clone()));
+                    }
+                    Content::Image { image_url, .. } => {
+                        if let Some(image) = image_block(image_url).await {
+                            blocks.push(ContentBlock::Image(image));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Empty text blocks are rejected by the API
+    blocks.retain(|block| !matches!(block, ContentBlock::Text(text) if text.is_empty()));
+    blocks
+}
+
+async fn image_block(image_url: &ImageUrl) -> Option<ImageBlock> {
+    let url = match make_data_url(&image_url.url).await {
+        Ok(url) => url,
+        Err(err) => {
+            println!("Error creating data URL: {}", err);
+            return None;
+        }
+    };
+
+    // data:image/png;base64,<data>
+    let format = url
+        .strip_prefix("data:image/")
+        .and_then(|url| url.split([';', ',']).next())
+        .map(|format| match format {
+            "jpg" => ImageFormat::Jpeg,
+            format => ImageFormat::from(format),
+        })
+        .unwrap_or(ImageFormat::Jpeg);
+
+    let bytes = match data_url_to_bytes(&url).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            println!("Error converting data URL to bytes: {}", err);
+            return None;
+        }
+    };
+
+    match ImageBlock::builder()
+        .set_format(Some(format))
+        .set_source(Some(ImageSource::Bytes(Blob::new(bytes))))
+        .build()
+    {
+        Ok(block) => Some(block),
+        Err(err) => {
+            println!("Error creating image block: {}", err);
+            None
+        }
+    }
+}
+
+fn text_of(content: &MessageContent) -> String {
+    match content {
+        MessageContent::String(text) => text.clone(),
+        MessageContent::Contents(contents) => contents
+            .iter()
+            .filter_map(|content| match content {
+                Content::Text { text, .. } => Some(text.as_str()),
+                Content::Image { .. } => None,
+            })
+            .collect::<Vec<&str>>()
+            .join("\n"),
+    }
+}
+
+fn without_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, without_nulls(value)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(without_nulls).collect()),
+        value => value,
+    }
+}
+
+pub fn to_document(value: Value) -> Document {
+    match value {
+        Value::Null => Document::Null,
+        Value::Bool(value) => Document::Bool(value),
+        Value::Number(number) => Document::Number(match (number.as_u64(), number.as_i64()) {
+            (Some(value), _) => Number::PosInt(value),
+            (None, Some(value)) => Number::NegInt(value),
+            (None, None) => Number::Float(number.as_f64().unwrap_or_default()),
+        }),
+        Value::String(value) => Document::String(value),
+        Value::Array(items) => Document::Array(items.into_iter().map(to_document).collect()),
+        Value::Object(map) => Document::Object(
+            map.into_iter()
+                .map(|(key, value)| (key, to_document(value)))
+                .collect(),
+        ),
+    }
+}
+
+pub fn from_document(document: &Document) -> Value {
+    match document {
+        Document::Null => Value::Null,
+        Document::Bool(value) => Value::Bool(*value),
+        Document::Number(Number::PosInt(value)) => json!(value),
+        Document::Number(Number::NegInt(value)) => json!(value),
+        Document::Number(Number::Float(value)) => json!(value),
+        Document::String(value) => Value::String(value.clone()),
+        Document::Array(items) => Value::Array(items.iter().map(from_document).collect()),
+        Document::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| (key.clone(), from_document(value)))
+                .collect(),
+        ),
+    }
 }
@@ -1,184 +1,188 @@
+use std::collections::HashMap;
+
+use super::history::from_document;
 use crate::{
     response::{Choice, FunctionCall, Response, ResponseFunction, ResponseMessage, Usage},
     response_chunk::{Delta, ResponseChunk, ResponseChunkChoice},
 };
 use aws_sdk_bedrockruntime::operation::converse::ConverseOutput;
-use aws_sdk_bedrockruntime::types::ConverseStreamOutput as ConverseStreamOutputType;
+use aws_sdk_bedrockruntime::types::{
+    ContentBlock, ContentBlockDelta, ContentBlockStart,
+    ConverseStreamOutput as ConverseStreamOutputType, StopReason, TokenUsage,
+};
+
+pub fn finish_reason(stop_reason: &StopReason) -> String {
+    match stop_reason {
+        StopReason::EndTurn | StopReason::StopSequence => "stop".to_string(),
+        StopReason::MaxTokens => "length".to_string(),
+        StopReason::ToolUse => "tool_calls".to_string(),
+        StopReason::ContentFiltered | StopReason::GuardrailIntervened => {
+            "content_filter".to_string()
+        }
+        other => other.as_str().to_string(),
+    }
+}
+
+fn usage(usage: &TokenUsage) -> Usage {
+    Usage {
+        completion_tokens: usage.output_tokens as u32,
+        prompt_tokens: usage.input_tokens as u32,
+        total_tokens: usage.total_tokens as u32,
+        ..Default::default()
+    }
+}
 
 pub async fn convert_bedrock_response(
     converse_output: ConverseOutput,
 ) -> flow_like_types::Result<Response> {
     let mut response = Response::default();
-    if let Some(usage) = converse_output.usage {
-        response.usage.completion_tokens = usage.output_tokens as u32;
-        response.usage.total_tokens = usage.total_tokens as u32;
-        response.usage.prompt_tokens = usage.input_tokens as u32;
+    if let Some(token_usage) = &converse_output.usage {
+        response.usage = usage(token_usage);
     }
 
-    let mut content = String::new();
+    let mut response_message = ResponseMessage::default();
 
-    if let Some(output) = converse_output.output {
-        if let Ok(message) = output.as_message() {
-            let mut response_message = ResponseMessage::default();
-            response_message.role = message.role.as_str().to_string();
-            // let mut tool_calls = vec![];
+    let message = converse_output
+        .output
+        .as_ref()
+        .and_then(|output| output.as_message().ok());
 
-            for chunk in message.content.iter() {
-                if let Ok(text) = chunk.as_text() {
-                    content.push_str(text);
-                }
+    if let Some(message) = message {
+        response_message.role = message.role.as_str().to_string();
+        let mut content = String::new();
 
-                if let Ok(tool_call) = chunk.as_tool_result() {
-                    // TODO
-                }
+        for (index, block) in message.content.iter().enumerate() {
+            match block {
+                ContentBlock::Text(text) => content.push_str(text),
+                ContentBlock::ToolUse(tool_use) => response_message.tool_calls.push(FunctionCall {
+                    index: Some(index as i32),
+                    id: tool_use.tool_use_id.clone(),
+                    tool_type: Some("function".to_string()),
+                    function: ResponseFunction {
+                        name: Some(tool_use.name.clone()),
+                        arguments: Some(from_document(&tool_use.input).to_string()),
+                    },
+                }),
+                _ => continue,
             }
-
-            response.choices.push(Choice {
-                index: 0,
-                finish_reason: converse_output.stop_reason.as_str().to_string(),
-                logprobs: None,
-                message: response_message,
-            });
-
-            return Ok(response);
         }
+
+        response_message.content = (!content.is_empty()).then_some(content);
     }
 
     response.choices.push(Choice {
         index: 0,
-        finish_reason: converse_output.stop_reason.as_str().to_string(),
+        finish_reason: finish_reason(&converse_output.stop_reason),
         logprobs: None,
-        message: ResponseMessage {
-            ..Default::default()
-        },
+        message: response_message,
     });
 
     Ok(response)
 }
 
-pub fn convert_bedrock_stream_output(
-    converse_output: ConverseStreamOutputType,
-) -> flow_like_types::Result<ResponseChunk> {
-    let mut response_chunk = ResponseChunk::default();
-    match converse_output {
-        ConverseStreamOutputType::ContentBlockDelta(val) => {
-            response_chunk.id = "0".to_string();
-            if let Some(delta) = val.delta() {
-                if let Ok(text) = delta.as_text() {
-                    response_chunk.choices.push(ResponseChunkChoice {
-                        index: val.content_block_index,
-                        delta: Some(Delta {
-                            content: Some(text.to_string()),
-                            role: None,
-                            refusal: None,
-                            tool_calls: None,
-                        }),
-                        finish_reason: None,
-                        logprobs: None,
-                    });
-                }
+/// Translates Converse stream events into chunks. Tool use ids are only sent when a block
+/// starts, so they are remembered per block to keep every chunk self contained.
+#[derive(Default)]
+pub struct BedrockStreamState {
+    tool_calls: HashMap<i32, String>,
+}
 
-                if let Ok(tool) = delta.as_tool_use() {
-                    response_chunk.choices.push(ResponseChunkChoice {
-                        index: val.content_block_index,
-                        delta: Some(Delta {
-                            content: None,
-                            role: None,
-                            refusal: None,
-                            tool_calls: Some(vec![FunctionCall {
-                                id: "".to_string(),
-                                index: None,
-                                function: ResponseFunction {
-                                    name: None,
-                                    arguments: Some(tool.input.clone()),
-                                },
-                                tool_type: None,
-                            }]),
-                        }),
-                        finish_reason: None,
-                        logprobs: None,
-                    });
-                }
+impl BedrockStreamState {
+    pub fn chunk(&mut self, converse_output: ConverseStreamOutputType) -> Option<ResponseChunk> {
+        let (delta, finish_reason, usage) = match converse_output {
+            ConverseStreamOutputType::MessageStart(message) => (
+                Delta {
+                    role: Some(message.role.as_str().to_string()),
+                    ..empty_delta()
+                },
+                None,
+                None,
+            ),
+            ConverseStreamOutputType::ContentBlockStart(start) => {
+                let Some(ContentBlockStart::ToolUse(tool)) = start.start else {
+                    return None;
+                };
+                let index = start.content_block_index;
+                self.tool_calls.insert(index, tool.tool_use_id.clone());
+                let tool_call = FunctionCall {
+                    id: tool.tool_use_id,
+                    index: Some(index),
+                    function: ResponseFunction {
+                        name: Some(tool.name),
+                        arguments: None,
+                    },
+                    tool_type: Some("function".to_string()),
+                };
+                (
+                    Delta {
+                        tool_calls: Some(vec![tool_call]),
+                        ..empty_delta()
+                    },
+                    None,
+                    None,
+                )
             }
-        }
-        ConverseStreamOutputType::ContentBlockStart(start) => {
-            response_chunk.id = "0".to_string();
-            if let Some(start) = start.start {
-                if let Ok(tool) = start.as_tool_use() {
-                    response_chunk.choices.push(ResponseChunkChoice {
-                        logprobs: None,
-                        index: 0,
-                        finish_reason: None,
-                        delta: Some(Delta {
-                            content: None,
-                            role: None,
-                            refusal: None,
-                            tool_calls: Some(vec![FunctionCall {
-                                id: tool.tool_use_id.clone(),
-                                index: None,
-                                function: ResponseFunction {
-                                    name: Some(tool.name.clone()),
-                                    arguments: None,
-                                },
-                                tool_type: None,
-                            }]),
-                        }),
-                    })
+            ConverseStreamOutputType::ContentBlockDelta(block) => {
+                let index = block.content_block_index;
+                match block.delta? {
+                    ContentBlockDelta::Text(text) => (
+                        Delta {
+                            content: Some(text),
+                            ..empty_delta()
+                        },
+                        None,
+                        None,
+                    ),
+                    ContentBlockDelta::ToolUse(tool) => {
+                        let tool_call = FunctionCall {
+                            id: self.tool_calls.get(&index).cloned().unwrap_or_default(),
+                            index: Some(index),
+                            function: ResponseFunction {
+                                name: None,
+                                arguments: Some(tool.input),
+                            },
+                            tool_type: None,
+                        };
+                        (
+                            Delta {
+                                tool_calls: Some(vec![tool_call]),
+                                ..empty_delta()
+                            },
+                            None,
+                            None,
+                        )
+                    }
+                    _ => return None,
                 }
             }
-        }
-        ConverseStreamOutputType::ContentBlockStop(stop) => {
-            response_chunk.choices.push(ResponseChunkChoice {
-                logprobs: None,
-                index: stop.content_block_index,
-                finish_reason: None,
-                delta: Some(Delta {
-                    content: None,
-                    role: None,
-                    refusal: None,
-                    tool_calls: None,
-                }),
-            });
-        }
-        ConverseStreamOutputType::MessageStart(message) => {
-            response_chunk.choices.push(ResponseChunkChoice {
-                logprobs: None,
+            ConverseStreamOutputType::MessageStop(stop) => {
+                (empty_delta(), Some(finish_reason(&stop.stop_reason)), None)
+            }
+            ConverseStreamOutputType::Metadata(metadata) => {
+                (empty_delta(), None, Some(usage(metadata.usage.as_ref()?)))
+            }
+            _ => return None,
+        };
+
+        Some(ResponseChunk {
+            id: "0".to_string(),
+            choices: vec![ResponseChunkChoice {
                 index: 0,
-                finish_reason: None,
-                delta: Some(Delta {
-                    content: None,
-                    role: Some(message.role.as_str().to_string()),
-                    refusal: None,
-                    tool_calls: None,
-                }),
-            });
-        }
-        ConverseStreamOutputType::MessageStop(stop) => {
-            response_chunk.choices.push(ResponseChunkChoice {
+                delta: Some(delta),
+                finish_reason,
                 logprobs: None,
-                index: 0,
-                finish_reason: Some(stop.stop_reason.as_str().to_string()),
-                delta: Some(Delta {
-                    content: None,
-                    role: None,
-                    refusal: None,
-                    tool_calls: None,
-                }),
-            });
-        }
-        ConverseStreamOutputType::Metadata(metadata) => {
-            if let Some(usage) = metadata.usage {
-                response_chunk.usage = Some(Usage {
-                    completion_tokens: usage.output_tokens as u32,
-                    prompt_tokens: usage.input_tokens as u32,
-                    total_tokens: usage.total_tokens as u32,
-                    ..Default::default()
-                })
-            }
-        }
-        _ => {
-            println!("Unknown response type");
-        }
+            }],
+            usage,
+            ..Default::default()
+        })
+    }
+}
+
+fn empty_delta() -> Delta {
+    Delta {
+        role: None,
+        content: None,
+        tool_calls: None,
+        refusal: None,
     }
-    Ok(response_chunk)
 }
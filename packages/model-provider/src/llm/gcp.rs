@@ -0,0 +1,230 @@
+use std::sync::Arc;
+
+use super::{LLMCallback, ModelLogic};
+use crate::{
+    history::History,
+    provider::{ModelProvider, ModelProviderConfiguration, gemini::GeminiClient},
+    response::Response,
+    routing::{ProviderRouter, RoutedEndpoint},
+};
+use flow_like_types::{
+    Result, async_trait, json,
+    reqwest_eventsource::{self, Event},
+};
+use futures::StreamExt;
+use history::GenerateContentRequest;
+use response::{GenerateContentResponse, StreamState};
+
+pub mod history;
+pub mod response;
+
+/// Gemini models, served by AI Studio (`gemini`) or Vertex AI (`vertex`).
+pub struct GeminiModel {
+    router: ProviderRouter<GeminiClient>,
+    provider: ModelProvider,
+}
+
+impl GeminiModel {
+    pub async fn new(
+        provider: &ModelProvider,
+        config: &ModelProviderConfiguration,
+    ) -> flow_like_types::Result<Self> {
+        let endpoints = GeminiClient::candidates(provider, config)
+            .into_iter()
+            .map(|gemini_config| {
+                let weight = gemini_config.weight;
+                let client = GeminiClient::from_gemini_config(provider, gemini_config)?;
+                Ok(RoutedEndpoint {
+                    key: client.endpoint().to_string(),
+                    weight,
+                    client,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(GeminiModel {
+            router: ProviderRouter::new(config, endpoints)?,
+            provider: provider.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl ModelLogic for GeminiModel {
+    async fn invoke(&self, history: &History, callback: Option<LLMCallback>) -> Result<Response> {
+        let model_id = self
+            .provider
+            .model_id
+            .clone()
+            .ok_or_else(|| flow_like_types::anyhow!("Model ID is missing"))?;
+        let request = GenerateContentRequest::from(history.clone());
+        let stream = history.stream.unwrap_or(false);
+
+        self.router
+            .route(|client| invoke_client(client, &model_id, &request, stream, callback.clone()))
+            .await
+    }
+}
+
+async fn invoke_client(
+    client: Arc<GeminiClient>,
+    model_id: &str,
+    request: &GenerateContentRequest,
+    stream: bool,
+    callback: Option<LLMCallback>,
+) -> Result<Response> {
+    let mut state = StreamState::default();
+    let mut output = Response::new();
+
+    if !stream {
+        let response = client.generate_content(model_id, request).await?;
+        let chunk = state.chunk(response)?;
+        output.id = Some(chunk.id.clone());
+        output.push_chunk(chunk);
+        return Ok(output);
+    }
+
+    let mut stream = client.stream_generate_content(model_id, request).await?;
+
+    while let Some(event) = stream.next().await {
+        let message = match event {
+            Ok(Event::Open) => continue,
+            Ok(Event::Message(message)) => message,
+            Err(reqwest_eventsource::Error::StreamEnded) => break,
+            Err(error) => {
+                stream.close();
+                return Err(GeminiClient::stream_error(error).await);
+            }
+        };
+
+        let response: GenerateContentResponse = match json::from_str(&message.data) {
+            Ok(response) => response,
+            Err(e) => {
+                eprintln!("Failed to parse Gemini chunk: {}", e);
+                continue;
+            }
+        };
+
+        let chunk = match state.chunk(response) {
+            Ok(chunk) => chunk,
+            Err(error) => {
+                stream.close();
+                return Err(error);
+            }
+        };
+
+        if let Some(callback) = &callback {
+            callback(chunk.clone()).await?;
+        }
+        output.id = Some(chunk.id.clone());
+        output.push_chunk(chunk);
+    }
+
+    stream.close();
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use flow_like_types::json::json;
+
+    use super::*;
+    use crate::history::{HistoryMessage, ResponseFormat, Role, ToolCall, ToolCallFunction};
+
+    #[test]
+    fn test_gemini_request_from_history() {
+        let mut assistant = HistoryMessage::from_string(Role::Assistant, "");
+        assistant.tool_calls = Some(vec![ToolCall {
+            id: "call_1".to_string(),
+            r#type: "function".to_string(),
+            function: ToolCallFunction {
+                name: Some("get_weather".to_string()),
+                arguments: Some(r#"{"city":"Berlin"}"#.to_string()),
+            },
+        }]);
+        let mut tool = HistoryMessage::from_string(Role::Tool, "Sunny");
+        tool.tool_call_id = Some("call_1".to_string());
+
+        let mut history = History::new(
+            "gemini-2.5-flash".to_string(),
+            vec![
+                HistoryMessage::from_string(Role::System, "You are a helpful assistant."),
+                HistoryMessage::from_string(Role::User, "What is the weather in Berlin?"),
+                assistant,
+                tool,
+            ],
+        );
+        history.response_format = Some(ResponseFormat::Object(json!({
+            "type": "json_schema",
+            "json_schema": { "name": "weather", "schema": { "type": "object", "additionalProperties": false } }
+        })));
+
+        let request = json::to_value(GenerateContentRequest::from(history)).unwrap();
+
+        assert_eq!(
+            request["systemInstruction"],
+            json!({ "parts": [{ "text": "You are a helpful assistant." }] })
+        );
+        assert_eq!(request["contents"].as_array().unwrap().len(), 3);
+        assert_eq!(request["contents"][1]["role"], "model");
+        assert_eq!(
+            request["contents"][1]["parts"][0]["functionCall"],
+            json!({ "name": "get_weather", "args": { "city": "Berlin" } })
+        );
+        assert_eq!(
+            request["contents"][2]["parts"][0]["functionResponse"],
+            json!({ "name": "get_weather", "response": { "content": "Sunny" } })
+        );
+        assert_eq!(
+            request["generationConfig"]["responseMimeType"],
+            "application/json"
+        );
+        assert_eq!(
+            request["generationConfig"]["responseSchema"],
+            json!({ "type": "object" })
+        );
+    }
+
+    #[test]
+    fn test_gemini_stream_chunks() {
+        let chunks = [
+            json!({
+                "candidates": [{ "content": { "role": "model", "parts": [{ "text": "Hello" }] }, "index": 0 }],
+                "usageMetadata": { "promptTokenCount": 10, "candidatesTokenCount": 1 },
+                "responseId": "resp_1"
+            }),
+            json!({
+                "candidates": [{
+                    "content": { "role": "model", "parts": [
+                        { "text": " world" },
+                        { "functionCall": { "name": "get_weather", "args": { "city": "Berlin" } } }
+                    ] },
+                    "finishReason": "STOP",
+                    "index": 0
+                }],
+                "usageMetadata": { "promptTokenCount": 10, "candidatesTokenCount": 6 },
+                "responseId": "resp_1"
+            }),
+        ];
+
+        let mut state = StreamState::default();
+        let mut output = Response::new();
+        for chunk in chunks {
+            let response: GenerateContentResponse = json::from_value(chunk).unwrap();
+            output.push_chunk(state.chunk(response).unwrap());
+        }
+
+        let message = output.last_message().unwrap();
+        assert_eq!(message.role, "assistant");
+        assert_eq!(message.content.as_deref(), Some("Hello world"));
+        assert_eq!(message.tool_calls.len(), 1);
+        assert_eq!(
+            message.tool_calls[0].function.arguments.as_deref(),
+            Some(r#"{"city":"Berlin"}"#)
+        );
+        assert_eq!(output.choices[0].finish_reason, "tool_calls");
+        assert_eq!(output.usage.prompt_tokens, 10);
+        assert_eq!(output.usage.completion_tokens, 6);
+    }
+}
@@ -0,0 +1,397 @@
+use std::collections::HashMap;
+
+use flow_like_types::{
+    Value,
+    json::{self, json},
+    mime_guess,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::history::{
+    Content, History, ImageUrl, MessageContent, ResponseFormat, Role, Tool, ToolChoice,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateContentRequest {
+    pub contents: Vec<GeminiContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_instruction: Option<GeminiContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<GeminiTool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_config: Option<ToolConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generation_config: Option<GenerationConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GeminiContent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub parts: Vec<Part>,
+}
+
+/// Gemini parts are a union, exactly one of the fields is set.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Part {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thought: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inline_data: Option<Blob>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_data: Option<FileData>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_call: Option<GeminiFunctionCall>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_response: Option<FunctionResponse>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Blob {
+    pub mime_type: String,
+    pub data: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileData {
+    pub mime_type: String,
+    pub file_uri: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GeminiFunctionCall {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub args: Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FunctionResponse {
+    pub name: String,
+    pub response: Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiTool {
+    pub function_declarations: Vec<FunctionDeclaration>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FunctionDeclaration {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolConfig {
+    pub function_calling_config: FunctionCallingConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionCallingConfig {
+    pub mode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_function_names: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub candidate_count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_schema: Option<Value>,
+}
+
+impl Part {
+    pub fn text(text: impl Into<String>) -> Self {
+        Part {
+            text: Some(text.into()),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&ImageUrl> for Part {
+    fn from(image_url: &ImageUrl) -> Self {
+        // data:image/png;base64,<data>
+        let inline = image_url
+            .url
+            .strip_prefix("data:")
+            .and_then(|url| url.split_once(','))
+            .and_then(|(header, data)| {
+                let mime_type = header.strip_suffix(";base64")?;
+                Some(Blob {
+                    mime_type: mime_type.to_string(),
+                    data: data.to_string(),
+                })
+            });
+
+        match inline {
+            Some(blob) => Part {
+                inline_data: Some(blob),
+                ..Default::default()
+            },
+            None => Part {
+                file_data: Some(FileData {
+                    mime_type: mime_guess::from_path(&image_url.url)
+                        .first_or_octet_stream()
+                        .to_string(),
+                    file_uri: image_url.url.clone(),
+                }),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl From<Tool> for FunctionDeclaration {
+    fn from(tool: Tool) -> Self {
+        FunctionDeclaration {
+            name: tool.function.name,
+            description: tool.function.description,
+            parameters: json::to_value(&tool.function.parameters).ok().map(schema),
+        }
+    }
+}
+
+impl From<ToolChoice> for ToolConfig {
+    fn from(tool_choice: ToolChoice) -> Self {
+        let (mode, allowed_function_names) = match tool_choice {
+            ToolChoice::None => ("NONE", None),
+            ToolChoice::Auto => ("AUTO", None),
+            ToolChoice::Required => ("ANY", None),
+            ToolChoice::Specific { function, .. } => ("ANY", Some(vec![function.name])),
+        };
+
+        ToolConfig {
+            function_calling_config: FunctionCallingConfig {
+                mode: mode.to_string(),
+                allowed_function_names,
+            },
+        }
+    }
+}
+
+impl From<History> for GenerateContentRequest {
+    fn from(history: History) -> Self {
+        let mut system = Vec::new();
+        let mut contents: Vec<GeminiContent> = Vec::new();
+        // Gemini answers function calls by name, the history references them by id
+        let mut tool_names = HashMap::new();
+
+        for message in &history.messages {
+            let (role, parts) = match message.role {
+                Role::System => {
+                    system.extend(
+                        to_parts(&message.content)
+                            .into_iter()
+                            .filter(|part| part.text.is_some()),
+                    );
+                    continue;
+                }
+                Role::User => ("user", to_parts(&message.content)),
+                Role::Assistant => {
+                    let mut parts = to_parts(&message.content);
+                    for tool_call in message.tool_calls.iter().flatten() {
+                        let name = tool_call.function.name.clone().unwrap_or_default();
+                        tool_names.insert(tool_call.id.clone(), name.clone());
+                        let args = tool_call
+                            .function
+                            .arguments
+                            .as_deref()
+                            .and_then(|arguments| json::from_str(arguments).ok())
+                            .unwrap_or_else(|| json!({}));
+                        parts.push(Part {
+                            function_call: Some(GeminiFunctionCall {
+                                id: None,
+                                name,
+                                args,
+                            }),
+                            ..Default::default()
+                        });
+                    }
+                    ("model", parts)
+                }
+                Role::Tool | Role::Function => {
+                    let name = message
+                        .tool_call_id
+                        .as_ref()
+                        .and_then(|id| tool_names.get(id).cloned())
+                        .or_else(|| message.name.clone())
+                        .unwrap_or_default();
+                    let part = Part {
+                        function_response: Some(FunctionResponse {
+                            name,
+                            response: tool_response(&message.content),
+                        }),
+                        ..Default::default()
+                    };
+                    ("user", vec![part])
+                }
+            };
+
+            if parts.is_empty() {
+                continue;
+            }
+
+            // Consecutive turns of the same role are merged into one content
+            match contents.last_mut() {
+                Some(last) if last.role.as_deref() == Some(role) => last.parts.extend(parts),
+                _ => contents.push(GeminiContent {
+                    role: Some(role.to_string()),
+                    parts,
+                }),
+            }
+        }
+
+        let (response_mime_type, response_schema) = history
+            .response_format
+            .map(response_format)
+            .unwrap_or_default();
+
+        let generation_config = GenerationConfig {
+            temperature: history.temperature,
+            top_p: history.top_p,
+            max_output_tokens: history.max_completion_tokens,
+            stop_sequences: history.stop,
+            seed: history.seed,
+            presence_penalty: history.presence_penalty,
+            frequency_penalty: history.frequency_penalty,
+            candidate_count: history.n,
+            response_mime_type,
+            response_schema,
+        };
+
+        GenerateContentRequest {
+            contents,
+            system_instruction: (!system.is_empty()).then_some(GeminiContent {
+                role: None,
+                parts: system,
+            }),
+            tools: history.tools.map(|tools| {
+                vec![GeminiTool {
+                    function_declarations: tools
+                        .into_iter()
+                        .map(FunctionDeclaration::from)
+                        .collect(),
+                }]
+            }),
+            tool_config: history.tool_choice.map(ToolConfig::from),
+            generation_config: Some(generation_config),
+        }
+    }
+}
+
+fn to_parts(content: &MessageContent) -> Vec<Part> {
+    let parts = match content {
+        MessageContent::String(text) => vec![Part::text(text.clone())],
+        MessageContent::Contents(contents) => contents
+            .iter()
+            .map(|content| match content {
+                Content::Text { text, .. } => Part::text(text.clone()),
+                Content::Image { image_url, .. } => Part::from(image_url),
+            })
+            .collect(),
+    };
+
+    // Empty text parts are rejected by the API
+    parts
+        .into_iter()
+        .filter(|part| part.text.as_deref() != Some(""))
+        .collect()
+}
+
+/// Function responses have to be objects, plain results are wrapped.
+fn tool_response(content: &MessageContent) -> Value {
+    let text = to_parts(content)
+        .into_iter()
+        .filter_map(|part| part.text)
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    match json::from_str::<Value>(&text) {
+        Ok(Value::Object(object)) => Value::Object(object),
+        Ok(value) => json!({ "content": value }),
+        Err(_) => json!({ "content": text }),
+    }
+}
+
+/// Maps OpenAI style response formats to Gemini's mime type and response schema.
+fn response_format(format: ResponseFormat) -> (Option<String>, Option<Value>) {
+    let json_mime = Some("application/json".to_string());
+    let value = match format {
+        ResponseFormat::String(format) => json::from_str(&format).unwrap_or(Value::String(format)),
+        ResponseFormat::Object(value) => value,
+    };
+
+    match &value {
+        Value::String(format) if format == "text" => (None, None),
+        Value::String(_) => (json_mime, None),
+        Value::Object(object) => match object.get("type").and_then(Value::as_str) {
+            Some("text") => (None, None),
+            Some("json_object") => (json_mime, None),
+            Some("json_schema") => (
+                json_mime,
+                object
+                    .get("json_schema")
+                    .and_then(|json_schema| json_schema.get("schema"))
+                    .cloned()
+                    .map(schema),
+            ),
+            _ => (json_mime, Some(schema(value.clone()))),
+        },
+        _ => (None, None),
+    }
+}
+
+/// Gemini accepts an OpenAPI subset of JSON schema, unsupported keywords are dropped.
+fn schema(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(key, value)| {
+                    !value.is_null()
+                        && !matches!(key.as_str(), "$schema" | "$id" | "additionalProperties")
+                })
+                .map(|(key, value)| match key.as_str() {
+                    "enum_values" => ("enum".to_string(), schema(value)),
+                    _ => (key, schema(value)),
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(schema).collect()),
+        value => value,
+    }
+}
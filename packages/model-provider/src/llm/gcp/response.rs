@@ -0,0 +1,162 @@
+use flow_like_types::{Result, bail, create_id};
+use serde::Deserialize;
+
+use super::history::GeminiContent;
+use crate::{
+    response::{FunctionCall, ResponseFunction, Usage},
+    response_chunk::{Delta, ResponseChunk, ResponseChunkChoice},
+};
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateContentResponse {
+    #[serde(default)]
+    pub candidates: Vec<Candidate>,
+    #[serde(default)]
+    pub usage_metadata: Option<UsageMetadata>,
+    #[serde(default)]
+    pub model_version: Option<String>,
+    #[serde(default)]
+    pub response_id: Option<String>,
+    #[serde(default)]
+    pub prompt_feedback: Option<PromptFeedback>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Candidate {
+    #[serde(default)]
+    pub content: Option<GeminiContent>,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+    #[serde(default)]
+    pub index: i32,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageMetadata {
+    #[serde(default)]
+    pub prompt_token_count: u32,
+    #[serde(default)]
+    pub candidates_token_count: u32,
+    #[serde(default)]
+    pub thoughts_token_count: u32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptFeedback {
+    #[serde(default)]
+    pub block_reason: Option<String>,
+}
+
+pub fn finish_reason(finish_reason: &str, called_tools: bool) -> String {
+    match finish_reason {
+        "STOP" if called_tools => "tool_calls",
+        "STOP" => "stop",
+        "MAX_TOKENS" => "length",
+        "SAFETY" | "RECITATION" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" | "IMAGE_SAFETY" => {
+            "content_filter"
+        }
+        other => return other.to_lowercase(),
+    }
+    .to_string()
+}
+
+/// Translates Gemini responses into chunks. Gemini reports usage cumulatively and does not always
+/// assign ids to function calls, both are tracked here so chunks can be merged like OpenAI's.
+#[derive(Default)]
+pub struct StreamState {
+    id: Option<String>,
+    prompt_tokens: u32,
+    completion_tokens: u32,
+    tool_calls: i32,
+}
+
+impl StreamState {
+    pub fn chunk(&mut self, response: GenerateContentResponse) -> Result<ResponseChunk> {
+        let blocked = response
+            .prompt_feedback
+            .as_ref()
+            .and_then(|feedback| feedback.block_reason.as_ref())
+            .filter(|_| response.candidates.is_empty());
+        if let Some(reason) = blocked {
+            bail!("Gemini blocked the prompt: {}", reason);
+        }
+
+        let id = self
+            .id
+            .get_or_insert_with(|| response.response_id.clone().unwrap_or_else(create_id))
+            .clone();
+
+        let mut choices = Vec::with_capacity(response.candidates.len());
+        for candidate in response.candidates {
+            let mut text = String::new();
+            let mut tool_calls = Vec::new();
+
+            let parts = candidate.content.map(|content| content.parts);
+            for part in parts.into_iter().flatten() {
+                if part.thought == Some(true) {
+                    continue;
+                }
+
+                if let Some(content) = part.text {
+                    text.push_str(&content);
+                }
+
+                if let Some(call) = part.function_call {
+                    tool_calls.push(FunctionCall {
+                        index: Some(self.tool_calls),
+                        id: call.id.unwrap_or_else(create_id),
+                        tool_type: Some("function".to_string()),
+                        function: ResponseFunction {
+                            name: Some(call.name),
+                            arguments: Some(call.args.to_string()),
+                        },
+                    });
+                    self.tool_calls += 1;
+                }
+            }
+
+            choices.push(ResponseChunkChoice {
+                index: candidate.index,
+                delta: Some(Delta {
+                    role: Some("assistant".to_string()),
+                    content: (!text.is_empty()).then_some(text),
+                    tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+                    refusal: None,
+                }),
+                finish_reason: candidate
+                    .finish_reason
+                    .as_deref()
+                    .map(|reason| finish_reason(reason, self.tool_calls > 0)),
+                logprobs: None,
+            });
+        }
+
+        Ok(ResponseChunk {
+            id,
+            choices,
+            model: response.model_version,
+            usage: response.usage_metadata.map(|usage| self.usage(usage)),
+            ..Default::default()
+        })
+    }
+
+    /// Converts the running totals into the increment since the last chunk.
+    fn usage(&mut self, usage: UsageMetadata) -> Usage {
+        let completion_tokens = usage.candidates_token_count + usage.thoughts_token_count;
+        let prompt = usage.prompt_token_count.saturating_sub(self.prompt_tokens);
+        let completion = completion_tokens.saturating_sub(self.completion_tokens);
+        self.prompt_tokens = self.prompt_tokens.max(usage.prompt_token_count);
+        self.completion_tokens = self.completion_tokens.max(completion_tokens);
+
+        Usage {
+            prompt_tokens: prompt,
+            completion_tokens: completion,
+            total_tokens: prompt + completion,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use flow_like_types::{
+    Value, json,
+    tokio::{
+        self,
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        task::JoinHandle,
+    },
+};
+
+/// A request as the mock server received it.
+pub struct MockRequest {
+    /// Request line and headers
+    pub head: String,
+    pub body: String,
+}
+
+impl MockRequest {
+    pub fn json(&self) -> Value {
+        json::from_str(&self.body).unwrap()
+    }
+}
+
+/// Answers a single request with `body` and hands back the request the client sent.
+/// Returns the base url of the server, without a path.
+pub async fn mock_server(
+    content_type: &'static str,
+    body: String,
+) -> (String, JoinHandle<MockRequest>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let handle = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buffer = Vec::new();
+        let mut read_buffer = [0u8; 4096];
+
+        let request = loop {
+            let read = socket.read(&mut read_buffer).await.unwrap();
+            buffer.extend_from_slice(&read_buffer[..read]);
+
+            let text = String::from_utf8_lossy(&buffer).to_string();
+            let Some(split) = text.find("\r\n\r\n") else {
+                continue;
+            };
+            let length = text[..split]
+                .lines()
+                .find_map(|line| {
+                    let line = line.to_ascii_lowercase();
+                    line.strip_prefix("content-length:")
+                        .and_then(|value| value.trim().parse::<usize>().ok())
+                })
+                .unwrap_or(0);
+
+            let start = split + 4;
+            if buffer.len() >= start + length {
+                break MockRequest {
+                    head: text[..split].to_string(),
+                    body: String::from_utf8_lossy(&buffer[start..start + length]).to_string(),
+                };
+            }
+        };
+
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: {}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+            content_type,
+            body.len(),
+            body
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+        socket.shutdown().await.ok();
+        request
+    });
+
+    (format!("http://{}", address), handle)
+}
@@ -1,10 +1,12 @@
-use std::sync::Arc;
-
 use super::{LLMCallback, ModelLogic};
 use crate::{
     history::History,
-    provider::{ModelProvider, ModelProviderConfiguration, openai::OpenAIClient},
+    provider::{
+        ModelProvider, ModelProviderConfiguration,
+        openai::{OpenAIClient, provider_error},
+    },
     response::Response,
+    routing::{ProviderRouter, RoutedEndpoint},
 };
 use flow_like_types::{Result, async_trait, sync::Mutex};
 use openai_api_rs::v1::chat_completion::ChatCompletionRequest;
@@ -12,7 +14,7 @@ mod history;
 mod response;
 
 pub struct OpenAIModel {
-    client: Arc<Mutex<OpenAIClient>>,
+    router: ProviderRouter<Mutex<OpenAIClient>>,
     provider: ModelProvider,
 }
 
@@ -21,10 +23,21 @@ impl OpenAIModel {
         provider: &ModelProvider,
         config: &ModelProviderConfiguration,
     ) -> flow_like_types::Result<Self> {
-        let client = OpenAIClient::from_config(provider, config).await?;
+        let endpoints = config
+            .openai_config
+            .iter()
+            .map(|openai_config| {
+                let client = OpenAIClient::from_openai_config(provider, openai_config)?;
+                Ok(RoutedEndpoint {
+                    key: client.endpoint().to_string(),
+                    weight: openai_config.weight,
+                    client: Mutex::new(client),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         Ok(OpenAIModel {
-            client: Arc::new(Mutex::new(client)),
+            router: ProviderRouter::new(config, endpoints)?,
             provider: provider.clone(),
         })
     }
@@ -41,18 +54,28 @@ impl ModelLogic for OpenAIModel {
         let mut request = ChatCompletionRequest::from(history.clone());
         request.model = model_id;
 
-        let completion = {
-            let mut client = self.client.lock().await;
-            client.chat_completion(request, callback).await?
-        };
-        let response = completion;
-        Ok(response)
+        self.router
+            .route(|client| {
+                let request = request.clone();
+                let callback = callback.clone();
+                async move {
+                    let mut client = client.lock().await;
+                    client
+                        .chat_completion(request, callback)
+                        .await
+                        .map_err(provider_error)
+                }
+            })
+            .await
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
 
     use flow_like_types::tokio;
 
@@ -71,6 +94,7 @@ mod tests {
             model_id: Some("gpt-4o-mini".to_string()),
             version: None,
             provider_name: "openai".to_string(),
+            remote: None,
         };
         let api_key = std::env::var("OPENAI_API_KEY").unwrap();
         let config = ModelProviderConfiguration {
@@ -79,8 +103,9 @@ mod tests {
                 organization: None,
                 endpoint: None,
                 proxy: None,
+                weight: None,
             }],
-            bedrock_config: vec![],
+            ..Default::default()
         };
 
         let model = OpenAIModel::new(&provider, &config).await.unwrap();
@@ -104,6 +129,7 @@ mod tests {
             model_id: Some("gpt-4o-mini".to_string()),
             version: Some("2024-02-15-preview".to_string()),
             provider_name: "azure".to_string(),
+            remote: None,
         };
         let api_key = std::env::var("AZURE_OPENAI_API_KEY").unwrap();
         let endpoint = std::env::var("AZURE_OPENAI_ENDPOINT").unwrap();
@@ -113,8 +139,9 @@ mod tests {
                 organization: None,
                 endpoint: Some(endpoint),
                 proxy: None,
+                weight: None,
             }],
-            bedrock_config: vec![],
+            ..Default::default()
         };
 
         let model = OpenAIModel::new(&provider, &config).await.unwrap();
@@ -139,6 +166,7 @@ mod tests {
             model_id: Some("gpt-4o-mini".to_string()),
             version: None,
             provider_name: "openai".to_string(),
+            remote: None,
         };
         let api_key = std::env::var("OPENAI_API_KEY").unwrap();
         let config = ModelProviderConfiguration {
@@ -147,8 +175,9 @@ mod tests {
                 organization: None,
                 endpoint: None,
                 proxy: None,
+                weight: None,
             }],
-            bedrock_config: vec![],
+            ..Default::default()
         };
 
         let model = OpenAIModel::new(&provider, &config).await.unwrap();
@@ -183,6 +212,7 @@ mod tests {
             model_id: Some("gpt-4o-mini".to_string()),
             version: Some("2024-02-15-preview".to_string()),
             provider_name: "azure".to_string(),
+            remote: None,
         };
         let api_key = std::env::var("AZURE_OPENAI_API_KEY").unwrap();
         let endpoint = std::env::var("AZURE_OPENAI_ENDPOINT").unwrap();
@@ -192,8 +222,9 @@ mod tests {
                 organization: None,
                 endpoint: Some(endpoint),
                 proxy: None,
+                weight: None,
             }],
-            bedrock_config: vec![],
+            ..Default::default()
         };
 
         let model = OpenAIModel::new(&provider, &config).await.unwrap();
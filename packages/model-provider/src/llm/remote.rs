@@ -1 +1,135 @@
-// Remote LLM Server
\ No newline at end of file
+// Remote LLM Server
+use std::sync::Arc;
+
+use super::{LLMCallback, ModelLogic};
+use crate::{
+    history::History,
+    provider::{ModelProvider, ModelProviderConfiguration, RemoteConfig, openai::OpenAIClient},
+    response::Response,
+};
+use flow_like_types::{Result, async_trait, sync::Mutex};
+use openai_api_rs::v1::chat_completion::ChatCompletionRequest;
+
+/// Self hosted model behind an OpenAI compatible API (Ollama, vLLM, LM Studio, ...).
+pub struct RemoteModel {
+    client: Arc<Mutex<OpenAIClient>>,
+    provider: ModelProvider,
+}
+
+impl RemoteModel {
+    pub async fn new(
+        provider: &ModelProvider,
+        config: &ModelProviderConfiguration,
+    ) -> flow_like_types::Result<Self> {
+        let remote = provider
+            .remote
+            .as_ref()
+            .ok_or_else(|| flow_like_types::anyhow!("Remote endpoint is missing"))?;
+        let api_key = config.remote_keys.get(&remote.endpoint);
+        let client = OpenAIClient::from_remote(remote, api_key.map(String::as_str))?;
+
+        Ok(RemoteModel {
+            client: Arc::new(Mutex::new(client)),
+            provider: provider.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl ModelLogic for RemoteModel {
+    async fn invoke(&self, history: &History, callback: Option<LLMCallback>) -> Result<Response> {
+        let model_id = self
+            .provider
+            .model_id
+            .clone()
+            .ok_or_else(|| flow_like_types::anyhow!("Model ID is missing"))?;
+        let mut request = ChatCompletionRequest::from(history.clone());
+        request.model = model_id;
+
+        let mut client = self.client.lock().await;
+        let response = client.chat_completion(request, callback).await?;
+        Ok(response)
+    }
+}
+
+/// Lists the model ids a remote server offers via `/v1/models`.
+pub async fn list_models(remote: &RemoteConfig, api_key: Option<&str>) -> Result<Vec<String>> {
+    let mut client = OpenAIClient::from_remote(remote, api_key)?;
+    let models = client.list_models().await?;
+    Ok(models.data.into_iter().map(|model| model.id).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use flow_like_types::tokio;
+
+    use super::*;
+    use crate::{
+        history::{HistoryMessage, Role},
+        llm::mock_server::mock_server,
+    };
+
+    fn remote_config(endpoint: String) -> RemoteConfig {
+        RemoteConfig {
+            endpoint,
+            headers: BTreeMap::from([("x-team".to_string(), "research".to_string())]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remote_list_models() {
+        let body = r#"{"object":"list","data":[{"id":"llama3.1:8b","object":"model","owned_by":"library"},{"id":"qwen2.5-coder:7b","object":"model","owned_by":"library"}]}"#;
+        let (endpoint, server) = mock_server("application/json", body.to_string()).await;
+
+        let models = list_models(&remote_config(endpoint), None).await.unwrap();
+        let request = server.await.unwrap().head.to_ascii_lowercase();
+
+        assert_eq!(models, vec!["llama3.1:8b", "qwen2.5-coder:7b"]);
+        assert!(request.starts_with("get /v1/models "));
+        assert!(request.contains("x-team: research"));
+        assert!(!request.contains("authorization:"));
+    }
+
+    #[tokio::test]
+    async fn test_remote_model_no_stream() {
+        let body = r#"{"id":"chatcmpl-1","object":"chat.completion","created":1700000000,"model":"llama3.1:8b","choices":[{"index":0,"message":{"role":"assistant","content":"Hello from Ollama"},"finish_reason":"stop"}],"usage":{"prompt_tokens":12,"completion_tokens":4,"total_tokens":16}}"#;
+        let (endpoint, server) = mock_server("application/json", body.to_string()).await;
+
+        let remote = remote_config(format!("{}/v1/", endpoint));
+        let config = ModelProviderConfiguration {
+            remote_keys: HashMap::from([(remote.endpoint.clone(), "secret".to_string())]),
+            ..Default::default()
+        };
+        let provider = ModelProvider {
+            provider_name: "remote".to_string(),
+            model_id: Some("llama3.1:8b".to_string()),
+            version: None,
+            remote: Some(remote),
+        };
+
+        let model = RemoteModel::new(&provider, &config).await.unwrap();
+        let mut history = History::new(
+            "llama3.1:8b".to_string(),
+            vec![HistoryMessage::from_string(Role::User, "Hello")],
+        );
+        history.set_stream(false);
+        let response = model.invoke(&history, None).await.unwrap();
+        let request = server.await.unwrap();
+
+        assert!(request.head.starts_with("POST /v1/chat/completions "));
+        assert_eq!(request.json()["model"], "llama3.1:8b");
+        assert!(
+            request
+                .head
+                .to_ascii_lowercase()
+                .contains("authorization: bearer secret")
+        );
+        assert_eq!(
+            response.last_message().unwrap().content.as_deref(),
+            Some("Hello from Ollama")
+        );
+        assert_eq!(response.usage.total_tokens, 16);
+    }
+}
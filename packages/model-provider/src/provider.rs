@@ -4,7 +4,12 @@ use flow_like_types::{
     rand::{self, Rng},
 };
 use schemars::JsonSchema;
+use std::collections::{BTreeMap, HashMap};
 
+use crate::routing::{ProviderHealth, RoutingPolicy};
+
+pub mod anthropic;
+pub mod gemini;
 pub mod openai;
 
 #[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
@@ -12,6 +17,18 @@ pub struct ModelProvider {
     pub provider_name: String,
     pub model_id: Option<String>,
     pub version: Option<String>,
+    /// Only set for the `remote` provider, the endpoint travels with the bit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<RemoteConfig>,
+}
+
+/// Any server speaking the OpenAI chat completions API, such as Ollama, vLLM or LM Studio.
+/// The API key is not part of it, see `ModelProviderConfiguration::remote_keys`.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RemoteConfig {
+    pub endpoint: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
@@ -49,6 +66,13 @@ pub enum Pooling {
 pub struct ModelProviderConfiguration {
     pub openai_config: Vec<OpenAIConfig>,
     pub bedrock_config: Vec<BedrockConfig>,
+    pub anthropic_config: Vec<AnthropicConfig>,
+    pub gemini_config: Vec<GeminiConfig>,
+    /// API keys of remote servers by `RemoteConfig::endpoint`, bits only carry the endpoint
+    pub remote_keys: HashMap<String, String>,
+    pub routing: RoutingPolicy,
+    /// Shared by every clone, so all models see which endpoints are failing
+    pub health: ProviderHealth,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -57,11 +81,34 @@ pub struct OpenAIConfig {
     pub endpoint: Option<String>,
     pub organization: Option<String>,
     pub proxy: Option<String>,
+    /// Share of the calls with `RoutingStrategy::Weighted`, defaults to 1
+    pub weight: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnthropicConfig {
+    pub api_key: Option<String>,
+    pub endpoint: Option<String>,
+    pub version: Option<String>,
+    pub proxy: Option<String>,
+    pub weight: Option<u32>,
+}
+
+/// Either an AI Studio `api_key` or a Vertex AI `service_account_key` (the JSON key file).
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeminiConfig {
+    pub api_key: Option<String>,
+    pub service_account_key: Option<String>,
+    pub project_id: Option<String>,
+    pub location: Option<String>,
+    pub endpoint: Option<String>,
+    pub weight: Option<u32>,
 }
 
 #[derive(Clone, Debug)]
 pub struct BedrockConfig {
     pub config: SdkConfig,
+    pub weight: Option<u32>,
 }
 
 pub fn random_provider<T>(vec: &[T]) -> flow_like_types::Result<T>
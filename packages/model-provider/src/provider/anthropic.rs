@@ -0,0 +1,106 @@
+use flow_like_types::{
+    Result, anyhow,
+    reqwest::{Client, Proxy, RequestBuilder},
+    reqwest_eventsource::{self, EventSource, RequestBuilderExt},
+};
+
+use crate::{
+    llm::anthropic::{history::MessagesRequest, response::MessagesResponse},
+    routing::ProviderError,
+};
+
+use super::{AnthropicConfig, ModelProviderConfiguration, random_provider};
+
+const API_URL_V1: &str = "https://api.anthropic.com/v1";
+const API_VERSION: &str = "2023-06-01";
+
+pub struct AnthropicClient {
+    api_endpoint: String,
+    api_key: String,
+    version: String,
+    client: Client,
+}
+
+impl AnthropicClient {
+    pub fn from_config(config: &ModelProviderConfiguration) -> Result<AnthropicClient> {
+        let anthropic_config = random_provider(&config.anthropic_config)?;
+        AnthropicClient::from_anthropic_config(anthropic_config)
+    }
+
+    pub fn from_anthropic_config(anthropic_config: AnthropicConfig) -> Result<AnthropicClient> {
+        let api_key = anthropic_config
+            .api_key
+            .ok_or_else(|| anyhow!("API key required for Anthropic"))?;
+
+        let api_endpoint = anthropic_config
+            .endpoint
+            .unwrap_or_else(|| API_URL_V1.to_string())
+            .trim_end_matches('/')
+            .to_string();
+
+        let mut client = Client::builder();
+        if let Some(proxy) = &anthropic_config.proxy {
+            client = client.proxy(Proxy::all(proxy)?);
+        }
+
+        Ok(AnthropicClient {
+            api_endpoint,
+            api_key,
+            version: anthropic_config
+                .version
+                .unwrap_or_else(|| API_VERSION.to_string()),
+            client: client.build()?,
+        })
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.api_endpoint
+    }
+
+    fn build_request(&self, path: &str) -> RequestBuilder {
+        self.client
+            .post(format!("{}/{}", self.api_endpoint, path))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", &self.version)
+    }
+
+    pub async fn messages(&self, request: &MessagesRequest) -> Result<MessagesResponse> {
+        let response = self.build_request("messages").json(request).send().await?;
+        let status = response.status();
+
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(ProviderError::new(
+                Some(status.as_u16()),
+                format!("Anthropic request failed with {}: {}", status, message),
+            )
+            .into());
+        }
+
+        Ok(response.json::<MessagesResponse>().await?)
+    }
+
+    pub fn messages_stream(&self, request: &MessagesRequest) -> Result<EventSource> {
+        let stream = self.build_request("messages").json(request).eventsource()?;
+        Ok(stream)
+    }
+
+    /// Turns a failed event stream into a readable error, including the body Anthropic sent along.
+    pub async fn stream_error(error: reqwest_eventsource::Error) -> flow_like_types::Error {
+        match error {
+            reqwest_eventsource::Error::InvalidStatusCode(status, response) => {
+                let message = response.text().await.unwrap_or_default();
+                ProviderError::new(
+                    Some(status.as_u16()),
+                    format!("Anthropic request failed with {}: {}", status, message),
+                )
+                .into()
+            }
+            // Nothing was streamed yet if the connection could not be opened
+            reqwest_eventsource::Error::Transport(error) if error.is_connect() => {
+                ProviderError::new(None, format!("Anthropic stream failed: {}", error)).into()
+            }
+            error => anyhow!("Anthropic stream failed: {}", error),
+        }
+    }
+}
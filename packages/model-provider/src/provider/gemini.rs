@@ -0,0 +1,337 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use flow_like_types::{
+    Result, Value, anyhow, bail,
+    json::{self, json},
+    reqwest::{Client, RequestBuilder},
+    reqwest_eventsource::{self, EventSource, RequestBuilderExt},
+    sync::Mutex,
+};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    llm::gcp::{history::GenerateContentRequest, response::GenerateContentResponse},
+    routing::ProviderError,
+};
+
+use super::{GeminiConfig, ModelProvider, ModelProviderConfiguration, random_provider};
+
+const AI_STUDIO_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+const DEFAULT_LOCATION: &str = "us-central1";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct ServiceAccountKey {
+    pub client_email: String,
+    pub private_key: String,
+    #[serde(default)]
+    pub token_uri: Option<String>,
+    #[serde(default)]
+    pub project_id: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Claims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+pub enum GeminiAuth {
+    ApiKey(String),
+    ServiceAccount {
+        key: ServiceAccountKey,
+        token: Mutex<Option<(String, Instant)>>,
+    },
+}
+
+/// Client for the Gemini API, served either by AI Studio (API key) or Vertex AI (service account).
+pub struct GeminiClient {
+    base_url: String,
+    auth: GeminiAuth,
+    client: Client,
+}
+
+impl GeminiClient {
+    pub fn from_config(
+        provider: &ModelProvider,
+        config: &ModelProviderConfiguration,
+    ) -> Result<GeminiClient> {
+        let gemini_config = random_provider(&GeminiClient::candidates(provider, config))?;
+        GeminiClient::from_gemini_config(provider, gemini_config)
+    }
+
+    /// Configs usable for the provider, Vertex AI needs a service account and AI Studio an API key.
+    pub fn candidates(
+        provider: &ModelProvider,
+        config: &ModelProviderConfiguration,
+    ) -> Vec<GeminiConfig> {
+        let vertex = provider.provider_name == "vertex";
+        config
+            .gemini_config
+            .iter()
+            .filter(|config| match vertex {
+                true => config.service_account_key.is_some(),
+                false => config.api_key.is_some(),
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub fn from_gemini_config(
+        provider: &ModelProvider,
+        gemini_config: GeminiConfig,
+    ) -> Result<GeminiClient> {
+        let vertex = provider.provider_name == "vertex";
+        let (base_url, auth) = match (vertex, gemini_config.api_key) {
+            (false, Some(api_key)) => (
+                gemini_config
+                    .endpoint
+                    .unwrap_or_else(|| AI_STUDIO_URL.to_string()),
+                GeminiAuth::ApiKey(api_key),
+            ),
+            _ => {
+                let key = gemini_config
+                    .service_account_key
+                    .ok_or_else(|| anyhow!("Service account key required for Vertex AI"))?;
+                let key: ServiceAccountKey = json::from_str(&key)?;
+                let project_id = gemini_config
+                    .project_id
+                    .or_else(|| key.project_id.clone())
+                    .ok_or_else(|| anyhow!("Project ID required for Vertex AI"))?;
+                let location = gemini_config
+                    .location
+                    .unwrap_or_else(|| DEFAULT_LOCATION.to_string());
+                let endpoint = gemini_config
+                    .endpoint
+                    .unwrap_or_else(|| match location.as_str() {
+                        "global" => "https://aiplatform.googleapis.com/v1".to_string(),
+                        location => format!("https://{}-aiplatform.googleapis.com/v1", location),
+                    });
+                (
+                    format!(
+                        "{}/projects/{}/locations/{}/publishers/google",
+                        endpoint, project_id, location
+                    ),
+                    GeminiAuth::ServiceAccount {
+                        key,
+                        token: Mutex::new(None),
+                    },
+                )
+            }
+        };
+
+        Ok(GeminiClient {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            auth,
+            client: Client::new(),
+        })
+    }
+
+    pub fn is_vertex(&self) -> bool {
+        matches!(self.auth, GeminiAuth::ServiceAccount { .. })
+    }
+
+    async fn access_token(
+        &self,
+        key: &ServiceAccountKey,
+        token: &Mutex<Option<(String, Instant)>>,
+    ) -> Result<String> {
+        let mut token = token.lock().await;
+
+        let valid = |(_, expires_at): &&(String, Instant)| {
+            *expires_at > Instant::now() + Duration::from_secs(60)
+        };
+        if let Some((access_token, _)) = token.as_ref().filter(valid) {
+            return Ok(access_token.clone());
+        }
+
+        let token_uri = key.token_uri.as_deref().unwrap_or(TOKEN_URL);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let claims = Claims {
+            iss: &key.client_email,
+            scope: SCOPE,
+            aud: token_uri,
+            iat: now,
+            exp: now + 3600,
+        };
+        let assertion = jsonwebtoken::encode(
+            &Header::new(Algorithm::RS256),
+            &claims,
+            &EncodingKey::from_rsa_pem(key.private_key.as_bytes())?,
+        )?;
+
+        let response = self
+            .client
+            .post(token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", assertion.as_str()),
+            ])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = response.text().await.unwrap_or_default();
+            bail!("Google token exchange failed with {}: {}", status, message);
+        }
+
+        let response = response.json::<TokenResponse>().await?;
+        let expires_at = Instant::now() + Duration::from_secs(response.expires_in);
+        *token = Some((response.access_token.clone(), expires_at));
+        Ok(response.access_token)
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.base_url
+    }
+
+    async fn build_request(&self, model: &str, method: &str) -> Result<RequestBuilder> {
+        let model = model.trim_start_matches("models/");
+        let request = self
+            .client
+            .post(format!("{}/models/{}:{}", self.base_url, model, method));
+
+        let request = match &self.auth {
+            GeminiAuth::ApiKey(api_key) => request.header("x-goog-api-key", api_key),
+            GeminiAuth::ServiceAccount { key, token } => {
+                request.bearer_auth(self.access_token(key, token).await?)
+            }
+        };
+
+        Ok(request)
+    }
+
+    async fn post<T: serde::de::DeserializeOwned>(
+        &self,
+        model: &str,
+        method: &str,
+        body: &impl Serialize,
+    ) -> Result<T> {
+        let response = self
+            .build_request(model, method)
+            .await?
+            .json(body)
+            .send()
+            .await?;
+        let status = response.status();
+
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(ProviderError::new(
+                Some(status.as_u16()),
+                format!("Gemini request failed with {}: {}", status, message),
+            )
+            .into());
+        }
+
+        Ok(response.json::<T>().await?)
+    }
+
+    pub async fn generate_content(
+        &self,
+        model: &str,
+        request: &GenerateContentRequest,
+    ) -> Result<GenerateContentResponse> {
+        self.post(model, "generateContent", request).await
+    }
+
+    pub async fn stream_generate_content(
+        &self,
+        model: &str,
+        request: &GenerateContentRequest,
+    ) -> Result<EventSource> {
+        let stream = self
+            .build_request(model, "streamGenerateContent?alt=sse")
+            .await?
+            .json(request)
+            .eventsource()?;
+        Ok(stream)
+    }
+
+    /// Embeds a batch of texts, `task_type` is one of Gemini's task types such as `RETRIEVAL_QUERY`.
+    pub async fn embed(
+        &self,
+        model: &str,
+        texts: &[String],
+        task_type: &str,
+        dimensions: Option<u32>,
+    ) -> Result<Vec<Vec<f32>>> {
+        let model = model.trim_start_matches("models/");
+
+        // Vertex keeps the older prediction interface for embeddings
+        let (method, body, pointer) = if self.is_vertex() {
+            let instances = texts
+                .iter()
+                .map(|text| json!({ "content": text, "task_type": task_type }))
+                .collect::<Vec<Value>>();
+            let body = json!({
+                "instances": instances,
+                "parameters": { "outputDimensionality": dimensions },
+            });
+            ("predict", body, ("predictions", "/embeddings/values"))
+        } else {
+            let requests = texts
+                .iter()
+                .map(|text| {
+                    json!({
+                        "model": format!("models/{}", model),
+                        "content": { "parts": [{ "text": text }] },
+                        "taskType": task_type,
+                        "outputDimensionality": dimensions,
+                    })
+                })
+                .collect::<Vec<Value>>();
+            let body = json!({ "requests": requests });
+            ("batchEmbedContents", body, ("embeddings", "/values"))
+        };
+
+        let response: Value = self.post(model, method, &body).await?;
+        let (list, values) = pointer;
+        let embeddings = response
+            .get(list)
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("Gemini returned no embeddings"))?
+            .iter()
+            .map(|embedding| {
+                let values = embedding
+                    .pointer(values)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Gemini returned an embedding without values"))?;
+                Ok(json::from_value::<Vec<f32>>(values)?)
+            })
+            .collect::<Result<Vec<Vec<f32>>>>()?;
+
+        Ok(embeddings)
+    }
+
+    /// Turns a failed event stream into a readable error, including the body Google sent along.
+    pub async fn stream_error(error: reqwest_eventsource::Error) -> flow_like_types::Error {
+        match error {
+            reqwest_eventsource::Error::InvalidStatusCode(status, response) => {
+                let message = response.text().await.unwrap_or_default();
+                ProviderError::new(
+                    Some(status.as_u16()),
+                    format!("Gemini request failed with {}: {}", status, message),
+                )
+                .into()
+            }
+            // Nothing was streamed yet if the connection could not be opened
+            reqwest_eventsource::Error::Transport(error) if error.is_connect() => {
+                ProviderError::new(None, format!("Gemini stream failed: {}", error)).into()
+            }
+            error => anyhow!("Gemini stream failed: {}", error),
+        }
+    }
+}
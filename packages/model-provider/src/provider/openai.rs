@@ -32,20 +32,35 @@ use flow_like_types::Value;
 use flow_like_types::reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use flow_like_types::reqwest::multipart::{Form, Part};
 use flow_like_types::reqwest::{Client, Method, Response};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::llm::LLMCallback;
 use crate::response_chunk::ResponseChunk;
+use crate::routing::ProviderError;
 use std::error::Error;
 use std::fs::{File, create_dir_all};
 use std::io::Read;
 use std::io::Write;
 use std::path::Path;
 
-use super::{ModelProvider, ModelProviderConfiguration, random_provider};
+use super::{
+    ModelProvider, ModelProviderConfiguration, OpenAIConfig, RemoteConfig, random_provider,
+};
 
 const API_URL_V1: &str = "https://api.openai.com/v1";
 
+#[derive(Deserialize, Debug, Clone)]
+pub struct ModelList {
+    pub data: Vec<ModelObject>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ModelObject {
+    pub id: String,
+    #[serde(default)]
+    pub owned_by: Option<String>,
+}
+
 #[derive(Default)]
 pub struct OpenAIClientBuilder {
     api_endpoint: Option<String>,
@@ -134,12 +149,23 @@ impl OpenAIClient {
         OpenAIClientBuilder::new()
     }
 
+    pub fn endpoint(&self) -> &str {
+        &self.api_endpoint
+    }
+
     pub async fn from_config(
         provider: &ModelProvider,
         config: &ModelProviderConfiguration,
     ) -> flow_like_types::Result<OpenAIClient> {
         let openai_config = random_provider(&config.openai_config)?;
+        OpenAIClient::from_openai_config(provider, &openai_config)
+    }
 
+    /// Client for one of the configured endpoints, models route over all of them.
+    pub fn from_openai_config(
+        provider: &ModelProvider,
+        openai_config: &OpenAIConfig,
+    ) -> flow_like_types::Result<OpenAIClient> {
         let mut client = OpenAIClient::builder();
 
         if let Some(api_key) = &openai_config.api_key {
@@ -186,6 +212,36 @@ impl OpenAIClient {
         Ok(client)
     }
 
+    /// Client for a self hosted OpenAI compatible server. The base URL may be given with or
+    /// without the `/v1` suffix, most servers document it without.
+    pub fn from_remote(
+        remote: &RemoteConfig,
+        api_key: Option<&str>,
+    ) -> flow_like_types::Result<OpenAIClient> {
+        let endpoint = remote.endpoint.trim_end_matches('/');
+        let endpoint = match endpoint.ends_with("/v1") {
+            true => endpoint.to_string(),
+            false => format!("{}/v1", endpoint),
+        };
+
+        // Local servers usually run without authentication
+        let mut client = OpenAIClient::builder()
+            .with_endpoint(endpoint)
+            .with_api_key(api_key.unwrap_or_default());
+
+        for (key, value) in &remote.headers {
+            HeaderName::from_bytes(key.as_bytes())
+                .map_err(|e| flow_like_types::anyhow!("Invalid header name {}: {}", key, e))?;
+            HeaderValue::from_str(value)
+                .map_err(|e| flow_like_types::anyhow!("Invalid header value for {}: {}", key, e))?;
+            client = client.with_header(key, value);
+        }
+
+        client
+            .build()
+            .map_err(|e| flow_like_types::anyhow!("Failed to create OpenAI client: {}", e))
+    }
+
     async fn build_request(
         &self,
         method: Method,
@@ -213,9 +269,11 @@ impl OpenAIClient {
 
         let client = client.build().unwrap();
 
-        let mut request = client
-            .request(method, url)
-            .header("Authorization", format!("Bearer {}", self.api_key));
+        let mut request = client.request(method, url);
+
+        if !self.api_key.is_empty() {
+            request = request.header("Authorization", format!("Bearer {}", self.api_key));
+        }
 
         if let Some(organization) = &self.organization {
             request = request.header("openai-organization", organization);
@@ -378,6 +436,10 @@ impl OpenAIClient {
         self.get_raw(&format!("files/{}/content", file_id)).await
     }
 
+    pub async fn list_models(&mut self) -> Result<ModelList, APIError> {
+        self.get("models").await
+    }
+
     pub async fn chat_completion(
         &mut self,
         req: ChatCompletionRequest,
@@ -403,25 +465,41 @@ impl OpenAIClient {
         let mut output = crate::response::Response::default();
 
         while let Some(event) = stream.next().await {
-            if let Ok(event) = event {
-                if let Event::Message(event) = event {
-                    let data = &event.data;
-                    if data == "[DONE]" {
-                        break;
-                    }
-                    let chunk: ResponseChunk = match flow_like_types::json::from_str(data) {
-                        Ok(chunk) => chunk,
-                        Err(e) => {
-                            eprintln!("Failed to parse chunk: {}", e);
-                            continue;
-                        }
-                    };
-                    output.push_chunk(chunk.clone());
-                    if let Some(callback) = &callback {
-                        callback(chunk).await.map_err(|e| APIError::CustomError {
-                            message: format!("Callback error: {}", e),
-                        })?;
+            let event = match event {
+                Ok(event) => event,
+                Err(flow_like_types::reqwest_eventsource::Error::StreamEnded) => break,
+                Err(flow_like_types::reqwest_eventsource::Error::InvalidStatusCode(
+                    status,
+                    response,
+                )) => {
+                    stream.close();
+                    let error_message = response
+                        .text()
+                        .await
+                        .unwrap_or_else(|_| "Unknown error".to_string());
+                    return Err(APIError::CustomError {
+                        message: format!("{}: {}", status, error_message),
+                    });
+                }
+                Err(_) => continue,
+            };
+            if let Event::Message(event) = event {
+                let data = &event.data;
+                if data == "[DONE]" {
+                    break;
+                }
+                let chunk: ResponseChunk = match flow_like_types::json::from_str(data) {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        eprintln!("Failed to parse chunk: {}", e);
+                        continue;
                     }
+                };
+                output.push_chunk(chunk.clone());
+                if let Some(callback) = &callback {
+                    callback(chunk).await.map_err(|e| APIError::CustomError {
+                        message: format!("Callback error: {}", e),
+                    })?;
                 }
             }
         }
@@ -758,3 +836,25 @@ impl OpenAIClient {
         Ok(form)
     }
 }
+
+/// Keeps the HTTP status of a failed call around, so the router knows whether to retry it.
+pub fn provider_error(error: APIError) -> flow_like_types::Error {
+    match error {
+        APIError::ReqwestError(error) if error.is_timeout() || error.is_connect() => {
+            ProviderError::new(None, error.to_string()).into()
+        }
+        APIError::ReqwestError(error) => match error.status() {
+            Some(status) => ProviderError::new(Some(status.as_u16()), error.to_string()).into(),
+            None => flow_like_types::anyhow!(error),
+        },
+        APIError::CustomError { message } => {
+            let status = message
+                .split_once(' ')
+                .and_then(|(status, _)| status.parse::<u16>().ok());
+            match status {
+                Some(status) => ProviderError::new(Some(status), message).into(),
+                None => flow_like_types::anyhow!(message),
+            }
+        }
+    }
+}
@@ -0,0 +1,698 @@
+use std::{
+    fmt,
+    future::Future,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use flow_like_types::{
+    Result, async_trait, bail,
+    json::{Deserialize, Serialize},
+    rand::{self, Rng},
+    reqwest,
+    sync::DashMap,
+    tokio,
+};
+use schemars::JsonSchema;
+
+use crate::{
+    history::History,
+    llm::{LLMCallback, ModelLogic},
+    provider::ModelProviderConfiguration,
+    response::Response,
+};
+
+/// How calls are spread over the configured endpoints of a provider.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RoutingStrategy {
+    #[default]
+    Random,
+    /// Random, proportional to the `weight` of each config
+    Weighted,
+    /// Lowest observed latency first, endpoints without calls yet count as fastest
+    LeastLatency,
+}
+
+#[derive(Clone, Debug)]
+pub struct RoutingPolicy {
+    pub strategy: RoutingStrategy,
+    /// Calls per invocation, including the first one
+    pub max_attempts: u32,
+    /// Doubled for every retry of the same endpoint, capped by `max_backoff`
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+    /// How long a failing endpoint is skipped by other calls
+    pub cooldown: Duration,
+}
+
+impl Default for RoutingPolicy {
+    fn default() -> Self {
+        Self {
+            strategy: RoutingStrategy::Random,
+            max_attempts: 3,
+            backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RoutingPolicy {
+    fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .backoff
+            .saturating_mul(1 << retry.min(16))
+            .min(self.max_backoff);
+        // Jitter keeps calls that failed together from retrying together
+        backoff.mul_f64(rand::rng().random_range(0.5..=1.0))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct EndpointState {
+    unhealthy_until: Option<Instant>,
+    latency: Option<Duration>,
+}
+
+/// Health of the endpoints, shared by all models built from the same configuration.
+#[derive(Clone, Debug, Default)]
+pub struct ProviderHealth {
+    endpoints: Arc<DashMap<String, EndpointState>>,
+}
+
+impl ProviderHealth {
+    pub fn is_healthy(&self, endpoint: &str) -> bool {
+        self.endpoints.get(endpoint).is_none_or(|state| {
+            state
+                .unhealthy_until
+                .is_none_or(|until| until <= Instant::now())
+        })
+    }
+
+    /// Moving average of the latency of successful calls.
+    pub fn latency(&self, endpoint: &str) -> Option<Duration> {
+        self.endpoints.get(endpoint).and_then(|state| state.latency)
+    }
+
+    fn succeeded(&self, endpoint: &str, latency: Duration) {
+        let mut state = self.endpoints.entry(endpoint.to_string()).or_default();
+        state.unhealthy_until = None;
+        state.latency = Some(match state.latency {
+            Some(average) => average.mul_f64(0.8) + latency.mul_f64(0.2),
+            None => latency,
+        });
+    }
+
+    fn failed(&self, endpoint: &str, cooldown: Duration) {
+        let mut state = self.endpoints.entry(endpoint.to_string()).or_default();
+        state.unhealthy_until = Some(Instant::now() + cooldown);
+    }
+}
+
+/// Failed provider call, lets the router tell transient from permanent failures.
+#[derive(Debug)]
+pub struct ProviderError {
+    /// HTTP status, `None` if the endpoint could not be reached
+    pub status: Option<u16>,
+    pub message: String,
+}
+
+impl ProviderError {
+    pub fn new(status: Option<u16>, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        match self.status {
+            None => true,
+            Some(status) => status == 408 || status == 429 || (500..600).contains(&status),
+        }
+    }
+}
+
+impl fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ProviderError {}
+
+/// Rate limits, server errors and unreachable endpoints are worth another try, anything else is not.
+pub fn is_retryable(error: &flow_like_types::Error) -> bool {
+    if let Some(error) = error.downcast_ref::<ProviderError>() {
+        return error.is_retryable();
+    }
+
+    if let Some(error) = error.downcast_ref::<reqwest::Error>() {
+        return error.is_timeout()
+            || error.is_connect()
+            || error
+                .status()
+                .is_some_and(|status| status.as_u16() == 429 || status.is_server_error());
+    }
+
+    false
+}
+
+fn status_of(error: &flow_like_types::Error) -> Option<u16> {
+    if let Some(error) = error.downcast_ref::<ProviderError>() {
+        return error.status;
+    }
+
+    error
+        .downcast_ref::<reqwest::Error>()
+        .and_then(|error| error.status())
+        .map(|status| status.as_u16())
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoutingOutcome {
+    Success,
+    /// Failed, the same endpoint is called again after the backoff
+    Retry,
+    /// Failed, another endpoint is called right away
+    Failover,
+    /// Failed and no attempts are left or the error is permanent
+    Failed,
+    /// The model failed, the next model of a `FallbackModel` is called
+    Fallback,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
+pub struct RoutingDecision {
+    pub endpoint: String,
+    pub attempt: u32,
+    pub outcome: RoutingOutcome,
+    pub latency_ms: u64,
+    pub status: Option<u16>,
+    pub backoff_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl fmt::Display for RoutingDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = self
+            .status
+            .map(|status| status.to_string())
+            .unwrap_or_else(|| "an error".to_string());
+        let error = self.error.as_deref().unwrap_or_default();
+
+        match self.outcome {
+            RoutingOutcome::Success => write!(
+                f,
+                "{} answered in {}ms (attempt {})",
+                self.endpoint, self.latency_ms, self.attempt
+            ),
+            RoutingOutcome::Retry => write!(
+                f,
+                "{} failed with {}, retrying in {}ms: {}",
+                self.endpoint,
+                status,
+                self.backoff_ms.unwrap_or_default(),
+                error
+            ),
+            RoutingOutcome::Failover => write!(
+                f,
+                "{} failed with {}, failing over: {}",
+                self.endpoint, status, error
+            ),
+            RoutingOutcome::Failed => write!(
+                f,
+                "{} failed with {} after {} attempts: {}",
+                self.endpoint, status, self.attempt, error
+            ),
+            RoutingOutcome::Fallback => write!(
+                f,
+                "{} failed, using the fallback model: {}",
+                self.endpoint, error
+            ),
+        }
+    }
+}
+
+tokio::task_local! {
+    static TRACE: Arc<Mutex<Vec<RoutingDecision>>>;
+}
+
+/// Runs the future and collects the routing decisions of all model calls it made.
+pub async fn trace_routing<F: Future>(future: F) -> (F::Output, Vec<RoutingDecision>) {
+    let trace = Arc::new(Mutex::new(Vec::new()));
+    let output = TRACE.scope(trace.clone(), future).await;
+    let decisions = match trace.lock() {
+        Ok(mut decisions) => std::mem::take(&mut *decisions),
+        Err(poisoned) => std::mem::take(&mut *poisoned.into_inner()),
+    };
+    (output, decisions)
+}
+
+fn record(decision: RoutingDecision) {
+    let _ = TRACE.try_with(|trace| {
+        if let Ok(mut decisions) = trace.lock() {
+            decisions.push(decision);
+        }
+    });
+}
+
+pub struct RoutedEndpoint<T> {
+    /// Identifies the endpoint in the health registry and the run trace
+    pub key: String,
+    pub weight: Option<u32>,
+    pub client: T,
+}
+
+struct Endpoint<T> {
+    key: String,
+    weight: u32,
+    client: Arc<T>,
+}
+
+/// Spreads the calls of a model over all configured endpoints of its provider.
+pub struct ProviderRouter<T> {
+    endpoints: Vec<Endpoint<T>>,
+    policy: RoutingPolicy,
+    health: ProviderHealth,
+}
+
+impl<T> ProviderRouter<T> {
+    pub fn new(
+        config: &ModelProviderConfiguration,
+        endpoints: impl IntoIterator<Item = RoutedEndpoint<T>>,
+    ) -> Result<Self> {
+        let mut routed: Vec<Endpoint<T>> = Vec::new();
+        for endpoint in endpoints {
+            // Configs for the same endpoint (e.g. multiple keys) are tracked separately
+            let duplicates = routed
+                .iter()
+                .filter(|routed| routed.key.split('#').next() == Some(endpoint.key.as_str()))
+                .count();
+            let key = match duplicates {
+                0 => endpoint.key,
+                duplicates => format!("{}#{}", endpoint.key, duplicates + 1),
+            };
+            routed.push(Endpoint {
+                key,
+                weight: endpoint.weight.unwrap_or(1),
+                client: Arc::new(endpoint.client),
+            });
+        }
+
+        if routed.is_empty() {
+            bail!("No Provider found");
+        }
+
+        Ok(Self {
+            endpoints: routed,
+            policy: config.routing.clone(),
+            health: config.health.clone(),
+        })
+    }
+
+    /// Calls endpoints until one succeeds, a permanent error occurs or `max_attempts` is reached.
+    pub async fn route<R, F, Fut>(&self, call: F) -> Result<R>
+    where
+        F: Fn(Arc<T>) -> Fut,
+        Fut: Future<Output = Result<R>>,
+    {
+        let max_attempts = self.policy.max_attempts.max(1);
+        let mut failed: Vec<usize> = Vec::new();
+        let mut index = self.select(&failed);
+        let mut attempt = 1;
+
+        loop {
+            let endpoint = &self.endpoints[index];
+            let started = Instant::now();
+            let result = call(endpoint.client.clone()).await;
+            let latency = started.elapsed();
+
+            let error = match result {
+                Ok(output) => {
+                    self.health.succeeded(&endpoint.key, latency);
+                    record(RoutingDecision {
+                        endpoint: endpoint.key.clone(),
+                        attempt,
+                        outcome: RoutingOutcome::Success,
+                        latency_ms: latency.as_millis() as u64,
+                        status: None,
+                        backoff_ms: None,
+                        error: None,
+                    });
+                    return Ok(output);
+                }
+                Err(error) => error,
+            };
+
+            let retryable = is_retryable(&error);
+            if retryable {
+                self.health.failed(&endpoint.key, self.policy.cooldown);
+                failed.push(index);
+            }
+
+            let mut decision = RoutingDecision {
+                endpoint: endpoint.key.clone(),
+                attempt,
+                outcome: RoutingOutcome::Failed,
+                latency_ms: latency.as_millis() as u64,
+                status: status_of(&error),
+                backoff_ms: None,
+                error: Some(error.to_string()),
+            };
+
+            if !retryable || attempt >= max_attempts {
+                record(decision);
+                return Err(error);
+            }
+
+            let next = self.select(&failed);
+            let retries = failed.iter().filter(|failed| **failed == next).count() as u32;
+            let backoff = match retries {
+                0 => None,
+                retries => Some(self.policy.backoff(retries - 1)),
+            };
+
+            decision.outcome = match backoff {
+                Some(_) => RoutingOutcome::Retry,
+                None => RoutingOutcome::Failover,
+            };
+            decision.backoff_ms = backoff.map(|backoff| backoff.as_millis() as u64);
+            record(decision);
+
+            if let Some(backoff) = backoff {
+                tokio::time::sleep(backoff).await;
+            }
+
+            index = next;
+            attempt += 1;
+        }
+    }
+
+    /// Healthy endpoints first, then the ones only other calls saw failing,
+    /// then the one that failed least often during this call.
+    fn select(&self, failed: &[usize]) -> usize {
+        let all = 0..self.endpoints.len();
+        let failures = |index: &usize| failed.iter().filter(|failed| *failed == index).count();
+
+        let mut candidates = all
+            .clone()
+            .filter(|index| {
+                failures(index) == 0 && self.health.is_healthy(&self.endpoints[*index].key)
+            })
+            .collect::<Vec<_>>();
+
+        if candidates.is_empty() {
+            candidates = all.clone().filter(|index| failures(index) == 0).collect();
+        }
+
+        if candidates.is_empty() {
+            return all.min_by_key(failures).unwrap_or_default();
+        }
+
+        match self.policy.strategy {
+            RoutingStrategy::Random => candidates[rand::rng().random_range(0..candidates.len())],
+            RoutingStrategy::Weighted => self.weighted(&candidates),
+            RoutingStrategy::LeastLatency => candidates
+                .iter()
+                .copied()
+                .min_by_key(|index| {
+                    self.health
+                        .latency(&self.endpoints[*index].key)
+                        .unwrap_or_default()
+                })
+                .unwrap_or(candidates[0]),
+        }
+    }
+
+    fn weighted(&self, candidates: &[usize]) -> usize {
+        let total: u64 = candidates
+            .iter()
+            .map(|index| self.endpoints[*index].weight as u64)
+            .sum();
+        if total == 0 {
+            return candidates[rand::rng().random_range(0..candidates.len())];
+        }
+
+        let mut pick = rand::rng().random_range(0..total);
+        for index in candidates {
+            let weight = self.endpoints[*index].weight as u64;
+            if pick < weight {
+                return *index;
+            }
+            pick -= weight;
+        }
+        candidates[candidates.len() - 1]
+    }
+}
+
+/// Calls the next model when one fails, e.g. the same model at another provider.
+pub struct FallbackModel {
+    models: Vec<(String, Arc<dyn ModelLogic>)>,
+}
+
+impl FallbackModel {
+    /// Models are tried in order, the name shows up in the run trace.
+    pub fn new(models: Vec<(String, Arc<dyn ModelLogic>)>) -> Self {
+        Self { models }
+    }
+}
+
+#[async_trait]
+impl ModelLogic for FallbackModel {
+    async fn invoke(&self, history: &History, callback: Option<LLMCallback>) -> Result<Response> {
+        let streamed = Arc::new(AtomicBool::new(false));
+        let callback = callback.map(|callback| {
+            let streamed = streamed.clone();
+            let callback: LLMCallback = Arc::new(move |chunk| {
+                streamed.store(true, Ordering::SeqCst);
+                callback(chunk)
+            });
+            callback
+        });
+
+        let mut models = self.models.iter().peekable();
+        while let Some((name, model)) = models.next() {
+            let started = Instant::now();
+            let error = match model.invoke(history, callback.clone()).await {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+
+            // A partly streamed answer can not be taken back
+            if models.peek().is_none() || streamed.load(Ordering::SeqCst) {
+                return Err(error);
+            }
+
+            record(RoutingDecision {
+                endpoint: name.clone(),
+                attempt: 1,
+                outcome: RoutingOutcome::Fallback,
+                latency_ms: started.elapsed().as_millis() as u64,
+                status: status_of(&error),
+                backoff_ms: None,
+                error: Some(error.to_string()),
+            });
+        }
+
+        bail!("No model to invoke")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU32;
+
+    use super::*;
+
+    fn config(strategy: RoutingStrategy) -> ModelProviderConfiguration {
+        ModelProviderConfiguration {
+            routing: RoutingPolicy {
+                strategy,
+                backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(2),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn endpoint(key: &str, weight: Option<u32>) -> RoutedEndpoint<String> {
+        RoutedEndpoint {
+            key: key.to_string(),
+            weight,
+            client: key.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn fails_over_to_healthy_endpoint() {
+        let config = config(RoutingStrategy::Random);
+        let router = ProviderRouter::new(
+            &config,
+            vec![endpoint("west", None), endpoint("east", None)],
+        )
+        .unwrap();
+
+        for _ in 0..10 {
+            let (result, decisions) = trace_routing(router.route(|client| async move {
+                match client.as_str() {
+                    "west" => Err(ProviderError::new(Some(503), "unavailable").into()),
+                    _ => Ok(client.to_string()),
+                }
+            }))
+            .await;
+            assert_eq!(result.unwrap(), "east");
+            assert_eq!(decisions.last().unwrap().outcome, RoutingOutcome::Success);
+        }
+
+        // West failed at most once, afterwards it was skipped while unhealthy
+        assert!(!config.health.is_healthy("west"));
+    }
+
+    #[tokio::test]
+    async fn retries_single_endpoint_with_backoff() {
+        let config = config(RoutingStrategy::Random);
+        let router = ProviderRouter::new(&config, vec![endpoint("only", None)]).unwrap();
+        let calls = AtomicU32::new(0);
+
+        let (result, decisions) = trace_routing(router.route(|_| async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(ProviderError::new(Some(429), "rate limited").into()),
+                _ => Ok(()),
+            }
+        }))
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        let outcomes = decisions
+            .iter()
+            .map(|decision| decision.outcome)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            vec![RoutingOutcome::Retry, RoutingOutcome::Success]
+        );
+        assert_eq!(decisions[0].status, Some(429));
+        assert!(config.health.is_healthy("only"));
+    }
+
+    #[tokio::test]
+    async fn permanent_errors_are_not_retried() {
+        let config = config(RoutingStrategy::Random);
+        let router = ProviderRouter::new(&config, vec![endpoint("only", None)]).unwrap();
+        let calls = AtomicU32::new(0);
+
+        let (result, decisions) = trace_routing(router.route(|_| async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(ProviderError::new(Some(400), "bad request").into())
+        }))
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(decisions[0].outcome, RoutingOutcome::Failed);
+        assert!(config.health.is_healthy("only"));
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let config = config(RoutingStrategy::Random);
+        let router = ProviderRouter::new(
+            &config,
+            vec![endpoint("west", None), endpoint("east", None)],
+        )
+        .unwrap();
+        let calls = AtomicU32::new(0);
+
+        let (result, decisions) = trace_routing(router.route(|_| async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(ProviderError::new(None, "connection refused").into())
+        }))
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(decisions[0].outcome, RoutingOutcome::Failover);
+        assert_eq!(decisions[2].outcome, RoutingOutcome::Failed);
+    }
+
+    #[test]
+    fn weighted_prefers_heavier_endpoints() {
+        let config = config(RoutingStrategy::Weighted);
+        let router = ProviderRouter::new(
+            &config,
+            vec![endpoint("never", Some(0)), endpoint("always", Some(5))],
+        )
+        .unwrap();
+
+        for _ in 0..50 {
+            assert_eq!(router.select(&[]), 1);
+        }
+    }
+
+    #[test]
+    fn least_latency_prefers_fastest_endpoint() {
+        let config = config(RoutingStrategy::LeastLatency);
+        let router = ProviderRouter::new(
+            &config,
+            vec![endpoint("slow", None), endpoint("fast", None)],
+        )
+        .unwrap();
+        config.health.succeeded("slow", Duration::from_millis(900));
+        config.health.succeeded("fast", Duration::from_millis(100));
+
+        assert_eq!(router.select(&[]), 1);
+        // A failure during the call moves on to the next fastest
+        assert_eq!(router.select(&[1]), 0);
+    }
+
+    #[test]
+    fn duplicate_endpoints_are_tracked_separately() {
+        let config = config(RoutingStrategy::Random);
+        let router =
+            ProviderRouter::new(&config, vec![endpoint("api", None), endpoint("api", None)])
+                .unwrap();
+
+        assert_eq!(router.endpoints[0].key, "api");
+        assert_eq!(router.endpoints[1].key, "api#2");
+    }
+
+    struct FailingModel;
+
+    #[async_trait]
+    impl ModelLogic for FailingModel {
+        async fn invoke(&self, _: &History, _: Option<LLMCallback>) -> Result<Response> {
+            Err(ProviderError::new(Some(500), "down").into())
+        }
+    }
+
+    struct WorkingModel;
+
+    #[async_trait]
+    impl ModelLogic for WorkingModel {
+        async fn invoke(&self, _: &History, _: Option<LLMCallback>) -> Result<Response> {
+            Ok(Response::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_next_model() {
+        let model = FallbackModel::new(vec![
+            ("primary".to_string(), Arc::new(FailingModel)),
+            ("fallback".to_string(), Arc::new(WorkingModel)),
+        ]);
+        let history = History::new("model".to_string(), vec![]);
+
+        let (result, decisions) = trace_routing(model.invoke(&history, None)).await;
+
+        assert!(result.is_ok());
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].endpoint, "primary");
+        assert_eq!(decisions[0].outcome, RoutingOutcome::Fallback);
+    }
+}
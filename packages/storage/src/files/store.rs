@@ -7,10 +7,12 @@ use flow_like_types::{
 use futures::StreamExt;
 use local_store::LocalObjectStore;
 use object_store::{ObjectMeta, ObjectStore, PutPayload, path::Path, signer::Signer};
+use scoped_store::ScopedObjectStore;
 use serde::{Deserialize, Serialize};
 use std::{sync::Arc, time::Duration};
 use urlencoding::encode;
 pub mod local_store;
+pub mod scoped_store;
 
 #[derive(Clone, Serialize, Deserialize, JsonSchema)]
 pub struct StorageItem {
@@ -40,6 +42,7 @@ pub enum FlowLikeStore {
     Azure(Arc<object_store::azure::MicrosoftAzure>),
     Google(Arc<object_store::gcp::GoogleCloudStorage>),
     Memory(Arc<object_store::memory::InMemory>),
+    Scoped(Arc<ScopedObjectStore>),
     Other(Arc<dyn ObjectStore>),
 }
 
@@ -61,6 +64,7 @@ impl FlowLikeStore {
             FlowLikeStore::Azure(store) => store.clone() as Arc<dyn ObjectStore>,
             FlowLikeStore::Google(store) => store.clone() as Arc<dyn ObjectStore>,
             FlowLikeStore::Memory(store) => store.clone() as Arc<dyn ObjectStore>,
+            FlowLikeStore::Scoped(store) => store.clone() as Arc<dyn ObjectStore>,
             FlowLikeStore::Other(store) => store.clone() as Arc<dyn ObjectStore>,
         }
     }
@@ -101,6 +105,18 @@ impl FlowLikeStore {
     }
 
     pub async fn sign(&self, method: &str, path: &Path, expires_after: Duration) -> Result<Url> {
+        if let FlowLikeStore::Scoped(store) = self {
+            let allowed = if method.eq_ignore_ascii_case("GET") {
+                store.scope().can_read(path)
+            } else {
+                store.scope().can_write(path)
+            };
+            if !allowed {
+                bail!("Path {} is outside of the credential scope", path);
+            }
+            return Box::pin(store.inner().sign(method, path, expires_after)).await;
+        }
+
         let method = match method.to_uppercase().as_str() {
             "GET" => reqwest::Method::GET,
             "PUT" => reqwest::Method::PUT,
@@ -142,6 +158,7 @@ impl FlowLikeStore {
                 let data_url = pathbuf_to_data_url(&local_path).await?;
                 return Ok(Url::parse(&data_url)?);
             }
+            FlowLikeStore::Scoped(_) => bail!("Scoped stores are signed by their inner store"),
             FlowLikeStore::Other(_) => bail!("Sign not implemented for this store"),
         };
 
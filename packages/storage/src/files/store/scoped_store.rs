@@ -0,0 +1,288 @@
+use flow_like_types::{Bytes, async_trait};
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use object_store::path::Path;
+use object_store::{
+    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore, PutMultipartOpts,
+    PutOptions, PutPayload, PutResult, Result,
+};
+use std::ops::Range;
+use std::sync::Arc;
+
+use super::FlowLikeStore;
+
+/// Prefixes a `ScopedObjectStore` may read from or write to.
+/// Write access implies read access.
+#[derive(Clone, Debug, Default)]
+pub struct StoreScope {
+    pub read: Vec<Path>,
+    pub write: Vec<Path>,
+}
+
+impl StoreScope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn allow_read(mut self, prefix: impl Into<Path>) -> Self {
+        self.read.push(prefix.into());
+        self
+    }
+
+    pub fn allow_write(mut self, prefix: impl Into<Path>) -> Self {
+        self.write.push(prefix.into());
+        self
+    }
+
+    pub fn can_read(&self, location: &Path) -> bool {
+        self.can_write(location) || self.read.iter().any(|prefix| is_within(location, prefix))
+    }
+
+    pub fn can_write(&self, location: &Path) -> bool {
+        self.write.iter().any(|prefix| is_within(location, prefix))
+    }
+
+    /// Whether listing below `prefix` can return anything readable.
+    fn can_list(&self, prefix: Option<&Path>) -> bool {
+        let Some(prefix) = prefix else {
+            return true;
+        };
+        self.read
+            .iter()
+            .chain(self.write.iter())
+            .any(|allowed| is_within(prefix, allowed) || is_within(allowed, prefix))
+    }
+}
+
+fn is_within(location: &Path, prefix: &Path) -> bool {
+    location.prefix_matches(prefix)
+}
+
+fn denied(location: &Path) -> object_store::Error {
+    object_store::Error::PermissionDenied {
+        path: location.to_string(),
+        source: "path is outside of the credential scope".into(),
+    }
+}
+
+/// Restricts an inner store to a set of path prefixes, e.g. for scoped runtime credentials
+/// on backends that have no native policy system (local filesystem, memory).
+#[derive(Debug)]
+pub struct ScopedObjectStore {
+    inner: FlowLikeStore,
+    store: Arc<dyn ObjectStore>,
+    scope: StoreScope,
+}
+
+impl std::fmt::Display for ScopedObjectStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Scoped({})", self.store)
+    }
+}
+
+impl ScopedObjectStore {
+    pub fn new(inner: FlowLikeStore, scope: StoreScope) -> Self {
+        let store = inner.as_generic();
+        Self {
+            inner,
+            store,
+            scope,
+        }
+    }
+
+    pub fn inner(&self) -> &FlowLikeStore {
+        &self.inner
+    }
+
+    pub fn scope(&self) -> &StoreScope {
+        &self.scope
+    }
+
+    fn check_read(&self, location: &Path) -> Result<()> {
+        if self.scope.can_read(location) {
+            return Ok(());
+        }
+        Err(denied(location))
+    }
+
+    fn check_write(&self, location: &Path) -> Result<()> {
+        if self.scope.can_write(location) {
+            return Ok(());
+        }
+        Err(denied(location))
+    }
+}
+
+#[async_trait]
+impl ObjectStore for ScopedObjectStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        self.check_write(location)?;
+        self.store.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.check_write(location)?;
+        self.store.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, opts: GetOptions) -> Result<GetResult> {
+        self.check_read(location)?;
+        self.store.get_opts(location, opts).await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.check_read(location)?;
+        self.store.get_range(location, range).await
+    }
+
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<usize>]) -> Result<Vec<Bytes>> {
+        self.check_read(location)?;
+        self.store.get_ranges(location, ranges).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.check_read(location)?;
+        self.store.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.check_write(location)?;
+        self.store.delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        if !self.scope.can_list(prefix) {
+            return futures::stream::empty().boxed();
+        }
+
+        self.store
+            .list(prefix)
+            .filter(move |meta| {
+                let visible = match meta {
+                    Ok(meta) => self.scope.can_read(&meta.location),
+                    Err(_) => true,
+                };
+                futures::future::ready(visible)
+            })
+            .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        if !self.scope.can_list(prefix) {
+            return Ok(ListResult {
+                common_prefixes: vec![],
+                objects: vec![],
+            });
+        }
+
+        let mut result = self.store.list_with_delimiter(prefix).await?;
+        result
+            .objects
+            .retain(|meta| self.scope.can_read(&meta.location));
+        result
+            .common_prefixes
+            .retain(|common| self.scope.can_list(Some(common)));
+        Ok(result)
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.check_read(from)?;
+        self.check_write(to)?;
+        self.store.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.check_write(from)?;
+        self.check_write(to)?;
+        self.store.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.check_read(from)?;
+        self.check_write(to)?;
+        self.store.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.check_write(from)?;
+        self.check_write(to)?;
+        self.store.rename_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flow_like_types::tokio;
+    use object_store::memory::InMemory;
+
+    fn scoped() -> ScopedObjectStore {
+        let inner = FlowLikeStore::Memory(Arc::new(InMemory::new()));
+        let scope = StoreScope::new()
+            .allow_read("apps/app1")
+            .allow_write("users/user1/apps/app1");
+        ScopedObjectStore::new(inner, scope)
+    }
+
+    #[tokio::test]
+    async fn enforces_prefixes() {
+        let store = scoped();
+        let payload = || PutPayload::from_static(b"data");
+
+        assert!(
+            store
+                .put(&Path::from("users/user1/apps/app1/file.txt"), payload())
+                .await
+                .is_ok()
+        );
+        assert!(
+            store
+                .put(&Path::from("apps/app1/board.json"), payload())
+                .await
+                .is_err()
+        );
+        assert!(
+            store
+                .put(&Path::from("users/user2/apps/app1/file.txt"), payload())
+                .await
+                .is_err()
+        );
+        // "apps/app10" must not match the "apps/app1" prefix
+        assert!(!store.scope().can_read(&Path::from("apps/app10/board.json")));
+        assert!(store.scope().can_read(&Path::from("apps/app1/board.json")));
+    }
+
+    #[tokio::test]
+    async fn hides_objects_outside_of_scope() {
+        let store = scoped();
+        let inner = store.inner().as_generic();
+        for path in ["apps/app1/a.json", "apps/app2/b.json"] {
+            inner
+                .put(&Path::from(path), PutPayload::from_static(b"data"))
+                .await
+                .unwrap();
+        }
+
+        let listed = store
+            .list(None)
+            .map(|meta| meta.unwrap().location.to_string())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(listed, vec!["apps/app1/a.json".to_string()]);
+
+        let listed = store
+            .list_with_delimiter(Some(&Path::from("apps")))
+            .await
+            .unwrap();
+        assert_eq!(listed.common_prefixes, vec![Path::from("apps/app1")]);
+    }
+}
@@ -16,9 +16,13 @@ import { AutoSizer } from "react-virtualized";
 import "react-virtualized/styles.css";
 import { VariableSizeList as List, type VariableSizeList } from "react-window";
 import { toast } from "sonner";
-import { type ILog, useBackend, useInfiniteInvoke } from "../..";
+import {
+	type ILog,
+	type ILogFilter,
+	useBackend,
+	useInfiniteInvoke,
+} from "../..";
 import { parseTimespan } from "../../lib/date";
-import { logLevelToNumber } from "../../lib/log-level";
 import { ILogLevel, type ILogMessage } from "../../lib/schema/flow/run";
 import { useLogAggregation } from "../../state/log-aggregation-state";
 import { EmptyState } from "../ui";
@@ -38,8 +42,7 @@ export function Traces({
 	const backend = useBackend();
 	const { currentMetadata } = useLogAggregation();
 
-	const [queryParts, setQueryParts] = useState<string[]>([]);
-	const [query, setQuery] = useState("");
+	const [filter, setFilter] = useState<ILogFilter>({});
 
 	const [logFilter, setLogFilter] = useState<Set<ILogLevel>>(
 		new Set([
@@ -55,7 +58,7 @@ export function Traces({
 		useInfiniteInvoke(
 			backend.boardState.queryRun,
 			backend.boardState,
-			[currentMetadata!, query],
+			[currentMetadata!, filter],
 			1000,
 			typeof currentMetadata !== "undefined",
 		);
@@ -80,12 +83,6 @@ export function Traces({
 		});
 	}, []);
 
-	const buildQuery = useCallback((parts: string[]) => {
-		if (parts.length === 0) return "";
-		if (parts.length === 1) return parts[0];
-		return parts.map((part) => `(${part})`).join(" AND ");
-	}, []);
-
 	const handleNodeSelect = useCallback(
 		(nodeId: string) => {
 			console.log("select node", nodeId);
@@ -108,44 +105,14 @@ export function Traces({
 	);
 
 	useEffect(() => {
-		const parts = [];
-
-		if (logFilter.size > 0 && logFilter.size < 5) {
-			parts.push(
-				`log_level IN (${Array.from(logFilter)
-					.map((level) => logLevelToNumber(level))
-					.join(", ")})`,
-			);
-		}
-
-		if (debouncedSearch.length > 0) {
-			parts.push(`message LIKE '%${debouncedSearch}%'`);
-		}
-
-		setQueryParts(parts);
-	}, [logFilter, debouncedSearch]);
-
-	useEffect(() => {
-		if (queryParts.length === 0) {
-			setQuery("");
-			return;
-		}
-
-		if (queryParts.length === 1) {
-			setQuery(queryParts[0]);
-			return;
-		}
-
-		let query = "";
-		queryParts.forEach((part, index) => {
-			if (index === 0) {
-				query += `(${part})`;
-			} else {
-				query += ` AND (${part})`;
-			}
+		setFilter({
+			levels:
+				logFilter.size > 0 && logFilter.size < 5
+					? Array.from(logFilter)
+					: undefined,
+			search: debouncedSearch.length > 0 ? debouncedSearch : undefined,
 		});
-		setQuery(query);
-	}, [queryParts]);
+	}, [logFilter, debouncedSearch]);
 
 	function getRowHeight(index: number) {
 		if (hasNextPage && index === (messages?.length ?? 0)) {
@@ -197,10 +164,6 @@ export function Traces({
 		],
 	);
 
-	useEffect(() => {
-		setQuery(buildQuery(queryParts));
-	}, [queryParts, buildQuery]);
-
 	function setRowHeight(index: number, height: number) {
 		listRef.current?.resetAfterIndex(0);
 		rowHeights.current = rowHeights.current.set(index, height);
@@ -0,0 +1,173 @@
+"use client";
+
+import { Input, Label, Switch } from "../../ui";
+import type { IConfigInterfaceProps } from "../interfaces";
+
+export function MqttConfig({
+	isEditing,
+	appId,
+	boardId,
+	config,
+	nodeId,
+	node,
+	onConfigUpdate,
+}: IConfigInterfaceProps) {
+	const setValue = (key: string, value: any) => {
+		if (onConfigUpdate) {
+			onConfigUpdate({
+				...config,
+				[key]: value,
+			});
+		}
+	};
+
+	const textField = (
+		key: string,
+		label: string,
+		description: string,
+		placeholder: string,
+		secret = false,
+	) => (
+		<div className="space-y-3">
+			<Label htmlFor={key}>{label}</Label>
+			{isEditing ? (
+				<Input
+					value={config?.[key] ?? ""}
+					onChange={(e) => setValue(key, e.target.value)}
+					type={secret ? "password" : "text"}
+					id={key}
+					placeholder={placeholder}
+				/>
+			) : (
+				<div className="flex h-10 w-full rounded-md border border-input bg-muted px-3 py-2 text-sm">
+					{config?.[key]
+						? secret
+							? "••••••••"
+							: config[key]
+						: "Not configured"}
+				</div>
+			)}
+			<p className="text-sm text-muted-foreground">{description}</p>
+		</div>
+	);
+
+	const switchField = (key: string, label: string, description: string) => (
+		<div className="space-y-4">
+			<div className="flex items-center space-x-2">
+				{isEditing ? (
+					<Switch
+						id={key}
+						checked={config?.[key] ?? false}
+						onCheckedChange={(checked) => setValue(key, checked)}
+					/>
+				) : (
+					<div
+						className={`h-5 w-9 rounded-full ${config?.[key] ? "bg-primary" : "bg-muted"} flex items-center ${config?.[key] ? "justify-end" : "justify-start"} px-0.5`}
+					>
+						<div className="h-4 w-4 rounded-full bg-white" />
+					</div>
+				)}
+				<Label htmlFor={key}>{label}</Label>
+				{!isEditing && (
+					<span className="text-sm text-muted-foreground">
+						{config?.[key] ? "Enabled" : "Disabled"}
+					</span>
+				)}
+			</div>
+			<p className="text-sm text-muted-foreground">{description}</p>
+		</div>
+	);
+
+	return (
+		<div className="w-full space-y-6">
+			{textField(
+				"host",
+				"Broker Host",
+				"Hostname of the MQTT broker",
+				"localhost",
+			)}
+
+			<div className="space-y-3">
+				<Label htmlFor="port">Port</Label>
+				{isEditing ? (
+					<Input
+						value={config?.port ?? 1883}
+						onChange={(e) => setValue("port", Number(e.target.value))}
+						type="number"
+						id="port"
+						min={1}
+						max={65535}
+					/>
+				) : (
+					<div className="flex h-10 w-full rounded-md border border-input bg-muted px-3 py-2 text-sm">
+						{config?.port ?? 1883}
+					</div>
+				)}
+				<p className="text-sm text-muted-foreground">
+					Port of the MQTT broker
+				</p>
+			</div>
+
+			{textField(
+				"topic_filter",
+				"Topic Filter",
+				"Every message on a matching topic starts a run, wildcards (+, #) are supported",
+				"sensors/+/temperature",
+			)}
+
+			<div className="space-y-3">
+				<Label htmlFor="qos">QoS</Label>
+				{isEditing ? (
+					<select
+						value={config?.qos ?? 1}
+						onChange={(e) => setValue("qos", Number(e.target.value))}
+						id="qos"
+						className="flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background file:border-0 file:bg-transparent file:text-sm file:font-medium placeholder:text-muted-foreground focus-visible:outline-hidden focus-visible:ring-2 focus-visible:ring-ring focus-visible:ring-offset-2 disabled:cursor-not-allowed disabled:opacity-50"
+					>
+						<option value={0}>0 - At most once</option>
+						<option value={1}>1 - At least once</option>
+						<option value={2}>2 - Exactly once</option>
+					</select>
+				) : (
+					<div className="flex h-10 w-full rounded-md border border-input bg-muted px-3 py-2 text-sm">
+						{config?.qos ?? 1}
+					</div>
+				)}
+				<p className="text-sm text-muted-foreground">
+					Delivery guarantee of the subscription
+				</p>
+			</div>
+
+			{textField(
+				"client_id",
+				"Client ID",
+				"MQTT client identifier, generated if empty",
+				"flow-like-sensor-listener",
+			)}
+			{textField(
+				"username",
+				"Username",
+				"Leave empty for anonymous access",
+				"username",
+			)}
+			{textField(
+				"secret_password",
+				"Password",
+				"Password of the broker user",
+				"••••••••",
+				true,
+			)}
+
+			{switchField(
+				"use_tls",
+				"Use TLS",
+				"Connect via TLS using the system root certificates",
+			)}
+			{switchField(
+				"include_retained",
+				"Include Retained Messages",
+				"Start runs for retained messages sent by the broker on subscribe",
+			)}
+		</div>
+	);
+}
@@ -1,5 +1,6 @@
 export * from "./chat-default";
 export * from "./configs/api";
+export * from "./configs/mqtt";
 export * from "./configs/simple_chat";
 export * from "./configs/translation";
 export * from "./configs/user_mail";
@@ -60,6 +60,9 @@ export class RolePermissions {
 	static readonly WriteTemplates = new RolePermissions(
 		0b00010000_00000000_00000000n,
 	);
+	static readonly ModerateComments = new RolePermissions(
+		0b10000000_00000000_00000000n,
+	);
 
 	constructor(value: bigint | number = 0n) {
 		this.value = typeof value === "number" ? BigInt(value) : value;
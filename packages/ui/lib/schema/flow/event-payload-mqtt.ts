@@ -0,0 +1,20 @@
+export interface IEventPayloadMqtt {
+	client_id?: null | string;
+	host?: null | string;
+	/**
+	 * Whether retained messages delivered on subscribe start a run
+	 */
+	include_retained?: boolean | null;
+	port?: number | null;
+	/**
+	 * 0 = at most once, 1 = at least once, 2 = exactly once
+	 */
+	qos?: number | null;
+	secret_password?: null | string;
+	/**
+	 * MQTT topic filter, wildcards (`+`, `#`) are supported
+	 */
+	topic_filter?: null | string;
+	use_tls?: boolean | null;
+	username?: null | string;
+}
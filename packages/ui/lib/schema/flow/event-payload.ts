@@ -19,5 +19,14 @@ export interface IEventPayload {
 	method?: null | string;
 	path_suffix?: null | string;
 	public_endpoint?: boolean | null;
+	client_id?: null | string;
+	host?: null | string;
+	include_retained?: boolean | null;
+	port?: number | null;
+	qos?: number | null;
+	secret_password?: null | string;
+	topic_filter?: null | string;
+	use_tls?: boolean | null;
+	username?: null | string;
 	[property: string]: any;
 }
@@ -41,6 +41,7 @@ export type { IEventPayload } from "./flow/event-payload";
 export type { IEventPayloadMail } from "./flow/event-payload-mail";
 export type { IEventPayloadChat } from "./flow/event-payload-chat";
 export type { IEventPayloadAPI } from "./flow/event-payload-api";
+export type { IEventPayloadMqtt } from "./flow/event-payload-mqtt";
 export { IVersionType } from "./flow/version-type";
 export type { ICanary } from "./flow/canary";
 export {
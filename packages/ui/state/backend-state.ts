@@ -33,6 +33,7 @@ export type {
 	IInvite,
 	IInviteLink,
 	IJoinRequest,
+	ILogFilter,
 	IMember,
 	IStorageItemActionResult,
 } from "./backend-state/types";
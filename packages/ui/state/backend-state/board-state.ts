@@ -11,6 +11,7 @@ import type {
 	IRunPayload,
 	IVersionType,
 } from "../../lib";
+import type { ILogFilter } from "./types";
 
 export interface IBoardState {
 	getBoards(appId: string): Promise<IBoard[]>;
@@ -56,7 +57,7 @@ export interface IBoardState {
 	): Promise<ILogMetadata[]>;
 	queryRun(
 		logMeta: ILogMetadata,
-		query: string,
+		filter: ILogFilter,
 		offset?: number,
 		limit?: number,
 	): Promise<ILog[]>;
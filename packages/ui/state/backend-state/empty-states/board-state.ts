@@ -6,6 +6,7 @@ import type {
 	IGenericCommand,
 	IIntercomEvent,
 	ILog,
+	ILogFilter,
 	ILogLevel,
 	ILogMetadata,
 	INode,
@@ -74,7 +75,7 @@ export class EmptyBoardState implements IBoardState {
 	}
 	queryRun(
 		logMeta: ILogMetadata,
-		query: string,
+		filter: ILogFilter,
 		offset?: number,
 		limit?: number,
 	): Promise<ILog[]> {
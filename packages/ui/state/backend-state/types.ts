@@ -1,3 +1,14 @@
+import type { ILogLevel } from "../../lib";
+
+/** Filter for the log messages of a run, mirrors `LogFilter` in core */
+export interface ILogFilter {
+	min_level?: ILogLevel;
+	levels?: ILogLevel[];
+	node_id?: string;
+	/** Case sensitive substring of the message */
+	search?: string;
+}
+
 export interface IStorageItemActionResult {
 	prefix: string;
 	url?: string;
@@ -71,4 +82,5 @@ export interface IUserLookup {
 
 export interface INotificationsOverview {
 	invites_count: number;
+	publication_updates?: number;
 }